@@ -302,7 +302,7 @@ pub(crate) async fn do_sync(
         }
     }
 
-    fastn_core::history::insert_into_history(&config.root, &to_be_in_history, &mut remote_history)
+    fastn_core::history::insert_into_history(config, &to_be_in_history, &mut remote_history)
         .await?;
     Ok(synced_files)
 }
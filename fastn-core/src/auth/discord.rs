@@ -97,20 +97,17 @@ pub async fn callback(req: actix_web::HttpRequest) -> fastn_core::Result<actix_w
                 user_id,
             };
             let user_detail_str = serde_json::to_string(&user_detail_obj)?;
-
+            let session = fastn_core::auth::session::create(
+                &fastn_core::auth::AuthProviders::Discord,
+                format!("discord:{}", user_detail_obj.user_id).as_str(),
+                user_detail_str.as_str(),
+            )?;
             return Ok(actix_web::HttpResponse::Found()
-                .cookie(
-                    actix_web::cookie::Cookie::build(
-                        fastn_core::auth::AuthProviders::Discord.as_str(),
-                        fastn_core::auth::utils::encrypt_str(&user_detail_str).await,
-                    )
-                    .domain(fastn_core::auth::utils::domain(
-                        req.connection_info().host(),
-                    ))
-                    .path("/")
-                    .permanent()
-                    .finish(),
-                )
+                .cookie(fastn_core::auth::session::cookie(
+                    &fastn_core::auth::AuthProviders::Discord,
+                    session,
+                    &req.connection_info(),
+                ))
                 .append_header((actix_web::http::header::LOCATION, "/".to_string()))
                 .finish());
         }
@@ -84,22 +84,17 @@ pub async fn callback(req: actix_web::HttpRequest) -> fastn_core::Result<actix_w
                 user_name,
             };
             let user_detail_str = serde_json::to_string(&user_detail_obj)?;
+            let session = fastn_core::auth::session::create(
+                &fastn_core::auth::AuthProviders::GitHub,
+                format!("github:{}", user_detail_obj.user_name).as_str(),
+                user_detail_str.as_str(),
+            )?;
             return Ok(actix_web::HttpResponse::Found()
-                .cookie(
-                    actix_web::cookie::Cookie::build(
-                        fastn_core::auth::AuthProviders::GitHub.as_str(),
-                        fastn_core::auth::utils::encrypt_str(&user_detail_str).await,
-                    )
-                    .domain(fastn_core::auth::utils::domain(
-                        req.connection_info().host(),
-                    ))
-                    .path("/")
-                    .permanent()
-                    // TODO: AbrarK is running on http,
-                    // will remove it later
-                    // .secure(true)
-                    .finish(),
-                )
+                .cookie(fastn_core::auth::session::cookie(
+                    &fastn_core::auth::AuthProviders::GitHub,
+                    session,
+                    &req.connection_info(),
+                ))
                 .append_header((actix_web::http::header::LOCATION, query.next))
                 .finish());
         }
@@ -19,6 +19,7 @@ pub(crate) mod okta;
 pub(crate) mod pintrest;
 pub(crate) mod processor;
 pub(crate) mod routes;
+pub(crate) mod session;
 pub(crate) mod slack;
 pub(crate) mod telegram;
 pub(crate) mod tiktok;
@@ -29,7 +30,7 @@ pub(crate) mod yahoo;
 pub(crate) mod zoho;
 
 pub mod utils;
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub(crate) enum AuthProviders {
     GitHub,
     TeleGram,
@@ -131,18 +132,30 @@ impl AuthProviders {
     }
 }
 
-pub fn secret_key() -> String {
-    match std::env::var("SECRET_KEY") {
-        Ok(secret) => secret,
-        Err(_e) => {
-            println!("WARN: SECRET_KEY not set");
-            // TODO: Need to change this approach later
-            "FASTN_TEMP_SECRET".to_string()
+/// `secret_key()` signs the session cookies. A server in remote mode is shared by everyone, so
+/// it must have its own `SECRET_KEY`, a local server falls back to a temporary one.
+pub fn secret_key() -> fastn_core::Result<String> {
+    resolve_secret_key(std::env::var("SECRET_KEY").ok(), cfg!(feature = "remote"))
+}
+
+pub(crate) fn resolve_secret_key(
+    secret: Option<String>,
+    remote: bool,
+) -> fastn_core::Result<String> {
+    static WARN: std::sync::Once = std::sync::Once::new();
+    match secret {
+        Some(secret) if !secret.is_empty() => Ok(secret),
+        _ if remote => fastn_core::usage_error(
+            "SECRET_KEY is not set, it is required to sign the sessions in remote mode".to_string(),
+        ),
+        _ => {
+            WARN.call_once(|| println!("WARN: SECRET_KEY not set, using a temporary secret"));
+            Ok("FASTN_TEMP_SECRET".to_string())
         }
     }
 }
 
-/// will fetch out the decrypted user data from cookies
+/// will fetch out the user data of the session in cookies
 /// and return it as string
 /// if no cookie wrt to platform found it throws an error
 pub async fn get_user_data_from_cookies(
@@ -150,19 +163,18 @@ pub async fn get_user_data_from_cookies(
     requested_field: &str,
     cookies: &std::collections::HashMap<String, String>,
 ) -> fastn_core::Result<Option<String>> {
-    let ud_encrypted = cookies.get(platform).ok_or_else(|| {
+    let ud_cookie = cookies.get(platform).ok_or_else(|| {
         fastn_core::Error::GenericError(format!(
             "user detail not found for platform {} in the cookies",
             platform
         ))
     });
-    match ud_encrypted {
-        Ok(encrypt_str) => {
-            if let Ok(ud_decrypted) = utils::decrypt_str(encrypt_str).await {
+    match ud_cookie {
+        Ok(session_cookie) => {
+            if let Some(ud_json) = session::user_detail(session_cookie)? {
                 match fastn_core::auth::AuthProviders::from_str(platform) {
                     fastn_core::auth::AuthProviders::GitHub => {
-                        let github_ud: github::UserDetail =
-                            serde_json::from_str(ud_decrypted.as_str())?;
+                        let github_ud: github::UserDetail = serde_json::from_str(ud_json.as_str())?;
                         return match requested_field {
                             "username" | "user_name" | "user-name" => Ok(Some(github_ud.user_name)),
                             "token" => Ok(Some(github_ud.token)),
@@ -174,7 +186,7 @@ pub async fn get_user_data_from_cookies(
                     }
                     fastn_core::auth::AuthProviders::TeleGram => {
                         let telegram_ud: telegram::UserDetail =
-                            serde_json::from_str(ud_decrypted.as_str())?;
+                            serde_json::from_str(ud_json.as_str())?;
                         return match requested_field {
                             "username" | "user_name" | "user-name" => {
                                 Ok(Some(telegram_ud.user_name))
@@ -189,7 +201,7 @@ pub async fn get_user_data_from_cookies(
                     }
                     fastn_core::auth::AuthProviders::Discord => {
                         let discord_ud: discord::UserDetail =
-                            serde_json::from_str(ud_decrypted.as_str())?;
+                            serde_json::from_str(ud_json.as_str())?;
                         return match requested_field {
                             "username" | "user_name" | "user-name" => {
                                 Ok(Some(discord_ud.user_name))
@@ -223,18 +235,17 @@ pub async fn get_auth_identities(
 ) -> fastn_core::Result<Vec<fastn_core::user_group::UserIdentity>> {
     let mut matched_identities: Vec<fastn_core::user_group::UserIdentity> = vec![];
 
-    let github_ud_encrypted = cookies
+    let github_ud_cookie = cookies
         .get(fastn_core::auth::AuthProviders::GitHub.as_str())
         .ok_or_else(|| {
             fastn_core::Error::GenericError(
                 "github user detail not found in the cookies".to_string(),
             )
         });
-    match github_ud_encrypted {
-        Ok(encrypt_str) => {
-            if let Ok(github_ud_decrypted) = utils::decrypt_str(encrypt_str).await {
-                let github_ud: github::UserDetail =
-                    serde_json::from_str(github_ud_decrypted.as_str())?;
+    match github_ud_cookie {
+        Ok(session_cookie) => {
+            if let Some(github_ud_json) = session::user_detail(session_cookie)? {
+                let github_ud: github::UserDetail = serde_json::from_str(github_ud_json.as_str())?;
                 matched_identities.extend(github::matched_identities(github_ud, identities).await?);
             }
         }
@@ -243,18 +254,18 @@ pub async fn get_auth_identities(
             format!("{}{}", "github user detail not found in the cookies", err);
         }
     };
-    let telegram_ud_encrypted = cookies
+    let telegram_ud_cookie = cookies
         .get(fastn_core::auth::AuthProviders::TeleGram.as_str())
         .ok_or_else(|| {
             fastn_core::Error::GenericError(
                 "telegram user detail not found in the cookies".to_string(),
             )
         });
-    match telegram_ud_encrypted {
-        Ok(encrypt_str) => {
-            if let Ok(telegram_ud_decrypted) = utils::decrypt_str(encrypt_str).await {
+    match telegram_ud_cookie {
+        Ok(session_cookie) => {
+            if let Some(telegram_ud_json) = session::user_detail(session_cookie)? {
                 let telegram_ud: telegram::UserDetail =
-                    serde_json::from_str(telegram_ud_decrypted.as_str())?;
+                    serde_json::from_str(telegram_ud_json.as_str())?;
                 matched_identities
                     .extend(telegram::matched_identities(telegram_ud, identities).await?);
             }
@@ -263,18 +274,18 @@ pub async fn get_auth_identities(
             format!("{}{}", "telegram user detail not found in the cookies", err);
         }
     };
-    let discord_ud_encrypted = cookies
+    let discord_ud_cookie = cookies
         .get(fastn_core::auth::AuthProviders::Discord.as_str())
         .ok_or_else(|| {
             fastn_core::Error::GenericError(
                 "discord user detail not found in the cookies".to_string(),
             )
         });
-    match discord_ud_encrypted {
-        Ok(encrypt_str) => {
-            if let Ok(discord_ud_decrypted) = utils::decrypt_str(encrypt_str).await {
+    match discord_ud_cookie {
+        Ok(session_cookie) => {
+            if let Some(discord_ud_json) = session::user_detail(session_cookie)? {
                 let discord_ud: discord::UserDetail =
-                    serde_json::from_str(discord_ud_decrypted.as_str())?;
+                    serde_json::from_str(discord_ud_json.as_str())?;
                 matched_identities
                     .extend(discord::matched_identities(discord_ud, identities).await?);
             }
@@ -283,7 +294,7 @@ pub async fn get_auth_identities(
             format!("{}{}", "discord user detail not found in the cookies", err);
         }
     };
-    let twitter_ud_encrypted = cookies
+    let twitter_ud_cookie = cookies
         .get(fastn_core::auth::AuthProviders::Twitter.as_str())
         .ok_or_else(|| {
             fastn_core::Error::GenericError(
@@ -291,11 +302,11 @@ pub async fn get_auth_identities(
             )
         });
 
-    match twitter_ud_encrypted {
-        Ok(encrypt_str) => {
-            if let Ok(twitter_ud_decrypted) = utils::decrypt_str(encrypt_str).await {
+    match twitter_ud_cookie {
+        Ok(session_cookie) => {
+            if let Some(twitter_ud_json) = session::user_detail(session_cookie)? {
                 let twitter_ud: twitter::UserDetail =
-                    serde_json::from_str(twitter_ud_decrypted.as_str())?;
+                    serde_json::from_str(twitter_ud_json.as_str())?;
                 matched_identities
                     .extend(twitter::matched_identities(twitter_ud, identities).await?);
             }
@@ -304,18 +315,17 @@ pub async fn get_auth_identities(
             format!("{}{}", "twitter user detail not found in the cookies", err);
         }
     };
-    let amazon_ud_encrypted = cookies
+    let amazon_ud_cookie = cookies
         .get(fastn_core::auth::AuthProviders::Amazon.as_str())
         .ok_or_else(|| {
             fastn_core::Error::GenericError(
                 "amazon user detail not found in the cookies".to_string(),
             )
         });
-    match amazon_ud_encrypted {
-        Ok(encrypt_str) => {
-            if let Ok(amazon_ud_decrypted) = utils::decrypt_str(encrypt_str).await {
-                let amazon_ud: amazon::UserDetail =
-                    serde_json::from_str(amazon_ud_decrypted.as_str())?;
+    match amazon_ud_cookie {
+        Ok(session_cookie) => {
+            if let Some(amazon_ud_json) = session::user_detail(session_cookie)? {
+                let amazon_ud: amazon::UserDetail = serde_json::from_str(amazon_ud_json.as_str())?;
                 matched_identities.extend(amazon::matched_identities(amazon_ud, identities).await?);
             }
         }
@@ -323,18 +333,18 @@ pub async fn get_auth_identities(
             format!("{}{}", "amazon user detail not found in the cookies", err);
         }
     };
-    let facebook_ud_encrypted = cookies
+    let facebook_ud_cookie = cookies
         .get(fastn_core::auth::AuthProviders::Facebook.as_str())
         .ok_or_else(|| {
             fastn_core::Error::GenericError(
                 "facebook user detail not found in the cookies".to_string(),
             )
         });
-    match facebook_ud_encrypted {
-        Ok(encrypt_str) => {
-            if let Ok(facebook_ud_decrypted) = utils::decrypt_str(encrypt_str).await {
+    match facebook_ud_cookie {
+        Ok(session_cookie) => {
+            if let Some(facebook_ud_json) = session::user_detail(session_cookie)? {
                 let facebook_ud: facebook::UserDetail =
-                    serde_json::from_str(facebook_ud_decrypted.as_str())?;
+                    serde_json::from_str(facebook_ud_json.as_str())?;
                 matched_identities
                     .extend(facebook::matched_identities(facebook_ud, identities).await?);
             }
@@ -343,18 +353,17 @@ pub async fn get_auth_identities(
             format!("{}{}", "facebook user detail not found in the cookies", err);
         }
     };
-    let gmail_ud_encrypted = cookies
+    let gmail_ud_cookie = cookies
         .get(fastn_core::auth::AuthProviders::Gmail.as_str())
         .ok_or_else(|| {
             fastn_core::Error::GenericError(
                 "gmail user detail not found in the cookies".to_string(),
             )
         });
-    match gmail_ud_encrypted {
-        Ok(encrypt_str) => {
-            if let Ok(gmail_ud_decrypted) = utils::decrypt_str(encrypt_str).await {
-                let gmail_ud: gmail::UserDetail =
-                    serde_json::from_str(gmail_ud_decrypted.as_str())?;
+    match gmail_ud_cookie {
+        Ok(session_cookie) => {
+            if let Some(gmail_ud_json) = session::user_detail(session_cookie)? {
+                let gmail_ud: gmail::UserDetail = serde_json::from_str(gmail_ud_json.as_str())?;
                 matched_identities.extend(gmail::matched_identities(gmail_ud, identities).await?);
             }
         }
@@ -362,18 +371,17 @@ pub async fn get_auth_identities(
             format!("{}{}", "gmail user detail not found in the cookies", err);
         }
     };
-    let slack_ud_encrypted = cookies
+    let slack_ud_cookie = cookies
         .get(fastn_core::auth::AuthProviders::Slack.as_str())
         .ok_or_else(|| {
             fastn_core::Error::GenericError(
                 "slack user detail not found in the cookies".to_string(),
             )
         });
-    match slack_ud_encrypted {
-        Ok(encrypt_str) => {
-            if let Ok(slack_ud_decrypted) = utils::decrypt_str(encrypt_str).await {
-                let slack_ud: slack::UserDetail =
-                    serde_json::from_str(slack_ud_decrypted.as_str())?;
+    match slack_ud_cookie {
+        Ok(session_cookie) => {
+            if let Some(slack_ud_json) = session::user_detail(session_cookie)? {
+                let slack_ud: slack::UserDetail = serde_json::from_str(slack_ud_json.as_str())?;
                 matched_identities.extend(slack::matched_identities(slack_ud, identities).await?);
             }
         }
@@ -381,18 +389,17 @@ pub async fn get_auth_identities(
             format!("{}{}", "slack user detail not found in the cookies", err);
         }
     };
-    let apple_ud_encrypted = cookies
+    let apple_ud_cookie = cookies
         .get(fastn_core::auth::AuthProviders::Apple.as_str())
         .ok_or_else(|| {
             fastn_core::Error::GenericError(
                 "apple user detail not found in the cookies".to_string(),
             )
         });
-    match apple_ud_encrypted {
-        Ok(encrypt_str) => {
-            if let Ok(apple_ud_decrypted) = utils::decrypt_str(encrypt_str).await {
-                let apple_ud: apple::UserDetail =
-                    serde_json::from_str(apple_ud_decrypted.as_str())?;
+    match apple_ud_cookie {
+        Ok(session_cookie) => {
+            if let Some(apple_ud_json) = session::user_detail(session_cookie)? {
+                let apple_ud: apple::UserDetail = serde_json::from_str(apple_ud_json.as_str())?;
                 matched_identities.extend(apple::matched_identities(apple_ud, identities).await?);
             }
         }
@@ -400,18 +407,17 @@ pub async fn get_auth_identities(
             format!("{}{}", "apple user detail not found in the cookies", err);
         }
     };
-    let baidu_ud_encrypted = cookies
+    let baidu_ud_cookie = cookies
         .get(fastn_core::auth::AuthProviders::Baidu.as_str())
         .ok_or_else(|| {
             fastn_core::Error::GenericError(
                 "baidu user detail not found in the cookies".to_string(),
             )
         });
-    match baidu_ud_encrypted {
-        Ok(encrypt_str) => {
-            if let Ok(baidu_ud_decrypted) = utils::decrypt_str(encrypt_str).await {
-                let baidu_ud: baidu::UserDetail =
-                    serde_json::from_str(baidu_ud_decrypted.as_str())?;
+    match baidu_ud_cookie {
+        Ok(session_cookie) => {
+            if let Some(baidu_ud_json) = session::user_detail(session_cookie)? {
+                let baidu_ud: baidu::UserDetail = serde_json::from_str(baidu_ud_json.as_str())?;
                 matched_identities.extend(baidu::matched_identities(baidu_ud, identities).await?);
             }
         }
@@ -419,18 +425,18 @@ pub async fn get_auth_identities(
             format!("{}{}", "baidu user detail not found in the cookies", err);
         }
     };
-    let bitbucket_ud_encrypted = cookies
+    let bitbucket_ud_cookie = cookies
         .get(fastn_core::auth::AuthProviders::BitBucket.as_str())
         .ok_or_else(|| {
             fastn_core::Error::GenericError(
                 "bitbucket user detail not found in the cookies".to_string(),
             )
         });
-    match bitbucket_ud_encrypted {
-        Ok(encrypt_str) => {
-            if let Ok(bitbucket_ud_decrypted) = utils::decrypt_str(encrypt_str).await {
+    match bitbucket_ud_cookie {
+        Ok(session_cookie) => {
+            if let Some(bitbucket_ud_json) = session::user_detail(session_cookie)? {
                 let bitbucket_ud: bitbucket::UserDetail =
-                    serde_json::from_str(bitbucket_ud_decrypted.as_str())?;
+                    serde_json::from_str(bitbucket_ud_json.as_str())?;
                 matched_identities
                     .extend(bitbucket::matched_identities(bitbucket_ud, identities).await?);
             }
@@ -442,18 +448,18 @@ pub async fn get_auth_identities(
             );
         }
     };
-    let digitalocean_ud_encrypted = cookies
+    let digitalocean_ud_cookie = cookies
         .get(fastn_core::auth::AuthProviders::DigitalOcean.as_str())
         .ok_or_else(|| {
             fastn_core::Error::GenericError(
                 "digitalocean user detail not found in the cookies".to_string(),
             )
         });
-    match digitalocean_ud_encrypted {
-        Ok(encrypt_str) => {
-            if let Ok(digitalocean_ud_decrypted) = utils::decrypt_str(encrypt_str).await {
+    match digitalocean_ud_cookie {
+        Ok(session_cookie) => {
+            if let Some(digitalocean_ud_json) = session::user_detail(session_cookie)? {
                 let digitalocean_ud: digitalocean::UserDetail =
-                    serde_json::from_str(digitalocean_ud_decrypted.as_str())?;
+                    serde_json::from_str(digitalocean_ud_json.as_str())?;
                 matched_identities
                     .extend(digitalocean::matched_identities(digitalocean_ud, identities).await?);
             }
@@ -465,18 +471,18 @@ pub async fn get_auth_identities(
             );
         }
     };
-    let doorkeeper_ud_encrypted = cookies
+    let doorkeeper_ud_cookie = cookies
         .get(fastn_core::auth::AuthProviders::DoorKeeper.as_str())
         .ok_or_else(|| {
             fastn_core::Error::GenericError(
                 "doorkeeper user detail not found in the cookies".to_string(),
             )
         });
-    match doorkeeper_ud_encrypted {
-        Ok(encrypt_str) => {
-            if let Ok(doorkeeper_ud_decrypted) = utils::decrypt_str(encrypt_str).await {
+    match doorkeeper_ud_cookie {
+        Ok(session_cookie) => {
+            if let Some(doorkeeper_ud_json) = session::user_detail(session_cookie)? {
                 let doorkeeper_ud: doorkeeper::UserDetail =
-                    serde_json::from_str(doorkeeper_ud_decrypted.as_str())?;
+                    serde_json::from_str(doorkeeper_ud_json.as_str())?;
                 matched_identities
                     .extend(doorkeeper::matched_identities(doorkeeper_ud, identities).await?);
             }
@@ -488,18 +494,18 @@ pub async fn get_auth_identities(
             );
         }
     };
-    let dropbox_ud_encrypted = cookies
+    let dropbox_ud_cookie = cookies
         .get(fastn_core::auth::AuthProviders::DropBox.as_str())
         .ok_or_else(|| {
             fastn_core::Error::GenericError(
                 "DropBox user detail not found in the cookies".to_string(),
             )
         });
-    match dropbox_ud_encrypted {
-        Ok(encrypt_str) => {
-            if let Ok(dropbox_ud_decrypted) = utils::decrypt_str(encrypt_str).await {
+    match dropbox_ud_cookie {
+        Ok(session_cookie) => {
+            if let Some(dropbox_ud_json) = session::user_detail(session_cookie)? {
                 let dropbox_ud: dropbox::UserDetail =
-                    serde_json::from_str(dropbox_ud_decrypted.as_str())?;
+                    serde_json::from_str(dropbox_ud_json.as_str())?;
                 matched_identities
                     .extend(dropbox::matched_identities(dropbox_ud, identities).await?);
             }
@@ -508,18 +514,17 @@ pub async fn get_auth_identities(
             format!("{}{}", "dropbox user detail not found in the cookies", err);
         }
     };
-    let gitlab_ud_encrypted = cookies
+    let gitlab_ud_cookie = cookies
         .get(fastn_core::auth::AuthProviders::GitLab.as_str())
         .ok_or_else(|| {
             fastn_core::Error::GenericError(
                 "GitLab user detail not found in the cookies".to_string(),
             )
         });
-    match gitlab_ud_encrypted {
-        Ok(encrypt_str) => {
-            if let Ok(gitlab_ud_decrypted) = utils::decrypt_str(encrypt_str).await {
-                let gitlab_ud: gitlab::UserDetail =
-                    serde_json::from_str(gitlab_ud_decrypted.as_str())?;
+    match gitlab_ud_cookie {
+        Ok(session_cookie) => {
+            if let Some(gitlab_ud_json) = session::user_detail(session_cookie)? {
+                let gitlab_ud: gitlab::UserDetail = serde_json::from_str(gitlab_ud_json.as_str())?;
                 matched_identities.extend(gitlab::matched_identities(gitlab_ud, identities).await?);
             }
         }
@@ -527,18 +532,18 @@ pub async fn get_auth_identities(
             format!("{}{}", "GitLab user detail not found in the cookies", err);
         }
     };
-    let instagram_ud_encrypted = cookies
+    let instagram_ud_cookie = cookies
         .get(fastn_core::auth::AuthProviders::Instagram.as_str())
         .ok_or_else(|| {
             fastn_core::Error::GenericError(
                 "Instagram user detail not found in the cookies".to_string(),
             )
         });
-    match instagram_ud_encrypted {
-        Ok(encrypt_str) => {
-            if let Ok(instagram_ud_decrypted) = utils::decrypt_str(encrypt_str).await {
+    match instagram_ud_cookie {
+        Ok(session_cookie) => {
+            if let Some(instagram_ud_json) = session::user_detail(session_cookie)? {
                 let instagram_ud: instagram::UserDetail =
-                    serde_json::from_str(instagram_ud_decrypted.as_str())?;
+                    serde_json::from_str(instagram_ud_json.as_str())?;
                 matched_identities
                     .extend(instagram::matched_identities(instagram_ud, identities).await?);
             }
@@ -550,18 +555,18 @@ pub async fn get_auth_identities(
             );
         }
     };
-    let linkedin_ud_encrypted = cookies
+    let linkedin_ud_cookie = cookies
         .get(fastn_core::auth::AuthProviders::LinkedIn.as_str())
         .ok_or_else(|| {
             fastn_core::Error::GenericError(
                 "LinkedIn user detail not found in the cookies".to_string(),
             )
         });
-    match linkedin_ud_encrypted {
-        Ok(encrypt_str) => {
-            if let Ok(linkedin_ud_decrypted) = utils::decrypt_str(encrypt_str).await {
+    match linkedin_ud_cookie {
+        Ok(session_cookie) => {
+            if let Some(linkedin_ud_json) = session::user_detail(session_cookie)? {
                 let linkedin_ud: linkedin::UserDetail =
-                    serde_json::from_str(linkedin_ud_decrypted.as_str())?;
+                    serde_json::from_str(linkedin_ud_json.as_str())?;
                 matched_identities
                     .extend(linkedin::matched_identities(linkedin_ud, identities).await?);
             }
@@ -570,18 +575,18 @@ pub async fn get_auth_identities(
             format!("{}{}", "LinkedIn user detail not found in the cookies", err);
         }
     };
-    let microsoft_ud_encrypted = cookies
+    let microsoft_ud_cookie = cookies
         .get(fastn_core::auth::AuthProviders::Microsoft.as_str())
         .ok_or_else(|| {
             fastn_core::Error::GenericError(
                 "Microsoft user detail not found in the cookies".to_string(),
             )
         });
-    match microsoft_ud_encrypted {
-        Ok(encrypt_str) => {
-            if let Ok(microsoft_ud_decrypted) = utils::decrypt_str(encrypt_str).await {
+    match microsoft_ud_cookie {
+        Ok(session_cookie) => {
+            if let Some(microsoft_ud_json) = session::user_detail(session_cookie)? {
                 let microsoft_ud: microsoft::UserDetail =
-                    serde_json::from_str(microsoft_ud_decrypted.as_str())?;
+                    serde_json::from_str(microsoft_ud_json.as_str())?;
                 matched_identities
                     .extend(microsoft::matched_identities(microsoft_ud, identities).await?);
             }
@@ -593,15 +598,15 @@ pub async fn get_auth_identities(
             );
         }
     };
-    let okta_ud_encrypted = cookies
+    let okta_ud_cookie = cookies
         .get(fastn_core::auth::AuthProviders::Okta.as_str())
         .ok_or_else(|| {
             fastn_core::Error::GenericError("Okta user detail not found in the cookies".to_string())
         });
-    match okta_ud_encrypted {
-        Ok(encrypt_str) => {
-            if let Ok(okta_ud_decrypted) = utils::decrypt_str(encrypt_str).await {
-                let okta_ud: okta::UserDetail = serde_json::from_str(okta_ud_decrypted.as_str())?;
+    match okta_ud_cookie {
+        Ok(session_cookie) => {
+            if let Some(okta_ud_json) = session::user_detail(session_cookie)? {
+                let okta_ud: okta::UserDetail = serde_json::from_str(okta_ud_json.as_str())?;
                 matched_identities.extend(okta::matched_identities(okta_ud, identities).await?);
             }
         }
@@ -609,18 +614,18 @@ pub async fn get_auth_identities(
             format!("{}{}", "Okta user detail not found in the cookies", err);
         }
     };
-    let pintrest_ud_encrypted = cookies
+    let pintrest_ud_cookie = cookies
         .get(fastn_core::auth::AuthProviders::Pintrest.as_str())
         .ok_or_else(|| {
             fastn_core::Error::GenericError(
                 "Pintrest user detail not found in the cookies".to_string(),
             )
         });
-    match pintrest_ud_encrypted {
-        Ok(encrypt_str) => {
-            if let Ok(pintrest_ud_decrypted) = utils::decrypt_str(encrypt_str).await {
+    match pintrest_ud_cookie {
+        Ok(session_cookie) => {
+            if let Some(pintrest_ud_json) = session::user_detail(session_cookie)? {
                 let pintrest_ud: pintrest::UserDetail =
-                    serde_json::from_str(pintrest_ud_decrypted.as_str())?;
+                    serde_json::from_str(pintrest_ud_json.as_str())?;
                 matched_identities
                     .extend(pintrest::matched_identities(pintrest_ud, identities).await?);
             }
@@ -629,18 +634,17 @@ pub async fn get_auth_identities(
             format!("{}{}", "Pintrest user detail not found in the cookies", err);
         }
     };
-    let tiktok_ud_encrypted = cookies
+    let tiktok_ud_cookie = cookies
         .get(fastn_core::auth::AuthProviders::TikTok.as_str())
         .ok_or_else(|| {
             fastn_core::Error::GenericError(
                 "TikTok user detail not found in the cookies".to_string(),
             )
         });
-    match tiktok_ud_encrypted {
-        Ok(encrypt_str) => {
-            if let Ok(tiktok_ud_decrypted) = utils::decrypt_str(encrypt_str).await {
-                let tiktok_ud: tiktok::UserDetail =
-                    serde_json::from_str(tiktok_ud_decrypted.as_str())?;
+    match tiktok_ud_cookie {
+        Ok(session_cookie) => {
+            if let Some(tiktok_ud_json) = session::user_detail(session_cookie)? {
+                let tiktok_ud: tiktok::UserDetail = serde_json::from_str(tiktok_ud_json.as_str())?;
                 matched_identities.extend(tiktok::matched_identities(tiktok_ud, identities).await?);
             }
         }
@@ -648,18 +652,17 @@ pub async fn get_auth_identities(
             format!("{}{}", "TikTok user detail not found in the cookies", err);
         }
     };
-    let twitch_ud_encrypted = cookies
+    let twitch_ud_cookie = cookies
         .get(fastn_core::auth::AuthProviders::Twitch.as_str())
         .ok_or_else(|| {
             fastn_core::Error::GenericError(
                 "Twitch user detail not found in the cookies".to_string(),
             )
         });
-    match twitch_ud_encrypted {
-        Ok(encrypt_str) => {
-            if let Ok(twitch_ud_decrypted) = utils::decrypt_str(encrypt_str).await {
-                let twitch_ud: twitch::UserDetail =
-                    serde_json::from_str(twitch_ud_decrypted.as_str())?;
+    match twitch_ud_cookie {
+        Ok(session_cookie) => {
+            if let Some(twitch_ud_json) = session::user_detail(session_cookie)? {
+                let twitch_ud: twitch::UserDetail = serde_json::from_str(twitch_ud_json.as_str())?;
                 matched_identities.extend(twitch::matched_identities(twitch_ud, identities).await?);
             }
         }
@@ -667,18 +670,18 @@ pub async fn get_auth_identities(
             format!("{}{}", "Twitch user detail not found in the cookies", err);
         }
     };
-    let twitter_ud_encrypted = cookies
+    let twitter_ud_cookie = cookies
         .get(fastn_core::auth::AuthProviders::Twitter.as_str())
         .ok_or_else(|| {
             fastn_core::Error::GenericError(
                 "Twitter user detail not found in the cookies".to_string(),
             )
         });
-    match twitter_ud_encrypted {
-        Ok(encrypt_str) => {
-            if let Ok(twitter_ud_decrypted) = utils::decrypt_str(encrypt_str).await {
+    match twitter_ud_cookie {
+        Ok(session_cookie) => {
+            if let Some(twitter_ud_json) = session::user_detail(session_cookie)? {
                 let twitter_ud: twitter::UserDetail =
-                    serde_json::from_str(twitter_ud_decrypted.as_str())?;
+                    serde_json::from_str(twitter_ud_json.as_str())?;
                 matched_identities
                     .extend(twitter::matched_identities(twitter_ud, identities).await?);
             }
@@ -687,18 +690,17 @@ pub async fn get_auth_identities(
             format!("{}{}", "Twitter user detail not found in the cookies", err);
         }
     };
-    let wechat_ud_encrypted = cookies
+    let wechat_ud_cookie = cookies
         .get(fastn_core::auth::AuthProviders::WeChat.as_str())
         .ok_or_else(|| {
             fastn_core::Error::GenericError(
                 "WeChat user detail not found in the cookies".to_string(),
             )
         });
-    match wechat_ud_encrypted {
-        Ok(encrypt_str) => {
-            if let Ok(wechat_ud_decrypted) = utils::decrypt_str(encrypt_str).await {
-                let wechat_ud: wechat::UserDetail =
-                    serde_json::from_str(wechat_ud_decrypted.as_str())?;
+    match wechat_ud_cookie {
+        Ok(session_cookie) => {
+            if let Some(wechat_ud_json) = session::user_detail(session_cookie)? {
+                let wechat_ud: wechat::UserDetail = serde_json::from_str(wechat_ud_json.as_str())?;
                 matched_identities.extend(wechat::matched_identities(wechat_ud, identities).await?);
             }
         }
@@ -706,18 +708,17 @@ pub async fn get_auth_identities(
             format!("{}{}", "WeChat user detail not found in the cookies", err);
         }
     };
-    let yahoo_ud_encrypted = cookies
+    let yahoo_ud_cookie = cookies
         .get(fastn_core::auth::AuthProviders::Yahoo.as_str())
         .ok_or_else(|| {
             fastn_core::Error::GenericError(
                 "Yahoo user detail not found in the cookies".to_string(),
             )
         });
-    match yahoo_ud_encrypted {
-        Ok(encrypt_str) => {
-            if let Ok(yahoo_ud_decrypted) = utils::decrypt_str(encrypt_str).await {
-                let yahoo_ud: yahoo::UserDetail =
-                    serde_json::from_str(yahoo_ud_decrypted.as_str())?;
+    match yahoo_ud_cookie {
+        Ok(session_cookie) => {
+            if let Some(yahoo_ud_json) = session::user_detail(session_cookie)? {
+                let yahoo_ud: yahoo::UserDetail = serde_json::from_str(yahoo_ud_json.as_str())?;
                 matched_identities.extend(yahoo::matched_identities(yahoo_ud, identities).await?);
             }
         }
@@ -725,15 +726,15 @@ pub async fn get_auth_identities(
             format!("{}{}", "Yahoo user detail not found in the cookies", err);
         }
     };
-    let zoho_ud_encrypted = cookies
+    let zoho_ud_cookie = cookies
         .get(fastn_core::auth::AuthProviders::Zoho.as_str())
         .ok_or_else(|| {
             fastn_core::Error::GenericError("Zoho user detail not found in the cookies".to_string())
         });
-    match zoho_ud_encrypted {
-        Ok(encrypt_str) => {
-            if let Ok(zoho_ud_decrypted) = utils::decrypt_str(encrypt_str).await {
-                let zoho_ud: zoho::UserDetail = serde_json::from_str(zoho_ud_decrypted.as_str())?;
+    match zoho_ud_cookie {
+        Ok(session_cookie) => {
+            if let Some(zoho_ud_json) = session::user_detail(session_cookie)? {
+                let zoho_ud: zoho::UserDetail = serde_json::from_str(zoho_ud_json.as_str())?;
                 matched_identities.extend(zoho::matched_identities(zoho_ud, identities).await?);
             }
         }
@@ -741,18 +742,17 @@ pub async fn get_auth_identities(
             format!("{}{}", "Zoho user detail not found in the cookies", err);
         }
     };
-    let google_ud_encrypted = cookies
+    let google_ud_cookie = cookies
         .get(fastn_core::auth::AuthProviders::Google.as_str())
         .ok_or_else(|| {
             fastn_core::Error::GenericError(
                 "Google user detail not found in the cookies".to_string(),
             )
         });
-    match google_ud_encrypted {
-        Ok(encrypt_str) => {
-            if let Ok(google_ud_decrypted) = utils::decrypt_str(encrypt_str).await {
-                let google_ud: google::UserDetail =
-                    serde_json::from_str(google_ud_decrypted.as_str())?;
+    match google_ud_cookie {
+        Ok(session_cookie) => {
+            if let Some(google_ud_json) = session::user_detail(session_cookie)? {
+                let google_ud: google::UserDetail = serde_json::from_str(google_ud_json.as_str())?;
                 matched_identities.extend(google::matched_identities(google_ud, identities).await?);
             }
         }
@@ -48,267 +48,43 @@ pub async fn login(
 }
 
 // route: /auth/logout/
+// revokes the sessions of this browser and clears the cookies of all the platforms
 pub fn logout(req: actix_web::HttpRequest) -> fastn_core::Result<actix_web::HttpResponse> {
-    // TODO: Refactor, Not happy with this code, too much of repetition of similar code
-    // It is logging out from all the platforms
+    for (_, value) in fastn_core::auth::session::session_cookies(&req) {
+        fastn_core::auth::session::delete(value.as_str())?;
+    }
+    Ok(clear_cookies(&req))
+}
 
-    // Ideally it should capture the platform in the request and then logged out
-    // only from that platform
-    Ok(actix_web::HttpResponse::Found()
-        .cookie(
-            actix_web::cookie::Cookie::build(fastn_core::auth::AuthProviders::GitHub.as_str(), "")
-                .domain(fastn_core::auth::utils::domain(
-                    req.connection_info().host(),
-                ))
-                .path("/")
-                .expires(actix_web::cookie::time::OffsetDateTime::now_utc())
-                .finish(),
-        )
-        .cookie(
-            actix_web::cookie::Cookie::build(
-                fastn_core::auth::AuthProviders::TeleGram.as_str(),
-                "",
-            )
-            .domain(fastn_core::auth::utils::domain(
-                req.connection_info().host(),
-            ))
-            .path("/")
-            .expires(actix_web::cookie::time::OffsetDateTime::now_utc())
-            .finish(),
-        )
-        .cookie(
-            actix_web::cookie::Cookie::build(fastn_core::auth::AuthProviders::Slack.as_str(), "")
-                .domain(fastn_core::auth::utils::domain(
-                    req.connection_info().host(),
-                ))
-                .path("/")
-                .expires(actix_web::cookie::time::OffsetDateTime::now_utc())
-                .finish(),
-        )
-        .cookie(
-            actix_web::cookie::Cookie::build(fastn_core::auth::AuthProviders::Discord.as_str(), "")
-                .domain(fastn_core::auth::utils::domain(
-                    req.connection_info().host(),
-                ))
-                .path("/")
-                .expires(actix_web::cookie::time::OffsetDateTime::now_utc())
-                .finish(),
-        )
-        .cookie(
-            actix_web::cookie::Cookie::build(fastn_core::auth::AuthProviders::Google.as_str(), "")
-                .domain(fastn_core::auth::utils::domain(
-                    req.connection_info().host(),
-                ))
-                .path("/")
-                .expires(actix_web::cookie::time::OffsetDateTime::now_utc())
-                .finish(),
-        )
-        .cookie(
-            actix_web::cookie::Cookie::build(fastn_core::auth::AuthProviders::Amazon.as_str(), "")
-                .domain(fastn_core::auth::utils::domain(
-                    req.connection_info().host(),
-                ))
-                .path("/")
-                .expires(actix_web::cookie::time::OffsetDateTime::now_utc())
-                .finish(),
-        )
-        .cookie(
-            actix_web::cookie::Cookie::build(fastn_core::auth::AuthProviders::Apple.as_str(), "")
-                .domain(fastn_core::auth::utils::domain(
-                    req.connection_info().host(),
-                ))
-                .path("/")
-                .expires(actix_web::cookie::time::OffsetDateTime::now_utc())
-                .finish(),
-        )
-        .cookie(
-            actix_web::cookie::Cookie::build(fastn_core::auth::AuthProviders::Baidu.as_str(), "")
-                .domain(fastn_core::auth::utils::domain(
-                    req.connection_info().host(),
-                ))
-                .path("/")
-                .expires(actix_web::cookie::time::OffsetDateTime::now_utc())
-                .finish(),
-        )
-        .cookie(
-            actix_web::cookie::Cookie::build(
-                fastn_core::auth::AuthProviders::BitBucket.as_str(),
-                "",
-            )
-            .domain(fastn_core::auth::utils::domain(
-                req.connection_info().host(),
-            ))
-            .path("/")
-            .expires(actix_web::cookie::time::OffsetDateTime::now_utc())
-            .finish(),
-        )
-        .cookie(
-            actix_web::cookie::Cookie::build(
-                fastn_core::auth::AuthProviders::DigitalOcean.as_str(),
-                "",
-            )
-            .domain(fastn_core::auth::utils::domain(
-                req.connection_info().host(),
-            ))
-            .path("/")
-            .expires(actix_web::cookie::time::OffsetDateTime::now_utc())
-            .finish(),
-        )
-        .cookie(
-            actix_web::cookie::Cookie::build(
-                fastn_core::auth::AuthProviders::DoorKeeper.as_str(),
-                "",
-            )
-            .domain(fastn_core::auth::utils::domain(
-                req.connection_info().host(),
-            ))
-            .path("/")
-            .expires(actix_web::cookie::time::OffsetDateTime::now_utc())
-            .finish(),
-        )
-        .cookie(
-            actix_web::cookie::Cookie::build(fastn_core::auth::AuthProviders::DropBox.as_str(), "")
-                .domain(fastn_core::auth::utils::domain(
-                    req.connection_info().host(),
-                ))
-                .path("/")
-                .expires(actix_web::cookie::time::OffsetDateTime::now_utc())
-                .finish(),
-        )
-        .cookie(
-            actix_web::cookie::Cookie::build(
-                fastn_core::auth::AuthProviders::Facebook.as_str(),
-                "",
-            )
-            .domain(fastn_core::auth::utils::domain(
-                req.connection_info().host(),
-            ))
-            .path("/")
-            .expires(actix_web::cookie::time::OffsetDateTime::now_utc())
-            .finish(),
-        )
-        .cookie(
-            actix_web::cookie::Cookie::build(fastn_core::auth::AuthProviders::GitLab.as_str(), "")
-                .domain(fastn_core::auth::utils::domain(
-                    req.connection_info().host(),
-                ))
-                .path("/")
-                .expires(actix_web::cookie::time::OffsetDateTime::now_utc())
-                .finish(),
-        )
-        .cookie(
-            actix_web::cookie::Cookie::build(
-                fastn_core::auth::AuthProviders::Instagram.as_str(),
-                "",
-            )
-            .domain(fastn_core::auth::utils::domain(
-                req.connection_info().host(),
-            ))
-            .path("/")
-            .expires(actix_web::cookie::time::OffsetDateTime::now_utc())
-            .finish(),
-        )
-        .cookie(
-            actix_web::cookie::Cookie::build(
-                fastn_core::auth::AuthProviders::LinkedIn.as_str(),
-                "",
-            )
-            .domain(fastn_core::auth::utils::domain(
-                req.connection_info().host(),
-            ))
-            .path("/")
-            .expires(actix_web::cookie::time::OffsetDateTime::now_utc())
-            .finish(),
-        )
-        .cookie(
-            actix_web::cookie::Cookie::build(
-                fastn_core::auth::AuthProviders::Microsoft.as_str(),
-                "",
-            )
-            .domain(fastn_core::auth::utils::domain(
-                req.connection_info().host(),
-            ))
-            .path("/")
-            .expires(actix_web::cookie::time::OffsetDateTime::now_utc())
-            .finish(),
-        )
-        .cookie(
-            actix_web::cookie::Cookie::build(fastn_core::auth::AuthProviders::Okta.as_str(), "")
-                .domain(fastn_core::auth::utils::domain(
-                    req.connection_info().host(),
-                ))
-                .path("/")
-                .expires(actix_web::cookie::time::OffsetDateTime::now_utc())
-                .finish(),
-        )
-        .cookie(
-            actix_web::cookie::Cookie::build(
-                fastn_core::auth::AuthProviders::Pintrest.as_str(),
-                "",
-            )
-            .domain(fastn_core::auth::utils::domain(
-                req.connection_info().host(),
-            ))
-            .path("/")
-            .expires(actix_web::cookie::time::OffsetDateTime::now_utc())
-            .finish(),
-        )
-        .cookie(
-            actix_web::cookie::Cookie::build(fastn_core::auth::AuthProviders::TikTok.as_str(), "")
-                .domain(fastn_core::auth::utils::domain(
-                    req.connection_info().host(),
-                ))
-                .path("/")
-                .expires(actix_web::cookie::time::OffsetDateTime::now_utc())
-                .finish(),
-        )
-        .cookie(
-            actix_web::cookie::Cookie::build(fastn_core::auth::AuthProviders::Twitch.as_str(), "")
-                .domain(fastn_core::auth::utils::domain(
-                    req.connection_info().host(),
-                ))
-                .path("/")
-                .expires(actix_web::cookie::time::OffsetDateTime::now_utc())
-                .finish(),
-        )
-        .cookie(
-            actix_web::cookie::Cookie::build(fastn_core::auth::AuthProviders::Twitter.as_str(), "")
-                .domain(fastn_core::auth::utils::domain(
-                    req.connection_info().host(),
-                ))
-                .path("/")
-                .expires(actix_web::cookie::time::OffsetDateTime::now_utc())
-                .finish(),
-        )
-        .cookie(
-            actix_web::cookie::Cookie::build(fastn_core::auth::AuthProviders::WeChat.as_str(), "")
-                .domain(fastn_core::auth::utils::domain(
-                    req.connection_info().host(),
-                ))
-                .path("/")
-                .expires(actix_web::cookie::time::OffsetDateTime::now_utc())
-                .finish(),
-        )
-        .cookie(
-            actix_web::cookie::Cookie::build(fastn_core::auth::AuthProviders::Yahoo.as_str(), "")
-                .domain(fastn_core::auth::utils::domain(
-                    req.connection_info().host(),
-                ))
-                .path("/")
-                .expires(actix_web::cookie::time::OffsetDateTime::now_utc())
-                .finish(),
-        )
-        .cookie(
-            actix_web::cookie::Cookie::build(fastn_core::auth::AuthProviders::Zoho.as_str(), "")
+// route: POST /auth/logout-everywhere/
+// revokes every session of the users logged in from this browser, on all their devices
+pub fn logout_everywhere(
+    req: actix_web::HttpRequest,
+) -> fastn_core::Result<actix_web::HttpResponse> {
+    for (_, value) in fastn_core::auth::session::session_cookies(&req) {
+        if let Some(session) = fastn_core::auth::session::get(value.as_str())? {
+            fastn_core::auth::session::delete_all(session.user_key.as_str())?;
+        }
+    }
+    Ok(clear_cookies(&req))
+}
+
+fn clear_cookies(req: &actix_web::HttpRequest) -> actix_web::HttpResponse {
+    let mut response = actix_web::HttpResponse::Found();
+    for auth_provider in fastn_core::auth::AuthProviders::AUTH_ITER.iter() {
+        response.cookie(
+            actix_web::cookie::Cookie::build(auth_provider.as_str(), "")
                 .domain(fastn_core::auth::utils::domain(
                     req.connection_info().host(),
                 ))
                 .path("/")
                 .expires(actix_web::cookie::time::OffsetDateTime::now_utc())
                 .finish(),
-        )
+        );
+    }
+    response
         .append_header((actix_web::http::header::LOCATION, "/".to_string()))
-        .finish())
+        .finish()
 }
 
 // handle: if request.url starts with /auth/
@@ -338,6 +114,11 @@ pub async fn handle_auth(
         fastn_core::auth::discord::CALLBACK_URL => fastn_core::auth::discord::callback(req).await,
         fastn_core::auth::twitter::CALLBACK_URL => fastn_core::auth::twitter::callback(req).await,
         "/auth/logout/" => logout(req),
+        // it revokes sessions on other devices too, so a link or an image can not trigger it
+        "/auth/logout-everywhere/" if req.method() == actix_web::http::Method::POST => {
+            logout_everywhere(req)
+        }
+        "/auth/logout-everywhere/" => Ok(actix_web::HttpResponse::MethodNotAllowed().finish()),
         _ => Ok(actix_web::HttpResponse::new(
             actix_web::http::StatusCode::NOT_FOUND,
         )),
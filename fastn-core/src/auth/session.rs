@@ -0,0 +1,467 @@
+// Sessions of the logged-in users. The cookie of an auth provider carries a signed session id,
+// the user detail it stands for is stored in a local sqlite database, so a session can expire,
+// be rotated, and be revoked on the server.
+//
+// The user detail contains the oauth token of the provider, so the database is kept in the state
+// directory of the user, out of the package `fastn serve` serves files from.
+
+/// how long a session lives, in seconds, `FASTN_SESSION_TTL` overrides it
+pub const DEFAULT_SESSION_TTL: i64 = 30 * 24 * 60 * 60;
+
+/// a session in use is given a new id once it is older than this, in seconds
+pub const ROTATE_AFTER: i64 = 24 * 60 * 60;
+
+/// a rotated out session id keeps working for this long, in seconds, so requests already in
+/// flight with the old cookie are not logged out
+const ROTATION_GRACE: i64 = 60;
+
+static DB_PATH: once_cell::sync::OnceCell<camino::Utf8PathBuf> = once_cell::sync::OnceCell::new();
+
+static CONNECTION: once_cell::sync::Lazy<antidote::Mutex<Option<rusqlite::Connection>>> =
+    once_cell::sync::Lazy::new(|| antidote::Mutex::new(None));
+
+#[derive(Debug, Clone)]
+pub struct Session {
+    pub id: String,
+    pub provider: String,
+    /// `user_key` identifies the user across sessions, eg `github:amitu`
+    pub user_key: String,
+    /// `detail` is the json of the provider specific user detail, eg `github::UserDetail`
+    pub detail: String,
+    pub created_at: i64,
+    pub expires_at: i64,
+}
+
+/// `init()` keeps the sessions of the package at `root` in
+/// `<state dir>/fastn.com/sessions/<root>/sessions.sqlite`, the state dir being the per user one
+/// of the platform, like `~/.local/state` on linux. `FASTN_SESSION_DB` overrides the path.
+/// `fastn serve` calls it before it starts serving.
+pub fn init(root: &camino::Utf8Path) -> fastn_core::Result<()> {
+    let path = match std::env::var("FASTN_SESSION_DB") {
+        Ok(path) => camino::Utf8PathBuf::from(path),
+        Err(_) => default_db_path(root)?,
+    };
+    if let Some(existing) = DB_PATH.get() {
+        if existing.ne(&path) {
+            return fastn_core::Error::generic_err(format!(
+                "sessions are already kept in {}",
+                existing
+            ));
+        }
+        return Ok(());
+    }
+    // a concurrent `init()` with the same path is fine
+    let _ = DB_PATH.set(path);
+    Ok(())
+}
+
+fn default_db_path(root: &camino::Utf8Path) -> fastn_core::Result<camino::Utf8PathBuf> {
+    let state_dir = match dirs::state_dir().or_else(dirs::data_local_dir) {
+        Some(dir) => camino::Utf8PathBuf::from_path_buf(dir).map_err(|dir| {
+            fastn_core::Error::generic(format!("state directory {:?} is not utf-8", dir))
+        })?,
+        None => {
+            return fastn_core::Error::generic_err(
+                "no state directory to keep the sessions in, set FASTN_SESSION_DB",
+            )
+        }
+    };
+    Ok(state_dir
+        .join("fastn.com")
+        .join("sessions")
+        .join(fastn_core::utils::id_to_cache_key(root.as_str()))
+        .join("sessions.sqlite"))
+}
+
+pub fn ttl() -> i64 {
+    std::env::var("FASTN_SESSION_TTL")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_SESSION_TTL)
+}
+
+fn now() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default()
+}
+
+fn with_connection<T>(
+    f: impl FnOnce(&rusqlite::Connection) -> rusqlite::Result<T>,
+) -> fastn_core::Result<T> {
+    let mut conn = CONNECTION.lock();
+    if conn.is_none() {
+        let path = match DB_PATH.get() {
+            Some(path) => path,
+            None => {
+                return fastn_core::Error::generic_err(
+                    "sessions are not initialised, they are only available in `fastn serve`",
+                )
+            }
+        };
+        if let Some(parent) = path.parent() {
+            if !parent.as_str().is_empty() {
+                std::fs::create_dir_all(parent)?;
+            }
+        }
+        let c = rusqlite::Connection::open(path)?;
+        c.execute_batch(
+            r#"
+            CREATE TABLE IF NOT EXISTS fastn_session (
+                id          TEXT PRIMARY KEY,
+                provider    TEXT NOT NULL,
+                user_key    TEXT NOT NULL,
+                detail      TEXT NOT NULL,
+                created_at  INTEGER NOT NULL,
+                expires_at  INTEGER NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS fastn_session_user_key ON fastn_session (user_key);
+            "#,
+        )?;
+        *conn = Some(c);
+    }
+    Ok(f(conn.as_ref().unwrap())?)
+}
+
+fn new_id() -> String {
+    rand::random::<[u8; 32]>()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+fn signature(id: &str) -> fastn_core::Result<String> {
+    use hmac::Mac;
+
+    let secret = fastn_core::auth::secret_key()?;
+    let mut mac = hmac::Hmac::<sha2::Sha256>::new_from_slice(secret.as_bytes())
+        .map_err(|e| fastn_core::Error::generic(e.to_string()))?;
+    mac.update(id.as_bytes());
+    Ok(format!("{:x}", mac.finalize().into_bytes()))
+}
+
+/// `sign()` is the cookie value for the session `id`: `<id>.<hmac of id>`
+pub fn sign(id: &str) -> fastn_core::Result<String> {
+    Ok(format!("{}.{}", id, signature(id)?))
+}
+
+/// `verify()` returns the session id of a signed cookie value, if the signature matches
+pub fn verify(value: &str) -> fastn_core::Result<Option<String>> {
+    let (id, sig) = match value.split_once('.') {
+        Some(v) => v,
+        None => return Ok(None),
+    };
+    let expected = signature(id)?;
+    // compared in constant time so the signature can not be guessed byte by byte
+    let matches = expected.len() == sig.len()
+        && expected
+            .bytes()
+            .zip(sig.bytes())
+            .fold(0, |acc, (a, b)| acc | (a ^ b))
+            == 0;
+    Ok(matches.then(|| id.to_string()))
+}
+
+fn insert(
+    c: &rusqlite::Connection,
+    id: &str,
+    provider: &str,
+    user_key: &str,
+    detail: &str,
+    now: i64,
+) -> rusqlite::Result<usize> {
+    c.execute(
+        "INSERT INTO fastn_session (id, provider, user_key, detail, created_at, expires_at) \
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        rusqlite::params![id, provider, user_key, detail, now, now + ttl()],
+    )
+}
+
+/// `create()` stores a new session for the user and returns the signed cookie value for it
+pub fn create(
+    provider: &fastn_core::auth::AuthProviders,
+    user_key: &str,
+    detail: &str,
+) -> fastn_core::Result<String> {
+    create_at(provider, user_key, detail, now())
+}
+
+fn create_at(
+    provider: &fastn_core::auth::AuthProviders,
+    user_key: &str,
+    detail: &str,
+    now: i64,
+) -> fastn_core::Result<String> {
+    let id = new_id();
+    with_connection(|c| {
+        // expired sessions are cleaned up whenever somebody logs in
+        c.execute("DELETE FROM fastn_session WHERE expires_at <= ?1", [now])?;
+        insert(c, id.as_str(), provider.as_str(), user_key, detail, now)
+    })?;
+    sign(id.as_str())
+}
+
+/// `get()` returns the live session for a signed cookie value
+pub fn get(value: &str) -> fastn_core::Result<Option<Session>> {
+    get_at(value, now())
+}
+
+fn get_at(value: &str, now: i64) -> fastn_core::Result<Option<Session>> {
+    let id = match verify(value)? {
+        Some(id) => id,
+        None => return Ok(None),
+    };
+    with_connection(|c| {
+        let mut stmt = c.prepare(
+            "SELECT id, provider, user_key, detail, created_at, expires_at FROM fastn_session \
+             WHERE id = ?1 AND expires_at > ?2",
+        )?;
+        let mut rows = stmt.query(rusqlite::params![id, now])?;
+        match rows.next()? {
+            Some(row) => Ok(Some(Session {
+                id: row.get(0)?,
+                provider: row.get(1)?,
+                user_key: row.get(2)?,
+                detail: row.get(3)?,
+                created_at: row.get(4)?,
+                expires_at: row.get(5)?,
+            })),
+            None => Ok(None),
+        }
+    })
+}
+
+/// `user_detail()` is the user detail json of the session of a signed cookie value
+pub fn user_detail(value: &str) -> fastn_core::Result<Option<String>> {
+    Ok(get(value)?.map(|s| s.detail))
+}
+
+/// `rotate()` moves a session older than `ROTATE_AFTER` to a new id, and returns the signed
+/// cookie value of the new id. The old id stays valid for `ROTATION_GRACE` seconds.
+pub fn rotate(value: &str) -> fastn_core::Result<Option<String>> {
+    rotate_at(value, now())
+}
+
+fn rotate_at(value: &str, now: i64) -> fastn_core::Result<Option<String>> {
+    let session = match get_at(value, now)? {
+        // a session already rotated out is only living out its grace period
+        Some(s) if now - s.created_at >= ROTATE_AFTER && s.expires_at > now + ROTATION_GRACE => s,
+        _ => return Ok(None),
+    };
+    let id = new_id();
+    with_connection(|c| {
+        insert(
+            c,
+            id.as_str(),
+            session.provider.as_str(),
+            session.user_key.as_str(),
+            session.detail.as_str(),
+            now,
+        )?;
+        c.execute(
+            "UPDATE fastn_session SET expires_at = MIN(expires_at, ?1) WHERE id = ?2",
+            rusqlite::params![now + ROTATION_GRACE, session.id],
+        )
+    })?;
+    Ok(Some(sign(id.as_str())?))
+}
+
+/// `delete()` revokes the session of a signed cookie value
+pub fn delete(value: &str) -> fastn_core::Result<()> {
+    if let Some(id) = verify(value)? {
+        with_connection(|c| c.execute("DELETE FROM fastn_session WHERE id = ?1", [id]))?;
+    }
+    Ok(())
+}
+
+/// `delete_all()` revokes every session of the user, on every device, returns how many were
+/// revoked
+pub fn delete_all(user_key: &str) -> fastn_core::Result<usize> {
+    with_connection(|c| c.execute("DELETE FROM fastn_session WHERE user_key = ?1", [user_key]))
+}
+
+/// `cookie()` builds the cookie of an auth provider carrying a signed session value, it is only
+/// sent back over https if the request it is set for came over https
+pub fn cookie<'a>(
+    provider: &fastn_core::auth::AuthProviders,
+    value: String,
+    connection_info: &actix_web::dev::ConnectionInfo,
+) -> actix_web::cookie::Cookie<'a> {
+    actix_web::cookie::Cookie::build(provider.as_str(), value)
+        .domain(fastn_core::auth::utils::domain(connection_info.host()))
+        .path("/")
+        .http_only(true)
+        // not sent with cross site posts, so other sites can not act on the session
+        .same_site(actix_web::cookie::SameSite::Lax)
+        .secure(connection_info.scheme().eq("https"))
+        .max_age(actix_web::cookie::time::Duration::seconds(ttl()))
+        .finish()
+}
+
+/// `session_cookies()` are the auth provider cookies of the request, as (provider, value)
+pub fn session_cookies(
+    req: &actix_web::HttpRequest,
+) -> Vec<(fastn_core::auth::AuthProviders, String)> {
+    fastn_core::auth::AuthProviders::AUTH_ITER
+        .into_iter()
+        .filter_map(|p| req.cookie(p.as_str()).map(|c| (p, c.value().to_string())))
+        .collect()
+}
+
+/// `rotate_cookies()` rotates the sessions of `cookies` that are due, and sets the cookies of
+/// the new ids on the response
+pub fn rotate_cookies(
+    response: &mut fastn_core::http::Response,
+    cookies: &[(fastn_core::auth::AuthProviders, String)],
+    connection_info: &actix_web::dev::ConnectionInfo,
+) -> fastn_core::Result<()> {
+    for (provider, value) in cookies {
+        if let Some(new_value) = rotate(value)? {
+            response
+                .add_cookie(&cookie(provider, new_value, connection_info))
+                .map_err(|e| fastn_core::Error::generic(e.to_string()))?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use fastn_core::auth::AuthProviders;
+
+    // all the tests share one database, each uses its own users
+    fn init() {
+        let path = camino::Utf8PathBuf::from_path_buf(std::env::temp_dir())
+            .unwrap()
+            .join(format!("fastn-sessions-test-{}", std::process::id()))
+            .join("sessions.sqlite");
+        let _ = super::DB_PATH.set(path);
+    }
+
+    #[test]
+    fn sessions_are_kept_out_of_the_package() {
+        let root = camino::Utf8PathBuf::from("/home/amitu/site");
+        if let Ok(path) = super::default_db_path(&root) {
+            assert!(!path.starts_with(&root));
+            assert!(path.ends_with("_home_amitu_site/sessions.sqlite"));
+        }
+    }
+
+    #[test]
+    fn sign_and_verify() {
+        let signed = super::sign("abc").unwrap();
+        assert_eq!(
+            super::verify(signed.as_str()).unwrap(),
+            Some("abc".to_string())
+        );
+        assert_eq!(super::verify("abc").unwrap(), None);
+        assert_eq!(super::verify("abd.0000").unwrap(), None);
+
+        let (_, sig) = signed.split_once('.').unwrap();
+        let forged = format!("abd.{}", sig);
+        assert_eq!(super::verify(forged.as_str()).unwrap(), None);
+    }
+
+    #[test]
+    fn cookies_are_secure_over_https() {
+        for (proto, secure) in [("https", Some(true)), ("http", Some(false))] {
+            let req = actix_web::test::TestRequest::default()
+                .insert_header(("x-forwarded-proto", proto))
+                .to_http_request();
+            let cookie = super::cookie(
+                &AuthProviders::GitHub,
+                "abc".to_string(),
+                &req.connection_info(),
+            );
+            assert_eq!(cookie.secure(), secure, "{}", proto);
+        }
+    }
+
+    #[test]
+    fn sessions_expire() {
+        init();
+        let now = super::now();
+        let live = super::create(&AuthProviders::GitHub, "github:live", "{}").unwrap();
+        let expired = super::create_at(
+            &AuthProviders::GitHub,
+            "github:expired",
+            "{}",
+            now - super::ttl() - 1,
+        )
+        .unwrap();
+
+        let session = super::get(live.as_str()).unwrap().unwrap();
+        assert_eq!(session.user_key, "github:live");
+        assert_eq!(
+            super::user_detail(live.as_str()).unwrap(),
+            Some("{}".to_string())
+        );
+        assert!(super::get(expired.as_str()).unwrap().is_none());
+        assert!(super::get_at(live.as_str(), now + super::ttl() + 1)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn rotate_keeps_old_id_for_grace_period() {
+        init();
+        let now = super::now();
+        let fresh = super::create(&AuthProviders::GitHub, "github:fresh", "{}").unwrap();
+        assert_eq!(super::rotate(fresh.as_str()).unwrap(), None);
+
+        let old = super::create_at(
+            &AuthProviders::GitHub,
+            "github:rotated",
+            "{\"a\":1}",
+            now - super::ROTATE_AFTER,
+        )
+        .unwrap();
+        let new = super::rotate_at(old.as_str(), now).unwrap().unwrap();
+        assert_ne!(old, new);
+
+        let session = super::get_at(new.as_str(), now).unwrap().unwrap();
+        assert_eq!(session.user_key, "github:rotated");
+        assert_eq!(session.detail, "{\"a\":1}");
+
+        // the old id keeps working for a little while, but is not rotated again
+        assert!(super::get_at(old.as_str(), now).unwrap().is_some());
+        assert_eq!(super::rotate_at(old.as_str(), now).unwrap(), None);
+
+        let later = now + super::ROTATION_GRACE + 1;
+        assert!(super::get_at(old.as_str(), later).unwrap().is_none());
+        assert!(super::get_at(new.as_str(), later).unwrap().is_some());
+    }
+
+    #[test]
+    fn delete_all_revokes_every_session_of_the_user() {
+        init();
+        let a1 = super::create(&AuthProviders::GitHub, "github:everywhere", "{}").unwrap();
+        let a2 = super::create(&AuthProviders::GitHub, "github:everywhere", "{}").unwrap();
+        let b = super::create(&AuthProviders::Discord, "discord:someone-else", "{}").unwrap();
+
+        assert_eq!(super::delete_all("github:everywhere").unwrap(), 2);
+        assert!(super::get(a1.as_str()).unwrap().is_none());
+        assert!(super::get(a2.as_str()).unwrap().is_none());
+        assert!(super::get(b.as_str()).unwrap().is_some());
+
+        super::delete(b.as_str()).unwrap();
+        assert!(super::get(b.as_str()).unwrap().is_none());
+    }
+
+    #[test]
+    fn remote_mode_requires_secret() {
+        use fastn_core::auth::resolve_secret_key;
+
+        assert!(resolve_secret_key(None, true).is_err());
+        assert!(resolve_secret_key(Some(String::new()), true).is_err());
+        assert_eq!(
+            resolve_secret_key(Some("s3cr3t".to_string()), true).unwrap(),
+            "s3cr3t"
+        );
+        assert_eq!(
+            resolve_secret_key(None, false).unwrap(),
+            "FASTN_TEMP_SECRET"
+        );
+    }
+}
@@ -57,20 +57,18 @@ pub async fn token(req: actix_web::HttpRequest) -> fastn_core::Result<actix_web:
         user_name: query.username,
     };
     let user_detail_str = serde_json::to_string(&user_detail_obj)?;
+    let session = fastn_core::auth::session::create(
+        &fastn_core::auth::AuthProviders::TeleGram,
+        format!("telegram:{}", user_detail_obj.user_id).as_str(),
+        user_detail_str.as_str(),
+    )?;
+    let cookie = fastn_core::auth::session::cookie(
+        &fastn_core::auth::AuthProviders::TeleGram,
+        session,
+        &req.connection_info(),
+    );
     return Ok(actix_web::HttpResponse::Found()
-        .cookie(
-            actix_web::cookie::Cookie::build(
-                fastn_core::auth::AuthProviders::TeleGram.as_str(),
-                fastn_core::auth::utils::encrypt_str(&user_detail_str).await,
-            )
-            .domain(fastn_core::auth::utils::domain(
-                req.connection_info().host(),
-            ))
-            .path("/")
-            .permanent()
-            .secure(true)
-            .finish(),
-        )
+        .cookie(cookie)
         .append_header((actix_web::http::header::LOCATION, "/".to_string()))
         .finish());
 }
@@ -6,6 +6,33 @@ pub async fn build(
     ignore_failed: bool,
     test: bool,
     check_build: bool,
+    jobs: Option<usize>,
+) -> fastn_core::Result<()> {
+    // the files downloaded while documents are built, by any of the workers, are written to
+    // FASTN.lock once the build is done
+    fastn_core::package::lock::hold(&config.root)?;
+    let result = build_(
+        config,
+        only_id,
+        base_url,
+        ignore_failed,
+        test,
+        check_build,
+        jobs,
+    )
+    .await;
+    fastn_core::package::lock::release(&config.root)?;
+    result
+}
+
+async fn build_(
+    config: &mut fastn_core::Config,
+    only_id: Option<&str>,
+    base_url: &str,
+    ignore_failed: bool,
+    test: bool,
+    check_build: bool,
+    jobs: Option<usize>,
 ) -> fastn_core::Result<()> {
     tokio::fs::create_dir_all(config.build_dir()).await?;
 
@@ -22,10 +49,22 @@ pub async fn build(
 
         match only_id {
             Some(id) => {
-                return handle_only_id(id, config, base_url, ignore_failed, test, documents).await
+                handle_only_id(id, config, base_url, ignore_failed, test, documents).await?;
+                // the pages pre-rendered from the document are rebuilt with it
+                return build_dynamic_urls(config, base_url, ignore_failed, test, Some(id)).await;
             }
             None => {
-                incremental_build(config, &documents, base_url, ignore_failed, test).await?;
+                let jobs = match jobs {
+                    Some(jobs) => jobs.max(1),
+                    // the output does not depend on the number of jobs, tests use a fixed number
+                    // so they build concurrently on every machine
+                    None if fastn_core::utils::is_test() => 4,
+                    None => std::thread::available_parallelism()
+                        .map(|v| v.get())
+                        .unwrap_or(1),
+                };
+                incremental_build(config, &documents, base_url, ignore_failed, test, jobs).await?;
+                build_dynamic_urls(config, base_url, ignore_failed, test, None).await?;
             }
         }
     }
@@ -125,7 +164,7 @@ mod cache {
         Ok((cache_hit, v))
     }
 
-    #[derive(serde::Serialize, serde::Deserialize, Debug)]
+    #[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
     pub(crate) struct Cache {
         // fastn_version: String, // TODO
         #[serde(skip)]
@@ -196,20 +235,86 @@ fn is_virtual_dep(path: &str) -> bool {
         || path.ends_with("/-/assets.ftd")
 }
 
-async fn handle_dependency_file(
+// documents whose id, with or without the package name, is `name_without_package_name`
+fn find_documents<'a>(
+    documents: &'a std::collections::BTreeMap<String, fastn_core::File>,
+    name_without_package_name: &'a str,
+) -> impl Iterator<Item = &'a fastn_core::File> {
+    documents.values().filter(move |document| {
+        remove_extension(document.get_id()).eq(name_without_package_name)
+            || remove_extension(&document.get_id_with_package()).eq(name_without_package_name)
+    })
+}
+
+/// `build_order()` groups documents into waves using the dependencies recorded in `fastn.cache`.
+/// A document comes in a later wave than every document it depends on, so documents of a wave
+/// do not depend on each other and can be built concurrently. Waves, and the ids in a wave, are
+/// sorted so the order does not change from one build to the next.
+///
+/// Dependency cycles are broken at the dependency that closes the cycle, documents not present in
+/// the cache have no known dependencies and go in the first wave.
+fn build_order(package_name: &str, c: &cache::Cache, ids: Vec<String>) -> Vec<Vec<String>> {
+    let mut levels: std::collections::BTreeMap<String, usize> = Default::default();
+    for id in ids.iter() {
+        dependency_level(package_name, c, id, &mut levels, &mut vec![]);
+    }
+
+    let mut waves: Vec<Vec<String>> = vec![];
+    for (id, level) in levels {
+        if waves.len() <= level {
+            waves.resize(level + 1, vec![]);
+        }
+        waves[level].push(id);
+    }
+    waves
+}
+
+fn dependency_level(
+    package_name: &str,
+    c: &cache::Cache,
+    id: &str,
+    levels: &mut std::collections::BTreeMap<String, usize>,
+    visiting: &mut Vec<String>,
+) -> usize {
+    if let Some(level) = levels.get(id) {
+        return *level;
+    }
+
+    visiting.push(id.to_string());
+    let mut level = 0;
+    if let Some(doc) = c.documents.get(id) {
+        for dep in doc.dependencies.iter() {
+            let dep = get_dependency_name_without_package_name(package_name, dep);
+            if is_virtual_dep(&dep) || visiting.contains(&dep) {
+                continue;
+            }
+            level = level.max(dependency_level(package_name, c, &dep, levels, visiting) + 1);
+        }
+    }
+    visiting.pop();
+
+    levels.insert(id.to_string(), level);
+    level
+}
+
+/// `build_wave()` builds `documents` on `jobs` worker threads. Each worker has its own copy of
+/// the config and the cache, since documents are rendered with per document state in both.
+/// Once all workers are done their cache entries, and the packages they resolved, are merged back.
+/// The lock is shared by all the copies of the config, so there is nothing to merge for it.
+///
+/// Workers capture what they print for each document, it is printed in the order of the
+/// documents once the wave is built, so the output is the same as that of a serial build.
+async fn build_wave(
     config: &mut fastn_core::Config,
-    cache: &mut cache::Cache,
-    documents: &std::collections::BTreeMap<String, fastn_core::File>,
+    c: &mut cache::Cache,
+    documents: Vec<&fastn_core::File>,
     base_url: &str,
     ignore_failed: bool,
     test: bool,
-    name_without_package_name: String,
+    jobs: usize,
 ) -> fastn_core::Result<()> {
-    for document in documents.values() {
-        if remove_extension(document.get_id()).eq(name_without_package_name.as_str())
-            || remove_extension(&document.get_id_with_package())
-                .eq(name_without_package_name.as_str())
-        {
+    if jobs <= 1 || documents.len() <= 1 {
+        for document in documents {
             handle_file(
                 document,
                 config,
@@ -217,13 +322,118 @@ async fn handle_dependency_file(
                 ignore_failed,
                 test,
                 true,
-                Some(cache),
+                Some(c),
             )
             .await?;
         }
+        return Ok(());
     }
 
-    Ok(())
+    let next = std::sync::atomic::AtomicUsize::new(0);
+    // once a document fails the documents after it are not started, as in a serial build
+    let failed = std::sync::atomic::AtomicBool::new(false);
+    let workers = tokio::task::block_in_place(|| {
+        std::thread::scope(|s| {
+            let handles: Vec<_> = (0..jobs.min(documents.len()))
+                .map(|_| {
+                    let mut config = config.clone();
+                    let mut c = c.clone();
+                    let (documents, next, failed) = (&documents, &next, &failed);
+                    s.spawn(move || {
+                        let mut built = vec![];
+                        let runtime = match tokio::runtime::Builder::new_current_thread()
+                            .enable_all()
+                            .build()
+                        {
+                            Ok(runtime) => runtime,
+                            Err(e) => return (config, c, built, Err((0, e.into()))),
+                        };
+                        let result: Result<(), (usize, fastn_core::Error)> =
+                            runtime.block_on(async {
+                                loop {
+                                    if failed.load(std::sync::atomic::Ordering::Relaxed) {
+                                        return Ok(());
+                                    }
+                                    let i = next.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                                    let document = match documents.get(i) {
+                                        Some(document) => *document,
+                                        None => return Ok(()),
+                                    };
+                                    fastn_core::utils::capture_output();
+                                    let result = handle_file(
+                                        document,
+                                        &mut config,
+                                        base_url,
+                                        ignore_failed,
+                                        test,
+                                        true,
+                                        Some(&mut c),
+                                    )
+                                    .await;
+                                    built.push((i, fastn_core::utils::captured_output()));
+                                    if let Err(e) = result {
+                                        failed.store(true, std::sync::atomic::Ordering::Relaxed);
+                                        return Err((i, e));
+                                    }
+                                }
+                            });
+                        (config, c, built, result)
+                    })
+                })
+                .collect();
+
+            handles
+                .into_iter()
+                .map(|h| h.join().expect("build worker panicked"))
+                .collect::<Vec<_>>()
+        })
+    });
+
+    let mut errors = vec![];
+    let mut outputs = vec![];
+    for (worker_config, worker_cache, built, result) in workers {
+        for (i, output) in built {
+            let id = remove_extension(documents[i].get_id());
+            if let Some(doc) = worker_cache.documents.get(&id) {
+                c.documents.insert(id.clone(), doc.clone());
+            }
+            if let Some(checksum) = worker_cache.file_checksum.get(&id) {
+                c.file_checksum.insert(id, checksum.clone());
+            }
+            outputs.push((i, output));
+        }
+        c.ftd_cache.extend(worker_cache.ftd_cache);
+        config
+            .all_packages
+            .borrow_mut()
+            .extend(worker_config.all_packages.into_inner());
+        config
+            .downloaded_assets
+            .extend(worker_config.downloaded_assets);
+        if let Err(e) = result {
+            errors.push(e);
+        }
+    }
+
+    // report the failure of the first document, in document order, so the error does not
+    // depend on how documents were scheduled. The documents after it are not printed, a serial
+    // build would have stopped before them.
+    let error = errors.into_iter().min_by_key(|(i, _)| *i);
+    let last = error.as_ref().map(|(i, _)| *i).unwrap_or(usize::MAX);
+    outputs.sort_by_key(|(i, _)| *i);
+    for (_, output) in outputs.into_iter().take_while(|(i, _)| *i <= last) {
+        for (stderr, text) in output {
+            if stderr {
+                fastn_core::utils::print_err(text);
+            } else {
+                fastn_core::utils::print_out(text);
+            }
+        }
+    }
+    match error {
+        Some((_, e)) => Err(e),
+        None => Ok(()),
+    }
 }
 
 // removes deleted documents from cache and build folder
@@ -283,124 +493,54 @@ async fn incremental_build(
     base_url: &str,
     ignore_failed: bool,
     test: bool,
+    jobs: usize,
 ) -> fastn_core::Result<()> {
     // https://fastn.com/rfc/incremental-build/
     use itertools::Itertools;
 
     let (cache_hit, mut c) = cache::get()?;
 
+    // dependencies are resolved, and FASTN.lock updated, before documents are built concurrently
+    config.resolve_all_dependencies().await?;
+
     if cache_hit {
-        let mut unresolved_dependencies: Vec<String> = documents
-            .iter()
-            .filter(|(_, f)| f.is_ftd())
-            .map(|(_, f)| remove_extension(f.get_id()))
+        let ids = documents
+            .values()
+            .filter(|f| f.is_ftd())
+            .map(|f| remove_extension(f.get_id()))
             .collect_vec();
-        let mut resolved_dependencies: Vec<String> = vec![];
-        let mut resolving_dependencies: Vec<String> = vec![];
-
-        while let Some(unresolved_dependency) = unresolved_dependencies.pop() {
-            // println!("Current UR: {}", unresolved_dependency.as_str());
-            if let Some(doc) = c.documents.get(unresolved_dependency.as_str()) {
-                // println!(
-                //     "[INCREMENTAL BUILD][CACHE FOUND] Processing: {}",
-                //     &unresolved_dependency
-                // );
-
-                let mut own_resolved_dependencies: Vec<String> = vec![];
-
-                let dependencies: Vec<String> = doc
-                    .dependencies
-                    .iter()
-                    .map(|dep| get_dependency_name_without_package_name(&config.package.name, dep))
-                    .collect_vec();
-
-                for dep in &dependencies {
-                    if resolved_dependencies.contains(dep) || dep.eq(&unresolved_dependency) {
-                        own_resolved_dependencies.push(dep.to_string());
-                        continue;
-                    }
-                    unresolved_dependencies.push(dep.to_string());
-                }
-
-                // println!(
-                //     "[INCREMENTAL] [R]: {} [RV]: {} [UR]: {} [ORD]: {}",
-                //     &resolved_dependencies.len(),
-                //     &resolving_dependencies.len(),
-                //     &unresolved_dependencies.len(),
-                //     own_resolved_dependencies.len(),
-                // );
-
-                if own_resolved_dependencies.eq(&dependencies) {
-                    handle_dependency_file(
-                        config,
-                        &mut c,
-                        documents,
-                        base_url,
-                        ignore_failed,
-                        test,
-                        unresolved_dependency.to_string(),
-                    )
-                    .await?;
-
-                    resolved_dependencies.push(unresolved_dependency.to_string());
-                    if unresolved_dependencies.is_empty() {
-                        if let Some(resolving_dependency) = resolving_dependencies.pop() {
-                            if resolving_dependency.eq(&unresolved_dependency.as_str()) {
-                                // println!("[INCREMENTAL][CIRCULAR]: {}", &unresolved_dependency);
-                                continue;
-                            }
-                            unresolved_dependencies.push(resolving_dependency);
-                        }
-                    }
-                } else {
-                    // println!("Adding to RD: {}", unresolved_dependency.as_str());
-                    resolving_dependencies.push(unresolved_dependency.to_string());
-                }
-            } else {
-                if is_virtual_dep(&unresolved_dependency) {
-                    resolved_dependencies.push(unresolved_dependency.clone());
-                } else {
-                    // println!("Not found in cache UR: {}", unresolved_dependency.as_str());
-
-                    handle_dependency_file(
-                        config,
-                        &mut c,
-                        documents,
-                        base_url,
-                        ignore_failed,
-                        test,
-                        unresolved_dependency.to_string(),
-                    )
-                    .await?;
 
-                    resolved_dependencies.push(unresolved_dependency.clone());
-                }
-                if unresolved_dependencies.is_empty() {
-                    if let Some(resolving_dependency) = resolving_dependencies.pop() {
-                        if resolving_dependency.eq(&unresolved_dependency.as_str()) {
-                            // println!("[INCREMENTAL][CIRCULAR]: {}", &unresolved_dependency);
-                            continue;
-                        }
-                        unresolved_dependencies.push(resolving_dependency);
-                    }
-                }
-            }
-        }
-
-        remove_deleted_documents(config, &mut c, documents)?;
-    } else {
-        for document in documents.values() {
-            handle_file(
-                document,
+        let mut built: std::collections::HashSet<String> = Default::default();
+        for wave in build_order(&config.package.name, &c, ids) {
+            let wave_documents = wave
+                .iter()
+                .flat_map(|id| find_documents(documents, id))
+                .filter(|document| built.insert(document.get_id().to_string()))
+                .collect_vec();
+            build_wave(
                 config,
+                &mut c,
+                wave_documents,
                 base_url,
                 ignore_failed,
                 test,
-                true,
-                Some(&mut c),
+                jobs,
             )
             .await?;
         }
+
+        remove_deleted_documents(config, &mut c, documents)?;
+    } else {
+        build_wave(
+            config,
+            &mut c,
+            documents.values().collect_vec(),
+            base_url,
+            ignore_failed,
+            test,
+            jobs,
+        )
+        .await?;
     }
 
     c.cache_it()?;
@@ -430,6 +570,157 @@ async fn handle_only_id(
     )))
 }
 
+/// Renders the dynamic urls that declare where the values of their params come from, one
+/// `<path>/index.html` for each of these values, the way `fastn serve` renders them on request.
+/// With `only_id` only the urls rendered by that document are built.
+async fn build_dynamic_urls(
+    config: &mut fastn_core::Config,
+    base_url: &str,
+    ignore_failed: bool,
+    test: bool,
+    only_id: Option<&str>,
+) -> fastn_core::Result<()> {
+    let mut pre_rendered_urls = match config.package.dynamic_urls {
+        Some(ref dynamic_urls) => dynamic_urls.pre_rendered_urls()?,
+        None => return Ok(()),
+    };
+    if let Some(id) = only_id {
+        let id = remove_extension(id);
+        pre_rendered_urls.retain(|url| {
+            let document = remove_extension(url.document.trim_start_matches('/'));
+            document.eq(&id) || format!("{}/{}", config.package.name, document).eq(&id)
+        });
+    }
+
+    for url in pre_rendered_urls {
+        let document = match fastn_core::get_file(
+            config.package.name.to_string(),
+            &config.root.join(url.document.trim_start_matches('/')),
+            &config.root,
+        )
+        .await?
+        {
+            fastn_core::File::Ftd(doc) => doc,
+            _ => {
+                return Err(fastn_core::Error::UsageError {
+                    message: format!(
+                        "dynamic url `{}`: `{}` is not an ftd document",
+                        url.url, url.document
+                    ),
+                })
+            }
+        };
+
+        let rows = url.rows(dynamic_url_params(config, &url).await?)?;
+        for row in rows.iter() {
+            let (path, named_parameters) = url.path(row)?;
+            let file_path = match path.trim_matches('/') {
+                "" => "index.html".to_string(),
+                path => format!("{}/index.html", path),
+            };
+
+            let start = std::time::Instant::now();
+            fastn_core::utils::print_start(format!("{}{}", config.package.name, path).as_str());
+
+            config.current_document = Some(document.id.to_string());
+            config.dependencies_during_render = vec![];
+            config.named_parameters = named_parameters;
+            config.extra_data = url.extra_data.clone();
+            let resp = fastn_core::package::package_doc::process_ftd(
+                config,
+                &document,
+                base_url,
+                true,
+                test,
+                file_path.as_str(),
+            )
+            .await;
+            config.named_parameters = vec![];
+            config.extra_data = Default::default();
+
+            match resp {
+                Ok(_) => fastn_core::utils::print_end(
+                    format!("Processed {}{}", config.package.name, path).as_str(),
+                    start,
+                ),
+                Err(e) => {
+                    fastn_core::utils::print_error(
+                        format!("Failed {}{}", config.package.name, path).as_str(),
+                        start,
+                    );
+                    print_diagnostic(config, &fastn_core::File::Ftd(document.clone()), &e);
+                    if !ignore_failed {
+                        return Err(e);
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// The list of params of `url`, read from its `ParamsSource`.
+async fn dynamic_url_params(
+    config: &fastn_core::Config,
+    url: &fastn_core::sitemap::dynamic_urls::PreRenderedUrl,
+) -> fastn_core::Result<serde_json::Value> {
+    match url.source {
+        fastn_core::sitemap::dynamic_urls::ParamsSource::Query { ref db, ref query } => {
+            let (columns, rows) =
+                fastn_core::library2022::processor::sqlite::execute_query_with_columns(
+                    &config.root.join(db),
+                    query,
+                    "FASTN.ftd",
+                    0,
+                )
+                .await?;
+            Ok(serde_json::Value::Array(
+                rows.into_iter()
+                    .map(|row| {
+                        serde_json::Value::Object(columns.iter().cloned().zip(row).collect())
+                    })
+                    .collect(),
+            ))
+        }
+        fastn_core::sitemap::dynamic_urls::ParamsSource::Json(ref path) => {
+            let content = tokio::fs::read(config.root.join(path)).await?;
+            Ok(serde_json::from_slice(content.as_slice())?)
+        }
+        fastn_core::sitemap::dynamic_urls::ParamsSource::Variable {
+            ref module,
+            ref name,
+        } => {
+            let doc = match fastn_core::get_file(
+                config.package.name.to_string(),
+                &config.root.join(format!("{}.ftd", module)),
+                &config.root,
+            )
+            .await?
+            {
+                fastn_core::File::Ftd(doc) => doc,
+                _ => {
+                    return Err(fastn_core::Error::UsageError {
+                        message: format!(
+                            "dynamic url `{}`: `{}` is not an ftd document",
+                            url.url, module
+                        ),
+                    })
+                }
+            };
+            let document = fastn_core::commands::check::interpret(config, &doc).await?;
+            let name = format!("{}#{}", document.name, name);
+            let value = document.tdoc().get_value(0, name.as_str())?;
+            value.to_serde_value().ok_or_else(|| {
+                fastn_core::Error::GenericError(format!(
+                    "dynamic url `{}`: `{}` can not be converted to json",
+                    url.url, name
+                ))
+            })
+        }
+    }
+}
+
 async fn handle_file(
     document: &fastn_core::File,
     config: &mut fastn_core::Config,
@@ -440,7 +731,7 @@ async fn handle_file(
     cache: Option<&mut cache::Cache>,
 ) -> fastn_core::Result<()> {
     let start = std::time::Instant::now();
-    print!("Processing {} ... ", document.get_id_with_package());
+    fastn_core::utils::print_start(document.get_id_with_package().as_str());
 
     let process_status = handle_file_(
         document,
@@ -463,7 +754,7 @@ async fn handle_file(
             start,
         );
     }
-    if process_status.is_err() {
+    if let Err(ref e) = process_status {
         fastn_core::utils::print_error(
             format!(
                 "Failed {}/{}",
@@ -473,11 +764,56 @@ async fn handle_file(
             .as_str(),
             start,
         );
+        print_diagnostic(config, document, e);
         return process_status;
     }
     Ok(())
 }
 
+/// `print_diagnostic()` shows an ftd error with the part of the document it is reported for
+/// underlined, if the document can be found
+fn print_diagnostic(
+    config: &fastn_core::Config,
+    document: &fastn_core::File,
+    error: &fastn_core::Error,
+) {
+    let source = match error
+        .doc_id()
+        .and_then(|doc_id| diagnostic_source(config, document, doc_id))
+    {
+        Some(source) => source,
+        None => return,
+    };
+    if let Some(diagnostic) = error.diagnostic(source.as_str()) {
+        fastn_core::utils::print_err(format!("{}\n", diagnostic.render(source.as_str())));
+    }
+}
+
+// the error can be in the document being built, or in a module it imports
+fn diagnostic_source(
+    config: &fastn_core::Config,
+    document: &fastn_core::File,
+    doc_id: &str,
+) -> Option<String> {
+    let id = doc_id.trim_end_matches(".ftd").trim_matches('/');
+    if let fastn_core::File::Ftd(doc) = document {
+        if document.get_id_with_package().trim_matches('/').eq(id) {
+            return Some(doc.content.to_string());
+        }
+    }
+
+    let path = match id.strip_prefix(config.package.name.as_str()) {
+        Some(rest) => config.root.join(rest.trim_matches('/')),
+        None => config.packages_root.join(id),
+    };
+    [
+        camino::Utf8PathBuf::from(format!("{}.ftd", path)),
+        path.join("index.ftd"),
+    ]
+    .iter()
+    .find_map(|p| std::fs::read_to_string(p).ok())
+}
+
 fn is_cached<'a>(
     cache: Option<&'a mut cache::Cache>,
     doc: &fastn_core::Document,
@@ -631,8 +967,9 @@ async fn handle_file_(
                         );
                     }
                 }
-                (_, true) => {
-                    print!("Failed ");
+                (Err(e), true) => {
+                    fastn_core::utils::print_out("Failed ".to_string());
+                    print_diagnostic(config, document, &e);
                     return Ok(());
                 }
                 (Err(e), _) => {
@@ -643,7 +980,7 @@ async fn handle_file_(
         fastn_core::File::Static(sa) => process_static(sa, &config.root, &config.package).await?,
         fastn_core::File::Markdown(_doc) => {
             // TODO: bring this feature back
-            print!("Skipped ");
+            fastn_core::utils::print_out("Skipped ".to_string());
             return Ok(());
         }
         fastn_core::File::Image(main_doc) => {
@@ -667,6 +1004,52 @@ async fn handle_file_(
     Ok(())
 }
 
+/// held while `optional_build_files()` writes
+static OPTIONAL_BUILD_FILES_LOCK: once_cell::sync::Lazy<tokio::sync::Mutex<()>> =
+    once_cell::sync::Lazy::new(|| tokio::sync::Mutex::new(()));
+
+/// `optional_build_files()` writes the files of the optional parts of the runtime a document uses,
+/// see `fastn_js::Features`, so the build only has the ones some document needs.
+///
+/// Documents are built in parallel and most of them need the same files. `write()` skips files
+/// that exist, so the lock makes sure a file is written by one document and is complete before
+/// the others see it.
+pub(crate) async fn optional_build_files(
+    build_dir: &camino::Utf8PathBuf,
+    features: &fastn_js::Features,
+) -> fastn_core::Result<()> {
+    let mut files: Vec<(&str, String)> = vec![];
+    if features.code {
+        let theme_css = ftd::theme_css();
+        for (theme, file_name) in fastn_core::utils::hashed_code_theme_css() {
+            if let Some(theme_content) = theme_css.get(theme) {
+                files.push((file_name.as_str(), theme_content.to_string()));
+            }
+        }
+        files.push((fastn_core::utils::hashed_prism_js(), ftd::prism_js()));
+        files.push((fastn_core::utils::hashed_prism_css(), ftd::prism_css()));
+    }
+    if features.rive {
+        files.push((
+            fastn_core::utils::hashed_rive_js(),
+            fastn_js::rive_js().to_string(),
+        ));
+    }
+    if features.web_component {
+        files.push((
+            fastn_core::utils::hashed_web_component_js(),
+            fastn_js::web_component_js().to_string(),
+        ));
+    }
+
+    let _lock = OPTIONAL_BUILD_FILES_LOCK.lock().await;
+    for (file_name, content) in files {
+        fastn_core::utils::write(build_dir, file_name, content.as_bytes()).await?;
+    }
+
+    Ok(())
+}
+
 #[tracing::instrument]
 pub async fn default_build_files(
     base_path: camino::Utf8PathBuf,
@@ -687,30 +1070,6 @@ pub async fn default_build_files(
         fastn_core::utils::update(save_markdown_js, markdown_js_content.as_bytes())
             .await
             .ok();
-
-        let theme_css = ftd::theme_css();
-        let hashed_code_themes = fastn_core::utils::hashed_code_theme_css();
-        for (theme, file_name) in hashed_code_themes {
-            let save_markdown_js = base_path.join(file_name);
-            let theme_content = theme_css.get(theme).unwrap();
-            fastn_core::utils::update(save_markdown_js, theme_content.as_bytes())
-                .await
-                .ok();
-        }
-
-        let prism_js_content = ftd::prism_js();
-        let hashed_prism_js_name = fastn_core::utils::hashed_prism_js();
-        let save_prism_js = base_path.join(hashed_prism_js_name);
-        fastn_core::utils::update(save_prism_js, prism_js_content.as_bytes())
-            .await
-            .ok();
-
-        let prism_css_content = ftd::prism_css();
-        let hashed_prism_css_name = fastn_core::utils::hashed_prism_css();
-        let save_prism_css = base_path.join(hashed_prism_css_name);
-        fastn_core::utils::update(save_prism_css, prism_css_content.as_bytes())
-            .await
-            .ok();
     } else {
         let default_css_content = ftd::css();
         let hashed_css_name = fastn_core::utils::hashed_default_css_name();
@@ -831,3 +1190,64 @@ async fn process_static(
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    fn cache(documents: &[(&str, &[&str])]) -> super::cache::Cache {
+        super::cache::Cache {
+            build_content: Default::default(),
+            ftd_cache: Default::default(),
+            documents: documents
+                .iter()
+                .map(|(id, dependencies)| {
+                    (
+                        id.to_string(),
+                        super::cache::Document {
+                            html_checksum: "".to_string(),
+                            dependencies: dependencies.iter().map(|d| d.to_string()).collect(),
+                        },
+                    )
+                })
+                .collect(),
+            file_checksum: Default::default(),
+        }
+    }
+
+    fn ids(ids: &[&str]) -> Vec<String> {
+        ids.iter().map(|v| v.to_string()).collect()
+    }
+
+    // cargo test --package fastn-core --lib commands::build::tests::build_order
+    #[test]
+    fn build_order() {
+        let c = cache(&[
+            (
+                "index",
+                &["foo.com/index", "foo.com/lib", "$fastn$/processors"],
+            ),
+            ("lib", &["foo.com/lib"]),
+            ("about", &["foo.com/about", "foo.com/index"]),
+            ("blog", &[]),
+        ]);
+
+        assert_eq!(
+            super::build_order("foo.com", &c, ids(&["about", "blog", "index", "new"])),
+            vec![
+                ids(&["blog", "lib", "new"]),
+                ids(&["index"]),
+                ids(&["about"])
+            ]
+        );
+    }
+
+    // cargo test --package fastn-core --lib commands::build::tests::build_order_cycle
+    #[test]
+    fn build_order_cycle() {
+        let c = cache(&[("a", &["foo.com/b"]), ("b", &["foo.com/a"])]);
+
+        assert_eq!(
+            super::build_order("foo.com", &c, ids(&["a", "b"])),
+            vec![ids(&["b"]), ids(&["a"])]
+        );
+    }
+}
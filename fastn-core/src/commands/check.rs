@@ -3,9 +3,24 @@ pub const BUILD_FOLDER: &str = ".build";
 pub const IGNORED_DIRECTORIES: [&str; 4] = ["-", "images", "static", "assets"];
 
 pub async fn post_build_check(config: &fastn_core::Config) -> fastn_core::Result<()> {
+    use colored::Colorize;
+
+    println!("Post build index assertion started ...");
+    for folder in folders_without_index(config).await? {
+        let warning_msg = format!(
+            "Warning: Directory {:?} does not have an index.html file.",
+            folder
+        );
+        println!("{}", warning_msg.yellow());
+    }
+    Ok(())
+}
+
+/// `folders_without_index()` lists the folders in `.build` that have files but no `index.html`.
+async fn folders_without_index(config: &fastn_core::Config) -> fastn_core::Result<Vec<String>> {
     let build_path = config.root.join(BUILD_FOLDER);
     let build_directory = build_path.as_str().to_string();
-    println!("Post build index assertion started ...");
+    let mut folders = vec![];
 
     if build_path.is_dir() {
         if !build_path.join(INDEX_FILE).exists() {
@@ -14,20 +29,545 @@ pub async fn post_build_check(config: &fastn_core::Config) -> fastn_core::Result
                 INDEX_FILE
             )));
         }
-        check_index_in_folders(build_path, build_directory.as_str())
+        check_index_in_folders(build_path, build_directory.as_str(), &mut folders)
             .await
             .map_err(|e| fastn_core::Error::GenericError(e.to_string()))?;
     }
 
-    Ok(())
+    Ok(folders)
+}
+
+/// `CheckReport` is the result of `fastn check`: the errors and warnings in the ftd documents of
+/// the package, and the folders of `.build` without an `index.html`.
+#[derive(serde::Serialize, Debug, Default)]
+pub struct CheckReport {
+    pub documents: usize,
+    pub errors: Vec<CheckDiagnostic>,
+    pub warnings: Vec<CheckDiagnostic>,
+    #[serde(rename = "folders-without-index")]
+    pub folders_without_index: Vec<String>,
+}
+
+/// `CheckDiagnostic` is an error or warning in `document`, `span` is where in the document it
+/// is, when that is known.
+#[derive(serde::Serialize, Debug)]
+pub struct CheckDiagnostic {
+    pub document: String,
+    pub message: String,
+    pub span: Option<ftd::p1::Span>,
+    pub help: Option<String>,
+    /// the diagnostic the way `fastn check` prints it
+    #[serde(skip)]
+    rendered: String,
+}
+
+impl fastn_core::commands::report::Report for CheckReport {
+    fn print(&self) {
+        use colored::Colorize;
+
+        for diagnostic in self.errors.iter().chain(self.warnings.iter()) {
+            eprintln!("{}", diagnostic.rendered);
+        }
+        println!(
+            "Checked {} document(s): {} error(s), {} warning(s)",
+            self.documents,
+            self.errors.len(),
+            self.warnings.len()
+        );
+        if self.errors.is_empty() {
+            println!("{}", "No errors found in ftd documents".green());
+        }
+        for folder in self.folders_without_index.iter() {
+            let warning_msg = format!(
+                "Warning: Directory {:?} does not have an index.html file.",
+                folder
+            );
+            println!("{}", warning_msg.yellow());
+        }
+    }
+
+    fn exit_status(&self) -> fastn_core::commands::report::ExitStatus {
+        if !self.errors.is_empty() {
+            fastn_core::commands::report::ExitStatus::Error
+        } else if !self.warnings.is_empty() || !self.folders_without_index.is_empty() {
+            fastn_core::commands::report::ExitStatus::Changes
+        } else {
+            fastn_core::commands::report::ExitStatus::Clean
+        }
+    }
+}
+
+/// `check()` is `fastn check`: `check_documents()`, and the folders of `.build`, if the package
+/// is built, without an `index.html`.
+pub async fn check(config: &fastn_core::Config) -> fastn_core::Result<CheckReport> {
+    let mut report = check_documents(config).await?;
+    report.folders_without_index = folders_without_index(config).await?;
+    Ok(report)
+}
+
+/// `check_documents()` interprets every ftd document of the package, without rendering it, and
+/// reports the errors in all of them at once, instead of stopping at the first one like
+/// `fastn build` does. The modules a document imports, including the ones from dependencies, are
+/// interpreted along with it. Unused imports and or-type variants that are never used are
+/// reported as warnings. `fastn check` fails if any document has an error, so that CI can reject
+/// it.
+pub async fn check_documents(config: &fastn_core::Config) -> fastn_core::Result<CheckReport> {
+    let documents = config
+        .get_files(&config.package)
+        .await?
+        .into_iter()
+        .filter_map(|file| match file {
+            fastn_core::File::Ftd(doc) => Some(doc),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    let mut report = CheckReport {
+        documents: documents.len(),
+        ..Default::default()
+    };
+    let mut or_types = vec![];
+    let mut references = vec![];
+    for doc in documents.iter() {
+        let (parsed, parse_errors) = ftd::interpreter::ParsedDocument::parse_with_recovery(
+            doc.id_with_package().as_str(),
+            doc.content.as_str(),
+        );
+        references.extend(parsed.ast.iter().flat_map(ast_references));
+        // the sections that do not parse are left out, the rest of the document is interpreted
+        let errors = if doc.id.eq("FASTN.ftd") {
+            parse_errors
+        } else {
+            interpret_all(config, doc).await
+        };
+        if !errors.is_empty() {
+            report
+                .errors
+                .extend(errors.iter().map(|error| error_diagnostic(doc, error)));
+            continue;
+        }
+
+        let doc_references = parsed
+            .ast
+            .iter()
+            .flat_map(ast_references)
+            .collect::<Vec<_>>();
+        for ast in parsed.ast {
+            match ast {
+                ftd::ast::AST::Import(import)
+                    if is_unused_import(&import, doc_references.as_slice()) =>
+                {
+                    report.warnings.push(warning_diagnostic(
+                        doc,
+                        format!("unused import `{}`", import.alias),
+                        import.line_number,
+                    ));
+                }
+                ftd::ast::AST::OrType(or_type) => or_types.push((doc, or_type)),
+                _ => {}
+            }
+        }
+    }
+
+    for (doc, or_type) in or_types {
+        for variant in or_type.variants.iter() {
+            let variant_name = variant.name();
+            if !is_used_variant(or_type.name.as_str(), variant_name.as_str(), &references) {
+                report.warnings.push(warning_diagnostic(
+                    doc,
+                    format!(
+                        "variant `{}` of or-type `{}` is never used",
+                        variant.name(),
+                        or_type.name
+                    ),
+                    variant.line_number(),
+                ));
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+/// `interpret()` interprets `doc` the way `fastn build` does, but stops before rendering it.
+pub(crate) async fn interpret(
+    config: &fastn_core::Config,
+    doc: &fastn_core::Document,
+) -> ftd::interpreter::Result<ftd::interpreter::Document> {
+    let (mut lib, content, line_number) = library(config, doc)?;
+    fastn_core::doc::interpret_helper(
+        doc.id_with_package().as_str(),
+        content.as_str(),
+        &mut lib,
+        "/",
+        false,
+        line_number,
+    )
+    .await
+}
+
+/// `interpret_with_recovery()` interprets `doc` like `interpret()`, but the sections of it that
+/// do not parse are left out, their errors are returned along with the result.
+async fn interpret_with_recovery(
+    config: &fastn_core::Config,
+    doc: &fastn_core::Document,
+) -> (
+    Vec<ftd::interpreter::Error>,
+    ftd::interpreter::Result<ftd::interpreter::Document>,
+) {
+    let (mut lib, content, line_number) = match library(config, doc) {
+        Ok(v) => v,
+        Err(e) => return (vec![], Err(e)),
+    };
+    fastn_core::doc::interpret_helper_with_recovery(
+        doc.id_with_package().as_str(),
+        content.as_str(),
+        &mut lib,
+        "/",
+        false,
+        line_number,
+    )
+    .await
+}
+
+/// `library()` is the library `doc` is interpreted with, and its content with the auto imports
+/// of its package prepended, along with the number of lines prepended.
+fn library(
+    config: &fastn_core::Config,
+    doc: &fastn_core::Document,
+) -> ftd::interpreter::Result<(fastn_core::Library2022, String, usize)> {
+    let mut lib_config = config.clone();
+    lib_config.current_document = Some(doc.id.to_string());
+    let package = config
+        .all_packages
+        .borrow()
+        .get(doc.package_name.as_str())
+        .cloned()
+        .unwrap_or_else(|| config.package.clone());
+    let lib = fastn_core::Library2022 {
+        config: lib_config,
+        markdown: None,
+        document_id: doc.id.clone(),
+        translated_data: Default::default(),
+        base_url: "/".to_string(),
+        module_package_map: Default::default(),
+    };
+
+    let content = package.get_prefixed_body(doc.content.as_str(), doc.id.as_str(), true);
+    let content = package.fix_imports_in_body(content.as_str(), doc.id.as_str())?;
+    let line_number = content.split('\n').count() - doc.content.split('\n').count();
+    Ok((lib, content, line_number))
+}
+
+/// only this many errors are reported for a document
+const MAX_ERRORS_PER_DOCUMENT: usize = 20;
+
+/// `interpret_all()` interprets `doc` like `interpret_with_recovery()`, but does not stop at the
+/// first error. The top level section an error is reported in is left out of the document, and
+/// the rest of it is interpreted again, till it has no more errors. An error in a module `doc`
+/// imports can not be left out of `doc`, so nothing after it is reported.
+async fn interpret_all(
+    config: &fastn_core::Config,
+    doc: &fastn_core::Document,
+) -> Vec<ftd::interpreter::Error> {
+    let (sections, _) =
+        ftd::p1::parse_with_recovery(doc.content.as_str(), doc.id_with_package().as_str());
+
+    let mut doc = doc.clone();
+    let mut left_out = vec![];
+    let (mut errors, mut result) = interpret_with_recovery(config, &doc).await;
+    while errors.len() < MAX_ERRORS_PER_DOCUMENT {
+        let error = match result {
+            Ok(_) => break,
+            Err(error) => error,
+        };
+        let section = error
+            .span()
+            .filter(|_| error.doc_id() == Some(doc.id_with_package().as_str()))
+            .and_then(|span| {
+                sections
+                    .iter()
+                    .position(|s| s.span.contains_line(span.start.line))
+            })
+            .filter(|index| !left_out.contains(index));
+        errors.push(error);
+        let index = match section {
+            Some(index) => index,
+            None => break,
+        };
+        left_out.push(index);
+        doc.content = blank_lines(
+            doc.content.as_str(),
+            sections[index].span.start.line,
+            sections[index].span.end.line,
+        );
+        result = interpret_with_recovery(config, &doc).await.1;
+    }
+    errors
+}
+
+/// `blank_lines()` empties the lines `start` to `end` of `content`, the other lines keep their
+/// line numbers.
+fn blank_lines(content: &str, start: usize, end: usize) -> String {
+    content
+        .split('\n')
+        .enumerate()
+        .map(|(index, line)| {
+            if (start..=end).contains(&(index + 1)) {
+                ""
+            } else {
+                line
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn error_diagnostic(
+    doc: &fastn_core::Document,
+    error: &ftd::interpreter::Error,
+) -> CheckDiagnostic {
+    let diagnostic = match error.doc_id() {
+        Some(doc_id) if doc_id.eq(doc.id_with_package().as_str()) => {
+            error.diagnostic(doc.content.as_str())
+        }
+        _ => None,
+    };
+    match diagnostic {
+        Some(diagnostic) => CheckDiagnostic {
+            document: doc.id.to_string(),
+            rendered: diagnostic.render(doc.content.as_str()),
+            message: diagnostic.message,
+            span: Some(diagnostic.span),
+            help: diagnostic.help,
+        },
+        None => CheckDiagnostic {
+            document: doc.id.to_string(),
+            message: error.to_string(),
+            span: None,
+            help: None,
+            rendered: format!("error: {}: {}", doc.id, error),
+        },
+    }
+}
+
+fn warning_diagnostic(
+    doc: &fastn_core::Document,
+    message: String,
+    line_number: usize,
+) -> CheckDiagnostic {
+    let diagnostic = ftd::Diagnostic::at_line(
+        doc.id_with_package().as_str(),
+        message,
+        doc.content.as_str(),
+        line_number,
+        None,
+    );
+    CheckDiagnostic {
+        document: doc.id.to_string(),
+        rendered: diagnostic.render_warning(doc.content.as_str()),
+        message: diagnostic.message,
+        span: Some(diagnostic.span),
+        help: diagnostic.help,
+    }
+}
+
+/// An import is unused if none of the `references` of the document start with its alias.
+/// Imports that re-export or expose things are used by the documents importing this one, so
+/// they are never reported.
+fn is_unused_import(import: &ftd::ast::Import, references: &[String]) -> bool {
+    import.exports.is_none()
+        && import.exposing.is_none()
+        && !references
+            .iter()
+            .any(|r| name_parts(r).first() == Some(&import.alias.as_str()))
+}
+
+/// A variant is used if a reference names it with its or-type, like `$my-type.small`, or if it
+/// is given as a value, like `size: small`, and the or-type is referred to, say as the kind of
+/// an argument.
+fn is_used_variant(or_type: &str, variant: &str, references: &[String]) -> bool {
+    references.iter().any(|r| refers_to(r, &[or_type, variant]))
+        || (references.iter().any(|r| r.eq(variant))
+            && references.iter().any(|r| refers_to(r, &[or_type])))
+}
+
+/// `refers_to()` checks if the name `reference` refers to `name`, given as its `.` separated
+/// parts: `lib.my-type.small` refers to `my-type.small`, `small-size` does not.
+fn refers_to(reference: &str, name: &[&str]) -> bool {
+    name_parts(reference)
+        .windows(name.len())
+        .any(|w| w.eq(name))
+}
+
+fn name_parts(reference: &str) -> Vec<&str> {
+    reference.split('.').collect()
+}
+
+/// `ast_references()` lists the names `ast` refers to: the components it invokes, the kinds of
+/// its variables and arguments, the processors it uses and the names in its references (`$name`),
+/// conditions, loops, events and function bodies. Text values are listed as they are, as they
+/// can name an or-type variant.
+fn ast_references(ast: &ftd::ast::AST) -> Vec<String> {
+    let mut references = vec![];
+    match ast {
+        ftd::ast::AST::Import(_) => {}
+        ftd::ast::AST::Record(record) => record_references(record, &mut references),
+        ftd::ast::AST::OrType(or_type) => {
+            for variant in or_type.variants.iter() {
+                match variant {
+                    ftd::ast::OrTypeVariant::AnonymousRecord(record) => {
+                        record_references(record, &mut references)
+                    }
+                    ftd::ast::OrTypeVariant::Regular(field)
+                    | ftd::ast::OrTypeVariant::Constant(field) => {
+                        field_references(field, &mut references)
+                    }
+                }
+            }
+        }
+        ftd::ast::AST::VariableDefinition(definition) => {
+            expression_references(definition.kind.kind.as_str(), &mut references);
+            references.extend(definition.processor.iter().cloned());
+            value_references(&definition.value, &mut references);
+        }
+        ftd::ast::AST::VariableInvocation(invocation) => {
+            references.push(invocation.name.to_string());
+            references.extend(invocation.processor.iter().cloned());
+            if let Some(ref condition) = invocation.condition {
+                expression_references(condition.expression.as_str(), &mut references);
+            }
+            value_references(&invocation.value, &mut references);
+        }
+        ftd::ast::AST::ComponentDefinition(definition) => {
+            for argument in definition.arguments.iter() {
+                field_references(argument, &mut references);
+            }
+            component_references(&definition.definition, &mut references);
+        }
+        ftd::ast::AST::ComponentInvocation(component) => {
+            component_references(component, &mut references)
+        }
+        ftd::ast::AST::FunctionDefinition(function) => {
+            expression_references(function.kind.kind.as_str(), &mut references);
+            for argument in function.arguments.iter() {
+                field_references(argument, &mut references);
+            }
+            expression_references(function.definition.value.as_str(), &mut references);
+            references.extend(
+                function
+                    .js
+                    .iter()
+                    .map(|v| v.trim_start_matches('$').to_string()),
+            );
+        }
+        ftd::ast::AST::WebComponentDefinition(definition) => {
+            for argument in definition.arguments.iter() {
+                field_references(argument, &mut references);
+            }
+            references.push(definition.js.trim_start_matches('$').to_string());
+        }
+    }
+    references
+}
+
+fn record_references(record: &ftd::ast::Record, references: &mut Vec<String>) {
+    for field in record.fields.iter() {
+        field_references(field, references);
+    }
+}
+
+fn field_references(field: &ftd::ast::Field, references: &mut Vec<String>) {
+    expression_references(field.kind.kind.as_str(), references);
+    if let Some(ref value) = field.value {
+        value_references(value, references);
+    }
+}
+
+fn component_references(component: &ftd::ast::Component, references: &mut Vec<String>) {
+    references.push(component.name.to_string());
+    for property in component.properties.iter() {
+        value_references(&property.value, references);
+        if let Some(ref condition) = property.condition {
+            expression_references(condition.as_str(), references);
+        }
+    }
+    if let Some(ref iteration) = component.iteration {
+        expression_references(iteration.on.as_str(), references);
+    }
+    if let Some(ref condition) = component.condition {
+        expression_references(condition.expression.as_str(), references);
+    }
+    for event in component.events.iter() {
+        expression_references(event.action.as_str(), references);
+    }
+    for child in component.children.iter() {
+        component_references(child, references);
+    }
+}
+
+fn value_references(value: &ftd::ast::VariableValue, references: &mut Vec<String>) {
+    match value {
+        ftd::ast::VariableValue::Optional { value, .. } => {
+            if let Some(ref value) = **value {
+                value_references(value, references);
+            }
+        }
+        ftd::ast::VariableValue::List { value, .. } => {
+            for v in value.iter() {
+                value_references(&v.value, references);
+            }
+        }
+        ftd::ast::VariableValue::Record {
+            name,
+            caption,
+            headers,
+            values,
+            ..
+        } => {
+            references.push(name.to_string());
+            if let Some(ref caption) = **caption {
+                value_references(caption, references);
+            }
+            for header in headers.0.iter() {
+                value_references(&header.value, references);
+                references.extend(header.kind.iter().cloned());
+                if let Some(ref condition) = header.condition {
+                    expression_references(condition.as_str(), references);
+                }
+            }
+            for v in values.iter() {
+                value_references(&v.value, references);
+            }
+        }
+        ftd::ast::VariableValue::String { value, .. } => {
+            if value.starts_with('$') {
+                expression_references(value.as_str(), references);
+            } else {
+                // or-type variants, like `my-type.small`, are passed as text
+                references.push(value.trim().to_string());
+            }
+        }
+    }
+}
+
+/// `expression_references()` lists the names in an expression, like `$lib.flag and not $open`.
+fn expression_references(expression: &str, references: &mut Vec<String>) {
+    references.extend(
+        expression
+            .split(|c: char| !(c.is_alphanumeric() || matches!(c, '-' | '_' | '.' | '#')))
+            .map(|name| name.trim_matches('.'))
+            .filter(|name| !name.is_empty())
+            .map(|name| name.to_string()),
+    );
 }
 
 #[async_recursion::async_recursion]
 async fn check_index_in_folders(
     folder: camino::Utf8PathBuf,
     build_path: &str,
+    folders_without_index: &mut Vec<String>,
 ) -> Result<(), fastn_core::Error> {
-    use colored::Colorize;
     let mut file_count = 0;
     let mut has_ignored_directory = false;
 
@@ -47,17 +587,14 @@ async fn check_index_in_folders(
                 file_count += 1;
             }
             if entry_path.is_dir() && !is_ignored_directory {
-                check_index_in_folders(entry_path, build_path).await?;
+                check_index_in_folders(entry_path, build_path, folders_without_index).await?;
             }
         }
         if file_count > 0 || !has_ignored_directory {
             let index_html_path = folder.join(INDEX_FILE);
             if !index_html_path.exists() {
-                let warning_msg = format!(
-                    "Warning: Directory {:?} does not have an index.html file.",
-                    folder.as_str().trim_start_matches(build_path)
-                );
-                println!("{}", warning_msg.yellow());
+                folders_without_index
+                    .push(folder.as_str().trim_start_matches(build_path).to_string());
             }
         }
     }
@@ -67,3 +604,95 @@ async fn check_index_in_folders(
 fn is_ignored_directory(path: &camino::Utf8PathBuf) -> bool {
     IGNORED_DIRECTORIES.iter().any(|dir| path.ends_with(dir))
 }
+
+#[cfg(test)]
+mod test {
+    fn references(source: &str) -> (Vec<ftd::ast::AST>, Vec<String>) {
+        let (parsed, errors) = ftd::interpreter::ParsedDocument::parse_with_recovery("foo", source);
+        assert!(errors.is_empty(), "{:?}", errors);
+        let references = parsed.ast.iter().flat_map(super::ast_references).collect();
+        (parsed.ast, references)
+    }
+
+    fn unused_imports(source: &str) -> Vec<String> {
+        let (ast, references) = references(source);
+        ast.into_iter()
+            .filter_map(|ast| match ast {
+                ftd::ast::AST::Import(import)
+                    if super::is_unused_import(&import, references.as_slice()) =>
+                {
+                    Some(import.alias)
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn unused_import() {
+        assert_eq!(
+            unused_imports(indoc::indoc!(
+                "
+                -- import: lib
+                -- import: other
+                -- import: colors
+
+                -- ftd.text: hello libraries, other and colors
+                color: $colors.red
+                "
+            )),
+            vec!["lib".to_string(), "other".to_string()]
+        );
+        assert!(unused_imports(indoc::indoc!(
+            "
+            -- import: lib
+
+            -- lib.card:
+            "
+        ))
+        .is_empty());
+        assert!(unused_imports(indoc::indoc!(
+            "
+            -- import: lib
+
+            -- boolean open: false
+
+            -- ftd.text: hello
+            if: { lib.flag and open }
+            "
+        ))
+        .is_empty());
+    }
+
+    #[test]
+    fn used_variant() {
+        let (_, references) = references(indoc::indoc!(
+            "
+            -- component card:
+            size-name size: small
+
+            -- ftd.text: $card.size-label
+            "
+        ));
+        assert!(super::is_used_variant(
+            "size-name",
+            "small",
+            references.as_slice()
+        ));
+        assert!(!super::is_used_variant(
+            "size-name",
+            "large",
+            references.as_slice()
+        ));
+        assert!(!super::is_used_variant(
+            "size",
+            "label",
+            references.as_slice()
+        ));
+        assert!(super::is_used_variant(
+            "size",
+            "small",
+            &["$lib.size.small".to_string()]
+        ));
+    }
+}
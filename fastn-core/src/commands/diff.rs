@@ -1,8 +1,65 @@
+/// `DiffReport` is the result of `fastn diff`: the un-synced changes of the files, and with
+/// `--all`, the changes in the files they track since they were last marked up to date. `diff` is
+/// a unified diff.
+#[derive(serde::Serialize, Debug, Default)]
+pub struct DiffReport {
+    pub files: Vec<FileDiff>,
+    pub tracks: Vec<TrackDiff>,
+}
+
+#[derive(serde::Serialize, Debug)]
+pub struct FileDiff {
+    pub file: String,
+    pub diff: String,
+}
+
+#[derive(serde::Serialize, Debug)]
+pub struct TrackDiff {
+    pub file: String,
+    pub tracking: String,
+    /// the snapshot of `tracking` when `file` was last marked up to date
+    pub from: String,
+    /// the latest snapshot of `tracking`
+    pub to: String,
+    pub diff: String,
+}
+
+impl fastn_core::commands::report::Report for DiffReport {
+    fn print(&self) {
+        for file in self.files.iter() {
+            println!("diff: {}", file.file);
+            println!("{}", colored_diff(file.diff.as_str()));
+        }
+        for track in self.tracks.iter() {
+            println!("diff {} -> {}: {}", track.file, track.from, track.to);
+            println!("{}", colored_diff(track.diff.as_str()));
+        }
+    }
+
+    fn exit_status(&self) -> fastn_core::commands::report::ExitStatus {
+        if self.files.is_empty() && self.tracks.is_empty() {
+            fastn_core::commands::report::ExitStatus::Clean
+        } else {
+            fastn_core::commands::report::ExitStatus::Changes
+        }
+    }
+}
+
+fn colored_diff(diff: &str) -> String {
+    match diffy::Patch::from_str(diff) {
+        Ok(patch) => diffy::PatchFormatter::new()
+            .with_color()
+            .fmt_patch(&patch)
+            .to_string(),
+        Err(_) => diff.to_string(),
+    }
+}
+
 pub async fn diff(
     config: &fastn_core::Config,
     files: Option<Vec<String>>,
     all: bool,
-) -> fastn_core::Result<()> {
+) -> fastn_core::Result<DiffReport> {
     let snapshots = fastn_core::snapshot::get_latest_snapshots(&config.root).await?;
     let all = all || files.is_some();
     let documents = if let Some(ref files) = files {
@@ -15,16 +72,21 @@ pub async fn diff(
     } else {
         config.get_files(&config.package).await?
     };
+    let mut report = DiffReport::default();
     for doc in documents {
         if let Some(diff) = get_diffy(&doc, &snapshots).await? {
-            println!("diff: {}", doc.get_id());
-            println!("{}", diff);
+            report.files.push(FileDiff {
+                file: doc.get_id().to_string(),
+                diff,
+            });
         }
         if all {
-            get_track_diff(&doc, &snapshots, config.root.as_str()).await?;
+            report
+                .tracks
+                .extend(get_track_diff(&doc, &snapshots, config.root.as_str()).await?);
         }
     }
-    Ok(())
+    Ok(report)
 }
 
 async fn get_diffy(
@@ -40,11 +102,7 @@ async fn get_diffy(
             return Ok(None);
         }
         let patch = diffy::create_patch(&existing_doc, &content);
-        let diff = diffy::PatchFormatter::new()
-            .with_color()
-            .fmt_patch(&patch)
-            .to_string();
-        return Ok(Some(diff));
+        return Ok(Some(patch.to_string()));
     }
     Ok(None)
 }
@@ -53,10 +111,11 @@ async fn get_track_diff(
     doc: &fastn_core::File,
     snapshots: &std::collections::BTreeMap<String, u128>,
     base_path: &str,
-) -> fastn_core::Result<()> {
+) -> fastn_core::Result<Vec<TrackDiff>> {
     let path = fastn_core::utils::track_path(doc.get_id(), doc.get_base_path());
+    let mut track_diffs = vec![];
     if std::fs::metadata(&path).is_err() {
-        return Ok(());
+        return Ok(track_diffs);
     }
     let tracks = fastn_core::tracker::get_tracks(base_path, &path)?;
     for track in tracks.values() {
@@ -79,22 +138,18 @@ async fn get_track_diff(
                 continue;
             }
             let patch = diffy::create_patch(&then_doc, &now_doc);
-            let diff = diffy::PatchFormatter::new()
-                .with_color()
-                .fmt_patch(&patch)
-                .to_string();
-            println!(
-                "diff {} -> {}: {}",
-                doc.get_id(),
-                then_path
+            track_diffs.push(TrackDiff {
+                file: doc.get_id().to_string(),
+                tracking: track.filename.to_string(),
+                from: then_path
                     .to_string()
                     .replace(&format!("{}/.history/", doc.get_base_path()), ""),
-                now_path
+                to: now_path
                     .to_string()
                     .replace(&format!("{}/.history/", doc.get_base_path()), ""),
-            );
-            println!("{}", diff);
+                diff: patch.to_string(),
+            });
         }
     }
-    Ok(())
+    Ok(track_diffs)
 }
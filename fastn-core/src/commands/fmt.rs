@@ -0,0 +1,87 @@
+pub const COMMAND: &str = "fmt";
+
+pub fn command() -> clap::Command {
+    clap::Command::new(COMMAND)
+        .about("Format ftd documents")
+        .arg(clap::arg!(file: [FILE]... "The documents to format (if specified only these are formatted, else all ftd documents of the package are)"))
+        .arg(clap::arg!(--check "Do not change any document, fail if some of them are not formatted"))
+        .arg(clap::arg!(--"sort-headers" "Sort the headers of component invocations by name"))
+}
+
+pub async fn handle_command(matches: &clap::ArgMatches) -> fastn_core::Result<()> {
+    let files = match matches.get_many::<String>("file") {
+        Some(files) => files.map(camino::Utf8PathBuf::from).collect(),
+        None => package_documents(&fastn_core::Config::read(None, false, None).await?).await?,
+    };
+    let options = ftd::p1::fmt::FormatOptions {
+        sort_headers: matches.get_flag("sort-headers"),
+    };
+    fmt(files.as_slice(), matches.get_flag("check"), &options).await
+}
+
+async fn package_documents(
+    config: &fastn_core::Config,
+) -> fastn_core::Result<Vec<camino::Utf8PathBuf>> {
+    Ok(config
+        .get_files(&config.package)
+        .await?
+        .into_iter()
+        .filter(|file| file.is_ftd())
+        .map(|file| file.get_full_path())
+        .collect())
+}
+
+/// `fmt()` formats the ftd documents `files` in place. With `check` no document is changed, the
+/// ones that are not formatted are listed and an error is returned instead, so that CI can
+/// reject them.
+///
+/// A document that can not be parsed, or can not be formatted without changing its meaning, is
+/// left as it is and reported.
+pub async fn fmt(
+    files: &[camino::Utf8PathBuf],
+    check: bool,
+    options: &ftd::p1::fmt::FormatOptions,
+) -> fastn_core::Result<()> {
+    let mut failed = 0;
+    let mut unformatted = 0;
+    for file in files {
+        let content = tokio::fs::read_to_string(file).await?;
+        let formatted =
+            match ftd::p1::fmt::format_with_options(content.as_str(), file.as_str(), options) {
+                Ok(formatted) => formatted,
+                Err(e) => {
+                    match e.diagnostic(content.as_str()) {
+                        Some(diagnostic) => eprintln!("{}", diagnostic.render(content.as_str())),
+                        None => eprintln!("error: {}: {}", file, e),
+                    }
+                    failed += 1;
+                    continue;
+                }
+            };
+        if formatted == content {
+            continue;
+        }
+
+        unformatted += 1;
+        if check {
+            println!("{}", file);
+        } else {
+            tokio::fs::write(file, formatted).await?;
+            println!("Formatted {}", file);
+        }
+    }
+
+    if failed > 0 {
+        return Err(fastn_core::Error::GenericError(format!(
+            "could not format {} document(s)",
+            failed
+        )));
+    }
+    if check && unformatted > 0 {
+        return Err(fastn_core::Error::GenericError(format!(
+            "{} document(s) are not formatted, run `fastn fmt` to format them",
+            unformatted
+        )));
+    }
+    Ok(())
+}
@@ -22,12 +22,398 @@ pub async fn merge(
 }
 
 async fn merge_cr_into_cr(
-    _config: &fastn_core::Config,
-    _src: usize,
-    _dest: usize,
-    _file: Option<&str>,
+    config: &fastn_core::Config,
+    src: usize,
+    dest: usize,
+    file: Option<&str>,
+) -> fastn_core::Result<()> {
+    use itertools::Itertools;
+    use sha2::Digest;
+
+    if src.eq(&dest) {
+        return fastn_core::usage_error(format!("Can't merge CR#{} into itself", src));
+    }
+
+    let (src_track_manifest, src_file_manifest) =
+        split_cr_manifest(config.get_cr_manifest(src).await?);
+    let (dest_track_manifest, dest_file_manifest) =
+        split_cr_manifest(config.get_cr_manifest(dest).await?);
+
+    let src_deleted_file_str = config.path_without_root(&config.cr_deleted_file_path(src))?;
+    let dest_deleted_file_str = config.path_without_root(&config.cr_deleted_file_path(dest))?;
+    let dest_deleted_file_edit = dest_file_manifest
+        .get(&dest_deleted_file_str)
+        .filter(|v| !v.is_deleted());
+    let mut dest_deleted_list = match dest_deleted_file_edit {
+        Some(file_edit) => {
+            let content = tokio::fs::read_to_string(
+                config.history_path(dest_deleted_file_str.as_str(), file_edit.version),
+            )
+            .await?;
+            fastn_core::cr::resolve_cr_deleted(content.as_str(), dest)
+                .await?
+                .into_iter()
+                .map(|v| (v.filename.to_string(), v))
+                .collect::<std::collections::BTreeMap<String, fastn_core::cr::CRDeleted>>()
+        }
+        None => Default::default(),
+    };
+    let mut dest_deleted_list_changed = false;
+
+    let mut new_file_status: std::collections::BTreeMap<
+        String,
+        fastn_core::sync_utils::FileStatus,
+    > = Default::default();
+    let mut conflicted_file_status = vec![];
+    let mut merge_conflicts = vec![];
+
+    for (src_file_path, src_file_edit) in src_file_manifest.iter() {
+        if src_file_edit.is_deleted() {
+            continue;
+        }
+        if src_file_path.eq(&src_deleted_file_str) {
+            let src_deleted_files = tokio::fs::read_to_string(
+                config.history_path(src_file_path.as_str(), src_file_edit.version),
+            )
+            .await?;
+            for cr_deleted in
+                fastn_core::cr::resolve_cr_deleted(src_deleted_files.as_str(), src).await?
+            {
+                if let Some(file) = file {
+                    if cr_deleted.filename.ne(file) {
+                        continue;
+                    }
+                }
+                let dest_file_path =
+                    format!("{}/{}", fastn_core::cr::cr_path(dest), cr_deleted.filename);
+                match dest_file_manifest.get(&dest_file_path) {
+                    Some(dest_file_edit) if !dest_file_edit.is_deleted() => {
+                        // Edited in destination CR, deleted in source CR
+                        conflicted_file_status.push(fastn_core::sync_utils::FileStatus::Update {
+                            path: dest_file_path.to_string(),
+                            content: tokio::fs::read(
+                                config
+                                    .history_path(dest_file_path.as_str(), dest_file_edit.version),
+                            )
+                            .await?,
+                            version: dest_file_edit.version,
+                            status: fastn_core::sync_utils::Status::CloneEditedRemoteDeleted(
+                                cr_deleted.version,
+                            ),
+                        });
+                    }
+                    _ => {
+                        if !dest_deleted_list.contains_key(&cr_deleted.filename) {
+                            dest_deleted_list.insert(cr_deleted.filename.to_string(), cr_deleted);
+                            dest_deleted_list_changed = true;
+                        }
+                    }
+                }
+            }
+            continue;
+        }
+
+        let filename = fastn_core::cr::cr_path_to_file_name(src, src_file_path.as_str())?
+            .trim_start_matches('/')
+            .to_string();
+        if filename.starts_with("-/") {
+            // CR's own files, like its meta
+            continue;
+        }
+        if let Some(file) = file {
+            if filename.ne(file) {
+                continue;
+            }
+        }
+
+        let theirs_content_bytes =
+            tokio::fs::read(config.history_path(src_file_path.as_str(), src_file_edit.version))
+                .await?;
+        let dest_file_path = format!("{}/{}", fastn_core::cr::cr_path(dest), filename);
+        let src_track_file_path_str =
+            config.path_without_root(&config.track_dir().join(src_file_path))?;
+        let dest_track_file_path_str =
+            config.path_without_root(&config.track_dir().join(dest_file_path.as_str()))?;
+
+        let dest_file_edit = match dest_file_manifest.get(&dest_file_path) {
+            Some(file_edit) if !file_edit.is_deleted() => file_edit,
+            _ => {
+                if let Some(cr_deleted) = dest_deleted_list.get(&filename) {
+                    // Deleted in destination CR, edited in source CR
+                    conflicted_file_status.push(fastn_core::sync_utils::FileStatus::Delete {
+                        path: dest_file_path.to_string(),
+                        version: cr_deleted.version,
+                        status: fastn_core::sync_utils::Status::CloneDeletedRemoteEdited(
+                            src_file_edit.version,
+                        ),
+                    });
+                    continue;
+                }
+
+                // Not edited in destination CR, so it takes the edit along with its tracking
+                new_file_status.insert(
+                    dest_file_path.to_string(),
+                    fastn_core::sync_utils::FileStatus::Add {
+                        path: dest_file_path.to_string(),
+                        content: theirs_content_bytes,
+                        status: fastn_core::sync_utils::Status::NoConflict,
+                    },
+                );
+                if let Some(track_file_edit) = src_track_manifest
+                    .get(&src_track_file_path_str)
+                    .filter(|v| !v.is_deleted())
+                {
+                    new_file_status.insert(
+                        dest_track_file_path_str.to_string(),
+                        fastn_core::sync_utils::FileStatus::Add {
+                            path: dest_track_file_path_str.to_string(),
+                            content: tokio::fs::read(config.history_path(
+                                src_track_file_path_str.as_str(),
+                                track_file_edit.version,
+                            ))
+                            .await?,
+                            status: fastn_core::sync_utils::Status::NoConflict,
+                        },
+                    );
+                }
+                continue;
+            }
+        };
+
+        let ours_content_bytes =
+            tokio::fs::read(config.history_path(dest_file_path.as_str(), dest_file_edit.version))
+                .await?;
+        if sha2::Sha256::digest(&ours_content_bytes)
+            .eq(&sha2::Sha256::digest(&theirs_content_bytes))
+        {
+            continue;
+        }
+
+        // Both the CRs edited the file, the common ancestor is the older of the versions they
+        // track, or nothing if both of them added it
+        let src_tracked_version = tracked_version(
+            config,
+            &src_track_manifest,
+            src_track_file_path_str.as_str(),
+            filename.as_str(),
+        )
+        .await?;
+        let dest_tracked_version = tracked_version(
+            config,
+            &dest_track_manifest,
+            dest_track_file_path_str.as_str(),
+            filename.as_str(),
+        )
+        .await?;
+        let ancestor_content = match (src_tracked_version, dest_tracked_version) {
+            (Some(src_version), Some(dest_version)) => tokio::fs::read_to_string(
+                config.history_path(filename.as_str(), src_version.min(dest_version)),
+            )
+            .await
+            .ok(),
+            _ => Some("".to_string()),
+        };
+
+        let (ancestor_content, ours_content, theirs_content) = match (
+            ancestor_content,
+            String::from_utf8(ours_content_bytes.clone()),
+            String::from_utf8(theirs_content_bytes),
+        ) {
+            (Some(ancestor), Ok(ours), Ok(theirs)) => (ancestor, ours, theirs),
+            _ => {
+                // binary file like images, can't resolve conflict
+                conflicted_file_status.push(fastn_core::sync_utils::FileStatus::Update {
+                    path: dest_file_path.to_string(),
+                    content: ours_content_bytes,
+                    version: dest_file_edit.version,
+                    status: fastn_core::sync_utils::Status::Conflict(src_file_edit.version),
+                });
+                continue;
+            }
+        };
+
+        match diffy::MergeOptions::new()
+            .set_conflict_style(diffy::ConflictStyle::Merge)
+            .merge(&ancestor_content, &ours_content, &theirs_content)
+        {
+            Ok(data) => {
+                new_file_status.insert(
+                    dest_file_path.to_string(),
+                    fastn_core::sync_utils::FileStatus::Update {
+                        path: dest_file_path.to_string(),
+                        content: data.into_bytes(),
+                        version: dest_file_edit.version,
+                        status: fastn_core::sync_utils::Status::NoConflict,
+                    },
+                );
+            }
+            Err(data) => merge_conflicts.push(MergeConflict {
+                path: dest_file_path,
+                version: dest_file_edit.version,
+                ours: ours_content_bytes,
+                marked: data,
+                source: src_file_path.to_string(),
+                source_version: src_file_edit.version,
+            }),
+        }
+    }
+
+    if dest_deleted_list_changed {
+        let content = fastn_core::cr::generate_deleted_files_content(
+            dest_deleted_list.into_values().collect_vec().as_slice(),
+        )
+        .into_bytes();
+        let file_status = match dest_deleted_file_edit {
+            Some(file_edit) => fastn_core::sync_utils::FileStatus::Update {
+                path: dest_deleted_file_str.to_string(),
+                content,
+                version: file_edit.version,
+                status: fastn_core::sync_utils::Status::NoConflict,
+            },
+            None => fastn_core::sync_utils::FileStatus::Add {
+                path: dest_deleted_file_str.to_string(),
+                content,
+                status: fastn_core::sync_utils::Status::NoConflict,
+            },
+        };
+        new_file_status.insert(dest_deleted_file_str, file_status);
+    }
+
+    if !conflicted_file_status.is_empty() {
+        // A file edited in one CR and deleted in the other, nothing is merged till the CRs agree
+        // on it
+        for file_status in conflicted_file_status.iter() {
+            fastn_core::commands::sync_status::print_status(file_status, false);
+        }
+        return Err(fastn_core::Error::MergeConflict {
+            src,
+            dest,
+            files: conflicted_file_status
+                .iter()
+                .map(|v| v.get_file_path())
+                .collect(),
+        });
+    }
+
+    let changed_files = new_file_status
+        .into_values()
+        .filter_map(|v| v.sync_request(Some(src)))
+        .collect_vec();
+    fastn_core::apis::sync2::do_sync(config, changed_files.as_slice()).await?;
+    write_merge_conflicts(config, dest, merge_conflicts).await?;
+    Ok(())
+}
+
+/// A file both the CRs edited that `merge_cr_into_cr()` could not merge.
+struct MergeConflict {
+    /// The file in the destination CR
+    path: String,
+    version: i32,
+    ours: Vec<u8>,
+    /// `ours` merged with `theirs`, with conflict markers
+    marked: String,
+    /// The file in the source CR
+    source: String,
+    source_version: i32,
+}
+
+/// Writes the conflict markers in the destination CR's files, they are tracked in the workspace
+/// till resolved with `fastn resolve-conflict` or `fastn mark-resolved`, `fastn abort-merge`
+/// brings back the destination CR's version.
+async fn write_merge_conflicts(
+    config: &fastn_core::Config,
+    dest: usize,
+    merge_conflicts: Vec<MergeConflict>,
 ) -> fastn_core::Result<()> {
-    unimplemented!()
+    use itertools::Itertools;
+
+    if merge_conflicts.is_empty() {
+        return Ok(());
+    }
+
+    let mut workspace = fastn_core::snapshot::get_workspace(config).await?;
+    let mut client_workspace = vec![];
+    for conflict in merge_conflicts {
+        println!("Conflicted: {}", conflict.path);
+        fastn_core::utils::update(
+            &config.root.join(conflict.path.as_str()),
+            conflict.marked.as_bytes(),
+        )
+        .await?;
+        fastn_core::utils::update1(
+            &config.conflicted_dir(),
+            conflict.path.as_str(),
+            &conflict.ours,
+        )
+        .await?;
+        workspace.insert(
+            conflict.path.to_string(),
+            fastn_core::snapshot::Workspace {
+                filename: conflict.path.to_string(),
+                base: conflict.version as u128,
+                conflicted: conflict.source_version as u128,
+                workspace: fastn_core::snapshot::WorkspaceType::Conflicted,
+                source: Some(conflict.source),
+            },
+        );
+        client_workspace.push(fastn_core::workspace::WorkspaceEntry {
+            filename: conflict.path,
+            deleted: None,
+            version: Some(conflict.version),
+            cr: Some(dest),
+        });
+    }
+
+    fastn_core::snapshot::create_workspace(
+        config,
+        workspace.into_values().collect_vec().as_slice(),
+    )
+    .await?;
+    if config.workspace_file().exists() {
+        config.update_workspace(client_workspace).await?;
+    }
+    Ok(())
+}
+
+type CRManifest = std::collections::HashMap<String, fastn_core::history::FileEdit>;
+
+/// Splits the manifest of a CR into the manifest of its track files and of its other files.
+fn split_cr_manifest(
+    cr_manifest: std::collections::BTreeMap<String, fastn_core::history::FileEdit>,
+) -> (CRManifest, CRManifest) {
+    cr_manifest.into_iter().fold(
+        (
+            std::collections::HashMap::new(),
+            std::collections::HashMap::new(),
+        ),
+        |(mut cr_track_status, mut cr_file_status), (file, file_edit)| {
+            if file.starts_with(".tracks/") {
+                cr_track_status.insert(file, file_edit);
+            } else {
+                cr_file_status.insert(file, file_edit);
+            }
+            (cr_track_status, cr_file_status)
+        },
+    )
+}
+
+/// The version of `filename` a CR edited, `None` if the CR added it.
+async fn tracked_version(
+    config: &fastn_core::Config,
+    cr_track_manifest: &CRManifest,
+    track_file_path_str: &str,
+    filename: &str,
+) -> fastn_core::Result<Option<i32>> {
+    let track_file_edit = match cr_track_manifest.get(track_file_path_str) {
+        Some(file_edit) if !file_edit.is_deleted() => file_edit,
+        _ => return Ok(None),
+    };
+    let track_file_path = config.history_path(track_file_path_str, track_file_edit.version);
+    Ok(fastn_core::track::get_tracking_info_(&track_file_path)
+        .await?
+        .into_iter()
+        .find(|v| v.filename.eq(filename))
+        .map(|v| v.version))
 }
 
 async fn merge_main_into_cr(
@@ -819,3 +1205,131 @@ async fn add_close_cr_status(
     fastn_core::commands::sync2::sync(config, changed_files).await?;
     Ok(())
 }*/
+
+#[cfg(test)]
+mod tests {
+    fn block_on<F: std::future::Future>(f: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+            .block_on(f)
+    }
+
+    /// A package in a new temporary directory, with nothing in its history.
+    fn package(name: &str) -> camino::Utf8PathBuf {
+        let root = camino::Utf8PathBuf::from_path_buf(std::env::temp_dir())
+            .unwrap()
+            .join(format!("fastn-merge-{}-{}", name, std::process::id()));
+        if root.exists() {
+            std::fs::remove_dir_all(&root).unwrap();
+        }
+        std::fs::create_dir_all(root.join(".remote-state")).unwrap();
+        std::fs::write(
+            root.join("FASTN.ftd"),
+            "-- import: fastn\n\n-- fastn.package: foo.com\n",
+        )
+        .unwrap();
+        std::fs::write(root.join(".remote-state/history.ftd"), "-- import: fastn\n").unwrap();
+        root
+    }
+
+    async fn config(root: &camino::Utf8Path) -> fastn_core::Config {
+        fastn_core::Config::read(Some(root.to_string()), false, None)
+            .await
+            .unwrap()
+    }
+
+    async fn add(config: &fastn_core::Config, path: &str, content: &str) {
+        fastn_core::apis::sync2::do_sync(
+            config,
+            &[fastn_core::apis::sync2::SyncRequestFile::Add {
+                path: path.to_string(),
+                content: content.as_bytes().to_vec(),
+                src_cr: None,
+            }],
+        )
+        .await
+        .unwrap();
+    }
+
+    fn deleted(filename: &str) -> String {
+        fastn_core::cr::generate_deleted_files_content(&[fastn_core::cr::CRDeleted::new(
+            filename, 1,
+        )])
+    }
+
+    fn conflicted_files(result: fastn_core::Result<()>) -> Vec<String> {
+        match result {
+            Err(fastn_core::Error::MergeConflict { src, dest, files }) => {
+                assert_eq!((src, dest), (1, 2));
+                files
+            }
+            result => panic!("expected a merge conflict, got {:?}", result),
+        }
+    }
+
+    // cargo test --package fastn-core --lib commands::merge::tests::merge_cr_into_cr
+    #[test]
+    fn merge_cr_into_cr() {
+        let root = package("new-file");
+        block_on(async {
+            let config = config(&root).await;
+            add(&config, "-/1/a.ftd", "-- ftd.text: hello\n").await;
+
+            super::merge_cr_into_cr(&config, 1, 2, None).await.unwrap();
+            assert_eq!(
+                std::fs::read_to_string(root.join("-/2/a.ftd")).unwrap(),
+                "-- ftd.text: hello\n"
+            );
+
+            assert!(matches!(
+                super::merge_cr_into_cr(&config, 2, 2, None).await,
+                Err(fastn_core::Error::UsageError { .. })
+            ));
+        });
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    // cargo test --package fastn-core --lib commands::merge::tests::merge_cr_into_cr_edit_delete
+    #[test]
+    fn merge_cr_into_cr_edit_delete() {
+        // edited in the source CR, deleted in the destination CR
+        let root = package("edit-delete");
+        block_on(async {
+            let config = config(&root).await;
+            add(&config, "-/1/a.ftd", "-- ftd.text: hello\n").await;
+            add(&config, "-/2/-/deleted.ftd", deleted("a.ftd").as_str()).await;
+
+            assert_eq!(
+                conflicted_files(super::merge_cr_into_cr(&config, 1, 2, None).await),
+                vec!["-/2/a.ftd".to_string()]
+            );
+            assert!(!root.join("-/2/a.ftd").exists());
+        });
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    // cargo test --package fastn-core --lib commands::merge::tests::merge_cr_into_cr_delete_edit
+    #[test]
+    fn merge_cr_into_cr_delete_edit() {
+        // deleted in the source CR, edited in the destination CR
+        let root = package("delete-edit");
+        block_on(async {
+            let config = config(&root).await;
+            add(&config, "-/1/-/deleted.ftd", deleted("a.ftd").as_str()).await;
+            add(&config, "-/2/a.ftd", "-- ftd.text: ours\n").await;
+
+            assert_eq!(
+                conflicted_files(super::merge_cr_into_cr(&config, 1, 2, None).await),
+                vec!["-/2/a.ftd".to_string()]
+            );
+            assert_eq!(
+                std::fs::read_to_string(root.join("-/2/a.ftd")).unwrap(),
+                "-- ftd.text: ours\n"
+            );
+            assert!(!root.join("-/2/-/deleted.ftd").exists());
+        });
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -8,10 +8,12 @@ pub mod create_cr;
 pub mod create_package;
 pub mod diff;
 pub mod edit;
+pub mod fmt;
 pub mod mark_resolved;
 pub mod mark_upto_date;
 pub mod merge;
 pub mod query;
+pub mod report;
 pub mod resolve_conflict;
 pub mod revert;
 pub mod rm;
@@ -24,3 +26,4 @@ pub mod sync2;
 pub mod sync_status;
 pub mod translation_status;
 pub mod update;
+pub mod vendor;
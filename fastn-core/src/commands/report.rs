@@ -0,0 +1,123 @@
+//! `fastn --format json <command>` prints what `status`, `diff`, `sync-status`,
+//! `translation-status` and `check` found as a single JSON object on stdout, instead of text:
+//!
+//! ```json
+//! {"version": 1, "command": "status", "status": "changes", "result": {...}}
+//! ```
+//!
+//! `status` is one of `clean`, `changes` or `error`, and is also the exit code of `fastn`: `0`,
+//! `1` and `2` respectively. `result` is the report of the command, see `StatusReport`,
+//! `DiffReport`, `SyncStatusReport`, `TranslationStatusReport` and `CheckReport` for their
+//! fields. If the command fails the object has `error` instead of `result`:
+//!
+//! ```json
+//! {"version": 1, "command": "status", "status": "error", "error": "..."}
+//! ```
+//!
+//! Fields are only ever added to a report, anything else bumps `version`.
+
+pub const FORMAT: &str = "format";
+pub const VERSION: usize = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+impl OutputFormat {
+    pub fn from_matches(matches: &clap::ArgMatches) -> OutputFormat {
+        match matches.get_one::<String>(FORMAT).map(|v| v.as_str()) {
+            Some("json") => OutputFormat::Json,
+            _ => OutputFormat::Text,
+        }
+    }
+}
+
+/// The global `--format` argument of `fastn`.
+pub fn arg() -> clap::Arg {
+    clap::arg!(--format <FORMAT> "The output format of status, diff, sync-status, translation-status and check")
+        .value_parser(["text", "json"])
+        .default_value("text")
+        .global(true)
+}
+
+#[derive(serde::Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ExitStatus {
+    Clean,
+    Changes,
+    Error,
+}
+
+impl ExitStatus {
+    pub fn code(&self) -> i32 {
+        match self {
+            ExitStatus::Clean => 0,
+            ExitStatus::Changes => 1,
+            ExitStatus::Error => 2,
+        }
+    }
+}
+
+/// `Report` is what a command found, printed as text or as json depending on `--format`.
+pub trait Report: serde::Serialize {
+    /// prints the report the way the command always did
+    fn print(&self);
+
+    fn exit_status(&self) -> ExitStatus;
+}
+
+/// `output()` prints `report` in `format`. With `--format text` the exit code of `fastn` stays
+/// `0` even if there are changes, and a report with errors fails the command.
+pub fn output(
+    command: &str,
+    report: &impl Report,
+    format: OutputFormat,
+) -> fastn_core::Result<ExitStatus> {
+    let status = report.exit_status();
+    match format {
+        OutputFormat::Text => {
+            report.print();
+            if status.eq(&ExitStatus::Error) {
+                return Err(fastn_core::Error::GenericError(format!(
+                    "`fastn {}` found errors",
+                    command
+                )));
+            }
+            Ok(ExitStatus::Clean)
+        }
+        OutputFormat::Json => {
+            println!(
+                "{}",
+                serde_json::json!({
+                    "version": VERSION,
+                    "command": command,
+                    "status": status,
+                    "result": report,
+                })
+            );
+            Ok(status)
+        }
+    }
+}
+
+/// `output_error()` prints the error a command failed with, in `format`.
+pub fn output_error<E>(command: &str, error: &E, format: OutputFormat)
+where
+    E: std::fmt::Debug + std::fmt::Display,
+{
+    match format {
+        OutputFormat::Text => eprintln!("{:?}", error),
+        OutputFormat::Json => println!(
+            "{}",
+            serde_json::json!({
+                "version": VERSION,
+                "command": command,
+                "status": ExitStatus::Error,
+                "error": error.to_string(),
+            })
+        ),
+    }
+}
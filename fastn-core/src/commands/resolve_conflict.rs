@@ -13,16 +13,26 @@ pub async fn resolve_conflict(
     if number_of_times_flag_used > 1 {
         return fastn_core::usage_error("AmbiguousOptionError: Use only one flag".to_string());
     }
-    let get_files_status = config.get_files_status().await?;
-    let file_status =
-        if let Some(file_status) = get_files_status.iter().find(|v| v.get_file_path().eq(path)) {
-            file_status
-        } else {
+    // conflicts of `fastn merge <src-cr> <dest-cr>` are not in the files status
+    let merge_conflict = fastn_core::snapshot::get_workspace(config)
+        .await?
+        .remove(path)
+        .filter(|v| v.source.is_some());
+    let get_files_status = if merge_conflict.is_none() {
+        config.get_files_status().await?
+    } else {
+        vec![]
+    };
+    let file_status = get_files_status.iter().find(|v| v.get_file_path().eq(path));
+    let conflicted_data = match (merge_conflict, file_status) {
+        (Some(merge_conflict), _) => get_merge_conflict_data(config, &merge_conflict).await?,
+        (None, Some(file_status)) => get_conflict_data(config, file_status).await?,
+        (None, None) => {
             return Err(fastn_core::Error::UsageError {
                 message: format!("{} not found", path),
             });
-        };
-    let conflicted_data = get_conflict_data(config, file_status).await?;
+        }
+    };
     if use_ours {
         let content = conflicted_data
             .ours
@@ -100,7 +110,10 @@ pub async fn resolve_conflict(
         fastn_core::utils::update(&config.root.join(path), edited.as_bytes()).await?;
     }
 
-    mark_resolve(config, file_status, delete_it).await?;
+    match file_status {
+        Some(file_status) => mark_resolve(config, file_status, delete_it).await?,
+        None => fastn_core::mark_resolved(config, path).await?,
+    }
     Ok(())
 }
 
@@ -291,3 +304,33 @@ async fn get_conflict_data(
         }
     }
 }
+
+/// `ours` is the destination CR's version, kept aside by the merge, `theirs` is the source CR's
+/// version and the merge left the conflict markers in the file itself.
+async fn get_merge_conflict_data(
+    config: &fastn_core::Config,
+    merge_conflict: &fastn_core::snapshot::Workspace,
+) -> fastn_core::Result<ConflictData> {
+    let source = merge_conflict
+        .source
+        .as_ref()
+        .ok_or(fastn_core::Error::UsageError {
+            message: format!("`{}` is not in merge conflict", merge_conflict.filename),
+        })?;
+    let ours = tokio::fs::read(
+        config
+            .conflicted_dir()
+            .join(merge_conflict.filename.as_str()),
+    )
+    .await?;
+    let theirs =
+        tokio::fs::read(config.history_path(source.as_str(), merge_conflict.conflicted as i32))
+            .await?;
+    let marker =
+        tokio::fs::read_to_string(config.root.join(merge_conflict.filename.as_str())).await?;
+    Ok(ConflictData {
+        ours: Content::Content(ours),
+        theirs: Content::Content(theirs),
+        marker: Some(marker),
+    })
+}
@@ -41,51 +41,8 @@ async fn serve_file(
     };
 
     // Auth Stuff
-    if !f.is_static() {
-        let req = if let Some(ref r) = config.request {
-            r
-        } else {
-            return fastn_core::server_error!("request not set");
-        };
-
-        match config.can_read(req, path.as_str(), true).await {
-            Ok(can_read) => {
-                if !can_read {
-                    tracing::error!(
-                        msg = "unauthorized-error: can not read",
-                        path = path.as_str()
-                    );
-                    return fastn_core::unauthorised!("You are unauthorized to access: {}", path);
-                }
-            }
-            Err(e) => {
-                tracing::error!(msg = "can_read-error", path = path.as_str());
-                return fastn_core::server_error!("fastn-Error: can_read error: {}, {:?}", path, e);
-            }
-        };
-
-        match fastn_core::package::app::can_read(config, path.as_str()).await {
-            Ok(can_read) => {
-                if !can_read {
-                    tracing::error!(
-                        msg = "unauthorized-error: can not access app",
-                        path = path.as_str()
-                    );
-                    return fastn_core::unauthorised!("You are unauthorized to access: {}", path);
-                }
-            }
-            Err(err) => {
-                tracing::error!(
-                    msg = "app::can_read-error: can not access app",
-                    path = path.as_str()
-                );
-                return fastn_core::server_error!(
-                    "fastn-Error: can_read error: {}, {:?}",
-                    path,
-                    err
-                );
-            }
-        };
+    if let Some(response) = check_access(config, path.as_str(), f.is_static()).await {
+        return response;
     }
 
     match f {
@@ -129,13 +86,148 @@ async fn serve_file(
     }
 }
 
-async fn serve_cr_file(
+/// `check_access()` is the response for a user who can not read `path`, if they can not. Documents
+/// are protected by their own readers, static files and proxied urls by the readers of the
+/// sitemap item they are under, and all of them by the readers of the app they are mounted in.
+async fn check_access(
+    config: &fastn_core::Config,
+    path: &str,
+    is_static: bool,
+) -> Option<fastn_core::http::Response> {
+    let req = if let Some(ref r) = config.request {
+        r
+    } else {
+        return Some(fastn_core::server_error!("request not set"));
+    };
+
+    let can_read = if is_static {
+        config.can_read_path(req, path).await
+    } else {
+        config.can_read(req, path, true).await
+    };
+    match can_read {
+        Ok(true) => {}
+        Ok(false) => return Some(access_denied(config, req, path)),
+        Err(e) => {
+            tracing::error!(msg = "can_read-error", path = path);
+            return Some(fastn_core::server_error!(
+                "fastn-Error: can_read error: {}, {:?}",
+                path,
+                e
+            ));
+        }
+    };
+
+    match fastn_core::package::app::can_read(config, path).await {
+        Ok(true) => None,
+        Ok(false) => Some(access_denied(config, req, path)),
+        Err(err) => {
+            tracing::error!(msg = "app::can_read-error: can not access app", path = path);
+            Some(fastn_core::server_error!(
+                "fastn-Error: can_read error: {}, {:?}",
+                path,
+                err
+            ))
+        }
+    }
+}
+
+/// `access_denied()` sends a `GET` to the `login-url` of the package, with the url asked for as
+/// `next`, if the package has one, anything else gets a 401.
+fn access_denied(
+    config: &fastn_core::Config,
     req: &fastn_core::http::Request,
+    path: &str,
+) -> fastn_core::http::Response {
+    tracing::error!(msg = "unauthorized-error: can not read", path = path);
+    denied_response(
+        config.package.login_url.as_deref(),
+        req.method(),
+        req.uri(),
+        path,
+    )
+}
+
+fn denied_response(
+    login_url: Option<&str>,
+    method: &str,
+    uri: &str,
+    path: &str,
+) -> fastn_core::http::Response {
+    match login_url {
+        Some(login_url) if method.eq_ignore_ascii_case("get") => {
+            let next: String = url::form_urlencoded::byte_serialize(uri.as_bytes()).collect();
+            let separator = if login_url.contains('?') { '&' } else { '?' };
+            fastn_core::http::redirect_with_code(
+                format!("{}{}next={}", login_url, separator, next),
+                302,
+            )
+        }
+        _ => fastn_core::unauthorised!("You are unauthorized to access: {}", path),
+    }
+}
+
+/// `check_write_access()` is the response for a user who can not send a `req_method` request to
+/// the proxied url `path`, see `Config::can_write_path()`.
+async fn check_write_access(
+    config: &fastn_core::Config,
+    path: &str,
+    req_method: &str,
+) -> Option<fastn_core::http::Response> {
+    if req_method.eq_ignore_ascii_case("get") || req_method.eq_ignore_ascii_case("head") {
+        return None;
+    }
+    let req = if let Some(ref r) = config.request {
+        r
+    } else {
+        return Some(fastn_core::server_error!("request not set"));
+    };
+    match config.can_write_path(req, path).await {
+        Ok(true) => None,
+        Ok(false) => {
+            tracing::error!(msg = "unauthorized-error: can not write", path = path);
+            Some(fastn_core::unauthorised!(
+                "You are unauthorized to access: {}",
+                path
+            ))
+        }
+        Err(e) => {
+            tracing::error!(msg = "can_write-error", path = path);
+            Some(fastn_core::server_error!(
+                "fastn-Error: can_write error: {}, {:?}",
+                path,
+                e
+            ))
+        }
+    }
+}
+
+async fn serve_cr_file(
     config: &mut fastn_core::Config,
     path: &camino::Utf8Path,
     cr_number: usize,
 ) -> fastn_core::http::Response {
     let _lock = LOCK.read().await;
+
+    // Auth Stuff
+    // A file under a change request is protected by the readers of the file it changes, checked
+    // before the change request is looked up so it does not tell anyone which files it has.
+    let file_path = match fastn_core::cr::cr_path_to_file_name(cr_number, path.as_str()) {
+        Ok(file_path) => file_path.trim_matches('/').to_string(),
+        Err(e) => {
+            return fastn_core::server_error!("fastn-Error: path: {}, {:?}", path, e);
+        }
+    };
+    let is_static = match fastn_core::file::is_static(file_path.as_str()) {
+        Ok(is_static) => is_static,
+        Err(e) => {
+            return fastn_core::server_error!("fastn-Error: path: {}, {:?}", path, e);
+        }
+    };
+    if let Some(response) = check_access(config, file_path.as_str(), is_static).await {
+        return response;
+    }
+
     let f = match config
         .get_file_and_package_by_cr_id(path.as_str(), cr_number)
         .await
@@ -146,20 +238,6 @@ async fn serve_cr_file(
         }
     };
 
-    // Auth Stuff
-    if !f.is_static() {
-        match config.can_read(req, path.as_str(), true).await {
-            Ok(can_read) => {
-                if !can_read {
-                    return fastn_core::unauthorised!("You are unauthorized to access: {}", path);
-                }
-            }
-            Err(e) => {
-                return fastn_core::server_error!("fastn-Error: can_read error: {}, {:?}", path, e);
-            }
-        }
-    }
-
     config.current_document = Some(f.get_id().to_string());
     match f {
         fastn_core::File::Ftd(main_document) => {
@@ -238,6 +316,13 @@ async fn static_file(file_path: camino::Utf8PathBuf) -> fastn_core::http::Respon
     }
 }
 
+/// `is_hidden()` tells if `path` is in a dot directory or is a dot file, like
+/// `/.history/index.ftd` or `/.env`. They hold the state of the package, the history, the
+/// downloaded packages and the like, and are never served.
+fn is_hidden(path: &str) -> bool {
+    path.split('/').any(|segment| segment.starts_with('.'))
+}
+
 #[tracing::instrument(skip_all)]
 pub async fn serve(
     req: fastn_core::http::Request,
@@ -247,13 +332,16 @@ pub async fn serve(
     external_css: Vec<String>,
     inline_css: Vec<String>,
 ) -> fastn_core::Result<fastn_core::http::Response> {
+    if is_hidden(req.path()) {
+        return Ok(fastn_core::not_found!("no such file: {}", req.path()));
+    }
+
     let _lock = LOCK.read().await;
 
     // TODO: remove unwrap
     let path: camino::Utf8PathBuf = req.path().replacen('/', "", 1).parse().unwrap();
-    let mut config = fastn_core::Config::read(None, false, Some(&req))
-        .await
-        .unwrap()
+    let mut config = fastn_core::Config::read_cached(&req)
+        .await?
         .add_edition(edition)?
         .add_external_js(external_js)
         .add_inline_js(inline_js)
@@ -265,7 +353,7 @@ pub async fn serve(
     } else if path.eq(&camino::Utf8PathBuf::new().join("")) {
         serve_file(&mut config, &path.join("/")).await
     } else if let Some(cr_number) = fastn_core::cr::get_cr_path_from_url(path.as_str()) {
-        serve_cr_file(&req, &mut config, &path, cr_number).await
+        serve_cr_file(&mut config, &path, cr_number).await
     } else {
         // url is present in config or not
         // If not present than proxy pass it
@@ -336,6 +424,14 @@ pub async fn serve(
             // TODO: Check if path exists in dynamic urls also, otherwise pass to endpoint
             // Already checked in the above method serve_file
             tracing::info!("executing proxy: path: {}", &path);
+            if let Some(response) = check_access(&config, path.as_str(), true).await {
+                return Ok(response);
+            }
+            if let Some(response) =
+                check_write_access(&config, path.as_str(), req_method.as_str()).await
+            {
+                return Ok(response);
+            }
             let (package_name, url, mut conf) =
                 fastn_core::config::utils::get_clean_url(&config, path.as_str())?;
             let package_name = package_name.unwrap_or_else(|| config.package.name.to_string());
@@ -449,7 +545,9 @@ pub async fn clear_cache(
     let from = actix_web::web::Query::<Temp>::from_query(req.query_string())?;
     if from.from.eq(&Some("temp-github".to_string())) {
         let _lock = LOCK.write().await;
-        return Ok(fastn_core::apis::cache::clear(&req).await);
+        let response = fastn_core::apis::cache::clear(&req).await;
+        fastn_core::Config::invalidate_cache();
+        return Ok(response);
     }
     // TODO: Remove After Demo, till here
 
@@ -464,6 +562,7 @@ pub async fn clear_cache(
 
     let _lock = LOCK.write().await;
     fastn_core::apis::cache::clear(&req).await;
+    fastn_core::Config::invalidate_cache();
     // TODO: Redirect to Referrer uri
     return Ok(actix_web::HttpResponse::Found()
         .append_header((actix_web::http::header::LOCATION, "/".to_string()))
@@ -607,6 +706,21 @@ fn handle_default_route(
                 .content_type(mime_guess::mime::TEXT_JAVASCRIPT)
                 .body(theme)
         });
+    } else if req.path().ends_with(fastn_core::utils::hashed_rive_js()) {
+        return Some(
+            actix_web::HttpResponse::Ok()
+                .content_type(mime_guess::mime::TEXT_JAVASCRIPT)
+                .body(fastn_js::rive_js()),
+        );
+    } else if req
+        .path()
+        .ends_with(fastn_core::utils::hashed_web_component_js())
+    {
+        return Some(
+            actix_web::HttpResponse::Ok()
+                .content_type(mime_guess::mime::TEXT_JAVASCRIPT)
+                .body(fastn_js::web_component_js()),
+        );
     } else if req.path().ends_with(fastn_core::utils::hashed_prism_js()) {
         return Some(
             actix_web::HttpResponse::Ok()
@@ -661,8 +775,10 @@ async fn route(
         )
         .await;
     }
+    let session_cookies = fastn_core::auth::session::session_cookies(&req);
+    let connection_info = req.connection_info().clone();
     let req = fastn_core::http::Request::from_actix(req, body);
-    match (req.method().to_lowercase().as_str(), req.path()) {
+    let mut response = match (req.method().to_lowercase().as_str(), req.path()) {
         ("post", "/-/sync/") if cfg!(feature = "remote") => sync(req).await,
         ("post", "/-/sync2/") if cfg!(feature = "remote") => sync2(req).await,
         ("get", "/-/clone/") if cfg!(feature = "remote") => clone(req).await,
@@ -688,7 +804,15 @@ async fn route(
             )
             .await
         }
+    }?;
+    // sessions in use are moved to new ids now and then, so a leaked cookie stops working
+    // the page is served even if the sessions database can not be written to
+    if let Err(e) =
+        fastn_core::auth::session::rotate_cookies(&mut response, &session_cookies, &connection_info)
+    {
+        tracing::error!(msg = "session-rotation-error", error = e.to_string());
     }
+    Ok(response)
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -706,6 +830,9 @@ pub async fn listen(
     use colored::Colorize;
     env_logger::init_from_env(env_logger::Env::new().default_filter_or("info"));
 
+    // refuse to start a remote server whose sessions anyone could forge
+    fastn_core::auth::secret_key()?;
+
     if package_download_base_url.is_some() {
         download_init_package(package_download_base_url).await?;
     }
@@ -724,6 +851,12 @@ pub async fn listen(
         }
     }
 
+    // the sessions of the logged-in users are kept in the root of the package being served
+    let current_dir: camino::Utf8PathBuf = tokio::fs::canonicalize(std::env::current_dir()?)
+        .await?
+        .try_into()?;
+    fastn_core::auth::session::init(&fastn_core::Config::get_root_path(&current_dir).await?)?;
+
     let tcp_listener = match fastn_core::http::get_available_port(port, bind_address) {
         Some(listener) => listener,
         None => {
@@ -744,6 +877,9 @@ You can try without providing port, it will automatically pick unused port."#,
         }
     };
 
+    // the watcher invalidates the config cached by `serve()`
+    fastn_core::watcher::start();
+
     let app = move || {
         actix_web::App::new()
             .app_data(actix_web::web::Data::new(AppData {
@@ -779,3 +915,101 @@ You can try without providing port, it will automatically pick unused port."#,
 // cargo install --features controller --path=.
 // FASTN_CONTROLLER=http://127.0.0.1:8000 FASTN_INSTANCE_ID=12345 fastn serve 8001
 // TRACING=INFO fastn serve
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn denied_get_is_sent_to_login() {
+        let response = super::denied_response(
+            Some("/-/auth/login/?provider=github"),
+            "GET",
+            "/docs/secret.png?v=1",
+            "docs/secret.png",
+        );
+        assert_eq!(response.status(), actix_web::http::StatusCode::FOUND);
+        assert_eq!(
+            response
+                .headers()
+                .get(actix_web::http::header::LOCATION)
+                .and_then(|v| v.to_str().ok()),
+            Some("/-/auth/login/?provider=github&next=%2Fdocs%2Fsecret.png%3Fv%3D1")
+        );
+    }
+
+    #[test]
+    fn denied_without_login_url_or_get_is_401() {
+        let response = super::denied_response(None, "GET", "/docs/", "docs/");
+        assert_eq!(response.status(), actix_web::http::StatusCode::UNAUTHORIZED);
+
+        let response = super::denied_response(Some("/login/"), "POST", "/api/add/", "api/add/");
+        assert_eq!(response.status(), actix_web::http::StatusCode::UNAUTHORIZED);
+    }
+
+    #[test]
+    fn dot_directories_are_not_served() {
+        for uri in [
+            "/.sessions/sessions.sqlite",
+            "/.history/index.ftd",
+            "/docs/.env",
+        ] {
+            let req = fastn_core::http::Request::from_actix(
+                actix_web::test::TestRequest::get()
+                    .uri(uri)
+                    .to_http_request(),
+                actix_web::web::Bytes::new(),
+            );
+            let response = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .unwrap()
+                .block_on(super::serve(req, None, vec![], vec![], vec![], vec![]))
+                .unwrap();
+            assert_eq!(
+                response.status(),
+                actix_web::http::StatusCode::NOT_FOUND,
+                "{}",
+                uri
+            );
+        }
+        assert!(super::is_hidden("/../FASTN.ftd"));
+        assert!(!super::is_hidden("/docs/index.html"));
+        assert!(!super::is_hidden("/"));
+    }
+
+    #[test]
+    fn protected_cr_asset_is_not_served() {
+        let root = camino::Utf8PathBuf::from_path_buf(std::env::temp_dir())
+            .unwrap()
+            .join(format!("fastn-cr-asset-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("docs")).unwrap();
+        std::fs::write(
+            root.join("FASTN.ftd"),
+            "-- import: fastn\n\n-- fastn.package: foo.com\n\n\
+             -- fastn.user-group: admins\nemail: amitu@example.com\n\n\
+             -- fastn.sitemap:\n\n# Docs: /docs/\nreaders: admins\n",
+        )
+        .unwrap();
+        std::fs::write(root.join("docs/index.ftd"), "-- ftd.text: docs\n").unwrap();
+        std::fs::write(root.join("docs/secret.png"), b"secret").unwrap();
+
+        let req = fastn_core::http::Request::from_actix(
+            actix_web::test::TestRequest::get()
+                .uri("/-/2/docs/secret.png")
+                .to_http_request(),
+            actix_web::web::Bytes::new(),
+        );
+        let response = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+            .block_on(async {
+                let mut config = fastn_core::Config::read(Some(root.to_string()), true, Some(&req))
+                    .await
+                    .unwrap();
+                super::serve_cr_file(&mut config, camino::Utf8Path::new("-/2/docs/secret.png"), 2)
+                    .await
+            });
+        std::fs::remove_dir_all(&root).unwrap();
+        assert_eq!(response.status(), actix_web::http::StatusCode::UNAUTHORIZED);
+    }
+}
@@ -1,7 +1,55 @@
-pub async fn status(config: &fastn_core::Config, source: Option<&str>) -> fastn_core::Result<()> {
+/// `StatusReport` is the result of `fastn status`: the files which are not up to date, and the
+/// tracking relations of the files which are not up to date.
+#[derive(serde::Serialize, Debug)]
+pub struct StatusReport {
+    pub files: std::collections::BTreeMap<String, FileStatus>,
+    pub tracks: Vec<TrackReport>,
+}
+
+/// `TrackReport` is the status of `file` wrt the file it tracks, like
+/// `{"file": "a.ftd", "tracking": "b.ftd", "status": "out-of-date", "seconds": 120}`.
+#[derive(serde::Serialize, Debug)]
+pub struct TrackReport {
+    pub file: String,
+    pub tracking: String,
+    #[serde(flatten)]
+    pub status: TrackStatus,
+}
+
+impl fastn_core::commands::report::Report for StatusReport {
+    fn print(&self) {
+        for (id, status) in self.files.iter() {
+            println!("{:?}: {}", status, id);
+        }
+        for track in self.tracks.iter() {
+            println!(
+                "{}: {} -> {}",
+                track.status.to_string(),
+                track.file,
+                track.tracking
+            );
+        }
+        if self.files.is_empty() && self.tracks.is_empty() {
+            println!("Nothing to sync, clean working tree");
+        }
+    }
+
+    fn exit_status(&self) -> fastn_core::commands::report::ExitStatus {
+        if self.files.is_empty() && self.tracks.is_empty() {
+            fastn_core::commands::report::ExitStatus::Clean
+        } else {
+            fastn_core::commands::report::ExitStatus::Changes
+        }
+    }
+}
+
+pub async fn status(
+    config: &fastn_core::Config,
+    source: Option<&str>,
+) -> fastn_core::Result<StatusReport> {
     let snapshots = fastn_core::snapshot::get_latest_snapshots(&config.root).await?;
     let workspaces = fastn_core::snapshot::get_workspace(config).await?;
-    match source {
+    let (file_status, track_status) = match source {
         Some(source) => {
             file_status(
                 config.package.name.clone(),
@@ -10,27 +58,55 @@ pub async fn status(config: &fastn_core::Config, source: Option<&str>) -> fastn_
                 &snapshots,
                 &workspaces,
             )
-            .await
+            .await?
+        }
+        None => all_status(config, &snapshots, &workspaces).await?,
+    };
+
+    let mut report = StatusReport {
+        files: file_status
+            .into_iter()
+            .filter(|(_, status)| !status.eq(&FileStatus::Uptodate))
+            .collect(),
+        tracks: vec![],
+    };
+    for (file, tracks) in track_status {
+        for (tracking, status) in tracks {
+            if status.eq(&TrackStatus::UptoDate) {
+                continue;
+            }
+            report.tracks.push(TrackReport {
+                file: file.to_string(),
+                tracking,
+                status,
+            });
         }
-        None => all_status(config, &snapshots, &workspaces).await,
     }
+    Ok(report)
 }
 
+type FileStatusMap = std::collections::BTreeMap<String, FileStatus>;
+type TrackStatusMap =
+    std::collections::BTreeMap<String, std::collections::BTreeMap<String, TrackStatus>>;
+
 async fn file_status(
     package_name: String,
     base_path: &camino::Utf8PathBuf,
     source: &str,
     snapshots: &std::collections::BTreeMap<String, u128>,
     workspaces: &std::collections::BTreeMap<String, fastn_core::snapshot::Workspace>,
-) -> fastn_core::Result<()> {
+) -> fastn_core::Result<(FileStatusMap, TrackStatusMap)> {
     let path = base_path.join(source);
     if !path.exists() {
         if snapshots.contains_key(source) {
-            println!("{:?}: {}", FileStatus::Deleted, source);
-        } else {
-            eprintln!("Error: {} does not exists", source);
+            return Ok((
+                std::iter::once((source.to_string(), FileStatus::Deleted)).collect(),
+                Default::default(),
+            ));
         }
-        return Ok(());
+        return Err(fastn_core::Error::UsageError {
+            message: format!("{} does not exists", source),
+        });
     }
 
     let file = fastn_core::get_file(package_name, &path, base_path).await?;
@@ -38,26 +114,17 @@ async fn file_status(
     let file_status = get_file_status(&file, snapshots, workspaces).await?;
     let track_status = get_track_status(&file, snapshots, base_path.as_str())?;
 
-    let mut clean = true;
-    if !file_status.eq(&FileStatus::Uptodate) {
-        println!("{:?}: {}", file_status, source);
-        clean = false;
-    }
-    for (i, j) in track_status {
-        println!("{}: {} -> {}", j.to_string(), source, i);
-        clean = false;
-    }
-    if clean {
-        println!("Nothing to sync, clean working tree");
-    }
-    Ok(())
+    Ok((
+        std::iter::once((source.to_string(), file_status)).collect(),
+        std::iter::once((source.to_string(), track_status)).collect(),
+    ))
 }
 
 async fn all_status(
     config: &fastn_core::Config,
     snapshots: &std::collections::BTreeMap<String, u128>,
     workspaces: &std::collections::BTreeMap<String, fastn_core::snapshot::Workspace>,
-) -> fastn_core::Result<()> {
+) -> fastn_core::Result<(FileStatusMap, TrackStatusMap)> {
     use itertools::Itertools;
 
     let mut file_status = std::collections::BTreeMap::new();
@@ -82,12 +149,7 @@ async fn all_status(
             .map(|v| (v.to_string(), FileStatus::Deleted)),
     );
 
-    let clean_file_status = print_file_status(&file_status);
-    let clean_track_status = print_track_status(&track_status);
-    if !clean_file_status && clean_track_status {
-        println!("Nothing to sync, clean working tree");
-    }
-    Ok(())
+    Ok((file_status, track_status))
 }
 
 pub(crate) async fn get_file_status(
@@ -180,44 +242,13 @@ fn get_track_status(
     Ok(track_list)
 }
 
-fn print_track_status(
-    track_status: &std::collections::BTreeMap<
-        String,
-        std::collections::BTreeMap<String, TrackStatus>,
-    >,
-) -> bool {
-    let mut status = true;
-    for (k, v) in track_status {
-        for (i, j) in v {
-            if j.eq(&TrackStatus::UptoDate) {
-                continue;
-            }
-            println!("{}: {} -> {}", j.to_string(), k, i);
-            status = false;
-        }
-    }
-    status
-}
-
-fn print_file_status(file_status: &std::collections::BTreeMap<String, FileStatus>) -> bool {
-    let mut any_modification = false;
-
-    for (id, status) in file_status
-        .iter()
-        .filter(|(_, f)| !f.eq(&&FileStatus::Uptodate))
-    {
-        any_modification = true;
-        println!("{:?}: {}", status, id);
-    }
-
-    any_modification
-}
-
-#[derive(Debug, PartialEq, Eq)]
-pub(crate) enum FileStatus {
+#[derive(serde::Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum FileStatus {
     Modified,
     Added,
     Deleted,
+    #[serde(rename = "up-to-date")]
     Uptodate,
     Conflicted,
     Outdated,
@@ -225,11 +256,15 @@ pub(crate) enum FileStatus {
     CloneDeletedRemoteEdited,
 }
 
-#[derive(Debug, PartialEq, Eq)]
-enum TrackStatus {
+#[derive(serde::Serialize, Debug, PartialEq, Eq)]
+#[serde(tag = "status", rename_all = "kebab-case")]
+pub enum TrackStatus {
+    #[serde(rename = "up-to-date")]
     UptoDate,
     NeverMarked,
-    OutOfDate { seconds: u64 },
+    OutOfDate {
+        seconds: u64,
+    },
 }
 
 impl ToString for TrackStatus {
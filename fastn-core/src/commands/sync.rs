@@ -344,6 +344,7 @@ async fn on_conflict(
                                 .get(path)
                                 .ok_or_else(|| error("File should be available in request file"))?,
                             workspace: fastn_core::snapshot::WorkspaceType::Conflicted,
+                            source: None,
                         },
                     );
                 } else if fastn_core::apis::sync::SyncStatus::CloneEditedRemoteDeleted.eq(status) {
@@ -362,6 +363,7 @@ async fn on_conflict(
                                 .ok_or_else(|| error("File should be available in request file"))?,
                             workspace:
                                 fastn_core::snapshot::WorkspaceType::CloneEditedRemoteDeleted,
+                            source: None,
                         },
                     );
                 } else if fastn_core::apis::sync::SyncStatus::CloneDeletedRemoteEdited.eq(status) {
@@ -379,6 +381,7 @@ async fn on_conflict(
                                 .ok_or_else(|| error("File should be available in request file"))?,
                             workspace:
                                 fastn_core::snapshot::WorkspaceType::CloneDeletedRemoteEdited,
+                            source: None,
                         },
                     );
                 }
@@ -67,7 +67,11 @@ pub(crate) async fn sync_(
     request_files: Vec<fastn_core::apis::sync2::SyncRequestFile>,
     workspace: &mut std::collections::BTreeMap<String, fastn_core::workspace::WorkspaceEntry>,
 ) -> fastn_core::Result<()> {
-    let history = tokio::fs::read_to_string(config.history_file()).await?;
+    use itertools::Itertools;
+
+    let history = fastn_core::history::FileHistory::to_ftd(
+        config.get_history().await?.iter().collect_vec().as_slice(),
+    );
     let sync_request = fastn_core::apis::sync2::SyncRequest {
         package_name: config.package.name.to_string(),
         files: request_files,
@@ -7,69 +7,157 @@ pub fn command() -> clap::Command {
         .hide(true) // hidden since the feature is not being released yet.
 }
 
-pub async fn handle_command(matches: &clap::ArgMatches) -> fastn_core::Result<()> {
+/// `SyncStatusReport` is the result of `fastn sync-status`: the files that differ from the
+/// latest synced version, or the status of the given file.
+#[derive(serde::Serialize, Debug)]
+pub struct SyncStatusReport {
+    pub files: Vec<FileSyncStatus>,
+}
+
+#[derive(serde::Serialize, Debug)]
+pub struct FileSyncStatus {
+    pub path: String,
+    pub status: SyncStatus,
+}
+
+#[derive(serde::Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum SyncStatus {
+    Added,
+    Updated,
+    Deleted,
+    UpToDate,
+    Conflicted,
+    CloneEditedRemoteDeleted,
+    CloneDeletedRemoteEdited,
+    CloneAddedRemoteAdded,
+}
+
+impl SyncStatus {
+    fn as_str(&self) -> &'static str {
+        match self {
+            SyncStatus::Added => "Added",
+            SyncStatus::Updated => "Updated",
+            SyncStatus::Deleted => "Deleted",
+            SyncStatus::UpToDate => "Up-to-date",
+            SyncStatus::Conflicted => "Conflicted",
+            SyncStatus::CloneEditedRemoteDeleted => "CloneEditedRemoteDeleted",
+            SyncStatus::CloneDeletedRemoteEdited => "CloneDeletedRemoteEdited",
+            SyncStatus::CloneAddedRemoteAdded => "CloneAddedRemoteAdded",
+        }
+    }
+}
+
+impl From<&fastn_core::sync_utils::FileStatus> for FileSyncStatus {
+    fn from(file_status: &fastn_core::sync_utils::FileStatus) -> FileSyncStatus {
+        let (path, file_status, status) = match file_status {
+            fastn_core::sync_utils::FileStatus::Add { path, status, .. } => {
+                (path, SyncStatus::Added, status)
+            }
+            fastn_core::sync_utils::FileStatus::Update { path, status, .. } => {
+                (path, SyncStatus::Updated, status)
+            }
+            fastn_core::sync_utils::FileStatus::Delete { path, status, .. } => {
+                (path, SyncStatus::Deleted, status)
+            }
+            fastn_core::sync_utils::FileStatus::Uptodate { path, .. } => {
+                return FileSyncStatus {
+                    path: path.to_string(),
+                    status: SyncStatus::UpToDate,
+                }
+            }
+        };
+        let status = match status {
+            fastn_core::sync_utils::Status::Conflict(_) => SyncStatus::Conflicted,
+            fastn_core::sync_utils::Status::CloneEditedRemoteDeleted(_) => {
+                SyncStatus::CloneEditedRemoteDeleted
+            }
+            fastn_core::sync_utils::Status::CloneDeletedRemoteEdited(_) => {
+                SyncStatus::CloneDeletedRemoteEdited
+            }
+            fastn_core::sync_utils::Status::NoConflict => file_status,
+            fastn_core::sync_utils::Status::CloneAddedRemoteAdded(_) => {
+                SyncStatus::CloneAddedRemoteAdded
+            }
+        };
+        FileSyncStatus {
+            path: path.to_string(),
+            status,
+        }
+    }
+}
+
+impl fastn_core::commands::report::Report for SyncStatusReport {
+    fn print(&self) {
+        for file in self.files.iter() {
+            println!("{}: {}", file.status.as_str(), file.path);
+        }
+    }
+
+    fn exit_status(&self) -> fastn_core::commands::report::ExitStatus {
+        if self
+            .files
+            .iter()
+            .all(|v| v.status.eq(&SyncStatus::UpToDate))
+        {
+            fastn_core::commands::report::ExitStatus::Clean
+        } else {
+            fastn_core::commands::report::ExitStatus::Changes
+        }
+    }
+}
+
+pub async fn handle_command(
+    matches: &clap::ArgMatches,
+) -> fastn_core::Result<fastn_core::commands::report::ExitStatus> {
     use fastn_core::utils::ValueOf;
 
-    sync_status(
+    let report = sync_status(
         &fastn_core::Config::read(None, true, None).await?,
         matches.value_of_("file"), // TODO: handle multiple files
     )
-    .await
+    .await?;
+    fastn_core::commands::report::output(
+        COMMAND,
+        &report,
+        fastn_core::commands::report::OutputFormat::from_matches(matches),
+    )
 }
 
-async fn sync_status(config: &fastn_core::Config, source: Option<&str>) -> fastn_core::Result<()> {
-    use itertools::Itertools;
-
+async fn sync_status(
+    config: &fastn_core::Config,
+    source: Option<&str>,
+) -> fastn_core::Result<SyncStatusReport> {
     let get_files_status = config.get_files_status().await?;
     if let Some(source) = source {
         if let Some(file_status) = get_files_status
             .iter()
             .find(|v| v.get_file_path().eq(source))
         {
-            print_status(file_status, true);
-            return Ok(());
+            return Ok(SyncStatusReport {
+                files: vec![file_status.into()],
+            });
         }
         return Err(fastn_core::Error::UsageError {
             message: format!("{} not found", source),
         });
     }
-    get_files_status
-        .iter()
-        .map(|v| print_status(v, false))
-        .collect_vec();
-    Ok(())
+    Ok(SyncStatusReport {
+        files: get_files_status
+            .iter()
+            .map(FileSyncStatus::from)
+            .filter(|v| !v.status.eq(&SyncStatus::UpToDate))
+            .collect(),
+    })
 }
 
 pub(crate) fn print_status(
     file_status: &fastn_core::sync_utils::FileStatus,
     print_untracked: bool,
 ) {
-    let (file_status, path, status) = match file_status {
-        fastn_core::sync_utils::FileStatus::Add { path, status, .. } => ("Added", path, status),
-        fastn_core::sync_utils::FileStatus::Update { path, status, .. } => {
-            ("Updated", path, status)
-        }
-        fastn_core::sync_utils::FileStatus::Delete { path, status, .. } => {
-            ("Deleted", path, status)
-        }
-        fastn_core::sync_utils::FileStatus::Uptodate { path, .. } => {
-            if print_untracked {
-                println!("Up-to-date: {}", path);
-            }
-            return;
-        }
-    };
-    match status {
-        fastn_core::sync_utils::Status::Conflict(_) => println!("Conflicted: {}", path),
-        fastn_core::sync_utils::Status::CloneEditedRemoteDeleted(_) => {
-            println!("CloneEditedRemoteDeleted: {}", path)
-        }
-        fastn_core::sync_utils::Status::CloneDeletedRemoteEdited(_) => {
-            println!("CloneDeletedRemoteEdited: {}", path)
-        }
-        fastn_core::sync_utils::Status::NoConflict => println!("{}: {}", file_status, path),
-        fastn_core::sync_utils::Status::CloneAddedRemoteAdded(_) => {
-            println!("CloneAddedRemoteAdded: {}", path)
-        }
+    let file_status = FileSyncStatus::from(file_status);
+    if file_status.status.eq(&SyncStatus::UpToDate) && !print_untracked {
+        return;
     }
+    println!("{}: {}", file_status.status.as_str(), file_status.path);
 }
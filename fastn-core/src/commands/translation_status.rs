@@ -1,35 +1,84 @@
-pub async fn translation_status(config: &fastn_core::Config) -> fastn_core::Result<()> {
+/// `TranslationStatusReport` is the result of `fastn translation-status`. In a translation
+/// package `files` is the status of every file of the original package, in an original package
+/// `translations` is the summary of every translation package.
+#[derive(serde::Serialize, Debug, Default)]
+pub struct TranslationStatusReport {
+    pub files: std::collections::BTreeMap<String, TranslationStatus>,
+    pub translations:
+        std::collections::BTreeMap<String, fastn_core::translation::TranslationStatusSummary>,
+}
+
+impl fastn_core::commands::report::Report for TranslationStatusReport {
+    fn print(&self) {
+        for (file, status) in self.files.iter() {
+            println!("{}: {}", status.as_str(), file);
+        }
+        for (name, status) in self.translations.iter() {
+            println!("Status for `{}` package:", name);
+            println!("{}", status.to_string());
+        }
+    }
+
+    fn exit_status(&self) -> fastn_core::commands::report::ExitStatus {
+        if self
+            .files
+            .values()
+            .all(|v| matches!(v, TranslationStatus::UptoDate))
+            && self
+                .translations
+                .values()
+                .all(|v| v.never_marked == 0 && v.missing == 0 && v.out_dated == 0)
+        {
+            fastn_core::commands::report::ExitStatus::Clean
+        } else {
+            fastn_core::commands::report::ExitStatus::Changes
+        }
+    }
+}
+
+pub async fn translation_status(
+    config: &fastn_core::Config,
+) -> fastn_core::Result<TranslationStatusReport> {
     // it can be original package or translation
     if config.is_translation_package() {
-        translation_package_status(config).await?;
+        translation_package_status(config).await
     } else if !config.package.translations.is_empty() {
-        original_package_status(config).await?;
+        Ok(original_package_status(config))
     } else {
-        return Err(fastn_core::Error::UsageError {
+        Err(fastn_core::Error::UsageError {
             message:
                 "`translation-status` works only when either `translation` or `translation-of` is set."
                     .to_string(),
-        });
-    };
-    Ok(())
+        })
+    }
 }
 
-async fn translation_package_status(config: &fastn_core::Config) -> fastn_core::Result<()> {
+async fn translation_package_status(
+    config: &fastn_core::Config,
+) -> fastn_core::Result<TranslationStatusReport> {
     let original_snapshots =
         fastn_core::snapshot::get_latest_snapshots(&config.original_path()?).await?;
-    let translation_status = get_translation_status(&original_snapshots, &config.root)?;
-    print_translation_status(&translation_status);
-    Ok(())
+    Ok(TranslationStatusReport {
+        files: get_translation_status(&original_snapshots, &config.root)?,
+        ..Default::default()
+    })
 }
 
-async fn original_package_status(config: &fastn_core::Config) -> fastn_core::Result<()> {
-    for translation in config.package.translations.iter() {
-        if let Some(ref status) = translation.translation_status_summary {
-            println!("Status for `{}` package:", translation.name);
-            println!("{}", status.to_string());
-        }
+fn original_package_status(config: &fastn_core::Config) -> TranslationStatusReport {
+    TranslationStatusReport {
+        translations: config
+            .package
+            .translations
+            .iter()
+            .filter_map(|translation| {
+                translation
+                    .translation_status_summary
+                    .clone()
+                    .map(|status| (translation.name.to_string(), status))
+            })
+            .collect(),
+        ..Default::default()
     }
-    Ok(())
 }
 
 pub(crate) fn get_translation_status(
@@ -65,17 +114,12 @@ pub(crate) fn get_translation_status(
     Ok(translation_status)
 }
 
-fn print_translation_status(
-    translation_status: &std::collections::BTreeMap<String, TranslationStatus>,
-) {
-    for (file, status) in translation_status {
-        println!("{}: {}", status.as_str(), file);
-    }
-}
-
-pub(crate) enum TranslationStatus {
+#[derive(serde::Serialize, Debug)]
+#[serde(rename_all = "kebab-case")]
+pub enum TranslationStatus {
     Missing,
     NeverMarked,
+    #[serde(rename = "out-dated")]
     Outdated,
     UptoDate,
 }
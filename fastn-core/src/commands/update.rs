@@ -6,7 +6,20 @@ pub async fn update(config: &fastn_core::Config) -> fastn_core::Result<()> {
         }
     };
 
+    // Dependencies are resolved again against their published versions, and the new resolution
+    // is recorded in a fresh FASTN.lock
+    if let Err(e) = std::fs::remove_file(config.lock_file()) {
+        match e.kind() {
+            std::io::ErrorKind::NotFound => {}
+            _ => return Err(e.into()),
+        }
+    };
+
     let c = fastn_core::Config::read(None, false, None).await?;
+    for dependency in c.package.dependencies.iter() {
+        c.resolve_package(&dependency.package).await?;
+    }
+
     if c.package.dependencies.is_empty() {
         println!("No dependencies to update.")
     } else if c.package.dependencies.len() == 1 {
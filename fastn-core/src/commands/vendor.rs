@@ -0,0 +1,156 @@
+/// `VENDOR_DIR` holds a copy of every dependency of the package, it is meant to be checked in
+/// along with the package so that it can be built without network access using `--offline`.
+pub const VENDOR_DIR: &str = ".vendor";
+
+pub const COMMAND: &str = "vendor";
+
+pub fn command() -> clap::Command {
+    clap::Command::new(COMMAND)
+        .about("Copy all dependencies of this package into the vendor directory")
+        .arg(clap::arg!(--partial "Vendor dependencies without a `zip` with only the files already downloaded"))
+        .after_help(
+            "Use `fastn build --offline` or `fastn serve --offline` to only use the vendored \
+            dependencies.",
+        )
+}
+
+pub async fn handle_command(matches: &clap::ArgMatches) -> fastn_core::Result<()> {
+    vendor(
+        &fastn_core::Config::read(None, false, None).await?,
+        matches.get_flag("partial"),
+    )
+    .await
+}
+
+/// `vendor()` resolves every transitive dependency of the package and snapshots it (FASTN.ftd,
+/// documents, assets and fonts) into `VENDOR_DIR`.
+///
+/// Dependencies that publish a `zip` are downloaded in full. For others we can only copy the
+/// files already downloaded into `.packages`, which may not be all the files the package needs,
+/// so they are refused unless `partial` is set.
+pub async fn vendor(config: &fastn_core::Config, partial: bool) -> fastn_core::Result<()> {
+    let vendor_dir = config.root.join(VENDOR_DIR);
+    let packages = config.resolve_all_dependencies().await?;
+
+    let without_zip: Vec<&str> = packages
+        .iter()
+        .filter(|p| p.zip.is_none())
+        .map(|p| p.name.as_str())
+        .collect();
+    if !without_zip.is_empty() && !partial {
+        return fastn_core::usage_error(format!(
+            "{} do not publish a `zip` and can not be vendored in full, run `fastn build` to \
+            download the files in use and then `fastn vendor --partial`",
+            without_zip.join(", ")
+        ));
+    }
+
+    if vendor_dir.exists() {
+        tokio::fs::remove_dir_all(&vendor_dir).await?;
+    }
+
+    for package in packages.iter() {
+        let start = std::time::Instant::now();
+        print!("Vendoring {} ... ", package.name);
+
+        let package_root = config.get_root_for_package(package);
+        match package.zip {
+            Some(ref zip) => extract_zip(package.name.as_str(), zip, &package_root).await?,
+            None => {
+                fastn_core::warning!(
+                    "{} does not publish a `zip`, only files already downloaded are vendored",
+                    package.name
+                );
+            }
+        }
+
+        for font in package.fonts.iter() {
+            if let Some(url) = font.get_url() {
+                if fastn_core::config::utils::is_http_url(&url) {
+                    continue;
+                }
+                package
+                    .resolve_by_file_name(url.as_str(), Some(&package_root), false)
+                    .await?;
+            }
+        }
+
+        fastn_core::copy_dir_all(package_root, vendor_dir.join(package.name.as_str())).await?;
+        fastn_core::utils::print_end(format!("Vendored {}", package.name).as_str(), start);
+    }
+
+    println!(
+        "Vendored {} dependencies into {}.",
+        packages.len(),
+        VENDOR_DIR
+    );
+    Ok(())
+}
+
+/// `use_vendored()` switches the current process to offline mode: `.packages` is replaced with
+/// the content of `VENDOR_DIR` and any attempt to download a dependency fails. It has to run
+/// before `Config::read()`, which resolves the dependencies.
+pub async fn use_vendored() -> fastn_core::Result<()> {
+    fastn_core::http::set_offline();
+
+    let current_dir: camino::Utf8PathBuf = tokio::fs::canonicalize(std::env::current_dir()?)
+        .await?
+        .try_into()?;
+    let root = fastn_core::Config::get_root_path(&current_dir).await?;
+    let vendor_dir = root.join(VENDOR_DIR);
+    if !vendor_dir.exists() {
+        return fastn_core::usage_error(format!(
+            "--offline needs the vendor directory {}, run `fastn vendor` to create it",
+            vendor_dir
+        ));
+    }
+
+    let packages_root = root.join(".packages");
+    if packages_root.exists() {
+        tokio::fs::remove_dir_all(&packages_root).await?;
+    }
+    fastn_core::copy_dir_all(vendor_dir, packages_root).await?;
+    Ok(())
+}
+
+async fn extract_zip(
+    package_name: &str,
+    download_url: &str,
+    package_root: &camino::Utf8PathBuf,
+) -> fastn_core::Result<()> {
+    let content = fastn_core::http::construct_url_and_get(download_url).await?;
+    fastn_core::package::lock::verify_download(
+        package_name,
+        package_root,
+        download_url,
+        content.as_slice(),
+    )?;
+    let path = std::env::temp_dir().join(format!("{}.zip", package_name.replace('/', "__")));
+    tokio::fs::write(&path, content).await?;
+
+    // TODO: switch to async_zip crate
+    let mut archive = zip::ZipArchive::new(std::fs::File::open(&path)?)?;
+    for i in 0..archive.len() {
+        let mut c_file = archive.by_index(i)?;
+        let out_path = match c_file.enclosed_name() {
+            Some(path) => path.to_owned(),
+            None => continue,
+        };
+        // zip files contain a top level folder, everything in it is the package
+        let out_path_without_folder = match out_path.to_str().and_then(|v| v.split_once('/')) {
+            Some((_, v)) if !v.is_empty() => v.to_string(),
+            _ => continue,
+        };
+        let file_extract_path = package_root.join(out_path_without_folder);
+        if c_file.name().ends_with('/') {
+            std::fs::create_dir_all(&file_extract_path)?;
+        } else {
+            if let Some(p) = file_extract_path.parent() {
+                std::fs::create_dir_all(p)?;
+            }
+            let mut outfile = std::fs::File::create(file_extract_path)?;
+            std::io::copy(&mut c_file, &mut outfile)?;
+        }
+    }
+    Ok(())
+}
@@ -26,6 +26,11 @@ impl FTDEdition {
     }
 }
 
+/// the config `fastn serve` shares across requests, see `Config::read_cached()`
+static CACHED_CONFIG: once_cell::sync::Lazy<antidote::Mutex<Option<Config>>> =
+    once_cell::sync::Lazy::new(|| antidote::Mutex::new(None));
+static CACHE_GENERATION: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
 #[derive(Debug, Clone)]
 pub struct Config {
     // Global Information
@@ -192,6 +197,19 @@ impl Config {
         self.fastn_dir().join("conflicted")
     }
 
+    /// `http_cache_dir` is where the `http` processor caches the responses of requests with a
+    /// `cache-ttl`, so that they are reused across `fastn build` runs.
+    pub fn http_cache_dir(&self) -> camino::Utf8PathBuf {
+        self.fastn_dir().join("http-cache")
+    }
+
+    /// `http_fixtures_dir` is where the `http` processor records the responses of requests with
+    /// `pin: true`. Unlike the cache they never expire and are meant to be committed along with
+    /// the package, so that building it does not need the network.
+    pub fn http_fixtures_dir(&self) -> camino::Utf8PathBuf {
+        self.root.join(".fixtures").join("http")
+    }
+
     /// every package's `.history` contains a file `.latest.ftd`. It looks a bit link this:
     ///
     /// ```ftd
@@ -324,7 +342,7 @@ impl Config {
                     continue;
                 }
                 let start = std::time::Instant::now();
-                print!("Processing {} ... ", url);
+                fastn_core::utils::print_start(url.as_str());
                 let content = self.get_file_and_resolve(url.as_str()).await?.1;
                 fastn_core::utils::update(&self.build_dir().join(&url), content.as_slice()).await?;
                 fastn_core::utils::print_end(format!("Processed {}", url).as_str(), start);
@@ -1024,6 +1042,12 @@ impl Config {
             )
             .await
             {
+                fastn_core::package::lock::verify(
+                    root,
+                    package.name.as_str(),
+                    "index.ftd",
+                    string.as_bytes(),
+                )?;
                 let base = root.join(".packages").join(package.name.as_str());
                 tokio::fs::create_dir_all(&base).await?;
                 tokio::fs::File::create(base.join("index.ftd"))
@@ -1037,6 +1061,12 @@ impl Config {
             )
             .await
             {
+                fastn_core::package::lock::verify(
+                    root,
+                    package.name.as_str(),
+                    "README.md",
+                    string.as_bytes(),
+                )?;
                 let base = root.join(".packages").join(package.name.as_str());
                 tokio::fs::create_dir_all(&base).await?;
                 tokio::fs::File::create(base.join("README.md"))
@@ -1055,6 +1085,12 @@ impl Config {
             crate::http::http_get_str(format!("{}/{}.ftd", base.trim_end_matches('/'), id).as_str())
                 .await
         {
+            fastn_core::package::lock::verify(
+                root,
+                package.name.as_str(),
+                format!("{}.ftd", id).as_str(),
+                string.as_bytes(),
+            )?;
             let (prefix, id) = match id.rsplit_once('/') {
                 Some((prefix, id)) => (format!("/{}", prefix), id.to_string()),
                 None => ("".to_string(), id),
@@ -1075,6 +1111,12 @@ impl Config {
         )
         .await
         {
+            fastn_core::package::lock::verify(
+                root,
+                package.name.as_str(),
+                format!("{}/index.ftd", id).as_str(),
+                string.as_bytes(),
+            )?;
             let base = root.join(".packages").join(package.name.as_str()).join(id);
             tokio::fs::create_dir_all(&base).await?;
             let file_path = base.join("index.ftd");
@@ -1088,6 +1130,12 @@ impl Config {
             crate::http::http_get_str(format!("{}/{}.md", base.trim_end_matches('/'), id).as_str())
                 .await
         {
+            fastn_core::package::lock::verify(
+                root,
+                package.name.as_str(),
+                format!("{}.md", id).as_str(),
+                string.as_bytes(),
+            )?;
             let base = root.join(".packages").join(package.name.as_str());
             tokio::fs::create_dir_all(&base).await?;
             tokio::fs::File::create(base.join(format!("{}.md", id)))
@@ -1101,6 +1149,12 @@ impl Config {
         )
         .await
         {
+            fastn_core::package::lock::verify(
+                root,
+                package.name.as_str(),
+                format!("{}/README.md", id).as_str(),
+                string.as_bytes(),
+            )?;
             let base = root.join(".packages").join(package.name.as_str());
             tokio::fs::create_dir_all(&base).await?;
             tokio::fs::File::create(base.join(format!("{}/README.md", id)))
@@ -1166,7 +1220,7 @@ impl Config {
         })
     }
 
-    async fn get_root_path(
+    pub(crate) async fn get_root_path(
         directory: &camino::Utf8PathBuf,
     ) -> fastn_core::Result<camino::Utf8PathBuf> {
         if let Some(fastn_ftd_root) = utils::find_root_for_file(directory, "FASTN.ftd") {
@@ -1275,6 +1329,7 @@ impl Config {
         };
         let fastn_doc = utils::fastn_doc(&root.join("FASTN.ftd")).await?;
         let package = fastn_core::Package::from_fastn_doc(&root, &fastn_doc)?;
+        fastn_core::package::lock::load(&root)?;
         let mut config = Config {
             package: package.clone(),
             packages_root: root.clone().join(".packages"),
@@ -1351,6 +1406,46 @@ impl Config {
         Ok(config)
     }
 
+    /// `read_cached()` is `Config::read()` for `fastn serve`: FASTN.ftd, the sitemap, the dynamic
+    /// urls and the manifests of the dependencies are parsed for the first request only, every
+    /// later request gets a clone of that config. `fastn_core::watcher` drops it when FASTN.ftd,
+    /// FASTN.lock, a dependency or any ftd document changes, see `Config::invalidate_cache()`.
+    pub async fn read_cached(req: &fastn_core::http::Request) -> fastn_core::Result<Config> {
+        // without the watcher nothing would ever invalidate the cache
+        if !fastn_core::watcher::is_watching() {
+            return Config::read(None, false, Some(req)).await;
+        }
+
+        Ok(Config::read_cached_at(None).await?.set_request(req.clone()))
+    }
+
+    /// `read_cached_at()` is the cached config of the package at `root`, it is read if there is
+    /// none.
+    async fn read_cached_at(root: Option<String>) -> fastn_core::Result<Config> {
+        if let Some(config) = CACHED_CONFIG.lock().as_ref() {
+            return Ok(config.clone());
+        }
+
+        let generation = CACHE_GENERATION.load(std::sync::atomic::Ordering::SeqCst);
+        let config = Config::read(root, false, None).await?;
+        {
+            let mut cached = CACHED_CONFIG.lock();
+            // the config may have changed while we were reading it, in which case the next
+            // request reads it again
+            if generation == CACHE_GENERATION.load(std::sync::atomic::Ordering::SeqCst) {
+                *cached = Some(config.clone());
+            }
+        }
+        Ok(config)
+    }
+
+    /// `invalidate_cache()` makes the next request read the config again.
+    pub fn invalidate_cache() {
+        let mut cached = CACHED_CONFIG.lock();
+        CACHE_GENERATION.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        *cached = None;
+    }
+
     pub fn set_request(mut self, req: fastn_core::http::Request) -> Self {
         self.request = Some(req);
         self
@@ -1368,14 +1463,44 @@ impl Config {
             return Ok(package.clone());
         }
 
-        let package = package
-            .get_and_resolve(&self.get_root_for_package(package))
-            .await?;
+        let package_root = self.get_root_for_package(package);
+        let locked = self.fetch_locked_fastn(package, &package_root).await?;
+        let mut package = package.get_and_resolve(&package_root).await?;
+        if locked.download_base_url.is_some() {
+            // files of a resolved version are served from where that version is published
+            package.download_base_url = locked.download_base_url;
+        }
 
         self.add_package(&package);
         Ok(package)
     }
 
+    /// `resolve_all_dependencies()` resolves every transitive dependency of the current package,
+    /// sorted by package name.
+    pub(crate) async fn resolve_all_dependencies(
+        &self,
+    ) -> fastn_core::Result<Vec<fastn_core::Package>> {
+        let mut resolved: Vec<fastn_core::Package> = vec![];
+        let mut unresolved: Vec<fastn_core::Package> = self
+            .package
+            .dependencies
+            .iter()
+            .map(|d| d.package.clone())
+            .collect();
+
+        while let Some(package) = unresolved.pop() {
+            if resolved.iter().any(|p| p.name.eq(&package.name)) {
+                continue;
+            }
+            let package = self.resolve_package(&package).await?;
+            unresolved.extend(package.dependencies.iter().map(|d| d.package.clone()));
+            resolved.push(package);
+        }
+
+        resolved.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(resolved)
+    }
+
     pub(crate) fn add_package(&self, package: &fastn_core::Package) {
         self.all_packages
             .borrow_mut()
@@ -1437,19 +1562,113 @@ impl Config {
         // can_read: false, confidential: true => false (cannot access)
         // can_read: false, confidential: false => true (can access)
 
-        use itertools::Itertools;
         let document_name = self.document_name_with_default(document_path);
-        if let Some(sitemap) = &self.package.sitemap {
+        self.can_read_id(req, document_name.as_str(), with_confidential)
+            .await
+    }
+
+    /// `can_read_path()` tells if the user can read `path`, a static file or a proxied url, which
+    /// are read protected by the sitemap item they are under, see `path_owner()`.
+    #[tracing::instrument(skip(req, self))]
+    pub(crate) async fn can_read_path(
+        &self,
+        req: &fastn_core::http::Request,
+        path: &str,
+    ) -> fastn_core::Result<bool> {
+        match self.path_owner(path).await? {
+            Some((package, owner)) => {
+                self.can_read_id_in(&package, req, owner.as_str(), true)
+                    .await
+            }
+            None => Ok(true),
+        }
+    }
+
+    /// `can_write_path()` tells if the user can send anything but a `GET` to `path`, a proxied
+    /// url. If the sitemap item it is under has readers or writers, only its writers can.
+    #[tracing::instrument(skip(req, self))]
+    pub(crate) async fn can_write_path(
+        &self,
+        req: &fastn_core::http::Request,
+        path: &str,
+    ) -> fastn_core::Result<bool> {
+        use itertools::Itertools;
+
+        let (package, owner) = match self.path_owner(path).await? {
+            Some(v) => v,
+            None => return Ok(true),
+        };
+        let sitemap = match package.sitemap.as_ref() {
+            Some(sitemap) => sitemap,
+            None => return Ok(true),
+        };
+        let (readers, _) = sitemap.readers(owner.as_str(), &package.groups);
+        let writers = sitemap.writers(owner.as_str(), &package.groups);
+        if readers.is_empty() && writers.is_empty() {
+            return Ok(true);
+        }
+        let identities =
+            fastn_core::user_group::group_identities(self, req, writers.as_slice()).await?;
+        fastn_core::user_group::belongs_to(
+            self,
+            writers.as_slice(),
+            identities.iter().collect_vec().as_slice(),
+        )
+    }
+
+    /// `path_owner()` is the package `path` belongs to, with the id of the sitemap item of that
+    /// package `path` is under, see `Sitemap::owner()`. `-/<dependency>/<path>` belongs to the
+    /// dependency, and is checked against its sitemap.
+    async fn path_owner(
+        &self,
+        path: &str,
+    ) -> fastn_core::Result<Option<(fastn_core::Package, String)>> {
+        let path = fastn_core::sitemap::resolve_path(path);
+        let (package_name, package) = self
+            .find_package_by_id(path.trim_start_matches('/'))
+            .await?;
+        let (package, path) = match utils::trim_package_name(path.as_str(), package_name.as_str()) {
+            Some(path) if package.name.eq(&self.package.name) => (self.package.clone(), path),
+            Some(path) if package.sitemap.is_none() => (self.update_sitemap(&package).await?, path),
+            Some(path) => (package, path),
+            None => (self.package.clone(), path),
+        };
+        let owner = package
+            .sitemap
+            .as_ref()
+            .and_then(|sitemap| sitemap.owner(path.as_str()));
+        Ok(owner.map(|owner| (package, owner)))
+    }
+
+    async fn can_read_id(
+        &self,
+        req: &fastn_core::http::Request,
+        document_name: &str,
+        with_confidential: bool,
+    ) -> fastn_core::Result<bool> {
+        self.can_read_id_in(&self.package, req, document_name, with_confidential)
+            .await
+    }
+
+    async fn can_read_id_in(
+        &self,
+        package: &fastn_core::Package,
+        req: &fastn_core::http::Request,
+        document_name: &str,
+        with_confidential: bool,
+    ) -> fastn_core::Result<bool> {
+        use itertools::Itertools;
+        if let Some(sitemap) = &package.sitemap {
             // TODO: This can be buggy in case of: if groups are used directly in sitemap are foreign groups
-            let (document_readers, confidential) =
-                sitemap.readers(document_name.as_str(), &self.package.groups);
+            let (document_readers, confidential) = sitemap.readers(document_name, &package.groups);
 
             // TODO: Need to check the confidential logic, if readers are not defined in the sitemap
             if document_readers.is_empty() {
                 return Ok(true);
             }
             let access_identities =
-                fastn_core::user_group::access_identities(self, req, &document_name, true).await?;
+                fastn_core::user_group::group_identities(self, req, document_readers.as_slice())
+                    .await?;
 
             let belongs_to = fastn_core::user_group::belongs_to(
                 self,
@@ -1492,3 +1711,64 @@ impl Config {
         Ok(false)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    // cargo test --package fastn-core --lib config::tests::read_cached
+    #[test]
+    fn read_cached() {
+        let root = camino::Utf8PathBuf::from_path_buf(std::env::temp_dir())
+            .unwrap()
+            .join(format!("fastn-config-{}", std::process::id()));
+        std::fs::create_dir_all(&root).unwrap();
+        let write = |name: &str| {
+            std::fs::write(
+                root.join("FASTN.ftd"),
+                format!("-- import: fastn\n\n-- fastn.package: {}\n", name),
+            )
+            .unwrap()
+        };
+        let read = || super::Config::read_cached_at(Some(root.to_string()));
+        // what the watcher does when `file` changes
+        let changed = |file: &str| {
+            if fastn_core::watcher::changes_config(root.join(file).as_std_path()) {
+                super::Config::invalidate_cache();
+            }
+        };
+        let write_id = |id: &str| {
+            std::fs::write(
+                root.join("index.ftd"),
+                format!("-- ftd.text: hello\nid: {}\n", id),
+            )
+            .unwrap()
+        };
+
+        write("foo.com");
+        write_id("greeting");
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+            .block_on(async {
+                super::Config::invalidate_cache();
+                assert_eq!(read().await.unwrap().package.name, "foo.com");
+
+                // the cached config is used till it is invalidated
+                write("bar.com");
+                assert_eq!(read().await.unwrap().package.name, "foo.com");
+
+                changed("FASTN.ftd");
+                assert_eq!(read().await.unwrap().package.name, "bar.com");
+
+                // the ids of the documents are cached with the config
+                assert!(read().await.unwrap().global_ids.contains_key("greeting"));
+                write_id("welcome");
+                changed("index.ftd");
+                let global_ids = read().await.unwrap().global_ids;
+                assert!(global_ids.contains_key("welcome"));
+                assert!(!global_ids.contains_key("greeting"));
+            });
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -36,7 +36,41 @@ pub async fn interpret_helper<'a>(
 ) -> ftd::interpreter::Result<ftd::interpreter::Document> {
     tracing::info!(document = name);
     let doc = cached_parse(name, source, line_number)?;
-    let mut s = ftd::interpreter::interpret_with_line_number(name, doc, line_number)?;
+    let s = ftd::interpreter::interpret_with_line_number(name, doc, line_number)?;
+    resolve_interpreter(name, s, lib, base_url, download_assets).await
+}
+
+/// `interpret_helper_with_recovery()` interprets `source` like `interpret_helper()`, but the
+/// sections of it that do not parse are left out instead of failing the document. Their errors
+/// are returned along with the result of interpreting the rest of it.
+pub async fn interpret_helper_with_recovery<'a>(
+    name: &str,
+    source: &str,
+    lib: &'a mut fastn_core::Library2022,
+    base_url: &str,
+    download_assets: bool,
+    line_number: usize,
+) -> (
+    Vec<ftd::interpreter::Error>,
+    ftd::interpreter::Result<ftd::interpreter::Document>,
+) {
+    let (errors, s) = ftd::interpreter::interpret_with_recovery(name, source, line_number);
+    let document = match s {
+        Ok(s) => resolve_interpreter(name, s, lib, base_url, download_assets).await,
+        Err(e) => Err(e),
+    };
+    (errors, document)
+}
+
+/// `resolve_interpreter()` runs the interpreter of the document `name` till it is done, it
+/// resolves the imports, processors and foreign variables the interpreter gets stuck on.
+async fn resolve_interpreter<'a>(
+    name: &str,
+    mut s: ftd::interpreter::Interpreter,
+    lib: &'a mut fastn_core::Library2022,
+    base_url: &str,
+    download_assets: bool,
+) -> ftd::interpreter::Result<ftd::interpreter::Document> {
     lib.module_package_map.insert(
         name.trim_matches('/').to_string(),
         lib.config.package.name.to_string(),
@@ -78,6 +112,7 @@ pub async fn interpret_helper<'a>(
                         doc_id: module,
                         line_number: ast.line_number(),
                         message: "Cannot find the module".to_string(),
+                        span: Default::default(),
                     },
                 )?;
                 let line_number = ast.line_number();
@@ -401,7 +436,9 @@ pub async fn resolve_foreign_variable2022(
                             doc_id: lib.document_id.to_string(),
                             line_number: 0,
                         })?;
-                    print!("Processing {}/{} ... ", package.name.as_str(), light_path);
+                    fastn_core::utils::print_start(
+                        format!("{}/{}", package.name.as_str(), light_path).as_str(),
+                    );
                     fastn_core::utils::write(
                         &lib.config.build_dir().join("-").join(package.name.as_str()),
                         light_path.as_str(),
@@ -455,7 +492,9 @@ pub async fn resolve_foreign_variable2022(
                         .resolve_by_file_name(dark_path.as_str(), None, false)
                         .await
                     {
-                        print!("Processing {}/{} ... ", package.name.as_str(), dark_path);
+                        fastn_core::utils::print_start(
+                            format!("{}/{}", package.name.as_str(), dark_path).as_str(),
+                        );
                         fastn_core::utils::write(
                             &lib.config.build_dir().join("-").join(package.name.as_str()),
                             dark_path.as_str(),
@@ -554,7 +593,7 @@ async fn download(
                 doc_id: lib.document_id.to_string(),
                 line_number: 0,
             })?;
-        print!("Processing {}/{} ... ", package.name, path);
+        fastn_core::utils::print_start(format!("{}/{}", package.name, path).as_str());
         fastn_core::utils::write(
             &lib.config.build_dir().join("-").join(package.name.as_str()),
             path,
@@ -676,7 +715,9 @@ pub async fn resolve_foreign_variable2(
                             doc_id: lib.document_id.to_string(),
                             line_number: 0,
                         })?;
-                    print!("Processing {}/{} ... ", package.name.as_str(), light_path);
+                    fastn_core::utils::print_start(
+                        format!("{}/{}", package.name.as_str(), light_path).as_str(),
+                    );
                     fastn_core::utils::write(
                         &lib.config.build_dir().join("-").join(package.name.as_str()),
                         light_path.as_str(),
@@ -731,7 +772,9 @@ pub async fn resolve_foreign_variable2(
                         .resolve_by_file_name(dark_path.as_str(), None, false)
                         .await
                     {
-                        print!("Processing {}/{} ... ", package.name.as_str(), dark_path);
+                        fastn_core::utils::print_start(
+                            format!("{}/{}", package.name.as_str(), dark_path).as_str(),
+                        );
                         fastn_core::utils::write(
                             &lib.config.build_dir().join("-").join(package.name.as_str()),
                             dark_path.as_str(),
@@ -72,12 +72,28 @@ pub enum Error {
     #[error("GenericError: {}", _0)]
     GenericError(String),
 
+    #[error("GitError: {}", _0)]
+    GitError(String),
+
+    #[error("SsrError: {doc_id}: {message}")]
+    SsrError { doc_id: String, message: String },
+
     #[error("GroupNotFound: id: {id}, {message}")]
     GroupNotFound { id: String, message: String },
 
     #[error("CRAboutNotFound CR#{cr_number}: {message}")]
     CRAboutNotFound { message: String, cr_number: usize },
 
+    #[error("MergeConflict CR#{src} into CR#{dest}: {}", .files.join(", "))]
+    MergeConflict {
+        src: usize,
+        dest: usize,
+        files: Vec<String>,
+    },
+
+    #[error("SqliteError: {}", _0)]
+    SqliteError(#[from] rusqlite::Error),
+
     #[error("QueryPayloadError: {}", _0)]
     QueryPayloadError(#[from] actix_web::error::QueryPayloadError),
 
@@ -98,4 +114,28 @@ impl Error {
     pub fn generic_err<T: AsRef<str> + ToString, O>(error: T) -> fastn_core::Result<O> {
         Err(Self::generic(error))
     }
+
+    /// `diagnostic()` locates an ftd error in `source`, the source of the document the error is
+    /// reported for, so it can be shown with the offending part of the document underlined.
+    pub fn diagnostic(&self, source: &str) -> Option<ftd::Diagnostic> {
+        match self {
+            Self::FTDP1Error(e) => e.diagnostic(source),
+            Self::FTDAstError(e) => e.diagnostic(source),
+            Self::FTDExecError(e) => e.diagnostic(source),
+            Self::FTDInterpreterError(e) => e.diagnostic(source),
+            _ => None,
+        }
+    }
+
+    /// `doc_id()` is the id of the document an ftd error is reported for
+    pub fn doc_id(&self) -> Option<&str> {
+        match self {
+            Self::FTDP1Error(e) => e.doc_id(),
+            Self::FTDAstError(e) => e.doc_id(),
+            Self::FTDExecError(e) => e.doc_id(),
+            Self::FTDInterpreterError(e) => e.doc_id(),
+            Self::SsrError { doc_id, .. } => Some(doc_id),
+            _ => None,
+        }
+    }
 }
@@ -153,6 +153,8 @@ pub fn package_ignores(
     overrides.add("!fastn")?;
     overrides.add("!rust-toolchain")?;
     overrides.add("!.build")?;
+    overrides.add(format!("!{}", fastn_core::commands::vendor::VENDOR_DIR).as_str())?;
+    overrides.add(format!("!{}", fastn_core::package::lock::LOCK_FILE).as_str())?;
     for ignored_path in &package.ignored_paths {
         overrides.add(format!("!{}", ignored_path).as_str())?;
     }
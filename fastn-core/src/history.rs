@@ -1,3 +1,5 @@
+mod git;
+
 #[derive(serde::Serialize, serde::Deserialize, std::fmt::Debug, PartialEq, Eq, Clone)]
 pub struct FileHistory {
     pub filename: String,
@@ -68,11 +70,34 @@ impl FileOperation {
 
 impl fastn_core::Config {
     pub async fn get_history(&self) -> fastn_core::Result<Vec<FileHistory>> {
+        if self
+            .package
+            .history_backend
+            .eq(&fastn_package::old_fastn::HistoryBackend::Git)
+        {
+            return git::get_history(self).await;
+        }
         let history_file_path = self.history_file();
         let history_content = tokio::fs::read_to_string(history_file_path).await?;
         FileHistory::from_ftd(history_content.as_str())
     }
 
+    /// `history_content()` is the content of `version` of `file`.
+    pub(crate) async fn history_content(
+        &self,
+        file: &str,
+        version: i32,
+    ) -> fastn_core::Result<Vec<u8>> {
+        if self
+            .package
+            .history_backend
+            .eq(&fastn_package::old_fastn::HistoryBackend::Git)
+        {
+            return git::read_version(self, file, version).await;
+        }
+        Ok(tokio::fs::read(self.history_path(file, version)).await?)
+    }
+
     pub async fn get_remote_manifest(
         &self,
         with_deleted: bool,
@@ -198,19 +223,42 @@ impl FileHistory {
     }
 }
 
+/// `close_cr()` records in the history that CR `cr` is closed. Only the git backend keeps a record,
+/// as a merge of the CR's branch.
+pub(crate) async fn close_cr(config: &fastn_core::Config, cr: usize) -> fastn_core::Result<()> {
+    if config
+        .package
+        .history_backend
+        .eq(&fastn_package::old_fastn::HistoryBackend::Git)
+    {
+        git::close_cr(config, cr).await?;
+    }
+    Ok(())
+}
+
 pub(crate) async fn insert_into_history(
-    root: &camino::Utf8PathBuf,
+    config: &fastn_core::Config,
     file_list: &std::collections::BTreeMap<String, fastn_core::history::FileEditTemp>,
     history: &mut Vec<fastn_core::history::FileHistory>,
 ) -> fastn_core::Result<()> {
     use itertools::Itertools;
 
+    if config
+        .package
+        .history_backend
+        .eq(&fastn_package::old_fastn::HistoryBackend::Git)
+    {
+        git::insert_into_history(config, file_list).await?;
+        *history = config.get_history().await?;
+        return Ok(());
+    }
+
     let mut file_history: std::collections::BTreeMap<String, fastn_core::history::FileHistory> =
         history
             .iter_mut()
             .map(|v| (v.filename.to_string(), v.clone()))
             .collect();
-    insert_into_history_(root, file_list, &mut file_history).await?;
+    insert_into_history_(&config.root, file_list, &mut file_history).await?;
     *history = file_history.into_values().collect_vec();
     Ok(())
 }
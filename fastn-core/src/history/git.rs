@@ -0,0 +1,882 @@
+//! `history-backend: git` keeps the history of the package as commits in the git repository at
+//! the package root, instead of `.remote-state/history.ftd`:
+//!
+//! - the files of the package are committed on the branch `HEAD` points to
+//! - the files of CR `n` are committed on the branch `cr-<n>`, forked from the main branch:
+//!   `-/<n>/<path>` as `<path>`, its own files like `-/<n>/-/meta.ftd` as `-/meta.ftd` and its
+//!   tracks `.tracks/-/<n>/<path>` as `-/tracks/<path>`
+//! - edits synced into the main branch from a CR, like `fastn merge <cr> main` does, are committed
+//!   as a merge of the CR's branch, and so is closing the CR
+//! - with a detached `HEAD`, the main branch is `HEAD` itself
+//!
+//! The versions of a file are the commits touching it on its branch. Their content is cached in
+//! `.remote-state/history`, and `.remote-state/history.ftd` is kept up to date, so everything
+//! reading the history works the same for both the backends.
+
+const DEFAULT_MESSAGE: &str = "fastn sync";
+const CR_TRAILER: &str = "fastn-cr:";
+const SRC_CR_TRAILER: &str = "fastn-src-cr:";
+
+/// every commit is built in its own index, see `Git::commit()`
+static INDEX_COUNTER: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Branch {
+    Main,
+    CR(usize),
+}
+
+impl Branch {
+    /// The branch a file of the package is committed on, and its path there.
+    fn of(file: &str) -> (Branch, String) {
+        fn cr_path(path: &str) -> Option<(usize, &str)> {
+            let (cr, path) = path.split_once('/')?;
+            Some((cr.parse().ok()?, path))
+        }
+
+        if let Some((cr, path)) = file.strip_prefix(".tracks/-/").and_then(cr_path) {
+            return (Branch::CR(cr), format!("-/tracks/{}", path));
+        }
+        if let Some((cr, path)) = file.strip_prefix("-/").and_then(cr_path) {
+            return (Branch::CR(cr), path.to_string());
+        }
+        (Branch::Main, file.to_string())
+    }
+
+    /// The inverse of `Branch::of()`.
+    fn file(&self, path: &str) -> String {
+        match self {
+            Branch::Main => path.to_string(),
+            Branch::CR(cr) => match path.strip_prefix("-/tracks/") {
+                Some(path) => format!(".tracks/{}/{}", fastn_core::cr::cr_path(*cr), path),
+                None => format!("{}/{}", fastn_core::cr::cr_path(*cr), path),
+            },
+        }
+    }
+}
+
+struct Commit {
+    timestamp: u128,
+    author: String,
+    message: Option<String>,
+    cr: Option<usize>,
+    src_cr: Option<usize>,
+    /// the files the commit touches, wrt its first parent
+    files: Vec<Change>,
+}
+
+struct Change {
+    operation: fastn_core::history::FileOperation,
+    path: String,
+    /// the blob of the file after the commit
+    blob: String,
+}
+
+struct Git<'a> {
+    root: &'a camino::Utf8Path,
+}
+
+impl<'a> Git<'a> {
+    async fn run(
+        &self,
+        args: &[&str],
+        envs: &[(&str, &str)],
+        stdin: Option<&[u8]>,
+    ) -> fastn_core::Result<Vec<u8>> {
+        use tokio::io::AsyncWriteExt;
+
+        let mut child = tokio::process::Command::new("git")
+            .args(args)
+            .envs(envs.iter().copied())
+            .current_dir(self.root)
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .spawn()
+            .map_err(|e| fastn_core::Error::GitError(format!("git {}: {}", args.join(" "), e)))?;
+        {
+            let mut child_stdin = child.stdin.take().expect("stdin is piped");
+            if let Some(stdin) = stdin {
+                child_stdin.write_all(stdin).await?;
+            }
+        }
+        let output = child.wait_with_output().await?;
+        if !output.status.success() {
+            return Err(fastn_core::Error::GitError(format!(
+                "git {}: {}",
+                args.join(" "),
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        Ok(output.stdout)
+    }
+
+    async fn output(&self, args: &[&str]) -> fastn_core::Result<String> {
+        Ok(String::from_utf8(self.run(args, &[], None).await?)?
+            .trim()
+            .to_string())
+    }
+
+    /// The commit `reference` points to, `None` if it does not exist yet.
+    async fn commit_of(&self, reference: &str) -> fastn_core::Result<Option<String>> {
+        let reference = format!("{}^{{commit}}", reference);
+        match self
+            .output(&["rev-parse", "--verify", "--quiet", reference.as_str()])
+            .await
+        {
+            Ok(commit) => Ok(Some(commit)),
+            Err(fastn_core::Error::GitError(_)) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn reference(&self, branch: Branch) -> fastn_core::Result<String> {
+        match branch {
+            Branch::Main => match self.output(&["symbolic-ref", "--quiet", "HEAD"]).await {
+                Ok(reference) => Ok(reference),
+                // a detached HEAD is updated in place
+                Err(fastn_core::Error::GitError(_)) => Ok("HEAD".to_string()),
+                Err(e) => Err(e),
+            },
+            Branch::CR(cr) => Ok(format!("refs/heads/cr-{}", cr)),
+        }
+    }
+
+    async fn cr_branches(&self) -> fastn_core::Result<Vec<usize>> {
+        Ok(self
+            .output(&[
+                "for-each-ref",
+                "--format=%(refname:short)",
+                "refs/heads/cr-*",
+            ])
+            .await?
+            .lines()
+            .filter_map(|v| v.strip_prefix("cr-")?.parse().ok())
+            .collect())
+    }
+
+    /// The first parent history of `reference`, newest first.
+    async fn log(&self, reference: &str) -> fastn_core::Result<Vec<Commit>> {
+        let output = String::from_utf8(
+            self.run(
+                &[
+                    "log",
+                    "--first-parent",
+                    "-m",
+                    "--no-renames",
+                    "--raw",
+                    "--no-abbrev",
+                    "--format=%x1e%H%x1f%ct%x1f%an%x1f%B%x1f",
+                    reference,
+                    "--",
+                ],
+                &[],
+                None,
+            )
+            .await?,
+        )?;
+
+        let mut commits = vec![];
+        for record in output.split('\x1e').filter(|v| !v.trim().is_empty()) {
+            let fields: Vec<&str> = record.split('\x1f').collect();
+            if fields.len() != 5 {
+                return Err(fastn_core::Error::GitError(format!(
+                    "can't read the log of {}",
+                    reference
+                )));
+            }
+
+            let mut message = None;
+            let mut cr = None;
+            let mut src_cr = None;
+            for line in fields[3].lines() {
+                if let Some(v) = line.strip_prefix(CR_TRAILER) {
+                    cr = v.trim().parse().ok();
+                } else if let Some(v) = line.strip_prefix(SRC_CR_TRAILER) {
+                    src_cr = v.trim().parse().ok();
+                } else if message.is_none() && !line.trim().is_empty() {
+                    message = Some(line.trim().to_string());
+                }
+            }
+
+            // `:<old mode> <new mode> <old blob> <new blob> <status>\t<path>`
+            let mut files = vec![];
+            for line in fields[4].lines() {
+                let (meta, path) = match line.split_once('\t') {
+                    Some(v) => v,
+                    None => continue,
+                };
+                let meta: Vec<&str> = meta.split_whitespace().collect();
+                let (blob, status) = match meta.as_slice() {
+                    [_, _, _, blob, status] => (blob, status),
+                    _ => continue,
+                };
+                let operation = match status.chars().next() {
+                    Some('A') => fastn_core::history::FileOperation::Added,
+                    Some('D') => fastn_core::history::FileOperation::Deleted,
+                    _ => fastn_core::history::FileOperation::Updated,
+                };
+                files.push(Change {
+                    operation,
+                    path: path.to_string(),
+                    blob: blob.to_string(),
+                });
+            }
+
+            commits.push(Commit {
+                timestamp: fields[1].parse::<u128>().unwrap_or_default() * 1_000_000_000,
+                author: fields[2].to_string(),
+                message: message.filter(|v| v.ne(DEFAULT_MESSAGE)),
+                cr,
+                src_cr,
+                files,
+            });
+        }
+        Ok(commits)
+    }
+
+    /// Commits `files` on `reference`, without touching the working tree or the index. A file
+    /// without content is deleted. The commit's tree is the first parent's with `files` applied.
+    ///
+    /// `reference` is only moved if it still points to `old`, `None` if it should not exist yet,
+    /// so a concurrent commit on the same branch fails instead of being lost.
+    #[allow(clippy::too_many_arguments)]
+    async fn commit(
+        &self,
+        reference: &str,
+        old: Option<&str>,
+        parents: &[String],
+        files: &[(String, Option<Vec<u8>>)],
+        message: &str,
+        author: Option<&str>,
+    ) -> fastn_core::Result<String> {
+        let git_dir = self.output(&["rev-parse", "--absolute-git-dir"]).await?;
+        let index = camino::Utf8PathBuf::from(git_dir).join(format!(
+            "fastn-index-{}-{}",
+            std::process::id(),
+            INDEX_COUNTER.fetch_add(1, std::sync::atomic::Ordering::Relaxed)
+        ));
+        let tree = self.write_tree(&index, parents.first(), files).await;
+        if index.exists() {
+            tokio::fs::remove_file(&index).await?;
+        }
+        let tree = tree?;
+
+        let mut args = vec!["commit-tree", tree.as_str()];
+        for parent in parents {
+            args.extend(["-p", parent.as_str()]);
+        }
+        args.extend(["-F", "-"]);
+        let envs = match author {
+            Some(author) => vec![("GIT_AUTHOR_NAME", author)],
+            None => vec![],
+        };
+        let commit = String::from_utf8(
+            self.run(args.as_slice(), envs.as_slice(), Some(message.as_bytes()))
+                .await?,
+        )?;
+        let commit = commit.trim().to_string();
+        self.run(
+            &[
+                "update-ref",
+                reference,
+                commit.as_str(),
+                old.unwrap_or_default(),
+            ],
+            &[],
+            None,
+        )
+        .await?;
+        Ok(commit)
+    }
+
+    async fn write_tree(
+        &self,
+        index: &camino::Utf8Path,
+        parent: Option<&String>,
+        files: &[(String, Option<Vec<u8>>)],
+    ) -> fastn_core::Result<String> {
+        let index_env = [("GIT_INDEX_FILE", index.as_str())];
+        match parent {
+            Some(parent) => self.run(&["read-tree", parent], &index_env, None).await?,
+            None => {
+                self.run(&["read-tree", "--empty"], &index_env, None)
+                    .await?
+            }
+        };
+        for (path, content) in files {
+            match content {
+                Some(content) => {
+                    let blob = String::from_utf8(
+                        self.run(&["hash-object", "-w", "--stdin"], &[], Some(content))
+                            .await?,
+                    )?;
+                    let cache_info = format!("100644,{},{}", blob.trim(), path);
+                    self.run(
+                        &["update-index", "--add", "--cacheinfo", cache_info.as_str()],
+                        &index_env,
+                        None,
+                    )
+                    .await?;
+                }
+                None => {
+                    self.run(
+                        &["update-index", "--force-remove", "--", path],
+                        &index_env,
+                        None,
+                    )
+                    .await?;
+                }
+            }
+        }
+        Ok(
+            String::from_utf8(self.run(&["write-tree"], &index_env, None).await?)?
+                .trim()
+                .to_string(),
+        )
+    }
+
+    /// The commits of `branch` that belong to it, newest first, `None` if it has no commits.
+    async fn branch_log(&self, branch: Branch) -> fastn_core::Result<Option<Vec<Commit>>> {
+        let reference = self.reference(branch).await?;
+        if self.commit_of(reference.as_str()).await?.is_none() {
+            return Ok(None);
+        }
+        let commits = self.log(reference.as_str()).await?;
+        Ok(Some(match branch {
+            Branch::Main => commits,
+            // the commits before the branch forked belong to the main branch
+            Branch::CR(cr) => commits
+                .into_iter()
+                .take_while(|v| v.cr.eq(&Some(cr)))
+                .collect(),
+        }))
+    }
+
+    /// The blobs of the files at `paths`, as git would store them.
+    async fn hash_objects(&self, paths: &[&str]) -> fastn_core::Result<Vec<String>> {
+        let mut blobs = vec![];
+        for paths in paths.chunks(256) {
+            let mut args = vec!["hash-object", "--no-filters", "--"];
+            args.extend(paths);
+            blobs.extend(
+                self.output(args.as_slice())
+                    .await?
+                    .lines()
+                    .map(|v| v.trim().to_string()),
+            );
+        }
+        Ok(blobs)
+    }
+}
+
+/// Reads the history of the package from its git repository.
+pub(crate) async fn get_history(
+    config: &fastn_core::Config,
+) -> fastn_core::Result<Vec<fastn_core::history::FileHistory>> {
+    use itertools::Itertools;
+
+    let git = Git {
+        root: config.root.as_path(),
+    };
+    let history = read_history(&git, |file, version| config.history_path(file, version)).await?;
+    fastn_core::utils::update(
+        &config.history_file(),
+        fastn_core::history::FileHistory::to_ftd(history.iter().collect_vec().as_slice())
+            .as_bytes(),
+    )
+    .await?;
+    Ok(history)
+}
+
+/// The history of every file of the package, with the content of each version kept at
+/// `history_path(file, version)`. A copy that does not match the version, because the branch was
+/// rewritten since it was made, is replaced.
+async fn read_history(
+    git: &Git<'_>,
+    history_path: impl Fn(&str, i32) -> camino::Utf8PathBuf,
+) -> fastn_core::Result<Vec<fastn_core::history::FileHistory>> {
+    use itertools::Itertools;
+
+    let mut branches = vec![];
+    if let Some(commits) = git.branch_log(Branch::Main).await? {
+        branches.push((Branch::Main, commits));
+    }
+    for cr in git.cr_branches().await? {
+        if let Some(commits) = git.branch_log(Branch::CR(cr)).await? {
+            branches.push((Branch::CR(cr), commits));
+        }
+    }
+
+    let mut history: std::collections::BTreeMap<String, fastn_core::history::FileHistory> =
+        Default::default();
+    // `(path, blob)` of the content of every version
+    let mut versions = vec![];
+    for (branch, commits) in branches {
+        for commit in commits.iter().rev() {
+            for change in commit.files.iter() {
+                if branch.eq(&Branch::Main) && Branch::of(&change.path).0.ne(&Branch::Main) {
+                    // CR files committed to the main branch by hand
+                    continue;
+                }
+                let file = branch.file(&change.path);
+                let file_history = history.entry(file.to_string()).or_insert_with(|| {
+                    fastn_core::history::FileHistory {
+                        filename: file.to_string(),
+                        file_edit: vec![],
+                    }
+                });
+                let version = file_history
+                    .file_edit
+                    .first()
+                    .map(|v| v.version + 1)
+                    .unwrap_or(1);
+                file_history.file_edit.insert(
+                    0,
+                    fastn_core::history::FileEdit {
+                        message: commit.message.clone(),
+                        timestamp: commit.timestamp,
+                        version,
+                        author: Some(commit.author.to_string()),
+                        src_cr: commit.src_cr,
+                        operation: change.operation.clone(),
+                    },
+                );
+                if !change.operation.is_deleted() {
+                    versions.push((history_path(file.as_str(), version), change.blob.clone()));
+                }
+            }
+        }
+    }
+
+    let existing = versions
+        .iter()
+        .filter(|(path, _)| path.exists())
+        .map(|(path, _)| path.as_str())
+        .collect_vec();
+    let existing: std::collections::HashMap<&str, String> = existing
+        .iter()
+        .copied()
+        .zip(git.hash_objects(existing.as_slice()).await?)
+        .collect();
+    for (path, blob) in versions.iter() {
+        if existing.get(path.as_str()) == Some(blob) {
+            continue;
+        }
+        let content = git.run(&["cat-file", "blob", blob], &[], None).await?;
+        fastn_core::utils::update(path, content.as_slice()).await?;
+    }
+
+    Ok(history.into_values().collect_vec())
+}
+
+/// The content of `version` of `file`, read from the repository.
+pub(crate) async fn read_version(
+    config: &fastn_core::Config,
+    file: &str,
+    version: i32,
+) -> fastn_core::Result<Vec<u8>> {
+    let git = Git {
+        root: config.root.as_path(),
+    };
+    let (branch, path) = Branch::of(file);
+    let commits = git.branch_log(branch).await?.unwrap_or_default();
+    let change = commits
+        .iter()
+        .rev()
+        .flat_map(|v| v.files.iter())
+        .filter(|v| v.path.eq(&path))
+        .nth((version as usize).saturating_sub(1))
+        .filter(|v| version > 0 && !v.operation.is_deleted());
+    match change {
+        Some(change) => {
+            git.run(&["cat-file", "blob", change.blob.as_str()], &[], None)
+                .await
+        }
+        None => Err(fastn_core::Error::GitError(format!(
+            "version {} of {} not found",
+            version, file
+        ))),
+    }
+}
+
+/// Closes CR `cr`: its meta, marked closed, is committed on its branch, and the branch is merged
+/// into the main branch. The merge keeps the files of the main branch, the edits of the CR are
+/// only synced into it by `fastn merge`.
+pub(crate) async fn close_cr(config: &fastn_core::Config, cr: usize) -> fastn_core::Result<()> {
+    let git = Git {
+        root: config.root.as_path(),
+    };
+    let cr_reference = git.reference(Branch::CR(cr)).await?;
+    let cr_commit = match git.commit_of(cr_reference.as_str()).await? {
+        Some(commit) => commit,
+        // nothing of the CR was synced, there is nothing to merge
+        None => return Ok(()),
+    };
+    let main_reference = git.reference(Branch::Main).await?;
+    let main_commit = match git.commit_of(main_reference.as_str()).await? {
+        Some(commit) => commit,
+        None => return Ok(()),
+    };
+
+    let (_, meta_path) = Branch::of(config.path_without_root(&config.cr_meta_path(cr))?.as_str());
+    let meta = tokio::fs::read(config.cr_meta_path(cr)).await?;
+    let cr_commit = git
+        .commit(
+            cr_reference.as_str(),
+            Some(cr_commit.as_str()),
+            &[cr_commit.clone()],
+            &[(meta_path, Some(meta))],
+            format!("Close CR {}\n\n{} {}", cr, CR_TRAILER, cr).as_str(),
+            None,
+        )
+        .await?;
+    git.commit(
+        main_reference.as_str(),
+        Some(main_commit.as_str()),
+        &[main_commit.clone(), cr_commit],
+        &[],
+        format!("Merge CR {}\n\n{} {}", cr, SRC_CR_TRAILER, cr).as_str(),
+        None,
+    )
+    .await?;
+    Ok(())
+}
+
+/// Commits the files in `file_list`, with their content in the package root, on their branches.
+pub(crate) async fn insert_into_history(
+    config: &fastn_core::Config,
+    file_list: &std::collections::BTreeMap<String, fastn_core::history::FileEditTemp>,
+) -> fastn_core::Result<()> {
+    let git = Git {
+        root: config.root.as_path(),
+    };
+    commit_files(&git, config.root.as_path(), file_list).await
+}
+
+async fn commit_files(
+    git: &Git<'_>,
+    root: &camino::Utf8Path,
+    file_list: &std::collections::BTreeMap<String, fastn_core::history::FileEditTemp>,
+) -> fastn_core::Result<()> {
+    type Group = (Branch, Option<usize>, Option<String>, Option<String>);
+
+    // one commit for the files of a branch with the same message, author and source CR, the CRs
+    // are committed first so the merges into the main branch include their latest edits
+    let mut groups: std::collections::BTreeMap<Group, Vec<(String, Option<Vec<u8>>)>> =
+        Default::default();
+    for (file, file_op) in file_list {
+        let (branch, path) = Branch::of(file);
+        let content = if file_op.operation.is_deleted() {
+            None
+        } else {
+            Some(tokio::fs::read(root.join(file)).await?)
+        };
+        let group = (
+            branch,
+            file_op.src_cr,
+            file_op.message.clone(),
+            file_op.author.clone(),
+        );
+        groups.entry(group).or_default().push((path, content));
+    }
+    let groups = {
+        let (mut crs, main): (Vec<_>, Vec<_>) = groups
+            .into_iter()
+            .partition(|((branch, ..), _)| branch.ne(&Branch::Main));
+        crs.extend(main);
+        crs
+    };
+
+    for ((branch, src_cr, message, author), files) in groups {
+        let reference = git.reference(branch).await?;
+        let old = git.commit_of(reference.as_str()).await?;
+        let mut parents = vec![];
+        match old {
+            Some(ref commit) => parents.push(commit.to_string()),
+            None => {
+                // a CR's branch forks from the main branch
+                let main = git.reference(Branch::Main).await?;
+                if let Some(commit) = git.commit_of(main.as_str()).await? {
+                    parents.push(commit);
+                }
+            }
+        }
+
+        let mut message = message.unwrap_or_else(|| DEFAULT_MESSAGE.to_string());
+        if let Branch::CR(cr) = branch {
+            message = format!("{}\n\n{} {}", message, CR_TRAILER, cr);
+        }
+        if let Some(src_cr) = src_cr {
+            message = format!("{}\n\n{} {}", message, SRC_CR_TRAILER, src_cr);
+            let src_reference = git.reference(Branch::CR(src_cr)).await?;
+            if branch.eq(&Branch::Main) {
+                if let Some(commit) = git.commit_of(src_reference.as_str()).await? {
+                    parents.push(commit);
+                }
+            }
+        }
+
+        git.commit(
+            reference.as_str(),
+            old.as_deref(),
+            parents.as_slice(),
+            files.as_slice(),
+            message.as_str(),
+            author.as_deref(),
+        )
+        .await?;
+
+        if branch.eq(&Branch::Main) {
+            // the synced files in the working tree are what got committed
+            let mut args = vec!["reset", "--quiet", "--"];
+            args.extend(files.iter().map(|(path, _)| path.as_str()));
+            git.run(args.as_slice(), &[], None).await?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn branch_of() {
+        use super::Branch;
+
+        for (file, branch, path) in [
+            ("index.ftd", Branch::Main, "index.ftd"),
+            ("-/ui/index.ftd", Branch::Main, "-/ui/index.ftd"),
+            ("-/2/index.ftd", Branch::CR(2), "index.ftd"),
+            ("-/2/-/meta.ftd", Branch::CR(2), "-/meta.ftd"),
+            (".tracks/-/2/a/b.ftd", Branch::CR(2), "-/tracks/a/b.ftd"),
+        ] {
+            assert_eq!(Branch::of(file), (branch, path.to_string()));
+            assert_eq!(branch.file(path), file);
+        }
+    }
+
+    fn block_on<F: std::future::Future>(f: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+            .block_on(f)
+    }
+
+    /// A git repository in a new temporary directory, with one commit on `main`.
+    fn repository(name: &str) -> camino::Utf8PathBuf {
+        let root = camino::Utf8PathBuf::from_path_buf(std::env::temp_dir())
+            .unwrap()
+            .join(format!("fastn-git-{}-{}", name, std::process::id()));
+        if root.exists() {
+            std::fs::remove_dir_all(&root).unwrap();
+        }
+        std::fs::create_dir_all(&root).unwrap();
+        for args in [
+            vec!["init", "--quiet", "--initial-branch=main"],
+            vec!["config", "user.name", "fastn"],
+            vec!["config", "user.email", "fastn@example.com"],
+            vec!["commit", "--quiet", "--allow-empty", "-m", "init"],
+        ] {
+            let status = std::process::Command::new("git")
+                .args(args)
+                .current_dir(&root)
+                .status()
+                .unwrap();
+            assert!(status.success());
+        }
+        root
+    }
+
+    fn edit(
+        root: &camino::Utf8Path,
+        file: &str,
+        content: Option<&str>,
+        src_cr: Option<usize>,
+    ) -> (String, fastn_core::history::FileEditTemp) {
+        let operation = match content {
+            Some(content) => {
+                let path = root.join(file);
+                std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+                std::fs::write(path, content).unwrap();
+                fastn_core::history::FileOperation::Updated
+            }
+            None => fastn_core::history::FileOperation::Deleted,
+        };
+        (
+            file.to_string(),
+            fastn_core::history::FileEditTemp {
+                message: None,
+                author: Some("amit".to_string()),
+                src_cr,
+                operation,
+            },
+        )
+    }
+
+    fn commit(root: &camino::Utf8Path, edits: Vec<(String, fastn_core::history::FileEditTemp)>) {
+        let git = super::Git { root };
+        block_on(super::commit_files(
+            &git,
+            root,
+            &edits.into_iter().collect(),
+        ))
+        .unwrap();
+    }
+
+    fn history(
+        root: &camino::Utf8Path,
+    ) -> std::collections::BTreeMap<String, Vec<(i32, fastn_core::history::FileOperation)>> {
+        let git = super::Git { root };
+        let history_path = |file: &str, version: i32| {
+            root.join(".remote-state/history")
+                .join(fastn_core::utils::snapshot_id(file, &(version as u128)))
+        };
+        block_on(super::read_history(&git, history_path))
+            .unwrap()
+            .into_iter()
+            .map(|v| {
+                let edits = v
+                    .file_edit
+                    .into_iter()
+                    .map(|e| (e.version, e.operation))
+                    .collect();
+                (v.filename, edits)
+            })
+            .collect()
+    }
+
+    fn cached(root: &camino::Utf8Path, file: &str, version: i32) -> String {
+        std::fs::read_to_string(
+            root.join(".remote-state/history")
+                .join(fastn_core::utils::snapshot_id(file, &(version as u128))),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn history_of_main_and_cr_branches() {
+        use fastn_core::history::FileOperation::{Added, Deleted, Updated};
+
+        let root = repository("history");
+        commit(&root, vec![edit(&root, "index.ftd", Some("one"), None)]);
+        commit(
+            &root,
+            vec![
+                edit(&root, "index.ftd", Some("two"), None),
+                edit(&root, "-/1/index.ftd", Some("cr"), None),
+                edit(&root, "-/1/-/meta.ftd", Some("meta"), None),
+            ],
+        );
+        commit(&root, vec![edit(&root, "-/1/index.ftd", None, None)]);
+
+        let history = history(&root);
+        assert_eq!(history["index.ftd"], vec![(2, Updated), (1, Added)]);
+        assert_eq!(history["-/1/index.ftd"], vec![(2, Deleted), (1, Updated)]);
+        assert_eq!(history["-/1/-/meta.ftd"], vec![(1, Added)]);
+        assert_eq!(cached(&root, "index.ftd", 1), "one");
+        assert_eq!(cached(&root, "index.ftd", 2), "two");
+        assert_eq!(cached(&root, "-/1/index.ftd", 1), "cr");
+
+        // the CR's branch forks from the main branch, with the CR's files at the root
+        let git = super::Git { root: &root };
+        assert!(block_on(git.output(&["show", "cr-1:index.ftd"])).is_err());
+        assert_eq!(
+            block_on(git.output(&["show", "cr-1~1:index.ftd"])).unwrap(),
+            "cr"
+        );
+        assert_eq!(
+            block_on(git.output(&["show", "cr-1~2:index.ftd"])).unwrap(),
+            "one"
+        );
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn stale_cached_versions_are_replaced() {
+        let root = repository("stale");
+        commit(&root, vec![edit(&root, "index.ftd", Some("one"), None)]);
+        assert_eq!(history(&root)["index.ftd"].len(), 1);
+        assert_eq!(cached(&root, "index.ftd", 1), "one");
+
+        // the branch is rewritten, version 1 is now another commit
+        let git = super::Git { root: &root };
+        block_on(git.run(&["update-ref", "refs/heads/main", "HEAD~1"], &[], None)).unwrap();
+        commit(&root, vec![edit(&root, "index.ftd", Some("uno"), None)]);
+        assert_eq!(history(&root)["index.ftd"].len(), 1);
+        assert_eq!(cached(&root, "index.ftd", 1), "uno");
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn detached_head_is_the_main_branch() {
+        let root = repository("detached");
+        let git = super::Git { root: &root };
+        block_on(git.run(&["checkout", "--quiet", "--detach"], &[], None)).unwrap();
+        commit(&root, vec![edit(&root, "index.ftd", Some("one"), None)]);
+        assert_eq!(history(&root)["index.ftd"].len(), 1);
+        assert_eq!(
+            block_on(git.output(&["show", "HEAD:index.ftd"])).unwrap(),
+            "one"
+        );
+        // the branch HEAD was detached from is left alone
+        assert!(block_on(git.output(&["show", "main:index.ftd"])).is_err());
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn concurrent_commits_use_their_own_index() {
+        let root = repository("concurrent");
+        let edits = (1..=4)
+            .map(|cr| {
+                let file = format!("-/{}/index.ftd", cr);
+                vec![edit(&root, file.as_str(), Some(file.as_str()), None)]
+            })
+            .collect::<Vec<_>>();
+        let git = super::Git { root: &root };
+        block_on(futures::future::try_join_all(edits.into_iter().map(
+            |edits| {
+                let edits: std::collections::BTreeMap<_, _> = edits.into_iter().collect();
+                let (git, root) = (&git, root.as_path());
+                async move { super::commit_files(git, root, &edits).await }
+            },
+        )))
+        .unwrap();
+        for cr in 1..=4 {
+            assert_eq!(
+                block_on(git.output(&["show", format!("cr-{}:index.ftd", cr).as_str()])).unwrap(),
+                format!("-/{}/index.ftd", cr)
+            );
+        }
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn stale_reference_is_not_overwritten() {
+        let root = repository("stale-reference");
+        let git = super::Git { root: &root };
+        let head = block_on(git.output(&["rev-parse", "HEAD"])).unwrap();
+        let files = [("index.ftd".to_string(), Some(b"one".to_vec()))];
+        block_on(git.commit(
+            "refs/heads/main",
+            Some(head.as_str()),
+            &[head.clone()],
+            &files,
+            "a",
+            None,
+        ))
+        .unwrap();
+        // a second commit made from the same, now stale, parent fails
+        assert!(block_on(git.commit(
+            "refs/heads/main",
+            Some(head.as_str()),
+            &[head.clone()],
+            &files,
+            "b",
+            None
+        ))
+        .is_err());
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
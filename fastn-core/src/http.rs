@@ -312,7 +312,7 @@ pub(crate) async fn construct_url_and_get_str(url: &str) -> fastn_core::Result<S
 
 #[tracing::instrument]
 pub(crate) async fn construct_url_and_get(url: &str) -> fastn_core::Result<Vec<u8>> {
-    println!("http_download_by_id: {url}");
+    fastn_core::utils::print_out(format!("http_download_by_id: {url}\n"));
     construct_url_and_return_response(
         url.to_string(),
         |f| async move { http_get(f.as_str()).await },
@@ -320,6 +320,18 @@ pub(crate) async fn construct_url_and_get(url: &str) -> fastn_core::Result<Vec<u
     .await
 }
 
+/// Set by `--offline`, dependencies must then come from the vendor directory and are never
+/// downloaded.
+static OFFLINE: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
+pub(crate) fn set_offline() {
+    OFFLINE.store(true, std::sync::atomic::Ordering::Relaxed);
+}
+
+pub(crate) fn is_offline() -> bool {
+    OFFLINE.load(std::sync::atomic::Ordering::Relaxed)
+}
+
 #[tracing::instrument(skip(f))]
 pub(crate) async fn construct_url_and_return_response<T, F, D>(
     url: String,
@@ -366,14 +378,18 @@ pub(crate) async fn post_json<T: serde::de::DeserializeOwned, B: Into<reqwest::B
         .await?)
 }
 
+/// `http_request_with_cookie()` sends a `method` request to `url`, with `body` if there is one.
+/// The request fails if the response does not arrive in `timeout`.
 #[tracing::instrument(skip_all)]
-pub(crate) async fn http_post_with_cookie(
+pub(crate) async fn http_request_with_cookie(
+    method: reqwest::Method,
     url: &str,
     cookie: Option<String>,
     headers: &std::collections::HashMap<String, String>,
-    body: &str,
+    body: Option<&str>,
+    timeout: Option<std::time::Duration>,
 ) -> fastn_core::Result<Vec<u8>> {
-    tracing::info!(url = url);
+    tracing::info!(url = url, method = method.as_str());
     let mut req_headers = reqwest::header::HeaderMap::new();
     req_headers.insert(
         reqwest::header::USER_AGENT,
@@ -397,23 +413,46 @@ pub(crate) async fn http_post_with_cookie(
         .default_headers(req_headers)
         .build()?;
 
-    let res = c.post(url).body(body.to_string()).send().await?;
+    let mut request = c.request(method, url);
+    if let Some(body) = body {
+        request = request.body(body.to_string());
+    }
+    if let Some(timeout) = timeout {
+        request = request.timeout(timeout);
+    }
+    let res = request.send().await?;
 
     if !res.status().eq(&reqwest::StatusCode::OK) {
+        let status = res.status();
+        // server errors keep their status, so the caller can tell them apart and retry them
+        let status_error = res.error_for_status_ref().err();
         let message = format!(
             "url: {}, response_status: {}, response: {:?}",
             url,
-            res.status(),
+            status,
             res.text().await
         );
         tracing::error!(url = url, msg = message);
-        return Err(fastn_core::Error::APIResponseError(message));
+        return Err(match status_error {
+            Some(e) if status.is_server_error() => fastn_core::Error::HttpError(e),
+            _ => fastn_core::Error::APIResponseError(message),
+        });
     }
     tracing::info!(msg = "returning success", url = url);
     Ok(res.bytes().await?.into())
 }
 
+/// `http_get()` is how dependencies are downloaded, with `--offline` it fails, see `set_offline()`.
 pub(crate) async fn http_get(url: &str) -> fastn_core::Result<Vec<u8>> {
+    if is_offline() {
+        return Err(fastn_core::Error::PackageError {
+            message: fastn_core::warning!(
+                "offline: {} is not vendored, run `fastn vendor` to add it to {}",
+                url,
+                fastn_core::commands::vendor::VENDOR_DIR
+            ),
+        });
+    }
     http_get_with_cookie(url, None, &std::collections::HashMap::new()).await
 }
 
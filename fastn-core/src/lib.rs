@@ -36,12 +36,12 @@ mod workspace;
 
 pub(crate) use auto_import::AutoImport;
 pub use commands::{
-    abort_merge::abort_merge, add::add, build::build, check::post_build_check, clone::clone,
-    close_cr::close_cr, create_cr::create_cr, create_package::create_package, diff::diff,
-    edit::edit, mark_resolved::mark_resolved, mark_upto_date::mark_upto_date, merge::merge,
-    query::query, resolve_conflict::resolve_conflict, revert::revert, rm::rm, serve::listen,
-    start_tracking::start_tracking, status::status, sync2::sync2,
-    translation_status::translation_status, update::update,
+    abort_merge::abort_merge, add::add, build::build, check::check, check::check_documents,
+    check::post_build_check, clone::clone, close_cr::close_cr, create_cr::create_cr,
+    create_package::create_package, diff::diff, edit::edit, mark_resolved::mark_resolved,
+    mark_upto_date::mark_upto_date, merge::merge, query::query, resolve_conflict::resolve_conflict,
+    revert::revert, rm::rm, serve::listen, start_tracking::start_tracking, status::status,
+    sync2::sync2, translation_status::translation_status, update::update,
 };
 pub use config::{Config, FTDEdition};
 pub use error::Error;
@@ -51,6 +51,7 @@ pub(crate) use font::Font;
 pub use library::{FastnLibrary, Library, Library2};
 pub use library2022::Library2022;
 pub(crate) use package::dependency::Dependency;
+pub use package::package_doc::interpret_ftd;
 pub use package::user_group;
 pub(crate) use package::Package;
 pub(crate) use snapshot::Snapshot;
@@ -10,6 +10,7 @@ pub async fn processor<'a>(
         message: format!("This is not CR Document `{:?}`", config.current_document),
         doc_id: doc.name.to_string(),
         line_number: section.line_number,
+        span: Default::default(),
     })?;
     let cr_meta = fastn_core::cr::get_cr_meta(config, cr_number)
         .await
@@ -17,6 +18,7 @@ pub async fn processor<'a>(
             message: e.to_string(),
             doc_id: doc.name.to_string(),
             line_number: section.line_number,
+            span: Default::default(),
         })?;
     doc.from_json(&cr_meta, section)
 }
@@ -49,6 +49,7 @@ impl Library2022 {
                     .to_string(),
                 doc_id: current_processing_module.to_string(),
                 line_number: 0,
+                span: Default::default(),
             })?;
 
         self.config
@@ -60,6 +61,7 @@ impl Library2022 {
                 message: format!("Can't find current package: {}", current_package_name),
                 doc_id: "".to_string(),
                 line_number: 0,
+                span: Default::default(),
             })
     }
 
@@ -280,7 +282,7 @@ impl Library2022 {
             "fastn-apps" => processor::apps::process(value, kind, doc, &self.config),
             "is-reader" => processor::user_group::is_reader(value, kind, doc, &self.config).await,
             "package-query" => processor::sqlite::process(value, kind, doc, &self.config).await,
-            "pg" => processor::pg::process(value, kind, doc).await,
+            "pg" => processor::pg::process(value, kind, doc, &self.config).await,
             "package-tree" => {
                 processor::package_tree::process(value, kind, doc, &self.config).await
             }
@@ -298,6 +300,7 @@ impl Library2022 {
                 doc_id: self.document_id.to_string(),
                 line_number,
                 message: format!("fastn-Error: No such processor: {}", t),
+                span: Default::default(),
             }),
         }
     }
@@ -324,6 +327,7 @@ fn get_processor_data(
                 "Cannot find kind for `{}`",
                 variable_definition.name.as_str(),
             ),
+            span: Default::default(),
         })?;
         let processor =
             variable_definition
@@ -332,6 +336,7 @@ fn get_processor_data(
                     message: format!("No processor found for `{}`", ast_name),
                     doc_id: doc.name.to_string(),
                     line_number,
+                    span: Default::default(),
                 })?;
         Ok((processor, variable_definition.value, kind.kind))
     } else {
@@ -349,6 +354,7 @@ fn get_processor_data(
                     message: format!("No processor found for `{}`", ast_name),
                     doc_id: doc.name.to_string(),
                     line_number,
+                    span: Default::default(),
                 })?;
         Ok((processor, variable_invocation.value, kind.kind))
     }
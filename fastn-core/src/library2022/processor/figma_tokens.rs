@@ -272,6 +272,7 @@ fn extract_light_dark_colors(
                 message: format!("Expected record of color-scheme found: {:?}", value),
                 doc_id: doc.name.to_string(),
                 line_number,
+                span: Default::default(),
             })
         }
     };
@@ -288,6 +289,7 @@ fn extract_light_dark_colors(
                     doc_id: doc.name.to_string(),
                     line_number,
                     message: format!("Expected string kind for name found: {:?}", variable_name),
+                    span: Default::default(),
                 })
             }
         };
@@ -300,6 +302,7 @@ fn extract_light_dark_colors(
             message: format!("`variable` named header not found: {:?}", value),
             doc_id: doc.name.to_string(),
             line_number,
+            span: Default::default(),
         });
     };
 
@@ -313,6 +316,7 @@ fn extract_light_dark_colors(
                 ),
                 doc_id: doc.name.to_string(),
                 line_number,
+                span: Default::default(),
             })
         }
     };
@@ -330,6 +334,7 @@ fn extract_light_dark_colors(
                 message: format!("Expected Variable reference, found: {:?}", t),
                 doc_id: doc.name.to_string(),
                 line_number,
+                span: Default::default(),
             })
         }
     };
@@ -347,6 +352,7 @@ fn extract_light_dark_colors(
                 ),
                 doc_id: doc.name.to_string(),
                 line_number,
+                span: Default::default(),
             })
         }
     };
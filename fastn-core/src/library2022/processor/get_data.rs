@@ -27,6 +27,7 @@ pub fn process(
             message: "Cannot pass both caption and body".to_string(),
             doc_id: doc.name.to_string(),
             line_number,
+            span: Default::default(),
         });
     }
 
@@ -39,6 +40,7 @@ pub fn process(
                             message: e.to_string(),
                             doc_id: doc.name.to_string(),
                             line_number,
+                            span: Default::default(),
                         })?;
                 doc.from_json(&value2, &kind, &value)
             }
@@ -49,6 +51,7 @@ pub fn process(
                             message: e.to_string(),
                             doc_id: doc.name.to_string(),
                             line_number,
+                            span: Default::default(),
                         })?;
                 doc.from_json(&value2, &kind, &value)
             }
@@ -59,6 +62,7 @@ pub fn process(
                             message: e.to_string(),
                             doc_id: doc.name.to_string(),
                             line_number,
+                            span: Default::default(),
                         })?;
                 doc.from_json(&value2, &kind, &value)
             }
@@ -76,6 +80,7 @@ pub fn process(
                         message: format!("only json file supported {}", path),
                         doc_id: doc.name.to_string(),
                         line_number,
+                        span: Default::default(),
                     });
                 }
             }
@@ -84,6 +89,7 @@ pub fn process(
                     message: format!("file does not have any extension {}", path),
                     doc_id: doc.name.to_string(),
                     line_number,
+                    span: Default::default(),
                 });
             }
         }
@@ -93,6 +99,7 @@ pub fn process(
                 message: format!("file path not found {}", path),
                 doc_id: doc.name.to_string(),
                 line_number,
+                span: Default::default(),
             }
         })?;
         return doc.from_json(
@@ -117,6 +124,7 @@ pub fn process(
                 message: format!("caption name not passed for section: {}", section_name),
                 doc_id: doc.name.to_string(),
                 line_number,
+                span: Default::default(),
             })
         }
     };
@@ -1,3 +1,21 @@
+/// headers of `$processor$: http` that configure the request, all the other headers are sent
+/// to the url
+const RESERVED_HEADERS: [&str; 6] = ["url", "method", "timeout", "retries", "cache-ttl", "pin"];
+
+/// `process()` fetches json from `url` and converts it to `kind`. Besides `url` and `method` the
+/// request can be configured with:
+///
+/// - `timeout`: seconds to wait for the response, by default there is no limit
+/// - `retries`: how many more times to send the request if it fails, `0` by default
+/// - `cache-ttl`: seconds to reuse the response for, it is cached in `.fastn/http-cache` so it
+///   is reused across `fastn build` runs
+/// - `pin`: if `true` the response is recorded in `.fixtures/http` the first time, and that
+///   recording is used from then on. Commit it to build the package without the network.
+///
+/// Responses are recorded by their method, url and body. A request sent with the cookies of the
+/// user, or with credentials in its headers, is never recorded, and does not use the cache. With
+/// `--offline` the request is never sent, the pinned or cached response is used regardless of
+/// `cache-ttl`.
 pub async fn process(
     value: ftd::ast::VariableValue,
     kind: ftd::interpreter::Kind,
@@ -15,13 +33,46 @@ pub async fn process(
         .unwrap_or_else(|| "GET".to_string())
         .to_lowercase();
 
-    if method.as_str().ne("get") && method.as_str().ne("post") {
+    if !["get", "post", "put", "patch", "delete"].contains(&method.as_str()) {
         return ftd::interpreter::utils::e2(
-            format!("only GET and POST methods are allowed, found: {}", method),
+            format!(
+                "only GET, POST, PUT, PATCH and DELETE methods are allowed, found: {}",
+                method
+            ),
             doc.name,
             line_number,
         );
     }
+    // the other headers are sent as json in the body of these, and in the query of the rest
+    let has_body = ["post", "put", "patch"].contains(&method.as_str());
+
+    let timeout = match get_optional_number::<f64>(&headers, "timeout", doc, line_number)? {
+        Some(v) if !v.is_finite() || v <= 0.0 => {
+            return ftd::interpreter::utils::e2(
+                format!(
+                    "`timeout` must be a positive number of seconds, found: {}",
+                    v
+                ),
+                doc.name,
+                line_number,
+            )
+        }
+        v => v.map(std::time::Duration::from_secs_f64),
+    };
+    let retries =
+        get_optional_number::<usize>(&headers, "retries", doc, line_number)?.unwrap_or_default();
+    let cache_ttl = get_optional_number::<u64>(&headers, "cache-ttl", doc, line_number)?;
+    let pin = match headers.get_optional_string_by_key("pin", doc.name, line_number)? {
+        Some(v) => v
+            .parse::<bool>()
+            .map_err(|e| ftd::interpreter::Error::ParseError {
+                message: format!("`pin` must be true or false: {}", e),
+                doc_id: doc.name.to_string(),
+                line_number,
+                span: Default::default(),
+            })?,
+        None => false,
+    };
 
     let url = match headers.get_optional_string_by_key("url", doc.name, line_number)? {
         Some(v) if v.starts_with('$') => match doc.get_thing(v.as_str(), line_number) {
@@ -62,13 +113,13 @@ pub async fn process(
             message: format!("invalid url: {:?}", e),
             doc_id: doc.name.to_string(),
             line_number,
+            span: Default::default(),
         })?;
 
     let mut body = vec![];
     for header in headers.0 {
         if header.key.as_str() == ftd::PROCESSOR_MARKER
-            || header.key.as_str() == "url"
-            || header.key.as_str() == "method"
+            || RESERVED_HEADERS.contains(&header.key.as_str())
         {
             continue;
         }
@@ -82,7 +133,7 @@ pub async fn process(
                 .get_value(header.line_number, value.as_str())?
                 .to_string()
             {
-                if method.as_str().eq("post") {
+                if has_body {
                     body.push(format!("\"{}\": {}", header.key, value));
                     continue;
                 }
@@ -90,7 +141,7 @@ pub async fn process(
                     .append_pair(header.key.as_str(), &value);
             }
         } else {
-            if method.as_str().eq("post") {
+            if has_body {
                 body.push(format!("\"{}\": {}", header.key, value));
                 continue;
             }
@@ -99,44 +150,386 @@ pub async fn process(
         }
     }
 
-    println!("calling `http` processor with url: {}", &url);
+    let body = if has_body {
+        Some(format!("{{{}}}", body.join(",")))
+    } else {
+        None
+    };
+    let cookies = config.request.as_ref().and_then(|v| v.cookies_string());
+    // a response to a request with credentials may be private to the user, it is never recorded
+    let private = is_private(cookies.as_deref(), &conf);
+    let key = fastn_core::utils::generate_hash(format!(
+        "{} {}\n{}",
+        method,
+        url,
+        body.as_deref().unwrap_or_default()
+    ));
+    let fixture_path = config.http_fixtures_dir().join(format!("{}.json", key));
+    let cache_path = config.http_cache_dir().join(format!("{}.json", key));
 
-    let response = if method.as_str().eq("post") {
-        fastn_core::http::http_post_with_cookie(
-            url.as_str(),
-            config.request.as_ref().and_then(|v| v.cookies_string()),
-            &conf,
-            dbg!(format!("{{{}}}", body.join(","))).as_str(),
-        )
-        .await
+    let recorded = if pin {
+        read_recorded_response(&fixture_path, None).await
+    } else if fastn_core::http::is_offline() {
+        // offline any cached response is better than none
+        match read_recorded_response(&fixture_path, None).await {
+            Some(response) => Some(response),
+            None => read_recorded_response(&cache_path, None).await,
+        }
     } else {
-        fastn_core::http::http_get_with_cookie(
-            url.as_str(),
-            config.request.as_ref().and_then(|v| v.cookies_string()),
-            &conf,
-        )
-        .await
+        match cache_ttl {
+            Some(cache_ttl) if !private => {
+                read_recorded_response(&cache_path, Some(cache_ttl)).await
+            }
+            _ => None,
+        }
     };
 
-    let response = match response {
-        Ok(v) => v,
-        Err(e) => {
+    let response_string = match recorded {
+        Some(response) => response,
+        None if fastn_core::http::is_offline() => {
             return ftd::interpreter::utils::e2(
-                format!("HTTP::get failed: {:?}", e),
+                format!(
+                    "offline: no recorded response for {} {}, build once with `pin: true` or \
+                    `cache-ttl` while online",
+                    method.to_uppercase(),
+                    url
+                ),
                 doc.name,
                 line_number,
             )
         }
+        None => {
+            println!("calling `http` processor with url: {}", &url);
+
+            let method = reqwest::Method::from_bytes(method.to_uppercase().as_bytes())
+                .expect("method is validated above");
+            let response = send_with_retries(
+                method.clone(),
+                &url,
+                cookies,
+                &conf,
+                body.as_deref(),
+                timeout,
+                retries,
+            )
+            .await;
+
+            let response = match response {
+                Ok(v) => v,
+                Err(e) => {
+                    return ftd::interpreter::utils::e2(
+                        format!("HTTP::{} failed: {:?}", method.as_str().to_lowercase(), e),
+                        doc.name,
+                        line_number,
+                    )
+                }
+            };
+
+            let response_string =
+                String::from_utf8(response).map_err(|e| ftd::interpreter::Error::ParseError {
+                    message: format!("`http` processor API response error: {}", e),
+                    doc_id: doc.name.to_string(),
+                    line_number,
+                    span: Default::default(),
+                })?;
+
+            let recorded = RecordedResponse {
+                method: method.to_string(),
+                url: url.to_string(),
+                body: body.clone(),
+                fetched_at: now(),
+                response: response_string.clone(),
+            };
+            if private {
+                if pin || cache_ttl.is_some() {
+                    tracing::warn!(
+                        url = url.as_str(),
+                        msg = "not recording a response to a request with credentials"
+                    );
+                }
+            } else if pin {
+                recorded.write(&fixture_path).await;
+            } else if cache_ttl.is_some() {
+                recorded.write(&cache_path).await;
+            }
+            response_string
+        }
     };
 
-    let response_string =
-        String::from_utf8(response).map_err(|e| ftd::interpreter::Error::ParseError {
-            message: format!("`http` processor API response error: {}", e),
-            doc_id: doc.name.to_string(),
-            line_number,
-        })?;
     let response_json: serde_json::Value = serde_json::from_str(&response_string)
         .map_err(|e| ftd::interpreter::Error::Serde { source: e })?;
 
     doc.from_json(&response_json, &kind, &value)
 }
+
+/// request headers that carry credentials, see `is_private()`
+const CREDENTIAL_HEADERS: [&str; 4] = [
+    "authorization",
+    "proxy-authorization",
+    "cookie",
+    "x-api-key",
+];
+
+/// `is_private()` is true if the request is sent with cookies, or with a header that carries
+/// credentials.
+fn is_private(cookies: Option<&str>, headers: &std::collections::HashMap<String, String>) -> bool {
+    cookies.map_or(false, |v| !v.is_empty())
+        || headers
+            .keys()
+            .any(|k| CREDENTIAL_HEADERS.contains(&k.to_lowercase().as_str()))
+}
+
+/// `send_with_retries()` sends the request, and sends it again up to `retries` times if the
+/// server could not be reached or answered with a server error. Other failures, like a `404`, are
+/// returned right away.
+async fn send_with_retries(
+    method: reqwest::Method,
+    url: &url::Url,
+    cookies: Option<String>,
+    headers: &std::collections::HashMap<String, String>,
+    body: Option<&str>,
+    timeout: Option<std::time::Duration>,
+    retries: usize,
+) -> fastn_core::Result<Vec<u8>> {
+    let mut attempt = 0;
+    loop {
+        let response = fastn_core::http::http_request_with_cookie(
+            method.clone(),
+            url.as_str(),
+            cookies.clone(),
+            headers,
+            body,
+            timeout,
+        )
+        .await;
+        match response {
+            Err(e) if attempt < retries && is_retryable(&e) => {
+                attempt += 1;
+                tracing::info!(url = url.as_str(), attempt = attempt, error = ?e);
+                // back off 100ms, 200ms, 400ms ... between the attempts
+                tokio::time::sleep(std::time::Duration::from_millis(
+                    100 << (attempt - 1).min(6),
+                ))
+                .await;
+            }
+            response => return response,
+        }
+    }
+}
+
+fn is_retryable(e: &fastn_core::Error) -> bool {
+    match e {
+        fastn_core::Error::HttpError(e) => {
+            e.is_connect() || e.status().map_or(false, |s| s.is_server_error())
+        }
+        _ => false,
+    }
+}
+
+/// a response of the `http` processor, recorded in the cache or as a fixture
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+struct RecordedResponse {
+    method: String,
+    url: String,
+    body: Option<String>,
+    /// unix timestamp, in seconds
+    #[serde(rename = "fetched-at")]
+    fetched_at: u64,
+    response: String,
+}
+
+impl RecordedResponse {
+    async fn write(&self, path: &camino::Utf8Path) {
+        // the response is already fetched, failing to record it should not fail the build
+        let content = match serde_json::to_string_pretty(self) {
+            Ok(v) => v,
+            Err(e) => {
+                tracing::error!(path = path.as_str(), error = ?e);
+                return;
+            }
+        };
+        if let Err(e) = fastn_core::utils::update(path, content.as_bytes()).await {
+            tracing::error!(path = path.as_str(), error = ?e);
+        }
+    }
+}
+
+/// `read_recorded_response()` is the response recorded at `path`, if it is not older than `ttl`
+/// seconds.
+async fn read_recorded_response(path: &camino::Utf8Path, ttl: Option<u64>) -> Option<String> {
+    let content = tokio::fs::read_to_string(path).await.ok()?;
+    let recorded: RecordedResponse = serde_json::from_str(content.as_str()).ok()?;
+    match ttl {
+        Some(ttl) if recorded.fetched_at.saturating_add(ttl) < now() => None,
+        _ => Some(recorded.response),
+    }
+}
+
+fn now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|v| v.as_secs())
+        .unwrap_or_default()
+}
+
+fn get_optional_number<T>(
+    headers: &ftd::ast::HeaderValues,
+    key: &str,
+    doc: &ftd::interpreter::TDoc<'_>,
+    line_number: usize,
+) -> ftd::interpreter::Result<Option<T>>
+where
+    T: std::str::FromStr,
+    T::Err: std::fmt::Display,
+{
+    match headers.get_optional_string_by_key(key, doc.name, line_number)? {
+        Some(v) => v
+            .parse::<T>()
+            .map(Some)
+            .map_err(|e| ftd::interpreter::Error::ParseError {
+                message: format!("`{}` must be a number, found `{}`: {}", key, v, e),
+                doc_id: doc.name.to_string(),
+                line_number,
+                span: Default::default(),
+            }),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    fn temp_path(name: &str) -> camino::Utf8PathBuf {
+        camino::Utf8PathBuf::from_path_buf(std::env::temp_dir())
+            .unwrap()
+            .join(format!("fastn-http-{}-{}.json", name, std::process::id()))
+    }
+
+    fn recorded(fetched_at: u64) -> super::RecordedResponse {
+        super::RecordedResponse {
+            method: "GET".to_string(),
+            url: "https://example.com/".to_string(),
+            body: None,
+            fetched_at,
+            response: "{}".to_string(),
+        }
+    }
+
+    fn block_on<F: std::future::Future>(f: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+            .block_on(f)
+    }
+
+    /// `serve()` answers the requests with `statuses` one by one, and returns the url of the
+    /// server and the number of requests it got.
+    fn serve(statuses: Vec<u16>) -> (url::Url, std::sync::Arc<std::sync::atomic::AtomicUsize>) {
+        use std::io::{Read, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = url::Url::parse(&format!("http://{}/", listener.local_addr().unwrap())).unwrap();
+        let count = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let served = count.clone();
+        std::thread::spawn(move || {
+            for (status, stream) in statuses.into_iter().zip(listener.incoming()) {
+                let mut stream = stream.unwrap();
+                let mut buf = [0; 4096];
+                let _ = stream.read(&mut buf).unwrap();
+                served.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                write!(
+                    stream,
+                    "HTTP/1.1 {} x\r\nContent-Length: 2\r\nConnection: close\r\n\r\n{{}}",
+                    status
+                )
+                .unwrap();
+            }
+        });
+        (url, count)
+    }
+
+    fn send(url: &url::Url, retries: usize) -> fastn_core::Result<Vec<u8>> {
+        block_on(super::send_with_retries(
+            reqwest::Method::GET,
+            url,
+            None,
+            &Default::default(),
+            None,
+            None,
+            retries,
+        ))
+    }
+
+    #[test]
+    fn cache_ttl() {
+        let path = temp_path("ttl");
+        block_on(recorded(super::now() - 60).write(&path));
+        assert_eq!(
+            block_on(super::read_recorded_response(&path, Some(120))),
+            Some("{}".to_string())
+        );
+        assert_eq!(
+            block_on(super::read_recorded_response(&path, Some(30))),
+            None
+        );
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            block_on(super::read_recorded_response(&path, Some(120))),
+            None
+        );
+    }
+
+    #[test]
+    fn pinned_response_never_expires() {
+        let path = temp_path("pin");
+        block_on(recorded(0).write(&path));
+        assert_eq!(
+            block_on(super::read_recorded_response(&path, None)),
+            Some("{}".to_string())
+        );
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn requests_with_credentials_are_private() {
+        let headers = |k: &str| std::collections::HashMap::from([(k.to_string(), "x".to_string())]);
+        assert!(!super::is_private(None, &Default::default()));
+        assert!(!super::is_private(Some(""), &headers("accept")));
+        assert!(super::is_private(Some("session=1"), &Default::default()));
+        assert!(super::is_private(None, &headers("Authorization")));
+        assert!(super::is_private(None, &headers("x-api-key")));
+    }
+
+    #[test]
+    fn server_errors_are_retried() {
+        let (url, count) = serve(vec![500, 503, 200]);
+        assert_eq!(send(&url, 2).unwrap(), b"{}".to_vec());
+        assert_eq!(count.load(std::sync::atomic::Ordering::SeqCst), 3);
+
+        let (url, count) = serve(vec![500, 500]);
+        assert!(send(&url, 1).is_err());
+        assert_eq!(count.load(std::sync::atomic::Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn client_errors_are_not_retried() {
+        let (url, count) = serve(vec![404, 200]);
+        assert!(send(&url, 3).is_err());
+        assert_eq!(count.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn connect_errors_are_retried() {
+        // nothing listens on the port once the listener is dropped
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let url = url::Url::parse(&format!("http://127.0.0.1:{}/", port)).unwrap();
+        match send(&url, 1) {
+            Err(fastn_core::Error::HttpError(e)) => assert!(e.is_connect()),
+            v => panic!("expected a connect error, found: {:?}", v.map(|_| ())),
+        }
+    }
+}
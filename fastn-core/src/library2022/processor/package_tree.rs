@@ -13,6 +13,7 @@ pub async fn process(
                 .to_string(),
             doc_id: doc.name.to_string(),
             line_number: value.line_number(),
+            span: Default::default(),
         })?;
     let workspaces = fastn_core::snapshot::get_workspace(config)
         .await
@@ -20,6 +21,7 @@ pub async fn process(
             message: "fastn-error: error in package-tree processor `get_workspace`".to_string(),
             doc_id: doc.name.to_string(),
             line_number: value.line_number(),
+            span: Default::default(),
         })?;
     let all_files = config
         .get_files(&config.package)
@@ -28,6 +30,7 @@ pub async fn process(
             message: "fastn-error: error in package-tree processor `get_files`".to_string(),
             doc_id: doc.name.to_string(),
             line_number: value.line_number(),
+            span: Default::default(),
         })?
         .into_iter()
         .map(|v| v.get_id().to_string())
@@ -44,6 +47,7 @@ pub async fn process(
                 .to_string(),
             doc_id: doc.name.to_string(),
             line_number: value.line_number(),
+            span: Default::default(),
         })?
         .into_iter()
         .filter(|v| v.is_file())
@@ -1,47 +1,120 @@
-async fn create_pool() -> Result<deadpool_postgres::Pool, deadpool_postgres::CreatePoolError> {
+/// `PoolKey` is the connection config of a database, as read from the environment. Pools are
+/// shared by the databases with the same config, and a database whose url or certificate changed
+/// gets a new pool.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct PoolKey {
+    url: String,
+    certificate: Option<String>,
+    pool_size: Option<usize>,
+    timeout: Option<u64>,
+}
+
+impl PoolKey {
+    fn of(database: &fastn_core::package::database::Database) -> Result<PoolKey, String> {
+        Ok(PoolKey {
+            url: std::env::var(&database.url_env).map_err(|_| {
+                format!(
+                    "environment variable `{}`, the url of the database `{}`, is not set",
+                    database.url_env, database.name
+                )
+            })?,
+            certificate: database
+                .certificate_env
+                .as_ref()
+                .and_then(|v| std::env::var(v).ok()),
+            pool_size: database.pool_size,
+            timeout: database.timeout,
+        })
+    }
+}
+
+async fn create_pool(key: &PoolKey, name: &str) -> Result<deadpool_postgres::Pool, String> {
     let mut cfg = deadpool_postgres::Config::new();
-    cfg.libpq_style_connection_string = Some(std::env::var("FASTN_PG_URL").unwrap());
+    cfg.libpq_style_connection_string = Some(key.url.to_string());
     cfg.manager = Some(deadpool_postgres::ManagerConfig {
         recycling_method: deadpool_postgres::RecyclingMethod::Verified,
     });
+    if key.pool_size.is_some() || key.timeout.is_some() {
+        let mut pool_config = match key.pool_size {
+            Some(pool_size) => deadpool_postgres::PoolConfig::new(pool_size),
+            None => Default::default(),
+        };
+        if let Some(timeout) = key.timeout {
+            let timeout = Some(std::time::Duration::from_secs(timeout));
+            pool_config.timeouts.wait = timeout;
+            pool_config.timeouts.create = timeout;
+            pool_config.timeouts.recycle = timeout;
+        }
+        cfg.pool = Some(pool_config);
+    }
+
     let runtime = Some(deadpool_postgres::Runtime::Tokio1);
-    match std::env::var("FASTN_PG_CERTIFICATE") {
-        Ok(cert) => {
-            let cert = tokio::fs::read(cert).await.unwrap();
-            let cert = native_tls::Certificate::from_pem(&cert).unwrap();
+    let pool = match key.certificate {
+        Some(ref cert) => {
+            let cert = tokio::fs::read(cert)
+                .await
+                .map_err(|e| format!("failed to read the certificate `{}`: {}", cert, e))?;
+            let cert = native_tls::Certificate::from_pem(&cert)
+                .map_err(|e| format!("invalid certificate: {}", e))?;
             let connector = native_tls::TlsConnector::builder()
                 .add_root_certificate(cert)
                 .build()
-                .unwrap();
+                .map_err(|e| format!("failed to set up tls: {}", e))?;
             let tls = postgres_native_tls::MakeTlsConnector::new(connector);
             cfg.create_pool(runtime, tls)
         }
-        _ => cfg.create_pool(runtime, tokio_postgres::NoTls),
-    }
+        None => cfg.create_pool(runtime, tokio_postgres::NoTls),
+    };
+    pool.map_err(|e| format!("failed to create a pool for `{}`: {}", name, e))
 }
 
-// TODO: I am a little confused about the use of `tokio::sync` here, both sides are async, so why
-//       do we need to use `tokio::sync`? Am I doing something wrong? How do I prove/verify that
-//       this is correct?
-static POOL_RESULT: tokio::sync::OnceCell<
-    Result<deadpool_postgres::Pool, deadpool_postgres::CreatePoolError>,
-> = tokio::sync::OnceCell::const_new();
-
-static EXECUTE_QUERY_LOCK: once_cell::sync::Lazy<tokio::sync::Mutex<()>> =
-    once_cell::sync::Lazy::new(|| tokio::sync::Mutex::new(()));
-
-async fn pool() -> &'static Result<deadpool_postgres::Pool, deadpool_postgres::CreatePoolError> {
-    POOL_RESULT.get_or_init(create_pool).await
+/// a pool for every connection config the `pg` processor queried
+static POOLS: once_cell::sync::Lazy<
+    antidote::RwLock<std::collections::HashMap<PoolKey, deadpool_postgres::Pool>>,
+> = once_cell::sync::Lazy::new(|| antidote::RwLock::new(Default::default()));
+
+async fn pool(
+    database: &fastn_core::package::database::Database,
+) -> Result<deadpool_postgres::Pool, String> {
+    let key = PoolKey::of(database)?;
+    if let Some(pool) = POOLS.read().get(&key) {
+        return Ok(pool.clone());
+    }
+    let pool = create_pool(&key, database.name.as_str()).await?;
+    Ok(POOLS.write().entry(key).or_insert(pool).clone())
 }
 
+/// `process()` runs the query in the body of `$processor$: pg` on the database named by the `db`
+/// header, see `fastn.database`, or on the default database.
 pub async fn process(
     value: ftd::ast::VariableValue,
     kind: ftd::interpreter::Kind,
     doc: &ftd::interpreter::TDoc<'_>,
+    config: &fastn_core::Config,
 ) -> ftd::interpreter::Result<ftd::interpreter::Value> {
     let (headers, query) = super::sqlite::get_p1_data("pg", &value, doc.name)?;
+    let line_number = value.line_number();
 
-    let query_response = execute_query(query.as_str(), doc, value.line_number(), headers).await;
+    let db = headers.get_optional_string_by_key("db", doc.name, line_number)?;
+    let database = match config.package.database(db.as_deref()) {
+        Ok(database) => database,
+        Err(e) => return ftd::interpreter::utils::e2(e.to_string(), doc.name, line_number),
+    };
+    let client = match pool(&database).await {
+        Ok(pool) => match pool.get().await {
+            Ok(client) => client,
+            Err(e) => {
+                return ftd::interpreter::utils::e2(
+                    format!("failed to connect to `{}`: {}", database.name, e),
+                    doc.name,
+                    line_number,
+                )
+            }
+        },
+        Err(e) => return ftd::interpreter::utils::e2(e, doc.name, line_number),
+    };
+
+    let query_response = execute_query(&client, query.as_str(), doc, line_number, headers).await;
 
     match query_response {
         Ok(result) => {
@@ -75,23 +148,7 @@ fn resolve_variable_from_doc(
     e: &postgres_types::Type,
     line_number: usize,
 ) -> ftd::interpreter::Result<Box<PGData>> {
-    let thing = match doc.get_thing(var, line_number) {
-        Ok(ftd::interpreter::Thing::Variable(v)) => v.value.resolve(doc, line_number)?,
-        Ok(v) => {
-            return ftd::interpreter::utils::e2(
-                format!("{var} is not a variable, it's a {v:?}"),
-                doc.name,
-                line_number,
-            )
-        }
-        Err(e) => {
-            return ftd::interpreter::utils::e2(
-                format!("${var} not found in the document: {e:?}"),
-                doc.name,
-                line_number,
-            )
-        }
-    };
+    let thing = super::sql::resolve_variable(doc, var, line_number)?;
 
     Ok(match (e, thing) {
         (&postgres_types::Type::TEXT, ftd::interpreter::Value::String { text, .. }) => {
@@ -263,20 +320,36 @@ fn prepare_args(
 }
 
 async fn execute_query(
+    client: &deadpool_postgres::Client,
     query: &str,
     doc: &ftd::interpreter::TDoc<'_>,
     line_number: usize,
     headers: ftd::ast::HeaderValues,
 ) -> ftd::interpreter::Result<Vec<Vec<serde_json::Value>>> {
-    let _lock = EXECUTE_QUERY_LOCK.lock().await;
-
     let (query, query_args) = super::sql::extract_arguments(query)?;
-    let client = pool().await.as_ref().unwrap().get().await.unwrap();
 
-    let stmt = client.prepare_cached(query.as_str()).await.unwrap();
+    let stmt = match client.prepare_cached(query.as_str()).await {
+        Ok(stmt) => stmt,
+        Err(e) => {
+            return ftd::interpreter::utils::e2(
+                format!("failed to prepare query: {}", e),
+                doc.name,
+                line_number,
+            )
+        }
+    };
 
     let args = prepare_args(query_args, stmt.params(), doc, line_number, headers)?;
-    let rows = client.query(&stmt, &args.pg_args()).await.unwrap();
+    let rows = match client.query(&stmt, &args.pg_args()).await {
+        Ok(rows) => rows,
+        Err(e) => {
+            return ftd::interpreter::utils::e2(
+                format!("failed to execute query: {}", e),
+                doc.name,
+                line_number,
+            )
+        }
+    };
     let mut result: Vec<Vec<serde_json::Value>> = vec![];
 
     for r in rows {
@@ -329,6 +402,74 @@ fn row_to_json(
     Ok(row)
 }
 
+#[cfg(test)]
+mod test {
+    fn database(
+        name: &str,
+        url_env: &str,
+        pool_size: Option<usize>,
+    ) -> fastn_core::package::database::Database {
+        fastn_core::package::database::Database {
+            name: name.to_string(),
+            url_env: url_env.to_string(),
+            certificate_env: None,
+            pool_size,
+            timeout: None,
+        }
+    }
+
+    #[test]
+    fn pool_key() {
+        std::env::set_var("FASTN_PG_TEST_URL_A", "postgres://localhost/a");
+        std::env::set_var("FASTN_PG_TEST_URL_B", "postgres://localhost/b");
+        let key = |name, url_env, pool_size| {
+            super::PoolKey::of(&database(name, url_env, pool_size)).unwrap()
+        };
+
+        assert_eq!(
+            key("main", "FASTN_PG_TEST_URL_A", Some(4)),
+            key("other", "FASTN_PG_TEST_URL_A", Some(4))
+        );
+        assert_ne!(
+            key("main", "FASTN_PG_TEST_URL_A", Some(4)),
+            key("main", "FASTN_PG_TEST_URL_B", Some(4))
+        );
+        assert_ne!(
+            key("main", "FASTN_PG_TEST_URL_A", Some(4)),
+            key("main", "FASTN_PG_TEST_URL_A", Some(8))
+        );
+        assert!(super::PoolKey::of(&database("main", "FASTN_PG_TEST_URL_UNSET", None)).is_err());
+    }
+
+    #[test]
+    fn pool_is_shared_by_config() {
+        std::env::set_var("FASTN_PG_TEST_URL_C", "postgres://localhost/c");
+        std::env::set_var("FASTN_PG_TEST_URL_D", "postgres://localhost/d");
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        runtime.block_on(async {
+            // pools connect lazily, so no database is needed to create them
+            super::pool(&database("main", "FASTN_PG_TEST_URL_C", Some(2)))
+                .await
+                .unwrap();
+            super::pool(&database("other", "FASTN_PG_TEST_URL_C", Some(2)))
+                .await
+                .unwrap();
+            super::pool(&database("main", "FASTN_PG_TEST_URL_D", Some(2)))
+                .await
+                .unwrap();
+        });
+        let pools = super::POOLS.read();
+        let urls = pools
+            .keys()
+            .filter(|k| k.url.ends_with("/c") || k.url.ends_with("/d"))
+            .count();
+        assert_eq!(urls, 2);
+    }
+}
+
 /*
 FASTN_PG_URL=postgres://amitu@localhost/amitu fastn serve
  */
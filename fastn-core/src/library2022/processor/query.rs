@@ -27,6 +27,7 @@ pub async fn process(
             message: format!("Cannot get path: {} {:?}", path.as_str(), e),
             doc_id: document_id.to_string(),
             line_number: value.line_number(),
+            span: Default::default(),
         })?;
     doc.from_json(
         &fastn_core::commands::query::get_ftd_json(&file, stage.as_str()).map_err(|e| {
@@ -34,6 +35,7 @@ pub async fn process(
                 message: format!("Cannot resolve json for path: {} {:?}", path.as_str(), e),
                 doc_id: document_id.to_string(),
                 line_number: value.line_number(),
+                span: Default::default(),
             }
         })?,
         &kind,
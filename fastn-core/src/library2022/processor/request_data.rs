@@ -4,8 +4,11 @@ pub fn process(
     doc: &ftd::interpreter::TDoc,
     config: &fastn_core::Config,
 ) -> ftd::interpreter::Result<ftd::interpreter::Value> {
-    let req = match config.request.as_ref() {
-        Some(v) => v,
+    let req = config.request.as_ref();
+    let mut data = match req {
+        Some(req) => req.query().clone(),
+        // `fastn build` renders the pages of dynamic urls without a request
+        None if !config.named_parameters.is_empty() => Default::default(),
         None if kind.is_optional() => {
             return Ok(ftd::interpreter::Value::Optional {
                 data: Box::new(None),
@@ -20,7 +23,6 @@ pub fn process(
             )
         }
     };
-    let mut data = req.query().clone();
 
     for (name, param_value) in config.named_parameters.iter() {
         let json_value =
@@ -34,17 +36,19 @@ pub fn process(
         data.insert(name.to_string(), json_value);
     }
 
-    match req.body_as_json() {
-        Ok(Some(b)) => {
-            data.extend(b);
-        }
-        Ok(None) => {}
-        Err(e) => {
-            return ftd::interpreter::utils::e2(
-                format!("Error while parsing request body: {e:?}"),
-                doc.name,
-                value.line_number(),
-            )
+    if let Some(req) = req {
+        match req.body_as_json() {
+            Ok(Some(b)) => {
+                data.extend(b);
+            }
+            Ok(None) => {}
+            Err(e) => {
+                return ftd::interpreter::utils::e2(
+                    format!("Error while parsing request body: {e:?}"),
+                    doc.name,
+                    value.line_number(),
+                )
+            }
         }
     }
 
@@ -6,6 +6,9 @@ const SPECIAL_CHARS: [char; 9] = [BACKSLASH, '$', '/', ':', '"', ',', '\'', ';',
 // TODO: Can improve the performance
 // Maybe I should use RegEx?
 
+/// `extract_arguments()` replaces the `$name`s of `query` with the positional parameters `$1`,
+/// `$2`, .., and returns the names in order. A `$` in a quoted string or identifier, in a `--` or
+/// `/* */` comment, or escaped with a `\` is left as it is.
 pub(crate) fn extract_arguments(query: &str) -> ftd::interpreter::Result<(String, Vec<String>)> {
     let chars: Vec<char> = query.chars().collect();
     let len = chars.len();
@@ -30,9 +33,24 @@ pub(crate) fn extract_arguments(query: &str) -> ftd::interpreter::Result<(String
                 output_query += &BACKSLASH.to_string().repeat(escape_count);
                 escaped = false;
             }
+
+            if i == len {
+                if escaped {
+                    output_query += &BACKSLASH.to_string();
+                }
+                break;
+            }
+        }
+
+        if !quote_open && !escaped {
+            if let Some(end) = comment_end(&chars, i) {
+                output_query.extend(&chars[i..end]);
+                i = end;
+                continue;
+            }
         }
 
-        if chars[i] == '"' && !escaped {
+        if (chars[i] == '"' || chars[i] == '\'') && !escaped {
             if quote_open {
                 if Some(chars[i]) == quote {
                     quote_open = false;
@@ -49,7 +67,7 @@ pub(crate) fn extract_arguments(query: &str) -> ftd::interpreter::Result<(String
             i += 1;
 
             while i < len {
-                if SPECIAL_CHARS.contains(&chars[i]) {
+                if SPECIAL_CHARS.contains(&chars[i]) || chars[i].is_whitespace() {
                     i -= 1;
                     break;
                 } else {
@@ -66,6 +84,8 @@ pub(crate) fn extract_arguments(query: &str) -> ftd::interpreter::Result<(String
                     let index = args.len();
                     output_query += &format!("${}", index);
                 }
+            } else {
+                output_query.push('$');
             }
         } else {
             if escaped {
@@ -86,6 +106,88 @@ pub(crate) fn extract_arguments(query: &str) -> ftd::interpreter::Result<(String
     Ok((output_query, args))
 }
 
+/// `comment_end()` is the index just after the comment starting at `chars[i]`, if a `--` or a
+/// `/*` starts there. A line comment ends before its newline, an unterminated block comment at
+/// the end of `chars`.
+fn comment_end(chars: &[char], i: usize) -> Option<usize> {
+    match (chars.get(i), chars.get(i + 1)) {
+        (Some('-'), Some('-')) => Some(
+            chars[i..]
+                .iter()
+                .position(|c| *c == '\n')
+                .map_or(chars.len(), |p| i + p),
+        ),
+        (Some('/'), Some('*')) => Some(
+            (i + 2..chars.len().saturating_sub(1))
+                .find(|j| chars[*j] == '*' && chars[*j + 1] == '/')
+                .map_or(chars.len(), |j| j + 2),
+        ),
+        _ => None,
+    }
+}
+
+/// `split_statements()` splits `query` at the `;`s that are not in a quoted string or
+/// identifier, or in a comment, so the statements can be prepared one by one. Empty statements
+/// are dropped.
+pub(crate) fn split_statements(query: &str) -> Vec<String> {
+    let chars: Vec<char> = query.chars().collect();
+    let mut statements = vec![];
+    let mut statement = String::new();
+    let mut quote: Option<char> = None;
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '\'' || c == '"' || c == '`' => quote = Some(c),
+            None if c == ';' => {
+                if !statement.trim().is_empty() {
+                    statements.push(statement.trim().to_string());
+                }
+                statement.clear();
+                i += 1;
+                continue;
+            }
+            None => {
+                if let Some(end) = comment_end(&chars, i) {
+                    statement.extend(&chars[i..end]);
+                    i = end;
+                    continue;
+                }
+            }
+        }
+        statement.push(c);
+        i += 1;
+    }
+    if !statement.trim().is_empty() {
+        statements.push(statement.trim().to_string());
+    }
+    statements
+}
+
+/// `resolve_variable()` is the value of the variable `var` of `doc`, for the `$var` arguments of
+/// a query.
+pub(crate) fn resolve_variable(
+    doc: &ftd::interpreter::TDoc<'_>,
+    var: &str,
+    line_number: usize,
+) -> ftd::interpreter::Result<ftd::interpreter::Value> {
+    match doc.get_thing(var, line_number) {
+        Ok(ftd::interpreter::Thing::Variable(v)) => v.value.resolve(doc, line_number),
+        Ok(v) => ftd::interpreter::utils::e2(
+            format!("{var} is not a variable, it's a {v:?}"),
+            doc.name,
+            line_number,
+        ),
+        Err(e) => ftd::interpreter::utils::e2(
+            format!("${var} not found in the document: {e:?}"),
+            doc.name,
+            line_number,
+        ),
+    }
+}
+
 #[cfg(test)]
 mod test {
     #[track_caller]
@@ -144,5 +246,52 @@ mod test {
             r#"SELECT * FROM test where name = \"$1\" and full_name = $1"#,
             vec!["name"],
         );
+        e(
+            "SELECT * FROM test where name = '$name' and full_name = $name",
+            "SELECT * FROM test where name = '$name' and full_name = $1",
+            vec!["name"],
+        );
+        e(
+            "SELECT 'it''s $1.00' AS price, $name",
+            "SELECT 'it''s $1.00' AS price, $1",
+            vec!["name"],
+        );
+        e(
+            "SELECT $name -- or $other\nFROM test",
+            "SELECT $1 -- or $other\nFROM test",
+            vec!["name"],
+        );
+        e(
+            "SELECT /* $other; 'x */ $name FROM test",
+            "SELECT /* $other; 'x */ $1 FROM test",
+            vec!["name"],
+        );
+        e("SELECT $name\n", "SELECT $1\n", vec!["name"]);
+        e(r"SELECT '\", r"SELECT '\", vec![]);
+    }
+
+    #[test]
+    fn split_statements() {
+        assert_eq!(
+            super::split_statements("SELECT 1; SELECT 2;\n"),
+            vec!["SELECT 1", "SELECT 2"]
+        );
+        assert_eq!(
+            super::split_statements("INSERT INTO t VALUES ('a;b', \"c;\"); SELECT ';'"),
+            vec!["INSERT INTO t VALUES ('a;b', \"c;\")", "SELECT ';'"]
+        );
+        assert_eq!(
+            super::split_statements("SELECT 'it''s; fine'"),
+            vec!["SELECT 'it''s; fine'"]
+        );
+        assert!(super::split_statements(" ;\n; ").is_empty());
+        assert_eq!(
+            super::split_statements("SELECT 1; -- it's done; really\nSELECT 2"),
+            vec!["SELECT 1", "-- it's done; really\nSELECT 2"]
+        );
+        assert_eq!(
+            super::split_statements("SELECT /* a; 'b */ 1; SELECT 2 /* c;"),
+            vec!["SELECT /* a; 'b */ 1", "SELECT 2 /* c;"]
+        );
     }
 }
@@ -24,6 +24,28 @@ pub(crate) fn get_p1_data(
     }
 }
 
+/// `process()` runs the statements in the body of `$processor$: package-query` on the sqlite
+/// database `db`. A `$name` in a statement is bound as a parameter, never pasted into the sql,
+/// like the `pg` processor does: its value is the header `name` if there is one, else the
+/// variable `name` of the document.
+///
+/// The database is opened read-only unless the header `mode` is `read-write`. Read-write queries
+/// only run for requests that are not GET, like the ones sent by `ftd.form`, so that rendering a
+/// page never writes. All the statements run in one transaction, if one fails none is applied.
+///
+/// The rows of the last statement that returns rows are the value of the variable. If the
+/// variable is a record, `results` names the fields the rows of each of these statements go to
+/// instead, in order:
+///
+/// ```ftd
+/// -- dashboard data:
+/// $processor$: pr.package-query
+/// db: db.sqlite
+/// results: people, departments
+///
+/// SELECT * FROM people WHERE department = $department;
+/// SELECT * FROM departments;
+/// ```
 pub async fn process(
     value: ftd::ast::VariableValue,
     kind: ftd::interpreter::Kind,
@@ -31,49 +53,96 @@ pub async fn process(
     config: &fastn_core::Config,
 ) -> ftd::interpreter::Result<ftd::interpreter::Value> {
     let (headers, query) = get_p1_data("package-data", &value, doc.name)?;
+    let line_number = value.line_number();
 
-    let sqlite_database =
-        match headers.get_optional_string_by_key("db", doc.name, value.line_number())? {
-            Some(k) => k,
-            None => {
-                return ftd::interpreter::utils::e2(
-                    "`db` is not specified".to_string(),
-                    doc.name,
-                    value.line_number(),
-                )
-            }
-        };
+    let sqlite_database = match headers.get_optional_string_by_key("db", doc.name, line_number)? {
+        Some(k) => k,
+        None => {
+            return ftd::interpreter::utils::e2(
+                "`db` is not specified".to_string(),
+                doc.name,
+                line_number,
+            )
+        }
+    };
     let mut sqlite_database_path = camino::Utf8PathBuf::new().join(sqlite_database.as_str());
     if !sqlite_database_path.exists() {
         if !config.root.join(sqlite_database_path.as_path()).exists() {
             return ftd::interpreter::utils::e2(
                 "`db` does not exists for package-query processor".to_string(),
                 doc.name,
-                value.line_number(),
+                line_number,
             );
         }
         sqlite_database_path = config.root.join(sqlite_database_path.as_path());
     }
 
-    // need the query params
-    // question is they can be multiple
-    // so lets say start with passing attributes from ftd file
-    // db-<param-name1>: value
-    // db-<param-name2>: value
-    // for now they wil be ordered
-    // select * from users where
+    let mode = match headers
+        .get_optional_string_by_key("mode", doc.name, line_number)?
+        .as_deref()
+    {
+        None | Some("read-only") => Mode::ReadOnly,
+        Some("read-write") => Mode::ReadWrite,
+        Some(v) => {
+            return ftd::interpreter::utils::e2(
+                format!("`mode` must be `read-only` or `read-write`, found: {}", v),
+                doc.name,
+                line_number,
+            )
+        }
+    };
+    if !mode.runs_for(config.request.as_ref().map(|r| r.method())) {
+        return ftd::interpreter::utils::e2(
+            "`mode: read-write` queries only run for requests that are not GET".to_string(),
+            doc.name,
+            line_number,
+        );
+    }
 
-    let query_response = execute_query(
+    let results = headers
+        .get_optional_string_by_key("results", doc.name, line_number)?
+        .map(|v| {
+            v.split(',')
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+                .collect::<Vec<_>>()
+        });
+
+    let mut statements = vec![];
+    for statement in super::sql::split_statements(query.as_str()) {
+        let (statement, args) = super::sql::extract_arguments(statement.as_str())?;
+        let mut params = vec![];
+        for arg in args {
+            params.push(resolve_argument(doc, &headers, arg.as_str(), line_number)?);
+        }
+        statements.push((statement, params));
+    }
+
+    let query_response = execute_statements(
         &sqlite_database_path,
-        query.as_str(),
+        statements.as_slice(),
+        mode,
         doc.name,
-        value.line_number(),
+        line_number,
     )
     .await;
 
-    match query_response {
-        Ok(result) => result_to_value(Ok(result), kind, doc, &value, super::sql::STATUS_OK),
-        Err(e) => result_to_value(
+    match (query_response, results) {
+        (Ok(result_sets), Some(results)) => {
+            result_sets_to_record(result_sets, results.as_slice(), &kind, doc, &value)
+        }
+        (Ok(result_sets), None) => result_to_value(
+            Ok(result_sets
+                .into_iter()
+                .last()
+                .map(|(_, rows)| rows)
+                .unwrap_or_default()),
+            kind,
+            doc,
+            &value,
+            super::sql::STATUS_OK,
+        ),
+        (Err(e), _) => result_to_value(
             Err(e.to_string()),
             kind,
             doc,
@@ -83,6 +152,156 @@ pub async fn process(
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Mode {
+    ReadOnly,
+    ReadWrite,
+}
+
+impl Mode {
+    /// `runs_for()` is false for a `read-write` query rendered without a request, or for a GET
+    /// request.
+    fn runs_for(&self, method: Option<&str>) -> bool {
+        match self {
+            Mode::ReadOnly => true,
+            Mode::ReadWrite => method.map_or(false, |m| !m.eq_ignore_ascii_case("get")),
+        }
+    }
+}
+
+/// `resolve_argument()` is the value bound to `$var` in a statement: the header `var`, which can
+/// itself refer to a variable, or the variable `var` of the document.
+fn resolve_argument(
+    doc: &ftd::interpreter::TDoc<'_>,
+    headers: &ftd::ast::HeaderValues,
+    var: &str,
+    line_number: usize,
+) -> ftd::interpreter::Result<rusqlite::types::Value> {
+    let value = match headers.optional_header_by_name(var, doc.name, line_number)? {
+        Some(header) => match &header.value {
+            ftd::ast::VariableValue::String { value, .. } => match value.strip_prefix('$') {
+                Some(stripped) => super::sql::resolve_variable(doc, stripped, line_number)?,
+                None => return Ok(rusqlite::types::Value::Text(value.to_string())),
+            },
+            v => {
+                return ftd::interpreter::utils::e2(
+                    format!("`{}` must be a string or a variable, found: {:?}", var, v),
+                    doc.name,
+                    line_number,
+                )
+            }
+        },
+        None => super::sql::resolve_variable(doc, var, line_number)?,
+    };
+    to_sqlite_value(value, var, doc.name, line_number)
+}
+
+fn to_sqlite_value(
+    value: ftd::interpreter::Value,
+    var: &str,
+    doc_name: &str,
+    line_number: usize,
+) -> ftd::interpreter::Result<rusqlite::types::Value> {
+    Ok(match value {
+        ftd::interpreter::Value::String { text } => rusqlite::types::Value::Text(text),
+        ftd::interpreter::Value::Integer { value } => rusqlite::types::Value::Integer(value),
+        ftd::interpreter::Value::Decimal { value } => rusqlite::types::Value::Real(value),
+        ftd::interpreter::Value::Boolean { value } => rusqlite::types::Value::Integer(value as i64),
+        ftd::interpreter::Value::Optional { data, .. } => match *data {
+            Some(value) => to_sqlite_value(value, var, doc_name, line_number)?,
+            None => rusqlite::types::Value::Null,
+        },
+        v => {
+            return ftd::interpreter::utils::e2(
+                format!(
+                    "${} can not be passed to sqlite, only string, integer, decimal and boolean \
+                    can, found: {:?}",
+                    var, v
+                ),
+                doc_name,
+                line_number,
+            )
+        }
+    })
+}
+
+/// `result_sets_to_record()` puts the rows of the `i`th statement returning rows in the field
+/// `results[i]` of the record `kind`.
+fn result_sets_to_record(
+    result_sets: Vec<ResultSet>,
+    results: &[String],
+    kind: &ftd::interpreter::Kind,
+    doc: &ftd::interpreter::TDoc<'_>,
+    value: &ftd::ast::VariableValue,
+) -> ftd::interpreter::Result<ftd::interpreter::Value> {
+    let name = match kind {
+        ftd::interpreter::Kind::Record { name } => name,
+        _ => {
+            return ftd::interpreter::utils::e2(
+                format!("`results` needs a record, found: {:?}", kind),
+                doc.name,
+                value.line_number(),
+            )
+        }
+    };
+    if result_sets.len() != results.len() {
+        return ftd::interpreter::utils::e2(
+            format!(
+                "`results` names {} result set(s), the query returned {}",
+                results.len(),
+                result_sets.len()
+            ),
+            doc.name,
+            value.line_number(),
+        );
+    }
+
+    let record = doc.get_record(name, value.line_number())?;
+    let mut fields: ftd::Map<ftd::interpreter::PropertyValue> = Default::default();
+    for (result, (_, rows)) in results.iter().zip(result_sets) {
+        let field = match record.fields.iter().find(|v| v.name.eq(result)) {
+            Some(field) if field.kind.is_list() => field,
+            _ => {
+                return ftd::interpreter::utils::e2(
+                    format!("`{}` is not a list field of record `{}`", result, name),
+                    doc.name,
+                    value.line_number(),
+                )
+            }
+        };
+        fields.insert(
+            result.to_string(),
+            doc.rows_to_value(rows.as_slice(), &field.kind.kind, value)?
+                .into_property_value(false, value.line_number()),
+        );
+    }
+    for field in record.fields.iter() {
+        if fields.contains_key(field.name.as_str()) {
+            continue;
+        }
+        match field.value {
+            Some(ref default) => {
+                fields.insert(field.name.to_string(), default.clone());
+            }
+            None => {
+                return ftd::interpreter::utils::e2(
+                    format!(
+                        "field `{}` of record `{}` is not in `results`",
+                        field.name, name
+                    ),
+                    doc.name,
+                    value.line_number(),
+                )
+            }
+        }
+    }
+
+    Ok(ftd::interpreter::Value::Record {
+        name: name.to_string(),
+        fields,
+    })
+}
+
 pub(crate) fn result_to_value(
     result: Result<Vec<Vec<serde_json::Value>>, String>,
     kind: ftd::interpreter::Kind,
@@ -118,21 +337,50 @@ pub(crate) fn result_to_value(
                 value: status as i64,
             }),
             "string" => Ok(ftd::interpreter::Value::String { text: (e) }),
-            _ => unimplemented!(),
+            _ => ftd::interpreter::utils::e2(e, doc.name, value.line_number()),
         },
     }
 }
 
-async fn execute_query(
+/// the names of the columns and the rows returned by a statement
+type ResultSet = (Vec<String>, Vec<Vec<serde_json::Value>>);
+
+/// `execute_query_with_columns()` runs the read-only `query`, which has no arguments, and returns
+/// the names of the columns of the result along with its rows.
+pub(crate) async fn execute_query_with_columns(
     database_path: &camino::Utf8Path,
     query: &str,
     doc_name: &str,
     line_number: usize,
-) -> ftd::interpreter::Result<Vec<Vec<serde_json::Value>>> {
-    let conn = match rusqlite::Connection::open_with_flags(
+) -> ftd::interpreter::Result<ResultSet> {
+    let statements = [(query.to_string(), vec![])];
+    Ok(execute_statements(
         database_path,
-        rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY,
-    ) {
+        &statements,
+        Mode::ReadOnly,
+        doc_name,
+        line_number,
+    )
+    .await?
+    .into_iter()
+    .last()
+    .unwrap_or_default())
+}
+
+/// `execute_statements()` runs `statements`, with the values bound to their parameters, in one
+/// transaction. It returns the result sets of the statements that return rows.
+async fn execute_statements(
+    database_path: &camino::Utf8Path,
+    statements: &[(String, Vec<rusqlite::types::Value>)],
+    mode: Mode,
+    doc_name: &str,
+    line_number: usize,
+) -> ftd::interpreter::Result<Vec<ResultSet>> {
+    let flags = match mode {
+        Mode::ReadOnly => rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY,
+        Mode::ReadWrite => rusqlite::OpenFlags::SQLITE_OPEN_READ_WRITE,
+    };
+    let mut conn = match rusqlite::Connection::open_with_flags(database_path, flags) {
         Ok(conn) => conn,
         Err(e) => {
             return ftd::interpreter::utils::e2(
@@ -142,54 +390,78 @@ async fn execute_query(
             );
         }
     };
-
-    let mut stmt = match conn.prepare(query) {
-        Ok(v) => v,
+    // dropping the transaction without committing it rolls it back
+    let tx = match conn.transaction() {
+        Ok(tx) => tx,
         Err(e) => {
             return ftd::interpreter::utils::e2(
-                format!("Failed to prepare query: {:?}", e),
+                format!("Failed to start a transaction: {:?}", e),
                 doc_name,
                 line_number,
-            )
+            );
         }
     };
 
-    let count = stmt.column_count();
-
-    // let mut stmt = conn.prepare("SELECT * FROM test where name = :name")?;
-    // let mut rows = stmt.query(rusqlite::named_params! { ":name": "one" })?
+    let mut result_sets = vec![];
+    for (query, params) in statements {
+        let mut stmt = match tx.prepare(query) {
+            Ok(v) => v,
+            Err(e) => {
+                return ftd::interpreter::utils::e2(
+                    format!("Failed to prepare query: {:?}", e),
+                    doc_name,
+                    line_number,
+                )
+            }
+        };
 
-    // let mut stmt = conn.prepare("SELECT * FROM test where name = ?")?;
-    // let mut rows = stmt.query([name])?;
-    let params: Vec<String> = vec![];
-    let mut rows = match stmt.query(rusqlite::params_from_iter(params)) {
-        Ok(v) => v,
-        Err(e) => {
-            return ftd::interpreter::utils::e2(
-                format!("Failed to prepare query: {:?}", e),
-                doc_name,
-                line_number,
-            )
-        }
-    };
+        let count = stmt.column_count();
+        let columns: Vec<String> = stmt
+            .column_names()
+            .into_iter()
+            .map(|v| v.to_string())
+            .collect();
 
-    let mut result: Vec<Vec<serde_json::Value>> = vec![];
-    loop {
-        match rows.next() {
-            Ok(None) => break,
-            Ok(Some(r)) => {
-                result.push(row_to_json(r, count, doc_name, line_number)?);
-            }
+        let mut rows = match stmt.query(rusqlite::params_from_iter(params.iter())) {
+            Ok(v) => v,
             Err(e) => {
                 return ftd::interpreter::utils::e2(
-                    format!("Failed to execute query: {:?}", e),
+                    format!("Failed to prepare query: {:?}", e),
                     doc_name,
                     line_number,
                 )
             }
+        };
+
+        let mut result: Vec<Vec<serde_json::Value>> = vec![];
+        loop {
+            match rows.next() {
+                Ok(None) => break,
+                Ok(Some(r)) => {
+                    result.push(row_to_json(r, count, doc_name, line_number)?);
+                }
+                Err(e) => {
+                    return ftd::interpreter::utils::e2(
+                        format!("Failed to execute query: {:?}", e),
+                        doc_name,
+                        line_number,
+                    )
+                }
+            }
+        }
+        if count > 0 {
+            result_sets.push((columns, result));
         }
     }
-    Ok(result)
+
+    if let Err(e) = tx.commit() {
+        return ftd::interpreter::utils::e2(
+            format!("Failed to commit the transaction: {:?}", e),
+            doc_name,
+            line_number,
+        );
+    }
+    Ok(result_sets)
 }
 
 fn row_to_json(
@@ -225,3 +497,103 @@ fn row_to_json(
     }
     Ok(row)
 }
+
+#[cfg(test)]
+mod test {
+    use super::Mode;
+
+    fn database(name: &str) -> camino::Utf8PathBuf {
+        let path = camino::Utf8PathBuf::from_path_buf(std::env::temp_dir())
+            .unwrap()
+            .join(format!("fastn-sqlite-{}-{}.db", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let conn = rusqlite::Connection::open(&path).unwrap();
+        conn.execute_batch("CREATE TABLE t (name TEXT NOT NULL UNIQUE);")
+            .unwrap();
+        path
+    }
+
+    fn run(
+        path: &camino::Utf8Path,
+        statements: &[&str],
+        mode: Mode,
+    ) -> ftd::interpreter::Result<Vec<super::ResultSet>> {
+        let statements = statements
+            .iter()
+            .map(|s| (s.to_string(), vec![]))
+            .collect::<Vec<_>>();
+        futures::executor::block_on(super::execute_statements(
+            path,
+            statements.as_slice(),
+            mode,
+            "foo",
+            1,
+        ))
+    }
+
+    fn names(path: &camino::Utf8Path) -> Vec<serde_json::Value> {
+        run(path, &["SELECT name FROM t ORDER BY name"], Mode::ReadOnly)
+            .unwrap()
+            .pop()
+            .unwrap()
+            .1
+            .into_iter()
+            .flatten()
+            .collect()
+    }
+
+    #[test]
+    fn read_write_commits_and_returns_result_sets() {
+        let path = database("commit");
+        let result_sets = run(
+            &path,
+            &[
+                "INSERT INTO t VALUES ('a')",
+                "INSERT INTO t VALUES ('b')",
+                "SELECT count(*) AS total FROM t",
+            ],
+            Mode::ReadWrite,
+        )
+        .unwrap();
+        assert_eq!(
+            result_sets,
+            vec![(vec!["total".to_string()], vec![vec![serde_json::json!(2)]])]
+        );
+        assert_eq!(
+            names(&path),
+            vec![serde_json::json!("a"), serde_json::json!("b")]
+        );
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn failing_statement_rolls_back_the_transaction() {
+        let path = database("rollback");
+        assert!(run(
+            &path,
+            &["INSERT INTO t VALUES ('a')", "INSERT INTO t VALUES ('a')"],
+            Mode::ReadWrite,
+        )
+        .is_err());
+        assert!(names(&path).is_empty());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn read_only_refuses_writes() {
+        let path = database("read-only");
+        assert!(run(&path, &["INSERT INTO t VALUES ('a')"], Mode::ReadOnly).is_err());
+        assert!(names(&path).is_empty());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn read_write_only_runs_for_requests_that_are_not_get() {
+        assert!(Mode::ReadOnly.runs_for(None));
+        assert!(Mode::ReadOnly.runs_for(Some("GET")));
+        assert!(!Mode::ReadWrite.runs_for(None));
+        assert!(!Mode::ReadWrite.runs_for(Some("GET")));
+        assert!(!Mode::ReadWrite.runs_for(Some("get")));
+        assert!(Mode::ReadWrite.runs_for(Some("POST")));
+    }
+}
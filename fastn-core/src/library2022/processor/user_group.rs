@@ -33,6 +33,7 @@ pub fn process_by_id(
                 message: "`id` field is mandatory in `user-group-by-id` processor".to_string(),
                 doc_id: doc.name.to_string(),
                 line_number: value.line_number(),
+                span: Default::default(),
             })
         }
     };
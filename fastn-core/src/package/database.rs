@@ -0,0 +1,87 @@
+/// `Database` is a postgres database declared in `FASTN.ftd` using `fastn.database`, for the
+/// `pg` processor to query.
+#[derive(serde::Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Database {
+    pub name: String,
+    /// the environment variable with the url of the database
+    #[serde(rename = "url-env")]
+    pub url_env: String,
+    /// the environment variable with the path of the certificate of the server
+    #[serde(rename = "certificate-env")]
+    pub certificate_env: Option<String>,
+    /// the most connections to keep open, deadpool's default if not set
+    #[serde(rename = "pool-size")]
+    pub pool_size: Option<usize>,
+    /// seconds to wait for a connection
+    pub timeout: Option<u64>,
+}
+
+pub const DEFAULT_URL_ENV: &str = "FASTN_PG_URL";
+pub const DEFAULT_CERTIFICATE_ENV: &str = "FASTN_PG_CERTIFICATE";
+
+impl Database {
+    /// `default_database()` is the database the `pg` processor queries when a package declares
+    /// none, configured by `FASTN_PG_URL` and `FASTN_PG_CERTIFICATE`.
+    pub fn default_database() -> Database {
+        Database {
+            name: "default".to_string(),
+            url_env: DEFAULT_URL_ENV.to_string(),
+            certificate_env: Some(DEFAULT_CERTIFICATE_ENV.to_string()),
+            pool_size: None,
+            timeout: None,
+        }
+    }
+}
+
+/// `validate()` rejects the databases of `package_name` that can never give out a connection.
+pub fn validate(package_name: &str, databases: &[Database]) -> fastn_core::Result<()> {
+    if let Some(database) = databases.iter().find(|v| v.pool_size == Some(0)) {
+        return Err(fastn_core::Error::PackageError {
+            message: format!(
+                "`pool-size` of the database `{}` in {}/FASTN.ftd has to be more than 0",
+                database.name, package_name
+            ),
+        });
+    }
+    Ok(())
+}
+
+impl fastn_core::Package {
+    /// `database()` is the database named `name`, or the default one if `name` is `None`.
+    pub fn database(&self, name: Option<&str>) -> fastn_core::Result<Database> {
+        match name {
+            Some(name) => self
+                .databases
+                .iter()
+                .find(|v| v.name.eq(name))
+                .cloned()
+                .ok_or_else(|| fastn_core::Error::PackageError {
+                    message: format!(
+                        "database `{}` is not declared using `fastn.database` in {}/FASTN.ftd",
+                        name, self.name
+                    ),
+                }),
+            None => Ok(self
+                .databases
+                .first()
+                .cloned()
+                .unwrap_or_else(Database::default_database)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn validate() {
+        let database = |pool_size| super::Database {
+            pool_size,
+            ..super::Database::default_database()
+        };
+        assert!(super::validate("foo", &[database(None), database(Some(1))]).is_ok());
+        assert!(matches!(
+            super::validate("foo", &[database(Some(4)), database(Some(0))]),
+            Err(fastn_core::Error::PackageError { .. })
+        ));
+    }
+}
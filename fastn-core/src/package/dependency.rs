@@ -115,12 +115,15 @@ impl fastn_core::Package {
         downloaded_package: &mut Vec<String>,
         download_translations: bool,
         download_dependencies: bool,
+        version: Option<&str>,
     ) -> fastn_core::Result<()> {
         use std::io::Write;
-        // TODO: in future we will check if we have a new version in the package's repo.
-        //       for now assume if package exists we have the latest package and if you
-        //       want to update a package, delete the corresponding folder and latest
-        //       version will get downloaded.
+        // If package exists we assume it is the version we want, if you want to update a
+        // package, delete the corresponding folder (or run `fastn update`) and the latest
+        // version matching `version` will get downloaded.
+        let requirement = version
+            .map(|v| fastn_core::package::lock::parse_requirement(self.name.as_str(), v))
+            .transpose()?;
 
         // TODO: Fix this. Removing this because if a package has been downloaded as both an intermediate dependency
         // and as a direct dependency, then the code results in non evaluation of the dependend package
@@ -140,7 +143,8 @@ impl fastn_core::Package {
             let file_extract_path = path.join(format!("{}.ftd", name));
             if !file_extract_path.exists() {
                 std::fs::create_dir_all(&path)?;
-                let fastn_string = get_fastn(self.name.as_str()).await?;
+                let fastn_string =
+                    get_fastn(base_dir, self.name.as_str(), requirement.as_ref()).await?;
                 let mut f = std::fs::File::create(&file_extract_path)?;
                 f.write_all(fastn_string.as_bytes())?;
             }
@@ -159,7 +163,8 @@ impl fastn_core::Package {
         // Download everything of dependent package
         if !root.exists() {
             // Download the FASTN.ftd file first for the package to download.
-            let fastn_string = get_fastn(self.name.as_str()).await?;
+            let fastn_string =
+                get_fastn(base_dir, self.name.as_str(), requirement.as_ref()).await?;
 
             // Read FASTN.ftd and get download zip url from `zip` argument
             let download_url = {
@@ -203,6 +208,12 @@ impl fastn_core::Package {
                     } else {
                         crate::http::http_get(format!("http://{}", download_url).as_str()).await?
                     };
+                fastn_core::package::lock::verify(
+                    base_dir,
+                    self.name.as_str(),
+                    download_url.as_str(),
+                    response.as_slice(),
+                )?;
                 let mut file = std::fs::File::create(&path)?;
                 // TODO: instead of reading the whole thing in memory use tokio::io::copy() somehow?
                 file.write_all(&response)?;
@@ -282,23 +293,16 @@ impl fastn_core::Package {
         )
         .await;
 
-        async fn get_fastn(name: &str) -> fastn_core::Result<String> {
-            if let Ok(response_fastn) =
-                crate::http::http_get_str(format!("https://{}/FASTN.ftd", name).as_str()).await
-            {
-                Ok(response_fastn)
-            } else if let Ok(response_fastn) =
-                crate::http::http_get_str(format!("http://{}/FASTN.ftd", name).as_str()).await
-            {
-                Ok(response_fastn)
-            } else {
-                Err(fastn_core::Error::UsageError {
-                    message: format!(
-                        "Unable to find the FASTN.ftd for the dependency package: {}",
-                        name
-                    ),
-                })
-            }
+        async fn get_fastn(
+            base_dir: &camino::Utf8Path,
+            name: &str,
+            requirement: Option<&semver::VersionReq>,
+        ) -> fastn_core::Result<String> {
+            Ok(
+                fastn_core::package::lock::fetch_locked(base_dir, name, requirement)
+                    .await?
+                    .0,
+            )
         }
     }
 
@@ -308,14 +312,17 @@ impl fastn_core::Package {
         downloaded_package: &mut Vec<String>,
         download_translations: bool,
         download_dependencies: bool,
+        version: Option<&str>,
     ) -> fastn_core::Result<()> {
         use std::io::Write;
         use tokio::io::AsyncWriteExt;
 
-        // TODO: in future we will check if we have a new version in the package's repo.
-        //       for now assume if package exists we have the latest package and if you
-        //       want to update a package, delete the corresponding folder and latest
-        //       version will get downloaded.
+        // If package exists we assume it is the version we want, if you want to update a
+        // package, delete the corresponding folder (or run `fastn update`) and the latest
+        // version matching `version` will get downloaded.
+        let requirement = version
+            .map(|v| fastn_core::package::lock::parse_requirement(self.name.as_str(), v))
+            .transpose()?;
 
         // TODO: Fix this. Removing this because if a package has been downloaded as both an intermediate dependency
         // and as a direct dependency, then the code results in non evaluation of the dependend package
@@ -336,7 +343,8 @@ impl fastn_core::Package {
             let file_extract_path = path.join(format!("{}.ftd", name));
             if !file_extract_path.exists() {
                 std::fs::create_dir_all(&path)?;
-                let fastn_string = get_fastn(self.name.as_str()).await?;
+                let fastn_string =
+                    get_fastn(base_dir, self.name.as_str(), requirement.as_ref()).await?;
                 let mut f = std::fs::File::create(&file_extract_path)?;
                 f.write_all(fastn_string.as_bytes())?;
             }
@@ -355,7 +363,8 @@ impl fastn_core::Package {
         // Download everything of dependent package
         if !root.exists() {
             // Download the FASTN.ftd file first for the package to download.
-            let fastn_string = get_fastn(self.name.as_str()).await?;
+            let fastn_string =
+                get_fastn(base_dir, self.name.as_str(), requirement.as_ref()).await?;
             std::fs::create_dir_all(&root)?;
             let mut file = tokio::fs::File::create(root.join("FASTN.ftd")).await?;
             file.write_all(fastn_string.as_bytes()).await?;
@@ -403,23 +412,16 @@ impl fastn_core::Package {
         )
         .await;
 
-        async fn get_fastn(name: &str) -> fastn_core::Result<String> {
-            if let Ok(response_fastn) =
-                crate::http::http_get_str(format!("https://{}/FASTN.ftd", name).as_str()).await
-            {
-                Ok(response_fastn)
-            } else if let Ok(response_fastn) =
-                crate::http::http_get_str(format!("http://{}/FASTN.ftd", name).as_str()).await
-            {
-                Ok(response_fastn)
-            } else {
-                Err(fastn_core::Error::UsageError {
-                    message: format!(
-                        "Unable to find the FASTN.ftd for the dependency package: {}",
-                        name
-                    ),
-                })
-            }
+        async fn get_fastn(
+            base_dir: &camino::Utf8Path,
+            name: &str,
+            requirement: Option<&semver::VersionReq>,
+        ) -> fastn_core::Result<String> {
+            Ok(
+                fastn_core::package::lock::fetch_locked(base_dir, name, requirement)
+                    .await?
+                    .0,
+            )
         }
     }
 
@@ -448,6 +450,15 @@ impl fastn_core::Package {
             } else {
                 crate::http::http_get(format!("http://{}", download_url).as_str()).await?
             };
+            // the package is unpacked in the current directory, so it is locked in the lock of
+            // the package there
+            let root: camino::Utf8PathBuf = std::env::current_dir()?.canonicalize()?.try_into()?;
+            fastn_core::package::lock::verify(
+                &root,
+                self.name.as_str(),
+                download_url.as_str(),
+                response.as_slice(),
+            )?;
             let mut file = std::fs::File::create(&path)?;
             // TODO: instead of reading the whole thing in memory use tokio::io::copy() somehow?
             file.write_all(&response)?;
@@ -569,7 +580,13 @@ impl fastn_core::Package {
                     .await?;
                 } else {
                     dep.package
-                        .process(base_path, downloaded_package, false, true)
+                        .process(
+                            base_path,
+                            downloaded_package,
+                            false,
+                            true,
+                            dep.version.as_deref(),
+                        )
                         .await?;
                 }
             }
@@ -605,7 +622,7 @@ impl fastn_core::Package {
                     .await?;
                 } else {
                     translation
-                        .process(base_path, downloaded_package, false, false)
+                        .process(base_path, downloaded_package, false, false, None)
                         .await?;
                 }
             }
@@ -691,7 +708,13 @@ impl fastn_core::Package {
                     .await?;
                 } else {
                     dep.package
-                        .process2(base_path, downloaded_package, false, true)
+                        .process2(
+                            base_path,
+                            downloaded_package,
+                            false,
+                            true,
+                            dep.version.as_deref(),
+                        )
                         .await?;
                 }
             }
@@ -730,7 +753,7 @@ impl fastn_core::Package {
                     .await?;
                 } else {
                     translation
-                        .process2(base_path, downloaded_package, false, false)
+                        .process2(base_path, downloaded_package, false, false, None)
                         .await?;
                 }
             }
@@ -0,0 +1,645 @@
+pub const LOCK_FILE: &str = "FASTN.lock";
+
+/// `LockedPackage` records the exact copy of a dependency that a package was built with. It is
+/// stored in `FASTN.lock`, next to `FASTN.ftd`:
+///
+/// ```ftd
+/// -- import: fastn
+///
+/// -- fastn.lock: fifthtry.github.io/doc-site
+/// version: 1.2.0
+/// checksum: 4A5D...
+/// download-base-url: fifthtry.github.io/doc-site/v1.2.0
+/// ```
+///
+/// `checksum` is the sha256 of the dependency's `FASTN.ftd`. `version` is only present if the
+/// dependency was declared with a `version` and was resolved against the dependency's
+/// `fastn.release` list.
+///
+/// Every other file downloaded for a dependency, and its zip archive, is recorded with a
+/// `fastn.lock-file` entry, see `LockedFile`.
+#[derive(serde::Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct LockedPackage {
+    pub name: String,
+    pub version: Option<String>,
+    pub checksum: String,
+    #[serde(rename = "download-base-url")]
+    pub download_base_url: Option<String>,
+}
+
+impl LockedPackage {
+    fn fastn_url(&self) -> String {
+        format!(
+            "{}/FASTN.ftd",
+            self.download_base_url
+                .as_deref()
+                .unwrap_or(self.name.as_str())
+                .trim_end_matches('/')
+        )
+    }
+
+    /// A locked package can be reused only if it still satisfies the version requirement
+    /// declared in `FASTN.ftd`. If the requirement was added or changed after the lock was
+    /// written, the dependency has to be resolved again.
+    fn satisfies(&self, requirement: Option<&semver::VersionReq>) -> bool {
+        match (requirement, self.version.as_ref()) {
+            (None, None) => true,
+            (Some(requirement), Some(version)) => match parse_version(version) {
+                Ok(version) => requirement.matches(&version),
+                Err(_) => false,
+            },
+            _ => false,
+        }
+    }
+}
+
+/// `LockedFile` is the checksum of a file of a dependency, the first time it was downloaded:
+///
+/// ```ftd
+/// -- fastn.lock-file: fifthtry.github.io/doc-site
+/// path: index.ftd
+/// checksum: 9F1C...
+/// ```
+///
+/// `path` is relative to the package, for the zip archive of the package it is the `zip` url.
+#[derive(serde::Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct LockedFile {
+    pub package: String,
+    pub path: String,
+    pub checksum: String,
+}
+
+/// `ReleaseTemp` maps the `fastn.release` entries a package publishes in its `FASTN.ftd`.
+#[derive(serde::Deserialize, Debug, Clone)]
+pub(crate) struct ReleaseTemp {
+    pub version: String,
+    #[serde(rename = "download-base-url")]
+    pub download_base_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Lockfile {
+    pub packages: std::collections::BTreeMap<String, LockedPackage>,
+    /// checksums of the downloaded files, by package and then by path
+    pub files: std::collections::BTreeMap<String, std::collections::BTreeMap<String, String>>,
+    /// `changed` is set when an entry is added, till the lock file is written
+    changed: bool,
+    /// `held` defers writing the lock file till `release()`, see `hold()`
+    held: bool,
+}
+
+impl Lockfile {
+    pub(crate) fn read(root: &camino::Utf8Path) -> fastn_core::Result<Lockfile> {
+        let path = root.join(LOCK_FILE);
+        if !path.exists() {
+            return Ok(Default::default());
+        }
+
+        let content = std::fs::read_to_string(&path)?;
+        if content.trim().is_empty() {
+            return Ok(Default::default());
+        }
+
+        let lib = fastn_core::FastnLibrary::default();
+        let doc = match fastn_core::doc::parse_ftd(LOCK_FILE, content.as_str(), &lib) {
+            Ok(v) => v,
+            Err(e) => {
+                return Err(fastn_core::Error::PackageError {
+                    message: format!("failed to parse {}: {:?}", LOCK_FILE, &e),
+                });
+            }
+        };
+
+        let packages: Vec<LockedPackage> = doc.get("fastn#lock")?;
+        let mut lockfile = Lockfile {
+            packages: packages
+                .into_iter()
+                .map(|v| (v.name.to_string(), v))
+                .collect(),
+            ..Default::default()
+        };
+        let files: Vec<LockedFile> = doc.get("fastn#lock-file")?;
+        for file in files {
+            lockfile
+                .files
+                .entry(file.package)
+                .or_default()
+                .insert(file.path, file.checksum);
+        }
+        Ok(lockfile)
+    }
+
+    pub(crate) fn write(&self, root: &camino::Utf8Path) -> fastn_core::Result<()> {
+        let mut lock_data = "-- import: fastn".to_string();
+
+        for package in self.packages.values() {
+            lock_data = format!("{}\n\n-- fastn.lock: {}", lock_data, package.name);
+            if let Some(ref version) = package.version {
+                lock_data = format!("{}\nversion: {}", lock_data, version);
+            }
+            lock_data = format!("{}\nchecksum: {}", lock_data, package.checksum);
+            if let Some(ref download_base_url) = package.download_base_url {
+                lock_data = format!("{}\ndownload-base-url: {}", lock_data, download_base_url);
+            }
+        }
+
+        for (package, files) in self.files.iter() {
+            for (path, checksum) in files.iter() {
+                lock_data = format!(
+                    "{}\n\n-- fastn.lock-file: {}\npath: {}\nchecksum: {}",
+                    lock_data, package, path, checksum
+                );
+            }
+        }
+        lock_data.push('\n');
+
+        std::fs::write(root.join(LOCK_FILE), lock_data)?;
+        Ok(())
+    }
+
+    pub fn get(&self, name: &str) -> Option<&LockedPackage> {
+        self.packages.get(name)
+    }
+
+    pub(crate) fn insert(&mut self, package: LockedPackage) {
+        if self.packages.get(package.name.as_str()) != Some(&package) {
+            self.packages.insert(package.name.to_string(), package);
+            self.changed = true;
+        }
+    }
+
+    /// `verify()` checks `content`, downloaded as `path` of `package`, against the checksum
+    /// recorded for it. A file that is downloaded for the first time is recorded.
+    pub(crate) fn verify(
+        &mut self,
+        package: &str,
+        path: &str,
+        content: &[u8],
+    ) -> fastn_core::Result<()> {
+        let checksum = fastn_core::utils::generate_hash(content);
+        let files = self.files.entry(package.to_string()).or_default();
+        match files.get(path) {
+            Some(locked) if locked.ne(&checksum) => Err(fastn_core::Error::PackageError {
+                message: format!(
+                    "`{}` of `{}` has changed since it was locked in {} (expected checksum {}, \
+                    found {}). Run `fastn update` to accept the new version",
+                    path, package, LOCK_FILE, locked, checksum
+                ),
+            }),
+            Some(_) => Ok(()),
+            None => {
+                files.insert(path.to_string(), checksum);
+                self.changed = true;
+                Ok(())
+            }
+        }
+    }
+}
+
+/// The lock files of the packages this process works on, by package root. They are shared by
+/// all the copies of the config, so that every download, including the ones of the build
+/// workers, is checked against and recorded in the same lock.
+static LOCKFILES: once_cell::sync::Lazy<
+    antidote::Mutex<std::collections::HashMap<camino::Utf8PathBuf, Lockfile>>,
+> = once_cell::sync::Lazy::new(|| antidote::Mutex::new(Default::default()));
+
+/// `load()` reads `FASTN.lock` of the package at `root` again, dropping what was read before.
+pub(crate) fn load(root: &camino::Utf8Path) -> fastn_core::Result<()> {
+    let lockfile = Lockfile::read(root)?;
+    LOCKFILES.lock().insert(root.to_path_buf(), lockfile);
+    Ok(())
+}
+
+/// `with_lockfile()` runs `f` on the lock of the package at `root`, and writes it if `f` changed
+/// it.
+pub(crate) fn with_lockfile<T>(
+    root: &camino::Utf8Path,
+    f: impl FnOnce(&mut Lockfile) -> fastn_core::Result<T>,
+) -> fastn_core::Result<T> {
+    let mut lockfiles = LOCKFILES.lock();
+    let lockfile = match lockfiles.entry(root.to_path_buf()) {
+        std::collections::hash_map::Entry::Occupied(e) => e.into_mut(),
+        std::collections::hash_map::Entry::Vacant(e) => e.insert(Lockfile::read(root)?),
+    };
+    let output = f(lockfile);
+    if lockfile.changed && !lockfile.held {
+        lockfile.write(root)?;
+        lockfile.changed = false;
+    }
+    output
+}
+
+/// `hold()` keeps the changes to the lock of the package at `root` in memory, they are written
+/// once by `release()`. `fastn build` holds the lock while documents are built, so the files
+/// downloaded by concurrent workers are recorded in a single write of `FASTN.lock`.
+pub(crate) fn hold(root: &camino::Utf8Path) -> fastn_core::Result<()> {
+    with_lockfile(root, |lockfile| {
+        lockfile.held = true;
+        Ok(())
+    })
+}
+
+/// `release()` writes the changes made to the lock since `hold()`.
+pub(crate) fn release(root: &camino::Utf8Path) -> fastn_core::Result<()> {
+    with_lockfile(root, |lockfile| {
+        lockfile.held = false;
+        Ok(())
+    })
+}
+
+/// `verify()` checks a file downloaded for the dependency `package` of the package at `root`,
+/// see `Lockfile::verify()`.
+pub(crate) fn verify(
+    root: &camino::Utf8Path,
+    package: &str,
+    path: &str,
+    content: &[u8],
+) -> fastn_core::Result<()> {
+    with_lockfile(root, |lockfile| {
+        lockfile.verify(package, path.trim_start_matches('/'), content)
+    })
+}
+
+/// `verify_download()` checks a file downloaded into `package_root`. Only files of dependencies,
+/// stored in `<root>/.packages/<package>`, are locked.
+pub(crate) fn verify_download(
+    package: &str,
+    package_root: &camino::Utf8Path,
+    path: &str,
+    content: &[u8],
+) -> fastn_core::Result<()> {
+    match root_of_dependency(package, package_root) {
+        Some(root) => verify(root, package, path, content),
+        None => Ok(()),
+    }
+}
+
+/// `root_of_dependency()` is `<root>` if `package_root` is in `<root>/.packages/<package>`.
+fn root_of_dependency<'a>(
+    package: &str,
+    package_root: &'a camino::Utf8Path,
+) -> Option<&'a camino::Utf8Path> {
+    package_root
+        .ancestors()
+        .filter(|v| v.file_name() == Some(".packages"))
+        .find(|v| package_root.starts_with(v.join(package)))
+        .and_then(|v| v.parent())
+}
+
+/// Published versions may leave out the minor or patch component (`v1`, `1.2`), `semver` does
+/// not, so we fill them with zeros.
+pub(crate) fn parse_version(s: &str) -> fastn_core::Result<semver::Version> {
+    let v = s.trim().strip_prefix(['v', 'V']).unwrap_or(s.trim());
+    let v = match v.matches('.').count() {
+        0 => format!("{}.0.0", v),
+        1 => format!("{}.0", v),
+        _ => v.to_string(),
+    };
+    semver::Version::parse(v.as_str()).map_err(|e| fastn_core::Error::PackageError {
+        message: format!("Invalid version `{}`: {}", s, e),
+    })
+}
+
+pub(crate) fn parse_requirement(
+    package_name: &str,
+    s: &str,
+) -> fastn_core::Result<semver::VersionReq> {
+    semver::VersionReq::parse(s.trim()).map_err(|e| fastn_core::Error::PackageError {
+        message: format!(
+            "Invalid version requirement `{}` for dependency `{}`: {}",
+            s, package_name, e
+        ),
+    })
+}
+
+/// Returns the content of `FASTN.ftd` of package `name` that best matches `requirement`, along
+/// with the resolved version and the base url the files of that version are served from.
+///
+/// Without a requirement this is whatever `https://{name}/FASTN.ftd` serves today.
+pub(crate) async fn fetch_fastn(
+    name: &str,
+    requirement: Option<&semver::VersionReq>,
+) -> fastn_core::Result<(String, Option<String>, Option<String>)> {
+    let latest =
+        fastn_core::http::construct_url_and_get_str(format!("{}/FASTN.ftd", name).as_str())
+            .await
+            .map_err(|_| fastn_core::Error::PackageError {
+                message: format!(
+                    "Unable to find the FASTN.ftd for the dependency package: {}",
+                    name
+                ),
+            })?;
+
+    let requirement = match requirement {
+        Some(requirement) => requirement,
+        None => return Ok((latest, None, None)),
+    };
+
+    let releases: Vec<ReleaseTemp> = {
+        let lib = fastn_core::FastnLibrary::default();
+        match fastn_core::doc::parse_ftd("fastn", latest.as_str(), &lib) {
+            Ok(v) => v.get("fastn#release")?,
+            Err(e) => {
+                return Err(fastn_core::Error::PackageError {
+                    message: format!("failed to parse FASTN.ftd of {}: {:?}", name, &e),
+                });
+            }
+        }
+    };
+
+    let mut matching = vec![];
+    for release in releases {
+        let version = parse_version(release.version.as_str())?;
+        if requirement.matches(&version) {
+            matching.push((version, release));
+        }
+    }
+
+    let (version, release) = match matching.into_iter().max_by(|a, b| a.0.cmp(&b.0)) {
+        Some(v) => v,
+        None => {
+            return Err(fastn_core::Error::PackageError {
+                message: format!(
+                    "No published version of `{}` matches `{}`. Published versions are listed \
+                    with `-- fastn.release:` in its FASTN.ftd",
+                    name, requirement
+                ),
+            })
+        }
+    };
+
+    let download_base_url = release.download_base_url.unwrap_or_else(|| {
+        format!(
+            "{}/v{}",
+            name,
+            release.version.trim_start_matches(['v', 'V'])
+        )
+    });
+    let content = fastn_core::http::construct_url_and_get_str(
+        format!("{}/FASTN.ftd", download_base_url.trim_end_matches('/')).as_str(),
+    )
+    .await
+    .map_err(|_| fastn_core::Error::PackageError {
+        message: format!(
+            "Unable to find the FASTN.ftd for version {} of the dependency package: {}",
+            version, name
+        ),
+    })?;
+
+    Ok((content, Some(version.to_string()), Some(download_base_url)))
+}
+
+/// `fetch_locked()` is the content of `FASTN.ftd` of the dependency `name` of the package at
+/// `root`, as recorded in its lock. A dependency that is not locked yet, or no longer satisfies
+/// `requirement`, is resolved against its published versions and added to the lock.
+pub(crate) async fn fetch_locked(
+    root: &camino::Utf8Path,
+    name: &str,
+    requirement: Option<&semver::VersionReq>,
+) -> fastn_core::Result<(String, LockedPackage)> {
+    let locked = with_lockfile(root, |lockfile| {
+        Ok(lockfile
+            .get(name)
+            .filter(|l| l.satisfies(requirement))
+            .cloned())
+    })?;
+
+    match locked {
+        Some(locked) => {
+            let content =
+                fastn_core::http::construct_url_and_get_str(locked.fastn_url().as_str()).await?;
+            let checksum = fastn_core::utils::generate_hash(content.as_str());
+            if checksum.ne(&locked.checksum) {
+                return Err(fastn_core::Error::PackageError {
+                    message: format!(
+                        "{} has changed since it was locked in {} (expected checksum {}, \
+                        found {}). Run `fastn update` to accept the new version",
+                        locked.fastn_url(),
+                        LOCK_FILE,
+                        locked.checksum,
+                        checksum
+                    ),
+                });
+            }
+            Ok((content, locked))
+        }
+        None => {
+            let (content, version, download_base_url) = fetch_fastn(name, requirement).await?;
+            let locked = LockedPackage {
+                name: name.to_string(),
+                version,
+                checksum: fastn_core::utils::generate_hash(content.as_str()),
+                download_base_url,
+            };
+            with_lockfile(root, |lockfile| {
+                lockfile.insert(locked.clone());
+                Ok(())
+            })?;
+            Ok((content, locked))
+        }
+    }
+}
+
+impl fastn_core::Config {
+    pub fn lock_file(&self) -> camino::Utf8PathBuf {
+        self.root.join(LOCK_FILE)
+    }
+
+    /// Version requirement for `name` as declared by the current package, or by any already
+    /// resolved package that depends on it.
+    pub(crate) fn dependency_requirement(
+        &self,
+        name: &str,
+    ) -> fastn_core::Result<Option<semver::VersionReq>> {
+        let version = self
+            .package
+            .dependencies
+            .iter()
+            .find(|d| d.package.name.eq(name))
+            .and_then(|d| d.version.clone())
+            .or_else(|| {
+                self.all_packages.borrow().values().find_map(|p| {
+                    p.dependencies
+                        .iter()
+                        .find(|d| d.package.name.eq(name))
+                        .and_then(|d| d.version.clone())
+                })
+            });
+
+        version
+            .map(|v| parse_requirement(name, v.as_str()))
+            .transpose()
+    }
+
+    /// Makes sure `FASTN.ftd` of a dependency is present in `package_root`, and that it is the
+    /// one recorded in `FASTN.lock`. Dependencies that are not in the lock file yet are resolved
+    /// against their published versions and added to it.
+    #[tracing::instrument(skip(self, package))]
+    pub(crate) async fn fetch_locked_fastn(
+        &self,
+        package: &fastn_core::Package,
+        package_root: &camino::Utf8PathBuf,
+    ) -> fastn_core::Result<LockedPackage> {
+        let fastn_path = package_root.join("FASTN.ftd");
+        let requirement = self.dependency_requirement(package.name.as_str())?;
+
+        if fastn_path.exists() {
+            let locked = with_lockfile(&self.root, |lockfile| {
+                Ok(lockfile
+                    .get(package.name.as_str())
+                    .filter(|l| l.satisfies(requirement.as_ref()))
+                    .cloned())
+            })?;
+            let checksum = fastn_core::utils::generate_hash(tokio::fs::read(&fastn_path).await?);
+            match locked {
+                Some(locked) if locked.checksum.ne(&checksum) => {
+                    return Err(fastn_core::Error::PackageError {
+                        message: format!(
+                            "{} of `{}` does not match {} (expected checksum {}, found {}). \
+                            Run `fastn update` to resolve the dependencies again",
+                            fastn_path, package.name, LOCK_FILE, locked.checksum, checksum
+                        ),
+                    });
+                }
+                Some(locked) => return Ok(locked),
+                // An unversioned dependency downloaded before the lock file existed
+                None if requirement.is_none() => {
+                    let locked = LockedPackage {
+                        name: package.name.to_string(),
+                        version: None,
+                        checksum,
+                        download_base_url: None,
+                    };
+                    with_lockfile(&self.root, |lockfile| {
+                        lockfile.insert(locked.clone());
+                        Ok(())
+                    })?;
+                    return Ok(locked);
+                }
+                // The requirement has changed since the dependency was downloaded
+                None => {}
+            }
+        }
+
+        let (content, locked) =
+            fetch_locked(&self.root, package.name.as_str(), requirement.as_ref()).await?;
+        fastn_core::utils::update(&fastn_path, content.as_bytes()).await?;
+        Ok(locked)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // cargo test --package fastn-core --lib package::lock::tests::parse_version
+    #[test]
+    fn parse_version() {
+        assert_eq!(
+            super::parse_version("v1").unwrap(),
+            semver::Version::new(1, 0, 0)
+        );
+        assert_eq!(
+            super::parse_version("1.2").unwrap(),
+            semver::Version::new(1, 2, 0)
+        );
+        assert_eq!(
+            super::parse_version("1.2.3").unwrap(),
+            semver::Version::new(1, 2, 3)
+        );
+        assert!(super::parse_version("one").is_err());
+    }
+
+    // cargo test --package fastn-core --lib package::lock::tests::locked_package_satisfies
+    #[test]
+    fn locked_package_satisfies() {
+        let locked = super::LockedPackage {
+            name: "fifthtry.github.io/doc-site".to_string(),
+            version: Some("1.2.0".to_string()),
+            checksum: "ABCD".to_string(),
+            download_base_url: None,
+        };
+        let requirement = |v| super::parse_requirement("doc-site", v).unwrap();
+        assert!(locked.satisfies(Some(&requirement("1"))));
+        assert!(locked.satisfies(Some(&requirement(">=1.1, <2"))));
+        assert!(!locked.satisfies(Some(&requirement("1.3"))));
+        assert!(!locked.satisfies(None));
+    }
+
+    // cargo test --package fastn-core --lib package::lock::tests::verify
+    #[test]
+    fn verify() {
+        let mut lockfile = super::Lockfile::default();
+        lockfile.verify("a.com/b", "index.ftd", b"hello").unwrap();
+        assert!(lockfile.changed);
+        lockfile.changed = false;
+        lockfile.verify("a.com/b", "index.ftd", b"hello").unwrap();
+        assert!(!lockfile.changed);
+        assert!(lockfile.verify("a.com/b", "index.ftd", b"bye").is_err());
+        // the same path of another package is locked separately
+        lockfile.verify("a.com/c", "index.ftd", b"bye").unwrap();
+    }
+
+    // cargo test --package fastn-core --lib package::lock::tests::lock_files_round_trip
+    #[test]
+    fn lock_files_round_trip() {
+        let root = camino::Utf8PathBuf::from_path_buf(std::env::temp_dir())
+            .unwrap()
+            .join(format!("fastn-lock-{}", std::process::id()));
+        std::fs::create_dir_all(&root).unwrap();
+        let mut lockfile = super::Lockfile::default();
+        lockfile.insert(super::LockedPackage {
+            name: "a.com/b".to_string(),
+            version: Some("1.0.0".to_string()),
+            checksum: "ABCD".to_string(),
+            download_base_url: Some("a.com/b/v1.0.0".to_string()),
+        });
+        lockfile
+            .verify("a.com/b", "a.com/b/archive/main.zip", b"zip")
+            .unwrap();
+        lockfile.verify("a.com/b", "index.ftd", b"hello").unwrap();
+        lockfile.write(&root).unwrap();
+
+        let read = super::Lockfile::read(&root).unwrap();
+        assert_eq!(read.packages, lockfile.packages);
+        assert_eq!(read.files, lockfile.files);
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    // cargo test --package fastn-core --lib package::lock::tests::held_lock_is_written_on_release
+    #[test]
+    fn held_lock_is_written_on_release() {
+        let root = camino::Utf8PathBuf::from_path_buf(std::env::temp_dir())
+            .unwrap()
+            .join(format!("fastn-lock-held-{}", std::process::id()));
+        std::fs::create_dir_all(&root).unwrap();
+        super::load(&root).unwrap();
+
+        super::hold(&root).unwrap();
+        super::verify(&root, "a.com/b", "index.ftd", b"hello").unwrap();
+        super::verify(&root, "a.com/b", "about.ftd", b"about").unwrap();
+        assert!(!root.join(super::LOCK_FILE).exists());
+
+        super::release(&root).unwrap();
+        let read = super::Lockfile::read(&root).unwrap();
+        assert_eq!(read.files["a.com/b"].len(), 2);
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    // cargo test --package fastn-core --lib package::lock::tests::root_of_dependency
+    #[test]
+    fn root_of_dependency() {
+        let root = |package, package_root| {
+            super::root_of_dependency(package, camino::Utf8Path::new(package_root))
+                .map(|v| v.to_string())
+        };
+        assert_eq!(
+            root("a.com/b", "/p/.packages/a.com/b"),
+            Some("/p".to_string())
+        );
+        assert_eq!(
+            root("a.com/b", "/p/.packages/a.com/b/src"),
+            Some("/p".to_string())
+        );
+        assert_eq!(root("a.com/b", "/p/.packages/a.com/c"), None);
+        assert_eq!(root("a.com/b", "/p"), None);
+    }
+}
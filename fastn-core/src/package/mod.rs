@@ -1,5 +1,7 @@
 pub mod app;
+pub mod database;
 pub mod dependency;
+pub mod lock;
 pub mod package_doc;
 pub mod redirects;
 pub mod user_group;
@@ -62,6 +64,13 @@ pub struct Package {
     /// Headers for the WASM backend
     pub backend_headers: Option<Vec<fastn_package::old_fastn::BackendHeader>>,
 
+    /// Where `fastn sync` keeps the history of the package
+    pub history_backend: fastn_package::old_fastn::HistoryBackend,
+
+    /// Where readers who can not access a document, static file or proxied url are sent to log
+    /// in, with the url they asked for as `next`. They get a 401 if it is not set.
+    pub login_url: Option<String>,
+
     /// Installed Apps
     pub apps: Vec<app::App>,
 
@@ -70,6 +79,9 @@ pub struct Package {
 
     /// Redirect URLs
     pub redirects: Option<ftd::Map<String>>,
+
+    /// `databases` are the postgres databases the `pg` processor can query
+    pub databases: Vec<database::Database>,
 }
 
 impl Package {
@@ -100,9 +112,12 @@ impl Package {
             endpoint: None,
             backend: false,
             backend_headers: None,
+            history_backend: Default::default(),
+            login_url: None,
             apps: vec![],
             icon: None,
             redirects: None,
+            databases: vec![],
         }
     }
 
@@ -615,6 +630,8 @@ impl Package {
 
         package.ignored_paths = fastn_doc.get::<Vec<String>>("fastn#ignore")?;
         package.fonts = fastn_doc.get("fastn#font")?;
+        package.databases = fastn_doc.get("fastn#database")?;
+        database::validate(package.name.as_str(), package.databases.as_slice())?;
         package.sitemap_temp = fastn_doc.get("fastn#sitemap")?;
         package.dynamic_urls_temp = fastn_doc.get("fastn#dynamic-urls")?;
 
@@ -731,9 +748,12 @@ impl PackageTempIntoPackage for fastn_package::old_fastn::PackageTemp {
             endpoint: self.endpoint,
             backend: self.backend,
             backend_headers: self.backend_headers,
+            history_backend: self.history_backend,
+            login_url: self.login_url,
             apps: vec![],
             icon: self.icon,
             redirects: None,
+            databases: vec![],
         }
     }
 }
@@ -128,6 +128,12 @@ impl fastn_core::Package {
         let package_root = self.package_root_with_default(package_root)?;
 
         let (file_path, data) = self.http_fetch_by_id(id).await?;
+        fastn_core::package::lock::verify_download(
+            self.name.as_str(),
+            &package_root,
+            file_path.as_str(),
+            data.as_slice(),
+        )?;
         fastn_core::utils::write(
             &package_root,
             file_path.trim_start_matches('/'),
@@ -147,6 +153,12 @@ impl fastn_core::Package {
         let package_root = self.package_root_with_default(package_root)?;
 
         let data = self.http_fetch_by_file_name(file_path).await?;
+        fastn_core::package::lock::verify_download(
+            self.name.as_str(),
+            &package_root,
+            file_path,
+            data.as_slice(),
+        )?;
         fastn_core::utils::write(&package_root, file_path, data.as_slice()).await?;
 
         Ok(data)
@@ -525,7 +537,11 @@ pub(crate) async fn read_ftd_2023(
     let ssr_body = fastn_js::ssr_with_js_string(
         &config.package.name,
         format!("{js_ftd_script}\n{js_document_script}").as_str(),
-    );
+    )
+    .map_err(|e| fastn_core::Error::SsrError {
+        doc_id: main.id_with_package(),
+        message: e.to_string(),
+    })?;
 
     all_packages.extend(lib.config.all_packages.into_inner());
     drop(all_packages);
@@ -534,21 +550,81 @@ pub(crate) async fn read_ftd_2023(
         .downloaded_assets
         .extend(lib.config.downloaded_assets);
 
+    let features = fastn_js::Features::of(js_ast_data.asts.as_slice());
+    if download_assets {
+        fastn_core::commands::build::optional_build_files(&config.build_dir(), &features).await?;
+    }
+    let js_functions_script = fastn_js::to_js(
+        ftd::js::default_functions_into_js_ast(&features.functions).as_slice(),
+        false,
+        config.package.name.as_str(),
+    );
+
     let font_style = config.get_font_style();
     let file_content = fastn_core::utils::replace_markers_2023(
         ftd::ftd_js_html(),
-        js_document_script.as_str(),
+        format!("{js_functions_script}\n{js_document_script}").as_str(),
         js_ast_data.scripts.join("").as_str(),
         ssr_body.as_str(),
         font_style.as_str(),
         ftd::ftd_js_css(),
         base_url,
+        features,
         config,
     );
 
     Ok(FTDResult::Html(file_content.into()))
 }
 
+/// `interpret_ftd()` interprets `content`, the source of the document `id` of the current
+/// package, the way `read_ftd()` does, but does not render it. Tools that need the definitions
+/// of a document, like the language server, use it.
+///
+/// The document is usually being edited, so the sections of it that do not parse are left out
+/// and the rest of it is interpreted. Their errors are not returned, the caller finds them when
+/// it parses `content`, see `ftd::interpreter::ParsedDocument::parse_with_recovery()`.
+pub async fn interpret_ftd(
+    config: &fastn_core::Config,
+    id: &str,
+    content: &str,
+) -> fastn_core::Result<ftd::interpreter::Document> {
+    let main = fastn_core::Document {
+        package_name: config.package.name.to_string(),
+        id: id.to_string(),
+        content: content.to_string(),
+        parent_path: config.root.to_string(),
+    };
+
+    let mut lib = fastn_core::Library2022 {
+        config: config.clone(),
+        markdown: None,
+        document_id: main.id.clone(),
+        translated_data: Default::default(),
+        base_url: "/".to_string(),
+        module_package_map: Default::default(),
+    };
+
+    let mut doc_content =
+        config
+            .package
+            .get_prefixed_body(main.content.as_str(), main.id.as_str(), true);
+    doc_content = config
+        .package
+        .fix_imports_in_body(doc_content.as_str(), main.id.as_str())?;
+
+    let line_number = doc_content.split('\n').count() - main.content.split('\n').count();
+    let (_, document) = fastn_core::doc::interpret_helper_with_recovery(
+        main.id_with_package().as_str(),
+        doc_content.as_str(),
+        &mut lib,
+        "/",
+        false,
+        line_number,
+    )
+    .await;
+    Ok(document?)
+}
+
 pub(crate) async fn process_ftd(
     config: &mut fastn_core::Config,
     main: &fastn_core::Document,
@@ -564,6 +564,24 @@ pub async fn access_identities(
     }
 }
 
+/// `group_identities()` are the identities of the user, found from their cookies, that any of
+/// `groups` is made of.
+pub async fn group_identities(
+    config: &fastn_core::Config,
+    req: &fastn_core::http::Request,
+    groups: &[&UserGroup],
+) -> fastn_core::Result<Vec<UserIdentity>> {
+    let mut identities = vec![];
+    for group in groups {
+        identities.extend(group.get_identities(config)?);
+    }
+    match fastn_core::auth::get_auth_identities(req.cookies(), identities.as_slice()).await {
+        Ok(ids) => Ok(ids),
+        Err(fastn_core::Error::GenericError(_err)) => Ok(vec![]),
+        e => e,
+    }
+}
+
 pub mod processor {
     use itertools::Itertools;
 
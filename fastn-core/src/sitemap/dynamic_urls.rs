@@ -48,9 +48,130 @@ impl DynamicUrls {
             });
         }
 
+        dynamic_urls.check_conflicts()?;
+        dynamic_urls.pre_rendered_urls()?;
+
         Ok(dynamic_urls)
     }
 
+    /// Two dynamic urls conflict if some request url matches both of them, `resolve_document()`
+    /// could only pick one of them for it.
+    fn check_conflicts(&self) -> Result<(), fastn_core::sitemap::ParseError> {
+        let urls = self.urls();
+        for (i, first) in urls.iter().enumerate() {
+            for second in urls[i + 1..].iter() {
+                if let Some(common) = fastn_core::sitemap::utils::common_url(
+                    first.path_parameters,
+                    second.path_parameters,
+                ) {
+                    return Err(fastn_core::sitemap::ParseError::InvalidDynamicUrls {
+                        message: format!(
+                            "dynamic urls `{}` and `{}` conflict, both of them match `{}`",
+                            first.url, second.url, common
+                        ),
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// All the dynamic urls, in the order they are declared.
+    fn urls(&self) -> Vec<DynamicUrl<'_>> {
+        fn dynamic_url<'a>(
+            id: &str,
+            document: Option<&'a String>,
+            path_parameters: &'a [fastn_core::sitemap::PathParams],
+            extra_data: &'a std::collections::BTreeMap<String, String>,
+        ) -> DynamicUrl<'a> {
+            DynamicUrl {
+                url: extra_data
+                    .get("url")
+                    .cloned()
+                    .unwrap_or_else(|| id.to_string()),
+                document,
+                path_parameters,
+                extra_data,
+            }
+        }
+
+        fn toc_urls<'a>(
+            toc: &'a fastn_core::sitemap::toc::TocItem,
+            urls: &mut Vec<DynamicUrl<'a>>,
+        ) {
+            if !toc.path_parameters.is_empty() {
+                urls.push(dynamic_url(
+                    toc.id.as_str(),
+                    toc.document.as_ref(),
+                    toc.path_parameters.as_slice(),
+                    &toc.extra_data,
+                ));
+            }
+            for child in toc.children.iter() {
+                toc_urls(child, urls);
+            }
+        }
+
+        let mut urls = vec![];
+        for section in self.sections.iter() {
+            if !section.path_parameters.is_empty() {
+                urls.push(dynamic_url(
+                    section.id.as_str(),
+                    section.document.as_ref(),
+                    section.path_parameters.as_slice(),
+                    &section.extra_data,
+                ));
+            }
+            for sub_section in section.subsections.iter() {
+                if !sub_section.path_parameters.is_empty() {
+                    urls.push(dynamic_url(
+                        sub_section.id.as_deref().unwrap_or_default(),
+                        sub_section.document.as_ref(),
+                        sub_section.path_parameters.as_slice(),
+                        &sub_section.extra_data,
+                    ));
+                }
+                for toc in sub_section.toc.iter() {
+                    toc_urls(toc, &mut urls);
+                }
+            }
+        }
+        urls
+    }
+
+    /// The dynamic urls that declare where the values of their params come from, `fastn build`
+    /// renders one page for each of these values.
+    pub fn pre_rendered_urls(
+        &self,
+    ) -> Result<Vec<PreRenderedUrl>, fastn_core::sitemap::ParseError> {
+        let mut pre_rendered = vec![];
+        for url in self.urls() {
+            let source = match ParamsSource::from_extra_data(url.url.as_str(), url.extra_data)? {
+                Some(source) => source,
+                None => continue,
+            };
+            let document = match url.document {
+                Some(document) => document.to_string(),
+                None => {
+                    return Err(fastn_core::sitemap::ParseError::InvalidDynamicUrls {
+                        message: format!(
+                            "dynamic url `{}` has params but no `document` to render them with",
+                            url.url
+                        ),
+                    })
+                }
+            };
+            pre_rendered.push(PreRenderedUrl {
+                url: url.url,
+                document,
+                path_parameters: url.path_parameters.to_vec(),
+                extra_data: url.extra_data.clone(),
+                source,
+            });
+        }
+        Ok(pre_rendered)
+    }
+
     // If any one does not have path parameters so return true
     // any_without_named_params
     pub fn any_without_named_params(&self) -> bool {
@@ -215,6 +336,232 @@ impl DynamicUrls {
     }
 }
 
+struct DynamicUrl<'a> {
+    url: String,
+    document: Option<&'a String>,
+    path_parameters: &'a [fastn_core::sitemap::PathParams],
+    extra_data: &'a std::collections::BTreeMap<String, String>,
+}
+
+/// Where `fastn build` gets the values of the params of a dynamic url from:
+///
+/// ```ftd
+/// -- fastn.dynamic-urls:
+///
+/// # Blog Post:
+///   url: /blog/<string:slug>/
+///   document: blog/post.ftd
+///   params-db: blog.sqlite
+///   params-query: SELECT slug FROM post
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParamsSource {
+    /// `params-query` on the sqlite database `params-db`, the columns are named after the params.
+    Query { db: String, query: String },
+    /// `params-json`, a json file containing the list of params.
+    Json(String),
+    /// `params-variable: <module>#<name>`, an ftd list containing the params.
+    Variable { module: String, name: String },
+}
+
+impl ParamsSource {
+    fn from_extra_data(
+        url: &str,
+        extra_data: &std::collections::BTreeMap<String, String>,
+    ) -> Result<Option<ParamsSource>, fastn_core::sitemap::ParseError> {
+        let error =
+            |message: &str| -> Result<Option<ParamsSource>, fastn_core::sitemap::ParseError> {
+                Err(fastn_core::sitemap::ParseError::InvalidDynamicUrls {
+                    message: format!("dynamic url `{}`: {}", url, message),
+                })
+            };
+
+        let db = extra_data.get("params-db");
+        let query = extra_data.get("params-query");
+        let json = extra_data.get("params-json");
+        let variable = extra_data.get("params-variable");
+
+        if [query, json, variable].iter().flatten().count() > 1 {
+            return error(
+                "only one of `params-query`, `params-json` and `params-variable` can be given",
+            );
+        }
+
+        match (db, query, json, variable) {
+            (Some(db), Some(query), _, _) => Ok(Some(ParamsSource::Query {
+                db: db.to_string(),
+                query: query.to_string(),
+            })),
+            (None, Some(_), _, _) => error("`params-query` needs `params-db`"),
+            (Some(_), None, _, _) => error("`params-db` needs `params-query`"),
+            (None, None, Some(json), _) => Ok(Some(ParamsSource::Json(json.to_string()))),
+            (None, None, None, Some(variable)) => match variable.split_once('#') {
+                Some((module, name)) if !module.is_empty() && !name.is_empty() => {
+                    Ok(Some(ParamsSource::Variable {
+                        module: module.trim_end_matches(".ftd").to_string(),
+                        name: name.to_string(),
+                    }))
+                }
+                _ => error("`params-variable` must be `<module>#<name>`"),
+            },
+            (None, None, None, None) => Ok(None),
+        }
+    }
+}
+
+/// A dynamic url with a `ParamsSource`, `fastn build` renders `document` once for every item
+/// of the source.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PreRenderedUrl {
+    pub url: String,
+    pub document: String,
+    pub path_parameters: Vec<fastn_core::sitemap::PathParams>,
+    pub extra_data: std::collections::BTreeMap<String, String>,
+    pub source: ParamsSource,
+}
+
+impl PreRenderedUrl {
+    /// Every item of `list` has the params of one page: an object keyed by the param names, or
+    /// just the value if the url has a single param.
+    pub fn rows(
+        &self,
+        list: serde_json::Value,
+    ) -> fastn_core::Result<Vec<serde_json::Map<String, serde_json::Value>>> {
+        let names: Vec<&str> = self
+            .path_parameters
+            .iter()
+            .filter_map(|param| match param {
+                fastn_core::sitemap::PathParams::NamedParm { name, .. }
+                | fastn_core::sitemap::PathParams::CatchAllParam { name, .. } => {
+                    Some(name.as_str())
+                }
+                fastn_core::sitemap::PathParams::ValueParam { .. } => None,
+            })
+            .collect();
+
+        let items = match list {
+            serde_json::Value::Array(items) => items,
+            _ => {
+                return Err(fastn_core::Error::GenericError(format!(
+                    "dynamic url `{}`: params must be a list",
+                    self.url
+                )))
+            }
+        };
+
+        items
+            .into_iter()
+            .map(|item| match item {
+                serde_json::Value::Object(row) => Ok(row),
+                value if names.len() == 1 => {
+                    Ok(std::iter::once((names[0].to_string(), value)).collect())
+                }
+                value => Err(fastn_core::Error::GenericError(format!(
+                    "dynamic url `{}`: expected an object with `{}`, found `{}`",
+                    self.url,
+                    names.join("`, `"),
+                    value
+                ))),
+            })
+            .collect()
+    }
+
+    /// The path of the page for the params in `row`, and the typed params `request-data` gets
+    /// when rendering it.
+    pub fn path(
+        &self,
+        row: &serde_json::Map<String, serde_json::Value>,
+    ) -> fastn_core::Result<(String, Vec<(String, ftd::Value)>)> {
+        let mut parts = vec![];
+        for param in self.path_parameters.iter() {
+            match param {
+                fastn_core::sitemap::PathParams::ValueParam { value, .. } => {
+                    parts.push(value.to_string())
+                }
+                fastn_core::sitemap::PathParams::NamedParm { name, optional, .. } => {
+                    match row.get(name) {
+                        // optional params are always the last ones
+                        None | Some(serde_json::Value::Null) if *optional => break,
+                        value => {
+                            let value = self.param_value(name, value)?;
+                            self.check_segments(name, std::iter::once(value.as_str()))?;
+                            parts.push(value)
+                        }
+                    }
+                }
+                fastn_core::sitemap::PathParams::CatchAllParam { name, .. } => {
+                    let value = self.param_value(name, row.get(name))?;
+                    // a trailing `/` is how urls usually end, every other empty segment is an error
+                    let value = value.strip_suffix('/').unwrap_or(value.as_str());
+                    self.check_segments(name, value.split('/'))?;
+                    parts.push(value.to_string())
+                }
+            }
+        }
+
+        let path = if parts.is_empty() {
+            "/".to_string()
+        } else {
+            format!("/{}/", parts.join("/"))
+        };
+
+        match fastn_core::sitemap::utils::url_match(path.as_str(), &self.path_parameters)? {
+            (true, params) => Ok((path, params)),
+            (false, _) => Err(fastn_core::Error::GenericError(format!(
+                "dynamic url `{}`: `{}` does not match it, check the types of the params",
+                self.url, path
+            ))),
+        }
+    }
+
+    /// The page is written to `.build/<path>/index.html`, so a param must not be able to point
+    /// it anywhere else: every segment of it has to be a plain file name.
+    fn check_segments<'a>(
+        &self,
+        name: &str,
+        segments: impl Iterator<Item = &'a str>,
+    ) -> fastn_core::Result<()> {
+        for segment in segments {
+            if segment.is_empty()
+                || segment == "."
+                || segment == ".."
+                || segment.contains(['/', '\\', ':'])
+            {
+                return Err(fastn_core::Error::GenericError(format!(
+                    "dynamic url `{}`: param `{}` has an invalid path segment `{}`",
+                    self.url, name, segment
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    fn param_value(
+        &self,
+        name: &str,
+        value: Option<&serde_json::Value>,
+    ) -> fastn_core::Result<String> {
+        let value = match value {
+            Some(serde_json::Value::String(v)) => v.to_string(),
+            Some(v @ (serde_json::Value::Number(_) | serde_json::Value::Bool(_))) => v.to_string(),
+            Some(serde_json::Value::Null) | None => String::new(),
+            Some(v) => {
+                return Err(fastn_core::Error::GenericError(format!(
+                    "dynamic url `{}`: param `{}` must be a string, number or boolean, found `{}`",
+                    self.url, name, v
+                )))
+            }
+        };
+        if value.is_empty() {
+            return Err(fastn_core::Error::GenericError(format!(
+                "dynamic url `{}`: no value for param `{}`",
+                self.url, name
+            )));
+        }
+        Ok(value)
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -231,7 +578,7 @@ mod tests {
   readers: readers/person
   writers: writers/person
 - Url 2
-  url: /person/<string:name>/
+  url: /person/<string:name>/<integer:age>/
   document: person.ftd
   readers: readers/person
   writers: writers/person
@@ -295,7 +642,7 @@ mod tests {
                             ],
                         },
                         fastn_core::sitemap::toc::TocItem {
-                            id: "/person/<string:name>/".to_string(),
+                            id: "/person/<string:name>/<integer:age>/".to_string(),
                             icon: None,
                             bury: false,
                             title: Some("Url 2".to_string()),
@@ -304,7 +651,7 @@ mod tests {
                             extra_data: vec![
                                 ("document", "person.ftd"),
                                 ("readers", "readers/person"),
-                                ("url", "/person/<string:name>/"),
+                                ("url", "/person/<string:name>/<integer:age>/"),
                                 ("writers", "writers/person"),
                             ]
                             .into_iter()
@@ -325,6 +672,11 @@ mod tests {
                                     "name".to_string(),
                                     "string".to_string(),
                                 ),
+                                fastn_core::sitemap::PathParams::named(
+                                    2,
+                                    "age".to_string(),
+                                    "integer".to_string(),
+                                ),
                             ],
                         },
                     ],
@@ -345,4 +697,185 @@ mod tests {
         });
         assert_eq!(left, right)
     }
+
+    #[test]
+    fn parse_conflicting_dynamic_urls() {
+        let parse = |body: &str| {
+            fastn_core::sitemap::DynamicUrls::parse(
+                &std::collections::HashMap::new(),
+                "abrark.com",
+                body,
+            )
+        };
+
+        assert_eq!(
+            parse(
+                r#"
+# Dynamic Urls Section
+- Url 1
+  url: /person/<string:name>/
+  document: person.ftd
+- Url 2
+  url: /person/<integer:id>/
+  document: person-by-id.ftd
+"#
+            ),
+            Err(fastn_core::sitemap::ParseError::InvalidDynamicUrls {
+                message: "dynamic urls `/person/<string:name>/` and `/person/<integer:id>/` \
+                conflict, both of them match `/person/1/`"
+                    .to_string()
+            })
+        );
+
+        assert_eq!(
+            parse(
+                r#"
+# Dynamic Urls Section
+- Url 1
+  url: /files/*path
+  document: file.ftd
+- Url 2
+  url: /files/<string:name>/<optional boolean:raw>/
+  document: raw-file.ftd
+"#
+            ),
+            Err(fastn_core::sitemap::ParseError::InvalidDynamicUrls {
+                message: "dynamic urls `/files/*path` and \
+                `/files/<string:name>/<optional boolean:raw>/` conflict, both of them match \
+                `/files/value/`"
+                    .to_string()
+            })
+        );
+
+        assert!(parse(
+            r#"
+# Dynamic Urls Section
+- Url 1
+  url: /person/<integer:id>/
+  document: person.ftd
+- Url 2
+  url: /person/<boolean:active>/
+  document: people.ftd
+- Url 3
+  url: /person/<small|large:size>/<optional decimal:weight>/
+  document: size.ftd
+- Url 4
+  url: /files/<integer:id>/*path
+  document: file.ftd
+"#
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn pre_rendered_urls() {
+        let parse = |body: &str| {
+            fastn_core::sitemap::DynamicUrls::parse(
+                &std::collections::HashMap::new(),
+                "abrark.com",
+                body,
+            )
+        };
+
+        let dynamic_urls = parse(
+            r#"
+# Dynamic Urls Section
+- Post
+  url: /blog/<string:slug>/<optional integer:page>/
+  document: post.ftd
+  params-db: blog.sqlite
+  params-query: SELECT slug, page FROM post
+- Person
+  url: /person/<integer:id>/
+  document: person.ftd
+- Docs
+  url: /docs/*path
+  document: docs.ftd
+  params-variable: docs/index.ftd#pages
+"#,
+        )
+        .unwrap();
+        let urls = dynamic_urls.pre_rendered_urls().unwrap();
+        assert_eq!(
+            urls.iter().map(|v| v.source.clone()).collect::<Vec<_>>(),
+            vec![
+                super::ParamsSource::Query {
+                    db: "blog.sqlite".to_string(),
+                    query: "SELECT slug, page FROM post".to_string()
+                },
+                super::ParamsSource::Variable {
+                    module: "docs/index".to_string(),
+                    name: "pages".to_string()
+                }
+            ]
+        );
+
+        let rows = urls[0]
+            .rows(serde_json::json!([
+                {"slug": "hello", "page": null},
+                {"slug": "hello", "page": 2}
+            ]))
+            .unwrap();
+        assert_eq!(
+            urls[0].path(&rows[0]).unwrap(),
+            (
+                "/blog/hello/".to_string(),
+                vec![(
+                    "slug".to_string(),
+                    ftd::Value::String {
+                        text: "hello".to_string(),
+                        source: ftd::TextSource::Default
+                    }
+                )]
+            )
+        );
+        assert_eq!(urls[0].path(&rows[1]).unwrap().0, "/blog/hello/2/");
+
+        let rows = urls[1]
+            .rows(serde_json::json!(["intro", "guide/install/"]))
+            .unwrap();
+        assert_eq!(urls[1].path(&rows[0]).unwrap().0, "/docs/intro/");
+        assert_eq!(urls[1].path(&rows[1]).unwrap().0, "/docs/guide/install/");
+
+        let rows = urls[0]
+            .rows(serde_json::json!([{"slug": "a/b"}, {"page": 1}]))
+            .unwrap();
+        assert!(urls[0].path(&rows[0]).is_err());
+        assert!(urls[0].path(&rows[1]).is_err());
+        assert!(urls[0].rows(serde_json::json!(["hello"])).is_err());
+
+        // params must not take the page out of its url, or over another page
+        for slug in ["..", ".", "../x", "a\\b", "c:"] {
+            let rows = urls[0].rows(serde_json::json!([{ "slug": slug }])).unwrap();
+            assert!(urls[0].path(&rows[0]).is_err(), "{}", slug);
+        }
+        for path in [
+            "..",
+            "../../x",
+            "a/../b",
+            "/etc/passwd",
+            "a//b",
+            "./a",
+            "a\\..\\b",
+        ] {
+            let rows = urls[1].rows(serde_json::json!([path])).unwrap();
+            assert!(urls[1].path(&rows[0]).is_err(), "{}", path);
+        }
+
+        assert_eq!(
+            parse(
+                r#"
+# Dynamic Urls Section
+- Person
+  url: /person/<integer:id>/
+  document: person.ftd
+  params-query: SELECT id FROM person
+"#
+            ),
+            Err(fastn_core::sitemap::ParseError::InvalidDynamicUrls {
+                message: "dynamic url `/person/<integer:id>/`: `params-query` needs `params-db`"
+                    .to_string()
+            })
+        );
+    }
 }
@@ -56,11 +56,18 @@ pub enum PathParams {
         index: usize,
         name: String,
         param_type: String,
+        // only the trailing params can be optional: `<optional string:tab>`
+        optional: bool,
     },
     ValueParam {
         index: usize,
         value: String,
     },
+    // `*rest`, the last param, gets the rest of the path
+    CatchAllParam {
+        index: usize,
+        name: String,
+    },
 }
 
 impl PathParams {
@@ -69,6 +76,16 @@ impl PathParams {
             index,
             name,
             param_type,
+            optional: false,
+        }
+    }
+
+    pub fn optional_named(index: usize, name: String, param_type: String) -> Self {
+        PathParams::NamedParm {
+            index,
+            name,
+            param_type,
+            optional: true,
         }
     }
 
@@ -76,8 +93,20 @@ impl PathParams {
         PathParams::ValueParam { index, value }
     }
 
+    pub fn catch_all(index: usize, name: String) -> Self {
+        PathParams::CatchAllParam { index, name }
+    }
+
     pub fn is_named_param(&self) -> bool {
-        matches!(self, Self::NamedParm { .. })
+        matches!(self, Self::NamedParm { .. } | Self::CatchAllParam { .. })
+    }
+
+    pub fn is_optional(&self) -> bool {
+        matches!(self, Self::NamedParm { optional: true, .. })
+    }
+
+    pub fn is_catch_all(&self) -> bool {
+        matches!(self, Self::CatchAllParam { .. })
     }
 }
 
@@ -1241,6 +1270,52 @@ impl Sitemap {
         }
     }
 
+    /// `owner()` is the id of the deepest section, subsection or toc item whose url `path` is
+    /// under, like `/foo/` for `/foo/images/a.png`. Static files and proxied urls are not in the
+    /// sitemap, they are read protected by their owner, see `readers()`. `path` is resolved first,
+    /// see `resolve_path()`, so `/./foo/a.png` and `/bar/../foo/a.png` are owned by `/foo/` too.
+    pub fn owner(&self, path: &str) -> Option<String> {
+        fn normalise(id: &str) -> String {
+            match id.trim_matches('/') {
+                "" => "/".to_string(),
+                id => format!("/{}/", id),
+            }
+        }
+
+        fn deeper(owner: &mut Option<String>, id: &str, path: &str) {
+            let url = normalise(id);
+            if path.starts_with(url.as_str())
+                && owner
+                    .as_ref()
+                    .map_or(true, |o| normalise(o).len() < url.len())
+            {
+                *owner = Some(id.to_string());
+            }
+        }
+
+        fn find_toc(toc: &toc::TocItem, path: &str, owner: &mut Option<String>) {
+            deeper(owner, toc.id.as_str(), path);
+            for child in toc.children.iter() {
+                find_toc(child, path, owner);
+            }
+        }
+
+        let path = resolve_path(path);
+        let mut owner = None;
+        for section in self.sections.iter() {
+            deeper(&mut owner, section.id.as_str(), path.as_str());
+            for subsection in section.subsections.iter() {
+                if let Some(id) = subsection.id.as_ref() {
+                    deeper(&mut owner, id.as_str(), path.as_str());
+                }
+                for toc in subsection.toc.iter() {
+                    find_toc(toc, path.as_str(), &mut owner);
+                }
+            }
+        }
+        owner
+    }
+
     /// This function will return all the readers and readers which are inherited from parent
 
     // TODO: need to handle special reader: everyone, writer: everyone
@@ -1673,3 +1748,75 @@ pub fn resolve(
 
     Ok((None, vec![], Default::default()))
 }
+
+/// `resolve_path()` is `path` with its `.`, `..` and empty components resolved, as `/a/b/`, the
+/// way the file it names is looked up. `..` never goes above the root.
+pub(crate) fn resolve_path(path: &str) -> String {
+    let mut components = vec![];
+    for component in path.split(['/', '\\']) {
+        match component {
+            "" | "." => {}
+            ".." => {
+                components.pop();
+            }
+            c => components.push(c),
+        }
+    }
+    if components.is_empty() {
+        "/".to_string()
+    } else {
+        format!("/{}/", components.join("/"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn owner() {
+        let sitemap = fastn_core::sitemap::Sitemap {
+            sections: vec![fastn_core::sitemap::section::Section {
+                id: "/docs/".to_string(),
+                subsections: vec![fastn_core::sitemap::section::Subsection {
+                    id: Some("/docs/guide/".to_string()),
+                    toc: vec![fastn_core::sitemap::toc::TocItem {
+                        id: "/docs/guide/install/".to_string(),
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+            readers: vec![],
+            writers: vec![],
+        };
+
+        assert_eq!(sitemap.owner("/docs/logo.png"), Some("/docs/".to_string()));
+        assert_eq!(
+            sitemap.owner("docs/guide/install/screen.png"),
+            Some("/docs/guide/install/".to_string())
+        );
+        assert_eq!(
+            sitemap.owner("/docs/guide/api/v1/"),
+            Some("/docs/guide/".to_string())
+        );
+        assert_eq!(sitemap.owner("/docsets/logo.png"), None);
+        assert_eq!(sitemap.owner("/static/logo.png"), None);
+        assert_eq!(
+            sitemap.owner("/./docs/secret.png"),
+            Some("/docs/".to_string())
+        );
+        assert_eq!(
+            sitemap.owner("/static/../docs//guide/./install/screen.png"),
+            Some("/docs/guide/install/".to_string())
+        );
+        assert_eq!(
+            sitemap.owner("/docs/guide/../../docs/secret.png"),
+            Some("/docs/".to_string())
+        );
+        assert_eq!(
+            sitemap.owner("/../../docs/secret.png"),
+            Some("/docs/".to_string())
+        );
+        assert_eq!(sitemap.owner("/docs/../static/logo.png"), None);
+    }
+}
@@ -18,25 +18,23 @@ pub fn url_match(
 ) -> fastn_core::Result<(bool, Vec<(String, ftd::Value)>)> {
     use itertools::Itertools;
     // request_attrs: [abrark, foo, 28]
-    let request_parts = request_url.trim_matches('/').split('/').collect_vec();
-    // This should go to config request [username: abrark, age: 28]
-    if request_parts.len().ne(&sitemap_params.len()) {
-        return Ok((false, vec![]));
-    }
+    let request_url = request_url.trim_matches('/');
+    let request_parts = if request_url.is_empty() {
+        vec![]
+    } else {
+        request_url.split('/').collect_vec()
+    };
 
     // match logic
     // req: [a, ak, foo]
     // d-urls: [(0, a, None), (1, username, Some(string)), (2, foo, None)]
     // [(param_name, value)]
     let mut path_parameters: Vec<(String, ftd::Value)> = vec![];
-    let mut count = 0;
-    for req_part in request_parts {
-        match &sitemap_params[count] {
+    for (count, sitemap_param) in sitemap_params.iter().enumerate() {
+        let req_part = request_parts.get(count);
+        match sitemap_param {
             fastn_core::sitemap::PathParams::ValueParam { index: _, value } => {
-                count += 1;
-                if req_part.eq(value) {
-                    continue;
-                } else {
+                if !req_part.map(|v| v.eq(value)).unwrap_or(false) {
                     return Ok((false, vec![]));
                 }
             }
@@ -44,41 +42,77 @@ pub fn url_match(
                 index: _,
                 name,
                 param_type,
-            } => {
-                count += 1;
-                if let Ok(value) = get_value_type(req_part, param_type) {
-                    path_parameters.push((name.to_string(), value));
-                } else {
+                optional,
+            } => match req_part {
+                Some(req_part) => match get_value_type(req_part, param_type) {
+                    Ok(value) => path_parameters.push((name.to_string(), value)),
+                    Err(_) => return Ok((false, vec![])),
+                },
+                // an optional param that is left out is not passed to the document
+                None if *optional => {}
+                None => return Ok((false, vec![])),
+            },
+            fastn_core::sitemap::PathParams::CatchAllParam { index: _, name } => {
+                if request_parts.len() <= count {
                     return Ok((false, vec![]));
                 }
+                path_parameters.push((
+                    name.to_string(),
+                    ftd::Value::String {
+                        text: request_parts[count..].join("/"),
+                        source: ftd::TextSource::Default,
+                    },
+                ));
+                return Ok((true, path_parameters));
             }
         };
     }
-    return Ok((true, path_parameters));
-
-    fn get_value_type(value: &str, r#type: &str) -> fastn_core::Result<ftd::Value> {
-        match r#type {
-            "string" => Ok(ftd::Value::String {
-                text: value.to_string(),
-                source: ftd::TextSource::Default,
-            }),
-            "integer" => {
-                let value = value.parse::<i64>()?;
-                Ok(ftd::Value::Integer { value })
-            }
-            "decimal" => {
-                let value = value.parse::<f64>()?;
-                Ok(ftd::Value::Decimal { value })
-            }
-            "boolean" => {
-                let value = value.parse::<bool>()?;
-                Ok(ftd::Value::Boolean { value })
-            }
-            _ => unimplemented!(),
+    if request_parts.len().gt(&sitemap_params.len()) {
+        return Ok((false, vec![]));
+    }
+    Ok((true, path_parameters))
+}
+
+fn get_value_type(value: &str, r#type: &str) -> fastn_core::Result<ftd::Value> {
+    match r#type {
+        "string" => Ok(ftd::Value::String {
+            text: value.to_string(),
+            source: ftd::TextSource::Default,
+        }),
+        "integer" => {
+            let value = value.parse::<i64>()?;
+            Ok(ftd::Value::Integer { value })
         }
+        "decimal" => {
+            let value = value.parse::<f64>()?;
+            Ok(ftd::Value::Decimal { value })
+        }
+        "boolean" => {
+            let value = value.parse::<bool>()?;
+            Ok(ftd::Value::Boolean { value })
+        }
+        // <small|medium|large:size>
+        t if t.split('|').any(|v| v.eq(value)) => Ok(ftd::Value::String {
+            text: value.to_string(),
+            source: ftd::TextSource::Default,
+        }),
+        t => Err(fastn_core::Error::GenericError(format!(
+            "`{}` is not a value of the type `{}`",
+            value, t
+        ))),
     }
 }
 
+/// The types a named param can have: `string`, `integer`, `decimal`, `boolean`, or the values
+/// it is constrained to, separated by `|`.
+fn is_param_type(r#type: &str) -> bool {
+    ["string", "integer", "decimal", "boolean"].contains(&r#type)
+        || (r#type.contains('|')
+            && r#type
+                .split('|')
+                .all(|v| !v.is_empty() && !v.contains(char::is_whitespace)))
+}
+
 /// Please check test case: `parse_path_params_test_0`
 /// This method is for parsing the dynamic params from fastn.dynamic-urls
 pub fn parse_named_params(
@@ -93,7 +127,19 @@ pub fn parse_named_params(
     let mut index = 0;
     for part in parts.into_iter().map(|x| x.trim()) {
         if !part.is_empty() {
-            if part.contains(':') && part.starts_with('<') && part.ends_with('>') {
+            if let Some(param_name_part) = part.strip_prefix('*') {
+                // *rest
+                if param_name_part.trim().is_empty() {
+                    return Err(fastn_core::sitemap::ParseError::InvalidDynamicUrls {
+                        message: format!("dynamic-urls format is wrong for: {}", part),
+                    });
+                }
+                output.push(fastn_core::sitemap::PathParams::catch_all(
+                    index,
+                    param_name_part.trim().to_string(),
+                ));
+                index += 1;
+            } else if part.contains(':') && part.starts_with('<') && part.ends_with('>') {
                 // <string:username>
                 if let Some(colon_index) = part.find(':') {
                     let type_part = part[1..colon_index].trim();
@@ -103,11 +149,32 @@ pub fn parse_named_params(
                             message: format!("dynamic-urls format is wrong for: {}", part),
                         });
                     }
-                    output.push(fastn_core::sitemap::PathParams::named(
-                        index,
-                        param_name_part.to_string(),
-                        type_part.to_string(), // TODO: check the type which are supported in the sitemap
-                    ));
+                    // <optional string:tab>
+                    let (optional, type_part) = match type_part.strip_prefix("optional ") {
+                        Some(type_part) => (true, type_part.trim()),
+                        None => (false, type_part),
+                    };
+                    if !is_param_type(type_part) {
+                        return Err(fastn_core::sitemap::ParseError::InvalidDynamicUrls {
+                            message: format!(
+                                "unknown type `{}` of the dynamic param `{}` in: {}",
+                                type_part, param_name_part, url
+                            ),
+                        });
+                    }
+                    output.push(if optional {
+                        fastn_core::sitemap::PathParams::optional_named(
+                            index,
+                            param_name_part.to_string(),
+                            type_part.to_string(),
+                        )
+                    } else {
+                        fastn_core::sitemap::PathParams::named(
+                            index,
+                            param_name_part.to_string(),
+                            type_part.to_string(),
+                        )
+                    });
                     index += 1;
                 }
             } else {
@@ -120,9 +187,117 @@ pub fn parse_named_params(
             }
         }
     }
+
+    // a catch-all param has to be the last one, and only optional params can follow an
+    // optional param
+    for (i, param) in output.iter().enumerate() {
+        let is_last = i + 1 == output.len();
+        if param.is_catch_all() && !is_last {
+            return Err(fastn_core::sitemap::ParseError::InvalidDynamicUrls {
+                message: format!("only the last param can be a catch-all param in: {}", url),
+            });
+        }
+        if param.is_optional() && !output[i + 1..].iter().all(|v| v.is_optional()) {
+            return Err(fastn_core::sitemap::ParseError::InvalidDynamicUrls {
+                message: format!("only the trailing params can be optional in: {}", url),
+            });
+        }
+    }
     Ok(output)
 }
 
+/// A request url that both `a` and `b` match, if there is one. Two dynamic urls with such a
+/// url conflict, as it is not clear which of them the url is for.
+pub fn common_url(
+    a: &[fastn_core::sitemap::PathParams],
+    b: &[fastn_core::sitemap::PathParams],
+) -> Option<String> {
+    for a in without_optional_params(a) {
+        for b in without_optional_params(b) {
+            if let Some(url) = common_url_(a.as_slice(), b.as_slice()) {
+                return Some(url);
+            }
+        }
+    }
+    return None;
+
+    // `/<string:a>/<optional string:b>/` is both `/<string:a>/` and `/<string:a>/<string:b>/`
+    fn without_optional_params(
+        params: &[fastn_core::sitemap::PathParams],
+    ) -> Vec<Vec<&fastn_core::sitemap::PathParams>> {
+        let required = params.iter().filter(|v| !v.is_optional()).count();
+        (required..=params.len())
+            .map(|len| params[..len].iter().collect())
+            .collect()
+    }
+
+    fn common_url_(
+        a: &[&fastn_core::sitemap::PathParams],
+        b: &[&fastn_core::sitemap::PathParams],
+    ) -> Option<String> {
+        let fixed = |params: &[&fastn_core::sitemap::PathParams]| {
+            params.iter().filter(|v| !v.is_catch_all()).count()
+        };
+        let (a_fixed, b_fixed) = (fixed(a), fixed(b));
+        // a catch-all param matches one or more parts
+        let len = match (a_fixed < a.len(), b_fixed < b.len()) {
+            (false, false) if a_fixed == b_fixed => a_fixed,
+            (true, false) if b_fixed > a_fixed => b_fixed,
+            (false, true) if a_fixed > b_fixed => a_fixed,
+            (true, true) => std::cmp::max(a_fixed, b_fixed) + 1,
+            _ => return None,
+        };
+
+        let mut parts = vec![];
+        for i in 0..len {
+            parts.push(common_part(
+                a.get(i.min(a.len() - 1)).copied()?,
+                b.get(i.min(b.len() - 1)).copied()?,
+            )?);
+        }
+        if parts.is_empty() {
+            return Some("/".to_string());
+        }
+        Some(format!("/{}/", parts.join("/")))
+    }
+
+    fn common_part(
+        a: &fastn_core::sitemap::PathParams,
+        b: &fastn_core::sitemap::PathParams,
+    ) -> Option<String> {
+        let candidates = [candidates(a), candidates(b)].concat();
+        candidates
+            .into_iter()
+            .find(|v| part_matches(a, v.as_str()) && part_matches(b, v.as_str()))
+    }
+
+    fn candidates(param: &fastn_core::sitemap::PathParams) -> Vec<String> {
+        match param {
+            fastn_core::sitemap::PathParams::ValueParam { value, .. } => vec![value.to_string()],
+            fastn_core::sitemap::PathParams::NamedParm { param_type, .. } => {
+                match param_type.as_str() {
+                    "string" => vec!["value".to_string()],
+                    "integer" => vec!["1".to_string()],
+                    "decimal" => vec!["1.5".to_string()],
+                    "boolean" => vec!["true".to_string(), "false".to_string()],
+                    t => t.split('|').map(|v| v.to_string()).collect(),
+                }
+            }
+            fastn_core::sitemap::PathParams::CatchAllParam { .. } => vec!["value".to_string()],
+        }
+    }
+
+    fn part_matches(param: &fastn_core::sitemap::PathParams, part: &str) -> bool {
+        match param {
+            fastn_core::sitemap::PathParams::ValueParam { value, .. } => value.eq(part),
+            fastn_core::sitemap::PathParams::NamedParm { param_type, .. } => {
+                get_value_type(part, param_type).is_ok()
+            }
+            fastn_core::sitemap::PathParams::CatchAllParam { .. } => true,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use ftd::TextSource;
@@ -326,4 +501,122 @@ mod tests {
             ]
         );
     }
+
+    // cargo test --package fastn --lib sitemap::utils::tests::parse_path_params_test_03
+    #[test]
+    fn parse_path_params_test_03() {
+        let output = super::parse_named_params(
+            "/b/<small|large:size>/<decimal:weight>/<optional boolean:raw>/",
+        );
+        let test_output = vec![
+            fastn_core::sitemap::PathParams::value(0, "b".to_string()),
+            fastn_core::sitemap::PathParams::named(
+                1,
+                "size".to_string(),
+                "small|large".to_string(),
+            ),
+            fastn_core::sitemap::PathParams::named(2, "weight".to_string(), "decimal".to_string()),
+            fastn_core::sitemap::PathParams::optional_named(
+                3,
+                "raw".to_string(),
+                "boolean".to_string(),
+            ),
+        ];
+        assert_eq!(Ok(test_output), output);
+
+        let output = super::parse_named_params("/files/<integer:id>/*path");
+        let test_output = vec![
+            fastn_core::sitemap::PathParams::value(0, "files".to_string()),
+            fastn_core::sitemap::PathParams::named(1, "id".to_string(), "integer".to_string()),
+            fastn_core::sitemap::PathParams::catch_all(2, "path".to_string()),
+        ];
+        assert_eq!(Ok(test_output), output);
+    }
+
+    // cargo test --package fastn --lib sitemap::utils::tests::parse_path_params_test_04
+    #[test]
+    fn parse_path_params_test_04() {
+        // unknown type
+        assert!(super::parse_named_params("/b/<person:username>/").is_err());
+        // the catch-all param is not the last one
+        assert!(super::parse_named_params("/b/*path/<string:username>/").is_err());
+        // a required param after an optional one
+        assert!(super::parse_named_params("/b/<optional string:tab>/<string:username>/").is_err());
+    }
+
+    // cargo test --package fastn --lib sitemap::utils::tests::url_match_6 -- --nocapture
+    #[test]
+    fn url_match_6() {
+        // sitemap_url: /a/<small|large:size>/<optional integer:page>/
+        let params = [
+            fastn_core::sitemap::PathParams::value(0, "a".to_string()),
+            fastn_core::sitemap::PathParams::named(
+                1,
+                "size".to_string(),
+                "small|large".to_string(),
+            ),
+            fastn_core::sitemap::PathParams::optional_named(
+                2,
+                "page".to_string(),
+                "integer".to_string(),
+            ),
+        ];
+
+        let output = super::url_match("/a/small/", &params).unwrap();
+        assert!(output.0);
+        assert_eq!(
+            output.1,
+            vec![(
+                "size".to_string(),
+                ftd::Value::String {
+                    text: "small".to_string(),
+                    source: TextSource::Default
+                }
+            )]
+        );
+
+        let output = super::url_match("/a/large/2/", &params).unwrap();
+        assert!(output.0);
+        assert_eq!(
+            output.1,
+            vec![
+                (
+                    "size".to_string(),
+                    ftd::Value::String {
+                        text: "large".to_string(),
+                        source: TextSource::Default
+                    }
+                ),
+                ("page".to_string(), ftd::Value::Integer { value: 2 })
+            ]
+        );
+
+        assert!(!super::url_match("/a/medium/", &params).unwrap().0);
+        assert!(!super::url_match("/a/large/2/3/", &params).unwrap().0);
+    }
+
+    // cargo test --package fastn --lib sitemap::utils::tests::url_match_7 -- --nocapture
+    #[test]
+    fn url_match_7() {
+        // sitemap_url: /files/*path
+        let params = [
+            fastn_core::sitemap::PathParams::value(0, "files".to_string()),
+            fastn_core::sitemap::PathParams::catch_all(1, "path".to_string()),
+        ];
+
+        let output = super::url_match("/files/docs/2023/report.pdf/", &params).unwrap();
+        assert!(output.0);
+        assert_eq!(
+            output.1,
+            vec![(
+                "path".to_string(),
+                ftd::Value::String {
+                    text: "docs/2023/report.pdf".to_string(),
+                    source: TextSource::Default
+                }
+            )]
+        );
+
+        assert!(!super::url_match("/files/", &params).unwrap().0);
+    }
 }
@@ -87,6 +87,9 @@ pub struct Workspace {
     pub base: u128,
     pub conflicted: u128,
     pub workspace: WorkspaceType, // workspace type ours/theirs/conflicted
+    /// The file the conflicting version comes from, when it is not `filename` itself, like the
+    /// source CR's file when merging one CR into another.
+    pub source: Option<String>,
 }
 
 impl Workspace {
@@ -127,9 +130,14 @@ pub(crate) async fn create_workspace(
     let mut data = vec!["-- import: fastn".to_string()];
 
     for workspace in workspaces {
+        let source = if let Some(ref source) = workspace.source {
+            format!("source: {}\n", source)
+        } else {
+            "".to_string()
+        };
         data.push(format!(
-            "-- fastn.workspace: {}\nbase: {}\nconflicted: {}\nworkspace: {:?}\n",
-            workspace.filename, workspace.base, workspace.conflicted, workspace.workspace
+            "-- fastn.workspace: {}\nbase: {}\nconflicted: {}\nworkspace: {:?}\n{}",
+            workspace.filename, workspace.base, workspace.conflicted, workspace.workspace, source
         ));
     }
 
@@ -250,7 +250,7 @@ pub(crate) fn get_translation_status_counts(
     Ok(translation_status_count)
 }
 
-#[derive(serde::Deserialize, Debug, Clone)]
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone)]
 pub struct TranslationStatusSummary {
     #[serde(rename = "never-marked")]
     pub never_marked: i32,
@@ -29,7 +29,7 @@ macro_rules! warning {
     }};
 }
 
-fn id_to_cache_key(id: &str) -> String {
+pub(crate) fn id_to_cache_key(id: &str) -> String {
     // TODO: use MAIN_SEPARATOR here
     id.replace(['/', '\\'], "_")
 }
@@ -107,15 +107,63 @@ pub fn print_end(msg: &str, start: std::time::Instant) {
     use colored::Colorize;
 
     if fastn_core::utils::is_test() {
-        println!("done in <omitted>");
+        print_out("done in <omitted>\n".to_string());
     } else {
-        println!(
+        print_out(format!(
             // TODO: instead of lots of spaces put proper erase current terminal line thing
-            "\r{:?} {} in {:?}.                          ",
+            "\r{:?} {} in {:?}.                          \n",
             std::time::Instant::now(),
             msg.green(),
             start.elapsed()
-        );
+        ));
+    }
+}
+
+/// `print_start()` prints the `Processing <id> ... ` that `print_end()` completes.
+pub(crate) fn print_start(id: &str) {
+    print_out(format!("Processing {} ... ", id));
+}
+
+thread_local! {
+    // see `capture_output()`
+    static CAPTURED: std::cell::RefCell<Option<Vec<(bool, String)>>> =
+        std::cell::RefCell::new(None);
+}
+
+/// `capture_output()` keeps what the current thread prints with `print_out()`, `print_err()`
+/// and the functions using them, till `captured_output()` is called. `fastn build` builds
+/// documents concurrently on threads that capture their output, and prints it in the order of
+/// the documents.
+pub(crate) fn capture_output() {
+    CAPTURED.with(|v| *v.borrow_mut() = Some(vec![]))
+}
+
+/// `captured_output()` stops capturing, and returns what was printed along with whether it was
+/// printed to stderr.
+pub(crate) fn captured_output() -> Vec<(bool, String)> {
+    CAPTURED.with(|v| v.borrow_mut().take().unwrap_or_default())
+}
+
+pub(crate) fn print_out(text: String) {
+    output(false, text)
+}
+
+pub(crate) fn print_err(text: String) {
+    output(true, text)
+}
+
+fn output(stderr: bool, text: String) {
+    let text = CAPTURED.with(|v| match v.borrow_mut().as_mut() {
+        Some(captured) => {
+            captured.push((stderr, text));
+            None
+        }
+        None => Some(text),
+    });
+    match text {
+        Some(text) if stderr => eprint!("{}", text),
+        Some(text) => print!("{}", text),
+        None => {}
     }
 }
 
@@ -152,20 +200,38 @@ mod test {
             "index.ftd/b/index.html"
         );
     }
+
+    #[test]
+    fn captured_output() {
+        super::capture_output();
+        super::print_start("foo.com/index");
+        super::print_end("Processed foo.com/index", std::time::Instant::now());
+        super::print_err("error\n".to_string());
+        assert_eq!(
+            super::captured_output(),
+            vec![
+                (false, "Processing foo.com/index ... ".to_string()),
+                (false, "done in <omitted>\n".to_string()),
+                (true, "error\n".to_string()),
+            ]
+        );
+        // the output is only captured till it is taken
+        assert!(super::captured_output().is_empty());
+    }
 }
 
 pub fn print_error(msg: &str, start: std::time::Instant) {
     use colored::Colorize;
 
     if fastn_core::utils::is_test() {
-        println!("done in <omitted>");
+        print_out("done in <omitted>\n".to_string());
     } else {
-        eprintln!(
-            "\r{:?} {} in {:?}.                          ",
+        print_err(format!(
+            "\r{:?} {} in {:?}.                          \n",
             std::time::Instant::now(),
             msg.red(),
             start.elapsed(),
-        );
+        ));
     }
 }
 
@@ -741,6 +807,8 @@ pub fn get_fastn_package_data(package: &fastn_core::Package) -> String {
     )
 }
 
+/// `replace_markers_2023()` fills in `ftd_js_html()` for a document. `features` are the optional
+/// parts of the runtime the document uses, the page only references the files of those.
 #[allow(clippy::too_many_arguments)]
 pub fn replace_markers_2023(
     s: &str,
@@ -750,8 +818,32 @@ pub fn replace_markers_2023(
     font_style: &str,
     default_css: &str,
     base_url: &str,
+    features: fastn_js::Features,
     config: &mut fastn_core::Config,
 ) -> String {
+    let (code_themes, mut script_files) = if features.code {
+        (
+            fastn_core::utils::available_code_themes(),
+            format!(
+                r#"
+                    <script src="{}"></script>
+                    <link rel="stylesheet" href="{}">
+                "#,
+                hashed_prism_js(),
+                hashed_prism_css(),
+            ),
+        )
+    } else {
+        (String::new(), String::new())
+    };
+    if features.rive {
+        script_files.push_str(format!(r#"<script src="{}"></script>"#, hashed_rive_js()).as_str());
+    }
+    if features.web_component {
+        script_files
+            .push_str(format!(r#"<script src="{}"></script>"#, hashed_web_component_js()).as_str());
+    }
+
     ftd::html::utils::trim_all_lines(
         s.replace(
             "__fastn_package__",
@@ -759,7 +851,7 @@ pub fn replace_markers_2023(
         )
         .replace(
             "__js_script__",
-            format!("{js_script}{}", fastn_core::utils::available_code_themes()).as_str(),
+            format!("{js_script}{code_themes}").as_str(),
         )
         .replace(
             "__html_body__",
@@ -781,14 +873,12 @@ pub fn replace_markers_2023(
                 r#"
                     <script src="{}"></script>
                     <script src="{}"></script>
-                    <script src="{}"></script>
-                    <link rel="stylesheet" href="{}">
+                    {}
                     {}
                 "#,
                 hashed_markdown_js(),
-                hashed_prism_js(),
                 hashed_default_ftd_js(config.package.name.as_str()),
-                hashed_prism_css(),
+                script_files,
                 scripts,
             )
             .as_str(),
@@ -1014,6 +1104,24 @@ pub fn hashed_markdown_js() -> &'static str {
     &MARKDOWN_HASH
 }
 
+static RIVE_JS_HASH: once_cell::sync::Lazy<String> =
+    once_cell::sync::Lazy::new(|| format!("rive-{}.js", generate_hash(fastn_js::rive_js())));
+
+pub fn hashed_rive_js() -> &'static str {
+    &RIVE_JS_HASH
+}
+
+static WEB_COMPONENT_JS_HASH: once_cell::sync::Lazy<String> = once_cell::sync::Lazy::new(|| {
+    format!(
+        "web-component-{}.js",
+        generate_hash(fastn_js::web_component_js())
+    )
+});
+
+pub fn hashed_web_component_js() -> &'static str {
+    &WEB_COMPONENT_JS_HASH
+}
+
 static PRISM_JS_HASH: once_cell::sync::Lazy<String> =
     once_cell::sync::Lazy::new(|| format!("prism-{}.js", generate_hash(ftd::prism_js().as_str()),));
 
@@ -5,6 +5,7 @@ static WATCHER: once_cell::sync::Lazy<(
 )> = once_cell::sync::Lazy::new(watcher);
 const POLL_TIMEOUT: std::time::Duration = std::time::Duration::from_millis(30 * 1000); // 30 seconds
 static GLOBAL_POLL_COUNT: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+static WATCHING: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
 
 fn watcher() -> (
     tokio::sync::mpsc::Sender<WatcherSender>,
@@ -57,24 +58,61 @@ fn watcher() -> (
     (tx, g_tx)
 }
 
-fn create_watcher(f_tx: tokio::sync::mpsc::Sender<()>) -> notify::RecommendedWatcher {
+fn create_watcher(f_tx: tokio::sync::mpsc::Sender<()>) -> Option<notify::RecommendedWatcher> {
     use notify::Watcher;
 
-    let mut watcher = notify::recommended_watcher(move |_res| {
-        if let Err(e) = f_tx.blocking_send(()) {
-            eprintln!("watcher: failed to send signal: {}", e);
-        }
-    })
-    .expect("watcher: failed to create watcher");
+    let mut watcher =
+        match notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+            if res
+                .as_ref()
+                .map_or(true, |e| e.paths.iter().any(|p| changes_config(p)))
+            {
+                fastn_core::Config::invalidate_cache();
+            }
+            if let Err(e) = f_tx.blocking_send(()) {
+                eprintln!("watcher: failed to send signal: {}", e);
+            }
+        }) {
+            Ok(watcher) => watcher,
+            Err(e) => {
+                eprintln!("watcher: failed to create watcher: {}", e);
+                return None;
+            }
+        };
+
+    if let Err(e) = watcher.watch(
+        &std::path::PathBuf::from("."), // TODO: how to get the root path?
+        notify::RecursiveMode::Recursive,
+    ) {
+        eprintln!("watcher: failed to watch: {}", e);
+        return None;
+    }
 
-    watcher
-        .watch(
-            &std::path::PathBuf::from(""), // TODO: how to get the root path?
-            notify::RecursiveMode::Recursive,
-        )
-        .expect("watcher: failed to watch");
+    WATCHING.store(true, std::sync::atomic::Ordering::SeqCst);
+    Some(watcher)
+}
+
+/// `is_watching()` tells if the files of the package are being watched, without the watcher
+/// nothing drops the cached config, so `fastn_core::Config::read_cached()` does not cache.
+pub(crate) fn is_watching() -> bool {
+    WATCHING.load(std::sync::atomic::Ordering::SeqCst)
+}
+
+/// `changes_config()` tells if a change to `path` changes what `fastn_core::Config::read()` reads:
+/// `FASTN.ftd` of the package or of a dependency, `FASTN.lock`, anything in `.packages`, or any
+/// other ftd document, whose `id`s are in `Config::global_ids` and the sitemap links made from
+/// them.
+pub(crate) fn changes_config(path: &std::path::Path) -> bool {
+    path.file_name().map_or(false, |v| {
+        v == "FASTN.ftd" || v == fastn_core::package::lock::LOCK_FILE
+    }) || path.extension().map_or(false, |v| v == "ftd")
+        || path.components().any(|v| v.as_os_str() == ".packages")
+}
 
-    watcher
+/// `start()` starts watching the files of the package, `fastn serve` calls it so the cached
+/// config is dropped when it changes, even if nobody polls.
+pub(crate) fn start() {
+    once_cell::sync::Lazy::force(&WATCHER);
 }
 
 fn next_id() -> usize {
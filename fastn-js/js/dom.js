@@ -153,6 +153,9 @@ fastn_dom.ElementKind = {
     // internal usage in js functions.
     WebComponent: (webcomponent, args) => { return [17, [webcomponent, args]]; },
     Video: 18,
+    Form: 19,
+    Select: 20,
+    Radio: 21,
 };
 
 fastn_dom.PropertyKind = {
@@ -275,6 +278,15 @@ fastn_dom.PropertyKind = {
     Controls: 114,
     Muted: 115,
     LinkColor: 116,
+    InputMinLength: 117,
+    InputPattern: 118,
+    InputName: 119,
+    InputValue: 120,
+    Required: 121,
+    SelectOptions: 122,
+    SelectValue: 123,
+    FormAction: 124,
+    FormMethod: 125,
 };
 
 
@@ -636,6 +648,7 @@ fastn_dom.Event = {
     Change: 7,
     Blur: 8,
     Focus: 9,
+    Submit: 10,
 }
 
 class PropertyValueAsClosure {
@@ -687,6 +700,9 @@ class Node2 {
 
         this.#mutables = [];
         this.#extraData = {};
+        if (kind === fastn_dom.ElementKind.Form && !ssr) {
+            this.#node.onsubmit = (event) => this.submitForm(event);
+        }
         /*if (!!parent.parent) {
             parent = parent.parent();
         }*/
@@ -1168,7 +1184,7 @@ class Node2 {
             this.attachCss("justify-content", value);
             return;
         }
-        if (node_kind === fastn_dom.ElementKind.Column) {
+        if (node_kind === fastn_dom.ElementKind.Column || node_kind === fastn_dom.ElementKind.Form) {
             switch (value) {
                 case 'top-left':
                     this.attachCss("justify-content", "start");
@@ -1250,7 +1266,7 @@ class Node2 {
             }
         }
     }
-    attachLinkColor(value) {
+    attachLinkColor(value, inherited) {
         ftd.dark_mode.addClosure(fastn.closure(() => {
             if (!ssr) {
                 const anchors = this.#node.tagName.toLowerCase() === 'a'
@@ -1553,7 +1569,7 @@ class Node2 {
         } else if (kind === fastn_dom.PropertyKind.BorderBottomColor) {
             this.attachColorCss("border-bottom-color", staticValue);
         } else if (kind === fastn_dom.PropertyKind.LinkColor) {
-            this.attachLinkColor(staticValue);
+            this.attachLinkColor(staticValue, inherited);
         } else if (kind === fastn_dom.PropertyKind.Color) {
             this.attachColorCss("color", staticValue, true);
         } else if (kind === fastn_dom.PropertyKind.Background) {
@@ -1610,6 +1626,37 @@ class Node2 {
             this.attachAttribute("value", staticValue);
         } else if (kind === fastn_dom.PropertyKind.InputMaxLength) {
             this.attachAttribute("maxlength", staticValue);
+        } else if (kind === fastn_dom.PropertyKind.InputMinLength) {
+            this.attachAttribute("minlength", staticValue);
+        } else if (kind === fastn_dom.PropertyKind.InputPattern) {
+            this.attachAttribute("pattern", staticValue);
+        } else if (kind === fastn_dom.PropertyKind.InputName) {
+            this.attachAttribute("name", staticValue);
+        } else if (kind === fastn_dom.PropertyKind.InputValue) {
+            this.attachAttribute("value", staticValue);
+        } else if (kind === fastn_dom.PropertyKind.Required) {
+            switch (staticValue) {
+                case "true":
+                case true:
+                    this.attachAttribute("required", "");
+                    break;
+                default:
+                    this.removeAttribute("required");
+            }
+        } else if (kind === fastn_dom.PropertyKind.SelectOptions) {
+            let options = fastn_utils.isNull(staticValue)
+                ? []
+                : staticValue.map(obj => fastn_utils.getStaticValue(obj.item));
+            this.attachSelectOptions(options);
+        } else if (kind === fastn_dom.PropertyKind.SelectValue) {
+            this.#extraData.selectValue = staticValue;
+            this.selectOption(staticValue);
+        } else if (kind === fastn_dom.PropertyKind.FormAction) {
+            this.#extraData.formAction = staticValue;
+            this.attachAttribute("action", staticValue);
+        } else if (kind === fastn_dom.PropertyKind.FormMethod) {
+            this.#extraData.formMethod = staticValue;
+            this.attachAttribute("method", staticValue);
         } else if (kind === fastn_dom.PropertyKind.Placeholder) {
             this.attachAttribute("placeholder", staticValue);
         } else if (kind === fastn_dom.PropertyKind.Multiline) {
@@ -1919,6 +1966,84 @@ class Node2 {
         } else if (event === fastn_dom.Event.Focus) {
             let onFocusEvents = this.mergeFnCalls(this.#node.onfocus, func);
             this.#node.onfocus = onFocusEvents;
+        } else if (event === fastn_dom.Event.Submit) {
+            // called by `submitForm()` once the fields of the form are valid
+            this.#extraData.onSubmit = this.mergeFnCalls(this.#extraData.onSubmit, func);
+        }
+    }
+    // Replaces the `option` elements of a `select` with one for each of `options`. While
+    // hydrating, `createElement()` gives back the options ssr rendered, they are reused and the
+    // ones left over are removed.
+    attachSelectOptions(options) {
+        if (!ssr && !hydrating) {
+            while (this.#node.firstChild) {
+                this.#node.removeChild(this.#node.firstChild);
+            }
+        }
+        let optionNodes = [];
+        for (let option of options) {
+            let optionNode = fastn_virtual.document.createElement("option");
+            if (fastn_utils.isNull(optionNode)) {
+                // ssr rendered fewer options than there are now
+                optionNode = document.createElement("option");
+            }
+            optionNode.setAttribute("value", option);
+            if (ssr) {
+                optionNode.innerHTML = `${option}`;
+            } else {
+                optionNode.textContent = `${option}`;
+            }
+            this.#node.appendChild(optionNode);
+            optionNodes.push(optionNode);
+        }
+        if (!ssr && hydrating) {
+            for (let child of Array.from(this.#node.children)) {
+                if (!optionNodes.includes(child)) {
+                    this.#node.removeChild(child);
+                }
+            }
+        }
+        if (!fastn_utils.isNull(this.#extraData.selectValue)) {
+            this.selectOption(this.#extraData.selectValue);
+        }
+    }
+    selectOption(value) {
+        let options = ssr ? this.#node.getChildren() : Array.from(this.#node.children);
+        for (let option of options) {
+            if (option.getAttribute("value") === value) {
+                option.setAttribute("selected", "");
+            } else {
+                option.removeAttribute("selected");
+            }
+        }
+        if (!ssr && !fastn_utils.isNull(value)) {
+            this.#node.value = value;
+        }
+    }
+    // The fields of the form are validated with the rules their elements declare (`required`,
+    // `pattern`, `min-length` and `max-length`), and the values of the named ones are posted to
+    // the `action` of the form through `ftd.http`.
+    submitForm(event) {
+        event.preventDefault();
+        if (!this.#node.reportValidity()) {
+            return;
+        }
+        let data = fastn_utils.formData(this.#node);
+        let action = fastn_utils.getStaticValue(this.#extraData.formAction);
+        if (!fastn_utils.isNull(action)) {
+            let method = fastn_utils.getStaticValue(this.#extraData.formMethod);
+            method = fastn_utils.isNull(method) ? "POST" : method.trim().toUpperCase();
+            if (method === "GET") {
+                let url = new URL(action, window.location.href);
+                for (let [name, value] of Object.entries(data.toObject())) {
+                    url.searchParams.append(name, value);
+                }
+                action = url.toString();
+            }
+            ftd.http(action, method, data);
+        }
+        if (this.#extraData.onSubmit instanceof Function) {
+            this.#extraData.onSubmit();
         }
     }
     destroy() {
@@ -319,12 +319,28 @@ fastn.mutableList = function (list) {
     return new MutableList(list);
 }
 
+class MapEntries {
+    #record;
+    constructor(record) {
+        this.#record = record;
+    }
+    getList() {
+        let fields = this.#record.getAllFields();
+        return Object.keys(fields).sort().map((key) => ({ item: fields[key], index: fastn.mutable(key) }));
+    }
+    get(idx) {
+        return this.getList()[idx];
+    }
+}
+
 class RecordInstance {
     #fields;
     #closures;
+    #loops;
     constructor(obj) {
         this.#fields = {};
         this.#closures = [];
+        this.#loops = [];
 
         for (let key in obj) {
             if (obj[key] instanceof fastn.mutableClass) {
@@ -344,6 +360,13 @@ class RecordInstance {
     unlinkNode(node) {
         this.#closures = this.#closures.filter(closure => closure.getNode() !== node);
     }
+    // Maps are stored as record instances, a loop over them goes over their entries in the
+    // order of the keys, the key being the loop counter.
+    forLoop(root, dom_constructor) {
+        let l = fastn_dom.forLoop(root, dom_constructor, new MapEntries(this));
+        this.#loops.push(l);
+        return l;
+    }
     get(key) {
         return this.#fields[key];
     }
@@ -360,10 +383,11 @@ class RecordInstance {
             }
 
             this.#fields = fields;
-        }
-        if (this.#fields[key] === undefined) {
+            this.#loops.forEach((l) => l.createAllNode());
+        } else if (this.#fields[key] === undefined) {
             this.#fields[key] = fastn.mutable(null);
             this.#fields[key].setWithoutUpdate(value);
+            this.#loops.forEach((l) => l.createAllNode());
         } else {
             this.#fields[key].set(value);
         }
@@ -1,6 +1,5 @@
 let ftd = {
     // source: https://stackoverflow.com/questions/400212/ (cc-by-sa)
-    riveNodes: {},
     is_empty(value) {
         value = fastn_utils.getFlattenStaticValue(value);
         return fastn_utils.isNull(value) || value.length === 0;
@@ -44,79 +43,15 @@ let ftd = {
         return args.a;
     },
 
-    set_rive_boolean(args, node) {
-        if (!!args.rive) {
-            let riveNode = ftd.riveNodes[`${args.rive}__${ftd.device.get()}`];
-            node = riveNode ? riveNode: node;
-        }
-        let riveConst = node.getExtraData().rive;
-        const stateMachineName = riveConst.stateMachineNames[0];
-        const inputs = riveConst.stateMachineInputs(stateMachineName);
-        const bumpTrigger = inputs.find(i => i.name === args.input);
-        bumpTrigger.value = args.value;
-    },
-
-    toggle_rive_boolean(args, node) {
-        if (!!args.rive) {
-            let riveNode = ftd.riveNodes[`${args.rive}__${ftd.device.get()}`];
-            node = riveNode ? riveNode: node;
-        }
-        let riveConst = node.getExtraData().rive;
-        const stateMachineName = riveConst.stateMachineNames[0];
-        const inputs = riveConst.stateMachineInputs(stateMachineName);
-        const trigger = inputs.find(i => i.name === args.input);
-        trigger.value = !trigger.value;
-    },
-
-    set_rive_integer(args, node) {
-        if (!!args.rive) {
-            let riveNode = ftd.riveNodes[`${args.rive}__${ftd.device.get()}`];
-            node = riveNode ? riveNode: node;
-        }
-        let riveConst = node.getExtraData().rive;
-        const stateMachineName = riveConst.stateMachineNames[0];
-        const inputs = riveConst.stateMachineInputs(stateMachineName);
-        const trigger = inputs.find(i => i.name === args.input);
-        trigger.value = args.value;
-    },
-
-    fire_rive(args, node) {
-        if (!!args.rive) {
-            let riveNode = ftd.riveNodes[`${args.rive}__${ftd.device.get()}`];
-            node = riveNode ? riveNode: node;
-        }
-        let riveConst = node.getExtraData().rive;
-        const stateMachineName = riveConst.stateMachineNames[0];
-        const inputs = riveConst.stateMachineInputs(stateMachineName);
-        const trigger = inputs.find(i => i.name === args.input);
-        trigger.fire();
-    },
-
-    play_rive(args, node) {
-        if (!!args.rive) {
-            let riveNode = ftd.riveNodes[`${args.rive}__${ftd.device.get()}`];
-            node = riveNode ? riveNode: node;
-        }
-        node.getExtraData().rive.play(args.input);
-    },
-
-    pause_rive(args, node) {
-        if (!!args.rive) {
-            let riveNode = ftd.riveNodes[`${args.rive}__${ftd.device.get()}`];
-            node = riveNode ? riveNode: node;
-        }
-        node.getExtraData().rive.pause(args.input);
-    },
-
-    toggle_play_rive(args, node) {
-        if (!!args.rive) {
-            let riveNode = ftd.riveNodes[`${args.rive}__${ftd.device.get()}`];
-            node = riveNode ? riveNode: node;
+    // Submits the `ftd.form` with the id `args.id`, as if a submit button of it was clicked.
+    submit_form(args) {
+        let id = fastn_utils.getStaticValue(args.id);
+        let form = document.getElementById(id);
+        if (fastn_utils.isNull(form) || form.tagName.toLowerCase() !== "form") {
+            console.error(`[submit_form]: no form with id ${id}`);
+            return;
         }
-        let riveConst = node.getExtraData().rive
-        riveConst.playingAnimationNames.includes(args.input)
-            ? riveConst.pause(args.input)
-            : riveConst.play(args.input);
+        form.requestSubmit();
     },
 
     get(value, index) {
@@ -154,7 +89,13 @@ ftd.clear_all = function (list) { list.clearAll() }
 ftd.clear = ftd.clear_all;
 ftd.set_list = function (list, value) { list.set(value) }
 
-ftd.http = function (url, method, body, headers) {
+// ftd.http(url, method, body, headers, response, loading, error)
+// The JSON the request gets back is set to the mutable `response`, converted to its kind the
+// way the server converts JSON. `loading` is true while the request is in flight and `error`
+// gets the reason the request failed, or null once it succeeds. The last argument, the kind of
+// `response` as a JSON string, is added by the compiler.
+ftd.http = function (url, method, body, headers, response, loading, error, response_kind) {
+    if (typeof response_kind === "string") response_kind = JSON.parse(response_kind);
     if (url instanceof fastn.mutableClass) url = url.get();
     if (method instanceof fastn.mutableClass) method = method.get();
     method = method.trim().toUpperCase();
@@ -171,18 +112,25 @@ ftd.http = function (url, method, body, headers) {
     if(body && body instanceof fastn.recordInstanceClass && method !== 'GET') {
         init.body = JSON.stringify(body.toObject());
     }
+    fastn_utils.setter(loading, true);
     fetch(url, init)
     .then(res => {
         if(!res.ok) {
-            return new Error("[http]: Request failed", res)
+            throw new Error(`[http]: Request failed: ${res.status} ${res.statusText}`);
         }
 
         return res.json();
     })
     .then(json => {
         console.log("[http]: Response OK", json);
+        fastn_utils.setter(response, fastn_utils.fromJson(json, response_kind));
+        fastn_utils.setter(error, null);
+    })
+    .catch(e => {
+        console.error(e);
+        fastn_utils.setter(error, e.message);
     })
-    .catch(console.error);
+    .finally(() => fastn_utils.setter(loading, false));
 }
 
 ftd.navigate = function(url, request_data) {
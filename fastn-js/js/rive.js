@@ -0,0 +1,79 @@
+// `ftd.rive` support. It is not part of the runtime every page loads, only pages with a
+// `ftd.rive` load it, see `fastn_js::Features`.
+
+ftd.riveNodes = {};
+
+ftd.set_rive_boolean = function (args, node) {
+    if (!!args.rive) {
+        let riveNode = ftd.riveNodes[`${args.rive}__${ftd.device.get()}`];
+        node = riveNode ? riveNode: node;
+    }
+    let riveConst = node.getExtraData().rive;
+    const stateMachineName = riveConst.stateMachineNames[0];
+    const inputs = riveConst.stateMachineInputs(stateMachineName);
+    const bumpTrigger = inputs.find(i => i.name === args.input);
+    bumpTrigger.value = args.value;
+}
+
+ftd.toggle_rive_boolean = function (args, node) {
+    if (!!args.rive) {
+        let riveNode = ftd.riveNodes[`${args.rive}__${ftd.device.get()}`];
+        node = riveNode ? riveNode: node;
+    }
+    let riveConst = node.getExtraData().rive;
+    const stateMachineName = riveConst.stateMachineNames[0];
+    const inputs = riveConst.stateMachineInputs(stateMachineName);
+    const trigger = inputs.find(i => i.name === args.input);
+    trigger.value = !trigger.value;
+}
+
+ftd.set_rive_integer = function (args, node) {
+    if (!!args.rive) {
+        let riveNode = ftd.riveNodes[`${args.rive}__${ftd.device.get()}`];
+        node = riveNode ? riveNode: node;
+    }
+    let riveConst = node.getExtraData().rive;
+    const stateMachineName = riveConst.stateMachineNames[0];
+    const inputs = riveConst.stateMachineInputs(stateMachineName);
+    const trigger = inputs.find(i => i.name === args.input);
+    trigger.value = args.value;
+}
+
+ftd.fire_rive = function (args, node) {
+    if (!!args.rive) {
+        let riveNode = ftd.riveNodes[`${args.rive}__${ftd.device.get()}`];
+        node = riveNode ? riveNode: node;
+    }
+    let riveConst = node.getExtraData().rive;
+    const stateMachineName = riveConst.stateMachineNames[0];
+    const inputs = riveConst.stateMachineInputs(stateMachineName);
+    const trigger = inputs.find(i => i.name === args.input);
+    trigger.fire();
+}
+
+ftd.play_rive = function (args, node) {
+    if (!!args.rive) {
+        let riveNode = ftd.riveNodes[`${args.rive}__${ftd.device.get()}`];
+        node = riveNode ? riveNode: node;
+    }
+    node.getExtraData().rive.play(args.input);
+}
+
+ftd.pause_rive = function (args, node) {
+    if (!!args.rive) {
+        let riveNode = ftd.riveNodes[`${args.rive}__${ftd.device.get()}`];
+        node = riveNode ? riveNode: node;
+    }
+    node.getExtraData().rive.pause(args.input);
+}
+
+ftd.toggle_play_rive = function (args, node) {
+    if (!!args.rive) {
+        let riveNode = ftd.riveNodes[`${args.rive}__${ftd.device.get()}`];
+        node = riveNode ? riveNode: node;
+    }
+    let riveConst = node.getExtraData().rive
+    riveConst.playingAnimationNames.includes(args.input)
+        ? riveConst.pause(args.input)
+        : riveConst.play(args.input);
+}
@@ -29,6 +29,14 @@ let fastn_utils = {
             attributes["type"] = "checkbox";
         } else if (kind === fastn_dom.ElementKind.TextInput) {
             node = "input";
+        } else if (kind === fastn_dom.ElementKind.Form) {
+            node = "form";
+            css.push("ft_column");
+        } else if (kind === fastn_dom.ElementKind.Select) {
+            node = "select";
+        } else if (kind === fastn_dom.ElementKind.Radio) {
+            node = "input";
+            attributes["type"] = "radio";
         } else if (kind === fastn_dom.ElementKind.Comment) {
             node = fastn_dom.commentNode;
         } else if (kind === fastn_dom.ElementKind.Wrapper) {
@@ -102,6 +110,119 @@ let fastn_utils = {
             classList.remove(classNameToRemove);
         }
     },
+    // The JS name of an ftd name, the way `fastn_js::utils::name_to_js_()` makes it.
+    nameToJs(name) {
+        if (/^[0-9]/.test(name)) {
+            name = `_${name}`;
+        }
+        return name.replaceAll("#", "__")
+            .replaceAll("-", "_")
+            .replaceAll(":", "___")
+            .replaceAll(",", "$")
+            .replaceAll("\\", "_")
+            .replaceAll("/", "_")
+            .replaceAll(".", "_");
+    },
+    // Converts `json` to the fastn value of `kind`, the way `TDoc::from_json()` does on the
+    // server. `kind` is the description of an ftd kind generated by
+    // `ftd::js::utils::kind_to_json()`, JSON is converted as it is for the kind `any`.
+    fromJson(json, kind) {
+        if (kind === undefined || kind === null || kind === "any") {
+            return json instanceof Object ? fastn_utils.staticToMutables(json) : json;
+        }
+        const found = JSON.stringify(json);
+        if (kind === "string") {
+            if (typeof json !== "string") {
+                throw new Error(`Can't parse to string, found: ${found}`);
+            }
+            return json;
+        }
+        if (kind === "integer") {
+            if (Number.isInteger(json)) {
+                return json;
+            }
+            if (typeof json === "string" && /^[+-]?[0-9]+$/.test(json.trim())) {
+                return parseInt(json, 10);
+            }
+            throw new Error(`Can't parse to integer, found: ${found}`);
+        }
+        if (kind === "decimal") {
+            if (typeof json === "number") {
+                return json;
+            }
+            if (typeof json === "string" && json.trim() !== "" && !isNaN(Number(json))) {
+                return Number(json);
+            }
+            throw new Error(`Can't parse to decimal, found: ${found}`);
+        }
+        if (kind === "boolean") {
+            if (typeof json === "boolean") {
+                return json;
+            }
+            if (json === "true" || json === "false") {
+                return json === "true";
+            }
+            throw new Error(`Can't parse to boolean, found: ${found}`);
+        }
+        if (kind.optional !== undefined) {
+            return fastn_utils.isNull(json) ? null : fastn_utils.fromJson(json, kind.optional);
+        }
+        if (kind.list !== undefined) {
+            if (!Array.isArray(json)) {
+                throw new Error(`expected object of list type, found: ${found}`);
+            }
+            return fastn.mutableList(json.map(item => fastn_utils.fromJson(item, kind.list)));
+        }
+        if (kind.map !== undefined) {
+            if (!(json instanceof Object) || Array.isArray(json)) {
+                throw new Error(`expected object of map type, found: ${found}`);
+            }
+            // keys are looked up by their JS name, keys that only differ in `-` and `_`, say,
+            // would silently overwrite each other
+            let entries = {};
+            let keys = new Map();
+            for (let key in json) {
+                let name = fastn_utils.nameToJs(key);
+                if (keys.has(name)) {
+                    throw new Error(`keys ${keys.get(name)} and ${key} of the map are the same key in fastn`);
+                }
+                keys.set(name, key);
+                entries[name] = fastn_utils.fromJson(json[key], kind.map);
+            }
+            return fastn.recordInstance(entries);
+        }
+        if (kind.record !== undefined) {
+            let fields = {};
+            if (typeof json === "string") {
+                let caption = kind.record.find(field => field.caption);
+                if (caption === undefined) {
+                    throw new Error(`expected object of record type, found: ${found}`);
+                }
+                fields[caption.key] = json;
+                return fastn.recordInstance(fields);
+            }
+            if (!(json instanceof Object) || Array.isArray(json)) {
+                throw new Error(`expected object of record type, found: ${found}`);
+            }
+            for (let field of kind.record) {
+                let value = json[field.name];
+                if (value === undefined) {
+                    if (field.kind.optional !== undefined) {
+                        value = null;
+                    } else if (field.kind.list !== undefined) {
+                        value = [];
+                    } else if (field.kind.map !== undefined) {
+                        value = {};
+                    } else {
+                        throw new Error(`key not found: ${field.name}`);
+                    }
+                }
+                fields[field.key] = fastn_utils.fromJson(value, field.kind);
+            }
+            return fastn.recordInstance(fields);
+        }
+        throw new Error(`Unknown kind: ${JSON.stringify(kind)}`);
+    },
     staticToMutables(obj) {
         if (!(obj instanceof fastn.mutableClass) &&
             !(obj instanceof fastn.mutableListClass) &&
@@ -382,6 +503,28 @@ let fastn_utils = {
     getNodeValue(node) {
         return node.getNode().value;
     },
+    // The values of the named fields of `form` as a record: checkboxes are booleans, radio
+    // buttons only give the value of the checked one and everything else is a string.
+    formData(form) {
+        let fields = {};
+        for (let element of form.elements) {
+            if (fastn_utils.isNull(element.name) || element.name === "" || element.disabled) {
+                continue;
+            }
+            if (element.type === "checkbox") {
+                fields[element.name] = element.checked;
+            } else if (element.type === "radio") {
+                if (element.checked) {
+                    fields[element.name] = element.value;
+                } else if (!(element.name in fields)) {
+                    fields[element.name] = null;
+                }
+            } else {
+                fields[element.name] = element.value;
+            }
+        }
+        return fastn.recordInstance(fields);
+    },
     setFullHeight() {
         if(!ssr) {
             document.body.style.height = `max(${document.documentElement.scrollHeight}px, 100%)`;
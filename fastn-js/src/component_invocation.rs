@@ -35,6 +35,9 @@ pub enum ElementKind {
     Device,
     CheckBox,
     TextInput,
+    Form,
+    Select,
+    Radio,
     Rive,
     Document,
     Code,
@@ -17,6 +17,7 @@ pub enum Event {
     Change,
     Blur,
     Focus,
+    Submit,
 }
 
 #[derive(Debug)]
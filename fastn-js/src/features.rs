@@ -0,0 +1,240 @@
+/// `Features` are the optional parts of the runtime a document uses. Every page loads the rest of
+/// the runtime, a page only loads these if it uses them.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Features {
+    /// `ftd.code`, which needs prism and the code themes
+    pub code: bool,
+    /// `ftd.rive`, which needs `rive_js()`
+    pub rive: bool,
+    /// web components, which need `web_component_js()`
+    pub web_component: bool,
+    /// the functions of the `ftd` module the document calls, like `ftd#toggle`
+    pub functions: std::collections::BTreeSet<String>,
+}
+
+impl Features {
+    /// `of()` finds the features `asts`, the js ast of a document, uses.
+    pub fn of(asts: &[fastn_js::Ast]) -> Features {
+        let mut features = Features::default();
+        for ast in asts {
+            features.ast(ast);
+        }
+        features
+    }
+
+    fn ast(&mut self, ast: &fastn_js::Ast) {
+        match ast {
+            fastn_js::Ast::Component(component) => {
+                for (_, value, _) in component.args.iter() {
+                    self.value(value);
+                }
+                self.statements(component.body.as_slice());
+            }
+            fastn_js::Ast::UDF(udf) => {
+                for (_, value) in udf.args.iter() {
+                    self.value(value);
+                }
+                for expression in udf.body.iter() {
+                    self.expression(expression);
+                }
+            }
+            fastn_js::Ast::StaticVariable(fastn_js::StaticVariable { value, .. })
+            | fastn_js::Ast::MutableVariable(fastn_js::MutableVariable { value, .. })
+            | fastn_js::Ast::MutableList(fastn_js::MutableList { value, .. })
+            | fastn_js::Ast::RecordInstance(fastn_js::RecordInstance { fields: value, .. }) => {
+                self.value(value)
+            }
+            fastn_js::Ast::Export { from, .. } => self.function(from),
+        }
+    }
+
+    fn statements(&mut self, statements: &[fastn_js::ComponentStatement]) {
+        for statement in statements {
+            match statement {
+                fastn_js::ComponentStatement::CreateKernel(kernel) => match kernel.element_kind {
+                    fastn_js::ElementKind::Code => self.code = true,
+                    fastn_js::ElementKind::Rive => self.rive = true,
+                    fastn_js::ElementKind::WebComponent(_) => self.web_component = true,
+                    _ => {}
+                },
+                fastn_js::ComponentStatement::StaticVariable(fastn_js::StaticVariable {
+                    value,
+                    ..
+                })
+                | fastn_js::ComponentStatement::MutableVariable(fastn_js::MutableVariable {
+                    value,
+                    ..
+                })
+                | fastn_js::ComponentStatement::MutableList(fastn_js::MutableList {
+                    value, ..
+                })
+                | fastn_js::ComponentStatement::RecordInstance(fastn_js::RecordInstance {
+                    fields: value,
+                    ..
+                })
+                | fastn_js::ComponentStatement::SetProperty(fastn_js::SetProperty {
+                    value, ..
+                }) => self.value(value),
+                fastn_js::ComponentStatement::InstantiateComponent(instantiate) => {
+                    if let fastn_js::InstantiateComponentData::Definition(ref definition) =
+                        instantiate.component
+                    {
+                        self.value(definition);
+                    }
+                    for (_, value) in instantiate.arguments.iter() {
+                        self.value(value);
+                    }
+                }
+                fastn_js::ComponentStatement::AddEventHandler(handler) => {
+                    self.call(&handler.action)
+                }
+                fastn_js::ComponentStatement::ConditionalComponent(conditional) => {
+                    self.expression(&conditional.condition);
+                    self.statements(conditional.statements.as_slice());
+                }
+                fastn_js::ComponentStatement::ForLoop(for_loop) => {
+                    self.value(&for_loop.list_variable);
+                    self.statements(for_loop.statements.as_slice());
+                }
+                fastn_js::ComponentStatement::DeviceBlock(device) => {
+                    self.statements(device.statements.as_slice())
+                }
+                fastn_js::ComponentStatement::Return { .. }
+                | fastn_js::ComponentStatement::AnyBlock(_) => {}
+            }
+        }
+    }
+
+    fn value(&mut self, value: &fastn_js::SetPropertyValue) {
+        match value {
+            fastn_js::SetPropertyValue::Reference(_) | fastn_js::SetPropertyValue::Clone(_) => {}
+            fastn_js::SetPropertyValue::Formula(formula) => match formula.type_ {
+                fastn_js::FormulaType::Conditional(ref values) => {
+                    for value in values.iter() {
+                        if let Some(ref condition) = value.condition {
+                            self.expression(condition);
+                        }
+                        self.value(&value.expression);
+                    }
+                }
+                fastn_js::FormulaType::FunctionCall(ref function) => self.call(function),
+            },
+            fastn_js::SetPropertyValue::Value(value) => match value {
+                fastn_js::Value::OrType {
+                    value: Some(value), ..
+                } => self.value(value),
+                fastn_js::Value::List { value } => {
+                    for value in value.iter() {
+                        self.value(value);
+                    }
+                }
+                fastn_js::Value::Record { fields, .. } => {
+                    for (_, value) in fields.iter() {
+                        self.value(value);
+                    }
+                }
+                fastn_js::Value::UI { value } => self.statements(value.as_slice()),
+                _ => {}
+            },
+        }
+    }
+
+    fn call(&mut self, function: &fastn_js::Function) {
+        match *function.name {
+            fastn_js::FunctionData::Name(ref name) => self.function(name),
+            fastn_js::FunctionData::Definition(ref definition) => self.value(definition),
+        }
+        for (_, value) in function.parameters.iter() {
+            self.value(value);
+        }
+    }
+
+    fn expression(&mut self, expression: &fastn_grammar::evalexpr::ExprNode) {
+        if let fastn_grammar::evalexpr::Operator::FunctionIdentifier { identifier } =
+            expression.operator()
+        {
+            self.function(identifier);
+        }
+        for child in expression.children() {
+            self.expression(child);
+        }
+    }
+
+    /// `function()` notes `name` if it is a function of the `ftd` module, either as `ftd#toggle`
+    /// or as its js name, `ftd.toggle`.
+    fn function(&mut self, name: &str) {
+        if let Some(name) = name
+            .strip_prefix("ftd#")
+            .or_else(|| name.strip_prefix("ftd."))
+        {
+            self.functions
+                .insert(format!("ftd#{}", name.replace('_', "-")));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    fn kernel(element_kind: fastn_js::ElementKind) -> fastn_js::ComponentStatement {
+        fastn_js::ComponentStatement::CreateKernel(fastn_js::Kernel {
+            element_kind,
+            name: "parenti0".to_string(),
+            parent: "parent".to_string(),
+        })
+    }
+
+    fn click(name: &str) -> fastn_js::ComponentStatement {
+        fastn_js::ComponentStatement::AddEventHandler(fastn_js::EventHandler {
+            event: fastn_js::Event::Click,
+            action: fastn_js::Function {
+                name: Box::new(fastn_js::FunctionData::Name(name.to_string())),
+                parameters: vec![],
+            },
+            element_name: "parenti0".to_string(),
+        })
+    }
+
+    #[test]
+    fn features() {
+        assert_eq!(
+            super::Features::of(&[fastn_js::component0(
+                "main",
+                vec![kernel(fastn_js::ElementKind::Text)]
+            )]),
+            super::Features::default()
+        );
+
+        let features = super::Features::of(&[fastn_js::component0(
+            "main",
+            vec![
+                kernel(fastn_js::ElementKind::Code),
+                fastn_js::ComponentStatement::ForLoop(fastn_js::ForLoop {
+                    list_variable: fastn_js::SetPropertyValue::Reference("items".to_string()),
+                    statements: vec![
+                        kernel(fastn_js::ElementKind::WebComponent(
+                            "word-count".to_string(),
+                        )),
+                        click("ftd#toggle"),
+                    ],
+                    parent: "parent".to_string(),
+                    should_return: false,
+                }),
+                click("foo#toggle"),
+            ],
+        )]);
+        assert!(features.code);
+        assert!(!features.rive);
+        assert!(features.web_component);
+        assert_eq!(
+            features.functions.into_iter().collect::<Vec<_>>(),
+            vec!["ftd#toggle".to_string()]
+        );
+
+        // a component named after a kernel is not the kernel
+        let features = super::Features::of(&[fastn_js::component0(
+            "fastn_dom.ElementKind.Code",
+            vec![kernel(fastn_js::ElementKind::Text)],
+        )]);
+        assert!(!features.code);
+    }
+}
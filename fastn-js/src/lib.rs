@@ -8,6 +8,7 @@ mod conditional_component;
 mod constants;
 mod device;
 mod event;
+mod features;
 mod loop_component;
 mod mutable_variable;
 mod property;
@@ -29,13 +30,14 @@ pub use conditional_component::ConditionalComponent;
 pub use constants::*;
 pub use device::{DeviceBlock, DeviceType};
 pub use event::{Event, EventHandler, Function, FunctionData};
+pub use features::Features;
 pub use loop_component::ForLoop;
 pub use mutable_variable::{mutable_integer, mutable_string, MutableList, MutableVariable};
 pub use property::{
     ConditionalValue, Formula, FormulaType, PropertyKind, SetProperty, SetPropertyValue, Value,
 };
 pub use record::RecordInstance;
-pub use ssr::{ssr, ssr_str, ssr_with_js_string};
+pub use ssr::{ssr, ssr_str, ssr_with_js_string, SsrError, SSR_TIMEOUT};
 pub use static_variable::{static_integer, static_string, StaticVariable};
 pub use to_js::to_js;
 pub use udf::{udf0, udf1, udf2, udf_with_arguments, UDF};
@@ -47,9 +49,8 @@ pub fn all_js_without_test_and_ftd_langugage_js() -> String {
     let utils_js = include_str!("../js/utils.js");
     let virtual_js = include_str!("../js/virtual.js");
     let ftd_js = include_str!("../js/ftd.js");
-    let web_component_js = include_str!("../js/web-component.js");
     let post_init_js = include_str!("../js/postInit.js");
-    format!("{fastn_js}{dom_js}{utils_js}{virtual_js}{ftd_js}{web_component_js}{post_init_js}")
+    format!("{fastn_js}{dom_js}{utils_js}{virtual_js}{ftd_js}{post_init_js}")
 }
 
 pub fn all_js_without_test() -> String {
@@ -58,8 +59,21 @@ pub fn all_js_without_test() -> String {
     format!("{ftd_language_js}{fastn_js}\nwindow.ftd = ftd;\n")
 }
 
+/// `rive_js()` is the support for `ftd.rive`, only pages that use it load it, see `Features`.
+pub fn rive_js() -> &'static str {
+    include_str!("../js/rive.js")
+}
+
+/// `web_component_js()` is the support for web components, only pages that use one load it, see
+/// `Features`.
+pub fn web_component_js() -> &'static str {
+    include_str!("../js/web-component.js")
+}
+
 pub fn all_js_with_test() -> String {
     let test_js = include_str!("../js/test.js");
     let all_js = all_js_without_test_and_ftd_langugage_js();
-    format!("{all_js}{test_js}")
+    let rive_js = rive_js();
+    let web_component_js = web_component_js();
+    format!("{all_js}{rive_js}{web_component_js}{test_js}")
 }
@@ -1,10 +1,10 @@
 fn main() {
     let start = std::time::Instant::now();
-    println!("{}", fastn_js::ssr_str(js()));
+    println!("{}", fastn_js::ssr_str(js()).unwrap());
     println!("elapsed: {:?}", start.elapsed());
 
     let start = std::time::Instant::now();
-    println!("{}", fastn_js::ssr(&js_constructor()));
+    println!("{}", fastn_js::ssr(&js_constructor()).unwrap());
     println!("elapsed: {:?}", start.elapsed());
 }
 
@@ -17,7 +17,7 @@ fn js() -> &'static str {
             i.done();
         }
 
-        fastn_virtual.ssr(main)
+        return fastn_virtual.ssr(main)
     "#
 }
 
@@ -396,7 +396,16 @@ pub enum PropertyKind {
     Multiline,
     TextInputType,
     InputMaxLength,
+    InputMinLength,
+    InputPattern,
+    InputName,
+    InputValue,
+    Required,
     DefaultTextInputValue,
+    SelectOptions,
+    SelectValue,
+    FormAction,
+    FormMethod,
     Loading,
     Alt,
     Src,
@@ -520,6 +529,15 @@ impl PropertyKind {
             PropertyKind::Multiline => "fastn_dom.PropertyKind.Multiline",
             PropertyKind::TextInputType => "fastn_dom.PropertyKind.TextInputType",
             PropertyKind::InputMaxLength => "fastn_dom.PropertyKind.InputMaxLength",
+            PropertyKind::InputMinLength => "fastn_dom.PropertyKind.InputMinLength",
+            PropertyKind::InputPattern => "fastn_dom.PropertyKind.InputPattern",
+            PropertyKind::InputName => "fastn_dom.PropertyKind.InputName",
+            PropertyKind::InputValue => "fastn_dom.PropertyKind.InputValue",
+            PropertyKind::Required => "fastn_dom.PropertyKind.Required",
+            PropertyKind::SelectOptions => "fastn_dom.PropertyKind.SelectOptions",
+            PropertyKind::SelectValue => "fastn_dom.PropertyKind.SelectValue",
+            PropertyKind::FormAction => "fastn_dom.PropertyKind.FormAction",
+            PropertyKind::FormMethod => "fastn_dom.PropertyKind.FormMethod",
             PropertyKind::DefaultTextInputValue => "fastn_dom.PropertyKind.DefaultTextInputValue",
             PropertyKind::Loading => "fastn_dom.PropertyKind.Loading",
             PropertyKind::Src => "fastn_dom.PropertyKind.Src",
@@ -1,47 +1,284 @@
-pub fn ssr_str(js: &str) -> String {
-    let all_js = fastn_js::all_js_with_test();
-    let js = format!("{all_js}{js}");
-
-    #[cfg(target_os = "windows")]
-    {
-        rquickjs::Context::full(&rquickjs::Runtime::new().unwrap())
-            .unwrap()
-            .with(|ctx| ctx.eval::<String, _>(js).unwrap())
-    }
-    #[cfg(not(target_os = "windows"))]
-    {
-        // Added logging support from console from within context
-        let context = quick_js::Context::builder()
-            .console(
-                |level: quick_js::console::Level, args: Vec<quick_js::JsValue>| {
-                    eprintln!("{}: {:?}", level, args);
-                },
-            )
-            .build()
-            .unwrap();
-        context.eval_as::<String>(js.as_str()).unwrap()
-    }
-}
-
-pub fn ssr(ast: &[fastn_js::Ast]) -> String {
+//! Server side rendering runs the generated js in QuickJS. Creating a context and evaluating the
+//! runtime (`fastn_js::all_js_with_test()`) is most of the cost of rendering a page, so a pool of
+//! worker threads keeps a context each, with the runtime already loaded, and renders pages one at
+//! a time.
+//!
+//! Every render is evaluated as the body of a function, so the `let`s and `function`s of a page
+//! are local to it, the runtime must be passed them, like `inherited`, and never look them up as
+//! globals. `RESET_JS` first resets the state the runtime keeps between renders. A
+//! render that runs past `SSR_TIMEOUT` is interrupted, and the worker goes on with the next one.
+
+/// how long a render may take before `SsrError::Timeout`
+pub const SSR_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// the state of `dom.js`, `virtual.js` and `rive.js` a render changes, reset before every render
+const RESET_JS: &str = r#"
+id_counter = 0;
+hydrating = false;
+ssr = false;
+fastn_dom.codeData = { availableThemes: {}, addedCssFile: [] };
+fastn_dom.externalCss = new Set();
+fastn_dom.externalJs = new Set();
+fastn_dom.webComponent = [];
+fastn_dom.classes = {};
+fastn_dom.unsanitised_classes = {};
+fastn_dom.class_count = 0;
+ftd.riveNodes = {};
+globalThis.__fastn_package_name__ = undefined;
+"#;
+
+/// QuickJS has no console, the messages are collected and printed after every render
+const CONSOLE_JS: &str = r#"
+globalThis.__fastn_console__ = [];
+globalThis.console = {};
+for (const level of ["log", "info", "warn", "error", "debug"]) {
+    console[level] = function (...args) {
+        __fastn_console__.push(level + ": " + args.map(String).join(" "));
+    };
+}
+"#;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SsrError {
+    /// the js threw, or did not return a string
+    Exception(String),
+    /// the render took longer than `SSR_TIMEOUT`
+    Timeout(std::time::Duration),
+    /// no worker could render the page
+    Unavailable(String),
+}
+
+impl std::fmt::Display for SsrError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SsrError::Exception(e) => write!(f, "js exception: {}", e),
+            SsrError::Timeout(t) => write!(f, "render did not finish in {:?}", t),
+            SsrError::Unavailable(e) => write!(f, "ssr worker unavailable: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for SsrError {}
+
+struct Job {
+    js: String,
+    result: std::sync::mpsc::Sender<Result<String, SsrError>>,
+}
+
+struct Pool {
+    jobs: std::sync::Mutex<std::sync::mpsc::Sender<Job>>,
+}
+
+static POOL: std::sync::OnceLock<Pool> = std::sync::OnceLock::new();
+
+impl Pool {
+    fn get() -> &'static Pool {
+        POOL.get_or_init(|| {
+            let (jobs, queue) = std::sync::mpsc::channel::<Job>();
+            let queue = std::sync::Arc::new(std::sync::Mutex::new(queue));
+            let size = std::thread::available_parallelism()
+                .map(|v| v.get())
+                .unwrap_or(1);
+            for _ in 0..size {
+                let queue = queue.clone();
+                std::thread::spawn(move || {
+                    let engine = Engine::new();
+                    loop {
+                        let job = match queue.lock() {
+                            Ok(queue) => match queue.recv() {
+                                Ok(job) => job,
+                                Err(_) => return,
+                            },
+                            Err(_) => return,
+                        };
+                        let result = match engine {
+                            Ok(ref engine) => engine.render(job.js.as_str(), SSR_TIMEOUT),
+                            Err(ref e) => Err(e.clone()),
+                        };
+                        // the caller may have gone away, the worker goes on regardless
+                        job.result.send(result).ok();
+                    }
+                });
+            }
+            Pool {
+                jobs: std::sync::Mutex::new(jobs),
+            }
+        })
+    }
+
+    fn render(&self, js: String) -> Result<String, SsrError> {
+        let (result, rx) = std::sync::mpsc::channel();
+        self.jobs
+            .lock()
+            .map_err(|e| SsrError::Unavailable(e.to_string()))?
+            .send(Job { js, result })
+            .map_err(|e| SsrError::Unavailable(e.to_string()))?;
+        // the render itself is bounded by `SSR_TIMEOUT`, the worker interrupts it
+        rx.recv()
+            .map_err(|e| SsrError::Unavailable(e.to_string()))?
+    }
+}
+
+/// `Engine` is a QuickJS context with the runtime loaded.
+struct Engine {
+    // the runtime has to outlive the context
+    _runtime: rquickjs::Runtime,
+    context: rquickjs::Context,
+    /// the interrupt handler stops the js running once this passes
+    deadline: std::sync::Arc<std::sync::Mutex<Option<std::time::Instant>>>,
+}
+
+impl Engine {
+    fn new() -> Result<Engine, SsrError> {
+        let all_js = fastn_js::all_js_with_test();
+
+        let runtime = rquickjs::Runtime::new().map_err(|e| SsrError::Unavailable(e.to_string()))?;
+        let deadline: std::sync::Arc<std::sync::Mutex<Option<std::time::Instant>>> =
+            Default::default();
+        let handler_deadline = deadline.clone();
+        runtime.set_interrupt_handler(Some(Box::new(move || match handler_deadline.lock() {
+            Ok(deadline) => deadline.map_or(false, |d| std::time::Instant::now() >= d),
+            Err(_) => false,
+        })));
+        let context =
+            rquickjs::Context::full(&runtime).map_err(|e| SsrError::Unavailable(e.to_string()))?;
+        context
+            .with(|ctx| ctx.eval::<(), _>(format!("{CONSOLE_JS}\n{all_js}")))
+            .map_err(|e| SsrError::Exception(e.to_string()))?;
+        Ok(Engine {
+            _runtime: runtime,
+            context,
+            deadline,
+        })
+    }
+
+    fn set_deadline(&self, deadline: Option<std::time::Instant>) {
+        if let Ok(mut d) = self.deadline.lock() {
+            *d = deadline;
+        }
+    }
+
+    fn render(&self, js: &str, timeout: std::time::Duration) -> Result<String, SsrError> {
+        let js = format!("(function () {{\n{RESET_JS}\n{js}\n}})()");
+
+        let start = std::time::Instant::now();
+        self.set_deadline(Some(start + timeout));
+        let result = self.context.with(|ctx| ctx.eval::<String, _>(js));
+        self.set_deadline(None);
+
+        if let Ok(messages) = self
+            .context
+            .with(|ctx| ctx.eval::<String, _>("__fastn_console__.splice(0).join('\\n')"))
+        {
+            if !messages.is_empty() {
+                eprintln!("{}", messages);
+            }
+        }
+
+        result.map_err(|e| {
+            if start.elapsed() >= timeout {
+                SsrError::Timeout(timeout)
+            } else {
+                SsrError::Exception(e.to_string())
+            }
+        })
+    }
+}
+
+/// `ssr_str()` renders `js`, the body of a function that returns the html, on a pooled context.
+pub fn ssr_str(js: &str) -> Result<String, SsrError> {
+    Pool::get().render(js.to_string())
+}
+
+pub fn ssr(ast: &[fastn_js::Ast]) -> Result<String, SsrError> {
     let js = format!("{}\nlet main_wrapper = function (parent) {{
             let parenti0 = fastn_dom.createKernel(parent, fastn_dom.ElementKind.Column);
             parenti0.setProperty(fastn_dom.PropertyKind.Width, fastn_dom.Resizing.FillContainer, inherited);
             parenti0.setProperty(fastn_dom.PropertyKind.Height, fastn_dom.Resizing.FillContainer, inherited);
             main(parenti0);
         }};
-        fastn_virtual.ssr(main_wrapper);", fastn_js::to_js(ast, false, "foo"));
+        return fastn_virtual.ssr(main_wrapper);", fastn_js::to_js(ast, false, "foo"));
     ssr_str(&js)
 }
 
-pub fn ssr_with_js_string(package_name: &str, js: &str) -> String {
-    let js = format!("let __fastn_package_name__ = \"{}\";\n{}\nlet main_wrapper = function (parent) {{
+pub fn ssr_with_js_string(package_name: &str, js: &str) -> Result<String, SsrError> {
+    let js = format!("__fastn_package_name__ = \"{}\";\n{}\nlet main_wrapper = function (parent) {{
             let parenti0 = fastn_dom.createKernel(parent, fastn_dom.ElementKind.Column);
             parenti0.setProperty(fastn_dom.PropertyKind.Width, fastn_dom.Resizing.FillContainer, inherited);
             parenti0.setProperty(fastn_dom.PropertyKind.Height, fastn_dom.Resizing.FillContainer, inherited);
             main(parenti0);
         }};
-        fastn_virtual.ssr(main_wrapper);", package_name, js);
+        return fastn_virtual.ssr(main_wrapper);", package_name, js);
 
     ssr_str(&js)
 }
+
+#[cfg(test)]
+mod tests {
+    const PAGE_A: &str = r#"
+let inherited = fastn.recordInstance({});
+let main = function (parent) {
+  for (let i = 1; i <= 5; i++) {
+    let t = fastn_dom.createKernel(parent, fastn_dom.ElementKind.Text);
+    t.setProperty(fastn_dom.PropertyKind.StringValue, "Hello " + i, inherited);
+    t.setProperty(fastn_dom.PropertyKind.Width, fastn_dom.Resizing.Fixed(fastn_dom.Length.Px(i * 10)), inherited);
+  }
+};
+return fastn_virtual.ssr(main);
+"#;
+
+    const PAGE_B: &str = r#"
+let inherited = fastn.recordInstance({});
+let main = function (parent) {
+  let t = fastn_dom.createKernel(parent, fastn_dom.ElementKind.Text);
+  t.setProperty(fastn_dom.PropertyKind.StringValue, "Hello", inherited);
+  t.setProperty(fastn_dom.PropertyKind.Width, fastn_dom.Resizing.Fixed(fastn_dom.Length.Px(25)), inherited);
+};
+return fastn_virtual.ssr(main);
+"#;
+
+    const PAGE_LINK_COLOR: &str = r#"
+ftd.dark_mode = fastn.mutable(false);
+let inherited = fastn.recordInstance({});
+let main = function (parent) {
+  let t = fastn_dom.createKernel(parent, fastn_dom.ElementKind.Text);
+  t.setProperty(fastn_dom.PropertyKind.StringValue, "Click me", inherited);
+  t.setProperty(fastn_dom.PropertyKind.Link, "https://fastn.com", inherited);
+  t.setProperty(fastn_dom.PropertyKind.LinkColor, fastn.recordInstance({light: "red", dark: "blue"}), inherited);
+};
+return fastn_virtual.ssr(main);
+"#;
+
+    fn render(engine: &super::Engine, js: &str) -> String {
+        engine.render(js, super::SSR_TIMEOUT).unwrap()
+    }
+
+    #[test]
+    fn renders_do_not_leak_into_each_other() {
+        let fresh = render(&super::Engine::new().unwrap(), PAGE_B);
+
+        let engine = super::Engine::new().unwrap();
+        render(&engine, PAGE_A);
+        assert_eq!(render(&engine, PAGE_B), fresh);
+        assert_eq!(render(&engine, PAGE_B), fresh);
+    }
+
+    #[test]
+    fn link_color_is_rendered_with_the_page_inherited() {
+        let html = render(&super::Engine::new().unwrap(), PAGE_LINK_COLOR);
+        assert!(html.contains(r#"href="https://fastn.com">Click me</a>"#));
+    }
+
+    #[test]
+    fn stuck_render_is_interrupted_and_engine_reused() {
+        let fresh = render(&super::Engine::new().unwrap(), PAGE_B);
+
+        let engine = super::Engine::new().unwrap();
+        let timeout = std::time::Duration::from_millis(200);
+        assert_eq!(
+            engine.render("while (true) {}", timeout),
+            Err(super::SsrError::Timeout(timeout))
+        );
+        assert_eq!(render(&engine, PAGE_B), fresh);
+    }
+}
@@ -125,6 +125,7 @@ impl fastn_js::Event {
             fastn_js::Event::Change => text("fastn_dom.Event.Change"),
             fastn_js::Event::Blur => text("fastn_dom.Event.Blur"),
             fastn_js::Event::Focus => text("fastn_dom.Event.Focus"),
+            fastn_js::Event::Submit => text("fastn_dom.Event.Submit"),
         }
     }
 }
@@ -187,6 +188,9 @@ impl fastn_js::ElementKind {
             fastn_js::ElementKind::Device => "fastn_dom.ElementKind.Wrapper".to_string(),
             fastn_js::ElementKind::CheckBox => "fastn_dom.ElementKind.CheckBox".to_string(),
             fastn_js::ElementKind::TextInput => "fastn_dom.ElementKind.TextInput".to_string(),
+            fastn_js::ElementKind::Form => "fastn_dom.ElementKind.Form".to_string(),
+            fastn_js::ElementKind::Select => "fastn_dom.ElementKind.Select".to_string(),
+            fastn_js::ElementKind::Radio => "fastn_dom.ElementKind.Radio".to_string(),
             fastn_js::ElementKind::Rive => "fastn_dom.ElementKind.Rive".to_string(),
             fastn_js::ElementKind::Document => "fastn_dom.ElementKind.Document".to_string(),
             fastn_js::ElementKind::Code => "fastn_dom.ElementKind.Code".to_string(),
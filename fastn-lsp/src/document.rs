@@ -0,0 +1,421 @@
+/// `Document` is an ftd document open in the editor. It is parsed with error recovery, so the
+/// sections that are fine can be used while the rest of the document is being edited.
+pub(crate) struct Document {
+    /// the name the interpreter knows the document by, errors are reported for this name
+    pub(crate) name: String,
+    pub(crate) source: String,
+    pub(crate) sections: Vec<ftd::p1::Section>,
+    pub(crate) parsed: ftd::interpreter::ParsedDocument,
+    pub(crate) errors: Vec<ftd::interpreter::Error>,
+    /// the document interpreted with its imports, without the sections in `errors`
+    pub(crate) interpreted: Option<ftd::interpreter::Document>,
+    pub(crate) interpreter_error: Option<fastn_core::Error>,
+}
+
+impl Document {
+    pub(crate) fn parse(name: &str, source: &str) -> Document {
+        let (sections, _) = ftd::p1::parse_with_recovery(source, name);
+        let (parsed, errors) = ftd::interpreter::ParsedDocument::parse_with_recovery(name, source);
+        Document {
+            name: name.to_string(),
+            source: source.to_string(),
+            sections,
+            parsed,
+            errors,
+            interpreted: None,
+            interpreter_error: None,
+        }
+    }
+
+    pub(crate) fn diagnostics(&self) -> Vec<lsp_types::Diagnostic> {
+        let mut diagnostics = self
+            .errors
+            .iter()
+            .map(|e| match e.diagnostic(self.source.as_str()) {
+                Some(d) => diagnostic(d),
+                None => error_at_start(e.to_string()),
+            })
+            .collect::<Vec<_>>();
+
+        if let Some(ref e) = self.interpreter_error {
+            let here = e
+                .doc_id()
+                .map(|id| id.trim_matches('/').eq(self.name.trim_matches('/')))
+                .unwrap_or(false);
+            diagnostics.push(match e.diagnostic(self.source.as_str()) {
+                Some(d) if here => diagnostic(d),
+                // the error is in an imported module, it is shown on the first line
+                _ => error_at_start(match e.doc_id() {
+                    Some(id) => format!("{}: {}", id, e),
+                    None => e.to_string(),
+                }),
+            });
+        }
+        diagnostics
+    }
+
+    /// `resolve()` returns the module and the name of the thing `name` refers to in this
+    /// document, `ds.page` is `page` of the module imported as `ds`.
+    pub(crate) fn resolve(&self, name: &str) -> (String, String) {
+        if let Some(module) = self.parsed.exposings.get(name) {
+            return (module.to_string(), name.to_string());
+        }
+        let full_name = ftd::interpreter::utils::resolve_name(
+            name,
+            self.name.as_str(),
+            &self.parsed.doc_aliases,
+        );
+        match full_name.split_once('#') {
+            // `person.name` is the `name` field of the `person` variable
+            Some((module, thing)) => (
+                module.to_string(),
+                thing.split('.').next().unwrap_or(thing).to_string(),
+            ),
+            None => (self.name.to_string(), full_name),
+        }
+    }
+
+    /// `hover()` describes the thing `name` refers to, using the interpreted document.
+    pub(crate) fn hover(&self, name: &str, line_number: usize) -> Option<String> {
+        let interpreted = self.interpreted.as_ref()?;
+        let thing = interpreted.tdoc().get_thing(name, line_number).ok()?;
+        describe(&thing)
+    }
+
+    /// `arguments()` returns the arguments of the component `name`, using the interpreted
+    /// document.
+    pub(crate) fn arguments(&self, name: &str, line_number: usize) -> Option<Vec<Argument>> {
+        let interpreted = self.interpreted.as_ref()?;
+        match interpreted.tdoc().get_thing(name, line_number).ok()? {
+            ftd::interpreter::Thing::Component(c) => {
+                Some(c.arguments.iter().map(Argument::from_field).collect())
+            }
+            ftd::interpreter::Thing::WebComponent(c) => {
+                Some(c.arguments.iter().map(Argument::from_field).collect())
+            }
+            _ => None,
+        }
+    }
+
+    /// `components()` returns the names components can be invoked with in this document.
+    pub(crate) fn components(&self) -> Vec<String> {
+        let mut names = definitions(&self.sections)
+            .filter(|s| s.kind.as_deref().eq(&Some("component")))
+            .map(|s| s.name.to_string())
+            .collect::<Vec<_>>();
+
+        if let Some(ref interpreted) = self.interpreted {
+            for (full_name, thing) in interpreted.data.iter() {
+                if !matches!(
+                    thing,
+                    ftd::interpreter::Thing::Component(_)
+                        | ftd::interpreter::Thing::WebComponent(_)
+                ) {
+                    continue;
+                }
+                if let Some(name) = self.local_name(full_name) {
+                    if !names.contains(&name) {
+                        names.push(name);
+                    }
+                }
+            }
+        }
+        names
+    }
+
+    // `foo#bar` is `bar` in `foo` itself, and `f.bar` where `foo` is imported as `f`
+    fn local_name(&self, full_name: &str) -> Option<String> {
+        let (module, name) = full_name.split_once('#')?;
+        if module.trim_matches('/').eq(self.name.trim_matches('/')) {
+            return Some(name.to_string());
+        }
+        self.parsed
+            .doc_aliases
+            .iter()
+            .find(|(_, m)| m.as_str().eq(module))
+            .map(|(alias, _)| format!("{}.{}", alias, name))
+    }
+
+    /// `section_at()` returns the innermost section that `line_number` is part of, the line
+    /// can be past the span of the section, when a header is being added to it.
+    pub(crate) fn section_at(&self, line_number: usize) -> Option<&ftd::p1::Section> {
+        fn find(sections: &[ftd::p1::Section], line_number: usize) -> Option<&ftd::p1::Section> {
+            let section = sections
+                .iter()
+                .rev()
+                .find(|s| s.span.start.line <= line_number)?;
+            find(&section.sub_sections, line_number).or(Some(section))
+        }
+        find(&self.sections, line_number)
+    }
+
+    pub(crate) fn symbols(&self) -> Vec<lsp_types::DocumentSymbol> {
+        symbols(&self.sections)
+    }
+}
+
+/// `find_definition()` returns the section defining `name`: a component, record, or-type,
+/// function or variable.
+pub(crate) fn find_definition<'a>(
+    sections: &'a [ftd::p1::Section],
+    name: &str,
+) -> Option<&'a ftd::p1::Section> {
+    definitions(sections).find(|s| definition_name(s).eq(name))
+}
+
+fn definitions(sections: &[ftd::p1::Section]) -> impl Iterator<Item = &ftd::p1::Section> {
+    sections
+        .iter()
+        .filter(|s| !s.is_commented && s.kind.is_some())
+}
+
+// functions are defined as `-- integer sum(a, b):`
+fn definition_name(section: &ftd::p1::Section) -> &str {
+    section
+        .name
+        .split_once('(')
+        .map(|(name, _)| name)
+        .unwrap_or(section.name.as_str())
+        .trim()
+}
+
+/// `Argument` is an argument of a component, as shown in completions.
+pub(crate) struct Argument {
+    pub(crate) name: String,
+    pub(crate) kind: String,
+}
+
+impl Argument {
+    fn from_field(field: &ftd::interpreter::Field) -> Argument {
+        Argument {
+            name: field.name.to_string(),
+            kind: kind_data(&field.kind),
+        }
+    }
+
+    /// `from_definition()` reads the arguments of a `-- component` section, for when the
+    /// document can not be interpreted.
+    pub(crate) fn from_definition(section: &ftd::p1::Section) -> Vec<Argument> {
+        section
+            .headers
+            .0
+            .iter()
+            .filter_map(|h| {
+                Some(Argument {
+                    kind: h.get_kind()?,
+                    name: h.get_key(),
+                })
+            })
+            .collect()
+    }
+}
+
+fn describe(thing: &ftd::interpreter::Thing) -> Option<String> {
+    let lines = match thing {
+        ftd::interpreter::Thing::Variable(v) => vec![format!(
+            "-- {} {}{}:",
+            kind_data(&v.kind),
+            if v.mutable { "$" } else { "" },
+            short_name(v.name.as_str())
+        )],
+        ftd::interpreter::Thing::Component(c) => {
+            std::iter::once(format!("-- component {}:", short_name(c.name.as_str())))
+                .chain(c.arguments.iter().map(field))
+                .collect()
+        }
+        ftd::interpreter::Thing::WebComponent(c) => {
+            std::iter::once(format!("-- web-component {}:", short_name(c.name.as_str())))
+                .chain(c.arguments.iter().map(field))
+                .collect()
+        }
+        ftd::interpreter::Thing::Record(r) => {
+            std::iter::once(format!("-- record {}:", short_name(r.name.as_str())))
+                .chain(r.fields.iter().map(field))
+                .collect()
+        }
+        ftd::interpreter::Thing::OrType(o) => {
+            std::iter::once(format!("-- or-type {}:", short_name(o.name.as_str())))
+                .chain(o.variants.iter().map(|v| format!("-- {}", v.name())))
+                .collect()
+        }
+        ftd::interpreter::Thing::Function(f) => vec![format!(
+            "-- {} {}({}):",
+            kind_data(&f.return_kind),
+            short_name(f.name.as_str()),
+            f.arguments
+                .iter()
+                .map(|a| a.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )],
+        _ => return None,
+    };
+    Some(format!("```ftd\n{}\n```", lines.join("\n")))
+}
+
+fn field(field: &ftd::interpreter::Field) -> String {
+    format!(
+        "{} {}{}:",
+        kind_data(&field.kind),
+        if field.mutable { "$" } else { "" },
+        field.name
+    )
+}
+
+fn short_name(name: &str) -> &str {
+    name.rsplit_once('#').map(|(_, v)| v).unwrap_or(name)
+}
+
+/// `kind_data()` shows a kind the way it is written in ftd, like `optional string` or
+/// `caption or body`.
+fn kind_data(kind: &ftd::interpreter::KindData) -> String {
+    let name = self::kind(&kind.kind);
+    match (kind.caption, kind.body) {
+        (true, true) if kind.kind.is_string() => "caption or body".to_string(),
+        (true, true) => format!("{} caption or body", name),
+        (true, false) => format!("caption {}", name),
+        (false, true) => format!("body {}", name),
+        (false, false) => name,
+    }
+}
+
+fn kind(kind: &ftd::interpreter::Kind) -> String {
+    match kind {
+        ftd::interpreter::Kind::List { kind } => format!("{} list", self::kind(kind)),
+        ftd::interpreter::Kind::Map { kind } => format!("{} map", self::kind(kind)),
+        ftd::interpreter::Kind::Optional { kind } => format!("optional {}", self::kind(kind)),
+        ftd::interpreter::Kind::Constant { kind } => format!("constant {}", self::kind(kind)),
+        ftd::interpreter::Kind::UI { .. } => "ftd.ui".to_string(),
+        ftd::interpreter::Kind::Record { name } => short_record_name(name.as_str()),
+        ftd::interpreter::Kind::OrType { name, .. } => short_name(name.as_str()).to_string(),
+        k => k.get_name(),
+    }
+}
+
+/// `short_record_name()` also drops the documents from the type arguments of an instantiated
+/// generic record, so `foo#card<foo#person>` is shown as `card<person>`.
+fn short_record_name(name: &str) -> String {
+    let mut short = String::new();
+    let mut word = String::new();
+    for c in name.chars() {
+        if matches!(c, '<' | '>' | ',' | ' ') {
+            short.push_str(short_name(word.as_str()));
+            short.push(c);
+            word.clear();
+        } else {
+            word.push(c);
+        }
+    }
+    short.push_str(short_name(word.as_str()));
+    short
+}
+
+fn symbols(sections: &[ftd::p1::Section]) -> Vec<lsp_types::DocumentSymbol> {
+    sections
+        .iter()
+        .filter(|s| !s.is_commented)
+        .map(|s| {
+            let (name, kind) = match s.kind.as_deref() {
+                _ if s.name.eq("import") => (
+                    s.caption
+                        .as_ref()
+                        .and_then(|c| match c {
+                            ftd::p1::Header::KV(kv) => kv.value.clone(),
+                            _ => None,
+                        })
+                        .unwrap_or_else(|| s.name.to_string()),
+                    lsp_types::SymbolKind::MODULE,
+                ),
+                Some("component") | Some("web-component") => {
+                    (s.name.to_string(), lsp_types::SymbolKind::CLASS)
+                }
+                Some("record") => (s.name.to_string(), lsp_types::SymbolKind::STRUCT),
+                Some("or-type") => (s.name.to_string(), lsp_types::SymbolKind::ENUM),
+                Some(_) if s.name.contains('(') => (
+                    definition_name(s).to_string(),
+                    lsp_types::SymbolKind::FUNCTION,
+                ),
+                Some(_) => (s.name.to_string(), lsp_types::SymbolKind::VARIABLE),
+                None => (s.name.to_string(), lsp_types::SymbolKind::OBJECT),
+            };
+            let children = symbols(&s.sub_sections);
+            #[allow(deprecated)]
+            lsp_types::DocumentSymbol {
+                name,
+                detail: s.kind.clone(),
+                kind,
+                tags: None,
+                deprecated: None,
+                range: range(s.span),
+                selection_range: range(ftd::p1::Span::new(
+                    s.span.start,
+                    ftd::p1::Position::new(s.span.start.line, s.span.start.column + 1),
+                )),
+                children: if children.is_empty() {
+                    None
+                } else {
+                    Some(children)
+                },
+            }
+        })
+        .collect()
+}
+
+/// `word_at()` returns the name at `position`, like `ds.page` or `$person.name` without the `$`.
+pub(crate) fn word_at(source: &str, position: lsp_types::Position) -> Option<String> {
+    let line = source.split('\n').nth(position.line as usize)?;
+    let chars = line.chars().collect::<Vec<_>>();
+    let is_name = |c: &char| c.is_alphanumeric() || matches!(c, '-' | '_' | '.' | '#' | '$');
+    let at = (position.character as usize).min(chars.len());
+    let start = chars[..at]
+        .iter()
+        .rposition(|c| !is_name(c))
+        .map(|i| i + 1)
+        .unwrap_or(0);
+    let end = chars[at..]
+        .iter()
+        .position(|c| !is_name(c))
+        .map(|i| at + i)
+        .unwrap_or(chars.len());
+    let word = chars[start..end].iter().collect::<String>();
+    let word = word.trim_start_matches('$').trim_end_matches('.');
+    if word.is_empty() || word.chars().all(|c| c.eq(&'-')) {
+        return None;
+    }
+    Some(word.to_string())
+}
+
+/// `range()` converts a span, whose lines and columns start from 1, to an lsp range, whose
+/// lines and characters start from 0.
+pub(crate) fn range(span: ftd::p1::Span) -> lsp_types::Range {
+    let position = |p: ftd::p1::Position| {
+        lsp_types::Position::new(
+            p.line.saturating_sub(1) as u32,
+            p.column.saturating_sub(1) as u32,
+        )
+    };
+    lsp_types::Range::new(position(span.start), position(span.end))
+}
+
+fn diagnostic(d: ftd::Diagnostic) -> lsp_types::Diagnostic {
+    lsp_types::Diagnostic {
+        range: range(d.span),
+        severity: Some(lsp_types::DiagnosticSeverity::ERROR),
+        source: Some("fastn".to_string()),
+        message: match d.help {
+            Some(help) => format!("{}\nhelp: {}", d.message, help),
+            None => d.message,
+        },
+        ..Default::default()
+    }
+}
+
+fn error_at_start(message: String) -> lsp_types::Diagnostic {
+    lsp_types::Diagnostic {
+        range: lsp_types::Range::default(),
+        severity: Some(lsp_types::DiagnosticSeverity::ERROR),
+        source: Some("fastn".to_string()),
+        message,
+        ..Default::default()
+    }
+}
@@ -0,0 +1,34 @@
+//! `fastn-lsp` is a language server for `.ftd` files. It talks the language server protocol
+//! over stdin/stdout, and offers diagnostics, go-to-definition, hover, completion of component
+//! arguments and document symbols.
+
+extern crate self as fastn_lsp;
+
+mod document;
+mod package;
+mod server;
+
+#[cfg(test)]
+mod test;
+
+pub use server::run;
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("ProtocolError: {}", _0)]
+    ProtocolError(#[from] lsp_server::ProtocolError),
+
+    #[error("SerdeJsonError: {}", _0)]
+    SerdeJsonError(#[from] serde_json::Error),
+
+    #[error("IoError: {}", _0)]
+    IoError(#[from] std::io::Error),
+
+    #[error("FastnCoreError: {}", _0)]
+    FastnCoreError(#[from] fastn_core::Error),
+
+    #[error("the client closed the connection")]
+    Disconnected,
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
@@ -0,0 +1,6 @@
+fn main() {
+    if let Err(e) = fastn_lsp::run() {
+        eprintln!("{:?}", e);
+        std::process::exit(1);
+    }
+}
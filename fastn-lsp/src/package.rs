@@ -0,0 +1,70 @@
+/// `Package` is the fastn package the edited documents belong to. It is read with
+/// `fastn_core::Config::read()`, the way `fastn serve` reads it, so imports and
+/// `fastn.dependency` packages resolve to the same files they resolve to when the package is
+/// served.
+pub(crate) struct Package {
+    config: fastn_core::Config,
+    // fastn_core is async, the language server is not
+    runtime: tokio::runtime::Runtime,
+}
+
+impl Package {
+    pub(crate) fn read(root: &std::path::Path) -> fastn_lsp::Result<Package> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+        let config = runtime.block_on(fastn_core::Config::read(
+            Some(root.to_string_lossy().to_string()),
+            false,
+            None,
+        ))?;
+        Ok(Package { config, runtime })
+    }
+
+    /// `document_id()` returns the id of the document at `path`, like `foo/index.ftd`, if the
+    /// document is in this package.
+    pub(crate) fn document_id(&self, path: &std::path::Path) -> Option<String> {
+        path.strip_prefix(self.config.root.as_std_path())
+            .ok()
+            .map(|p| p.to_string_lossy().replace(std::path::MAIN_SEPARATOR, "/"))
+    }
+
+    /// `document_name()` is the name the interpreter knows the document `id` by, the same as
+    /// `fastn_core::Document::id_with_package()`.
+    pub(crate) fn document_name(&self, id: &str) -> String {
+        format!(
+            "{}/{}",
+            self.config.package.name,
+            id.replace("/index.ftd", "/")
+                .replace("index.ftd", "")
+                .replace(".ftd", "/")
+        )
+    }
+
+    /// `module_path()` returns the file an `-- import:`ed module is read from, in this package
+    /// or in one of its dependencies.
+    pub(crate) fn module_path(&self, module: &str) -> Option<std::path::PathBuf> {
+        let id = format!("-/{}", module.trim_matches('/'));
+        let path = self
+            .runtime
+            .block_on(self.config.get_file_path(id.as_str()))
+            .ok()?;
+        let path = self.config.root.join(path.trim_start_matches('/'));
+        if path.is_file() {
+            Some(path.into_std_path_buf())
+        } else {
+            None
+        }
+    }
+
+    /// `interpret()` interprets the document `id` with its imports resolved, without rendering
+    /// it.
+    pub(crate) fn interpret(
+        &self,
+        id: &str,
+        source: &str,
+    ) -> fastn_core::Result<ftd::interpreter::Document> {
+        self.runtime
+            .block_on(fastn_core::interpret_ftd(&self.config, id, source))
+    }
+}
@@ -0,0 +1,362 @@
+use lsp_types::notification::Notification as _;
+use lsp_types::request::Request as _;
+
+/// `run()` serves the language server protocol over stdin/stdout, until the client shuts the
+/// server down.
+pub fn run() -> fastn_lsp::Result<()> {
+    let (connection, io_threads) = lsp_server::Connection::stdio();
+    let params = connection.initialize(serde_json::to_value(capabilities())?)?;
+    let params: lsp_types::InitializeParams = serde_json::from_value(params)?;
+
+    #[allow(deprecated)]
+    let root = params
+        .workspace_folders
+        .as_ref()
+        .and_then(|f| f.first())
+        .map(|f| f.uri.clone())
+        .or(params.root_uri)
+        .and_then(|uri| uri.to_file_path().ok())
+        .map_or_else(std::env::current_dir, Ok)?;
+
+    let package = match fastn_lsp::package::Package::read(root.as_path()) {
+        Ok(package) => Some(package),
+        Err(e) => {
+            // documents outside a fastn package still get diagnostics and symbols
+            eprintln!("fastn-lsp: could not read the package at {:?}: {}", root, e);
+            None
+        }
+    };
+
+    Server {
+        connection: &connection,
+        package,
+        documents: Default::default(),
+    }
+    .main_loop()?;
+    io_threads.join()?;
+    Ok(())
+}
+
+fn capabilities() -> lsp_types::ServerCapabilities {
+    lsp_types::ServerCapabilities {
+        text_document_sync: Some(lsp_types::TextDocumentSyncCapability::Kind(
+            lsp_types::TextDocumentSyncKind::FULL,
+        )),
+        hover_provider: Some(lsp_types::HoverProviderCapability::Simple(true)),
+        definition_provider: Some(lsp_types::OneOf::Left(true)),
+        completion_provider: Some(lsp_types::CompletionOptions {
+            trigger_characters: Some(vec!["-".to_string(), " ".to_string()]),
+            ..Default::default()
+        }),
+        document_symbol_provider: Some(lsp_types::OneOf::Left(true)),
+        ..Default::default()
+    }
+}
+
+struct Server<'a> {
+    connection: &'a lsp_server::Connection,
+    package: Option<fastn_lsp::package::Package>,
+    documents: std::collections::HashMap<lsp_types::Url, fastn_lsp::document::Document>,
+}
+
+impl Server<'_> {
+    fn main_loop(&mut self) -> fastn_lsp::Result<()> {
+        let connection = self.connection;
+        for message in &connection.receiver {
+            match message {
+                lsp_server::Message::Request(request) => {
+                    if connection.handle_shutdown(&request)? {
+                        return Ok(());
+                    }
+                    self.handle_request(request)?;
+                }
+                lsp_server::Message::Notification(notification) => {
+                    self.handle_notification(notification)?;
+                }
+                lsp_server::Message::Response(_) => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn handle_request(&mut self, request: lsp_server::Request) -> fastn_lsp::Result<()> {
+        let result = match request.method.as_str() {
+            lsp_types::request::GotoDefinition::METHOD => {
+                serde_json::to_value(self.definition(serde_json::from_value(request.params)?))?
+            }
+            lsp_types::request::HoverRequest::METHOD => {
+                serde_json::to_value(self.hover(serde_json::from_value(request.params)?))?
+            }
+            lsp_types::request::Completion::METHOD => {
+                serde_json::to_value(self.completion(serde_json::from_value(request.params)?))?
+            }
+            lsp_types::request::DocumentSymbolRequest::METHOD => serde_json::to_value(
+                self.document_symbols(serde_json::from_value(request.params)?),
+            )?,
+            _ => {
+                return self.send(lsp_server::Response::new_err(
+                    request.id,
+                    lsp_server::ErrorCode::MethodNotFound as i32,
+                    format!("unsupported request: {}", request.method),
+                ));
+            }
+        };
+        self.send(lsp_server::Response {
+            id: request.id,
+            result: Some(result),
+            error: None,
+        })
+    }
+
+    fn handle_notification(
+        &mut self,
+        notification: lsp_server::Notification,
+    ) -> fastn_lsp::Result<()> {
+        match notification.method.as_str() {
+            lsp_types::notification::DidOpenTextDocument::METHOD => {
+                let params: lsp_types::DidOpenTextDocumentParams =
+                    serde_json::from_value(notification.params)?;
+                self.update(params.text_document.uri, params.text_document.text, true)
+            }
+            lsp_types::notification::DidChangeTextDocument::METHOD => {
+                let params: lsp_types::DidChangeTextDocumentParams =
+                    serde_json::from_value(notification.params)?;
+                // the server asks for full document sync, the last change is the whole text
+                match params.content_changes.into_iter().last() {
+                    Some(change) => self.update(params.text_document.uri, change.text, false),
+                    None => Ok(()),
+                }
+            }
+            lsp_types::notification::DidSaveTextDocument::METHOD => {
+                let params: lsp_types::DidSaveTextDocumentParams =
+                    serde_json::from_value(notification.params)?;
+                let uri = params.text_document.uri;
+                match self.documents.get(&uri).map(|d| d.source.to_string()) {
+                    Some(source) => self.update(uri, source, true),
+                    None => Ok(()),
+                }
+            }
+            lsp_types::notification::DidCloseTextDocument::METHOD => {
+                let params: lsp_types::DidCloseTextDocumentParams =
+                    serde_json::from_value(notification.params)?;
+                self.documents.remove(&params.text_document.uri);
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// `update()` parses the new source of a document and publishes its diagnostics. The
+    /// document is interpreted with its imports only when asked, on open and save, since that
+    /// reads the imported modules from disk.
+    fn update(
+        &mut self,
+        uri: lsp_types::Url,
+        source: String,
+        interpret: bool,
+    ) -> fastn_lsp::Result<()> {
+        let id = self.document_id(&uri);
+        let name = match self.package {
+            Some(ref package) => package.document_name(id.as_str()),
+            None => id.to_string(),
+        };
+        let mut document = fastn_lsp::document::Document::parse(name.as_str(), source.as_str());
+
+        let old = self.documents.remove(&uri);
+        if !interpret {
+            // keep what was interpreted last, hover and completion keep working while typing
+            document.interpreted = old.and_then(|d| d.interpreted);
+        }
+        // the sections with errors are left out, the rest of the document is interpreted
+        if interpret {
+            if let Some(ref package) = self.package {
+                match package.interpret(id.as_str(), source.as_str()) {
+                    Ok(interpreted) => document.interpreted = Some(interpreted),
+                    Err(e) => document.interpreter_error = Some(e),
+                }
+            }
+        }
+
+        let diagnostics = document.diagnostics();
+        self.documents.insert(uri.clone(), document);
+        self.notify::<lsp_types::notification::PublishDiagnostics>(
+            lsp_types::PublishDiagnosticsParams {
+                uri,
+                diagnostics,
+                version: None,
+            },
+        )
+    }
+
+    fn definition(
+        &self,
+        params: lsp_types::GotoDefinitionParams,
+    ) -> Option<lsp_types::GotoDefinitionResponse> {
+        let position = params.text_document_position_params;
+        let document = self.documents.get(&position.text_document.uri)?;
+        let word = fastn_lsp::document::word_at(document.source.as_str(), position.position)?;
+        let (uri, section) = self.definition_section(&position.text_document.uri, &word)?;
+        Some(lsp_types::GotoDefinitionResponse::Scalar(
+            lsp_types::Location::new(uri, fastn_lsp::document::range(section.span)),
+        ))
+    }
+
+    /// `definition_section()` finds the section defining `word` as used in the document `uri`,
+    /// in the document itself or in the module it is imported from.
+    fn definition_section(
+        &self,
+        uri: &lsp_types::Url,
+        word: &str,
+    ) -> Option<(lsp_types::Url, ftd::p1::Section)> {
+        let document = self.documents.get(uri)?;
+        let (module, name) = document.resolve(word);
+        if module.trim_matches('/').eq(document.name.trim_matches('/')) {
+            let section = fastn_lsp::document::find_definition(&document.sections, &name)?;
+            return Some((uri.clone(), section.clone()));
+        }
+
+        // `ftd` and `inherited` are built in, they are not read from a file
+        if ftd::interpreter::default::default_aliases()
+            .values()
+            .any(|m| m.eq(&module))
+        {
+            return None;
+        }
+        let path = self.package.as_ref()?.module_path(module.as_str())?;
+        let uri = lsp_types::Url::from_file_path(path.as_path()).ok()?;
+        // the module may be open, with changes not saved yet
+        let sections = match self.documents.get(&uri) {
+            Some(d) => d.sections.clone(),
+            None => {
+                let source = std::fs::read_to_string(path).ok()?;
+                ftd::p1::parse_with_recovery(source.as_str(), module.as_str()).0
+            }
+        };
+        let section = fastn_lsp::document::find_definition(&sections, &name)?;
+        Some((uri, section.clone()))
+    }
+
+    fn hover(&self, params: lsp_types::HoverParams) -> Option<lsp_types::Hover> {
+        let position = params.text_document_position_params;
+        let document = self.documents.get(&position.text_document.uri)?;
+        let word = fastn_lsp::document::word_at(document.source.as_str(), position.position)?;
+        let line_number = position.position.line as usize + 1;
+
+        let value = match document.hover(&word, line_number) {
+            Some(value) => value,
+            None => {
+                // not interpreted yet, the kind is shown as it is written in the definition
+                let (_, section) = self.definition_section(&position.text_document.uri, &word)?;
+                format!(
+                    "```ftd\n-- {} {}:\n```",
+                    section.kind.as_deref()?,
+                    section.name
+                )
+            }
+        };
+        Some(lsp_types::Hover {
+            contents: lsp_types::HoverContents::Markup(lsp_types::MarkupContent {
+                kind: lsp_types::MarkupKind::Markdown,
+                value,
+            }),
+            range: None,
+        })
+    }
+
+    fn completion(
+        &self,
+        params: lsp_types::CompletionParams,
+    ) -> Option<lsp_types::CompletionResponse> {
+        let position = params.text_document_position;
+        let uri = &position.text_document.uri;
+        let document = self.documents.get(uri)?;
+        let line_number = position.position.line as usize + 1;
+        let line = document
+            .source
+            .split('\n')
+            .nth(position.position.line as usize)?;
+
+        // `-- ` starts a section, the components are completed
+        if line.starts_with("-- ") && !line.contains(':') {
+            let items = document
+                .components()
+                .into_iter()
+                .map(|name| lsp_types::CompletionItem {
+                    label: name.to_string(),
+                    kind: Some(lsp_types::CompletionItemKind::CLASS),
+                    insert_text: Some(format!("{}:", name)),
+                    ..Default::default()
+                })
+                .collect();
+            return Some(lsp_types::CompletionResponse::Array(items));
+        }
+
+        // anywhere else in a section, the arguments of the component it invokes are completed
+        let section = document.section_at(line_number)?;
+        if section.kind.is_some() {
+            return None;
+        }
+        let arguments = match document.arguments(&section.name, line_number) {
+            Some(arguments) => arguments,
+            None => {
+                let (_, definition) = self.definition_section(uri, &section.name)?;
+                fastn_lsp::document::Argument::from_definition(&definition)
+            }
+        };
+        let passed = section
+            .headers
+            .0
+            .iter()
+            .map(|h| h.get_key())
+            .collect::<Vec<_>>();
+        let items = arguments
+            .into_iter()
+            .filter(|a| !passed.contains(&a.name))
+            .map(|a| lsp_types::CompletionItem {
+                label: a.name.to_string(),
+                kind: Some(lsp_types::CompletionItemKind::FIELD),
+                detail: Some(a.kind),
+                insert_text: Some(format!("{}: ", a.name)),
+                ..Default::default()
+            })
+            .collect();
+        Some(lsp_types::CompletionResponse::Array(items))
+    }
+
+    fn document_symbols(
+        &self,
+        params: lsp_types::DocumentSymbolParams,
+    ) -> Option<lsp_types::DocumentSymbolResponse> {
+        let document = self.documents.get(&params.text_document.uri)?;
+        Some(lsp_types::DocumentSymbolResponse::Nested(
+            document.symbols(),
+        ))
+    }
+
+    /// `document_id()` is the id of the document in its package, like `foo/index.ftd`, or its
+    /// path if it is not in the package.
+    fn document_id(&self, uri: &lsp_types::Url) -> String {
+        let path = match uri.to_file_path() {
+            Ok(path) => path,
+            Err(_) => return uri.to_string(),
+        };
+        self.package
+            .as_ref()
+            .and_then(|p| p.document_id(path.as_path()))
+            .unwrap_or_else(|| path.to_string_lossy().to_string())
+    }
+
+    fn notify<N: lsp_types::notification::Notification>(
+        &self,
+        params: N::Params,
+    ) -> fastn_lsp::Result<()> {
+        self.send(lsp_server::Notification::new(N::METHOD.to_string(), params))
+    }
+
+    fn send<M: Into<lsp_server::Message>>(&self, message: M) -> fastn_lsp::Result<()> {
+        self.connection
+            .sender
+            .send(message.into())
+            .map_err(|_| fastn_lsp::Error::Disconnected)
+    }
+}
@@ -0,0 +1,72 @@
+use pretty_assertions::assert_eq; // macro
+
+const SOURCE: &str = r#"-- import: foo.com/ds
+exposing: page
+
+-- component card:
+caption title:
+optional body description:
+
+-- ftd.text: $card.title
+
+-- end: card
+
+-- card: hello
+"#;
+
+#[test]
+fn word_at() {
+    let word = |line, character| {
+        super::document::word_at(SOURCE, lsp_types::Position::new(line, character))
+    };
+    assert_eq!(word(7, 16), Some("card.title".to_string()));
+    assert_eq!(word(11, 4), Some("card".to_string()));
+    assert_eq!(word(11, 0), None);
+}
+
+#[test]
+fn definitions() {
+    let document = super::document::Document::parse("foo.com/doc/", SOURCE);
+    assert!(document.errors.is_empty());
+
+    assert_eq!(
+        document.resolve("card"),
+        ("foo.com/doc/".to_string(), "card".to_string())
+    );
+    assert_eq!(
+        document.resolve("ds.sidebar"),
+        ("foo.com/ds".to_string(), "sidebar".to_string())
+    );
+    assert_eq!(
+        document.resolve("page"),
+        ("foo.com/ds".to_string(), "page".to_string())
+    );
+
+    let card = super::document::find_definition(&document.sections, "card").unwrap();
+    assert_eq!(card.span.start, ftd::p1::Position::new(4, 1));
+    assert_eq!(
+        super::document::Argument::from_definition(card)
+            .iter()
+            .map(|a| (a.name.as_str(), a.kind.as_str()))
+            .collect::<Vec<_>>(),
+        vec![("title", "caption"), ("description", "optional body")]
+    );
+}
+
+#[test]
+fn symbols() {
+    let document = super::document::Document::parse("foo.com/doc/", SOURCE);
+    let symbols = document.symbols();
+    assert_eq!(
+        symbols
+            .iter()
+            .map(|s| (s.name.as_str(), s.kind))
+            .collect::<Vec<_>>(),
+        vec![
+            ("foo.com/ds", lsp_types::SymbolKind::MODULE),
+            ("card", lsp_types::SymbolKind::CLASS),
+            ("card", lsp_types::SymbolKind::OBJECT),
+        ]
+    );
+    assert_eq!(symbols[1].children.as_ref().map(|c| c.len()), Some(1));
+}
@@ -64,6 +64,17 @@ pub struct BackendHeader {
     pub header_value: String,
 }
 
+/// Where `fastn sync` keeps the history of the package: `fastn` keeps it in `.remote-state`, `git`
+/// keeps it as commits in the git repository of the package
+#[derive(serde::Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+pub enum HistoryBackend {
+    #[default]
+    #[serde(rename = "fastn")]
+    Fastn,
+    #[serde(rename = "git")]
+    Git,
+}
+
 /// PackageTemp is a struct that is used for mapping the `fastn.package` data in FASTN.ftd file. It is
 /// not used elsewhere in program, it is immediately converted to `fastn_core::Package` struct during
 /// deserialization process
@@ -93,6 +104,10 @@ pub struct PackageTemp {
     pub backend: bool,
     #[serde(rename = "backend-headers")]
     pub backend_headers: Option<Vec<BackendHeader>>,
+    #[serde(rename = "history-backend")]
+    pub history_backend: HistoryBackend,
+    #[serde(rename = "login-url")]
+    pub login_url: Option<String>,
     #[serde(rename = "icon")]
     pub icon: Option<ftd::ImageSrc>,
 }
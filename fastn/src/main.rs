@@ -10,9 +10,21 @@ pub fn main() {
 }
 
 async fn outer_main() {
-    if let Err(e) = async_main().await {
-        eprintln!("{:?}", e);
-        std::process::exit(1);
+    use fastn_core::commands::report::{ExitStatus, OutputFormat};
+
+    let matches = app(version()).get_matches();
+    let format = OutputFormat::from_matches(&matches);
+    match async_main(&matches, format).await {
+        Ok(ExitStatus::Clean) => {}
+        Ok(status) => std::process::exit(status.code()),
+        Err(e) => {
+            let command = matches.subcommand_name().unwrap_or_default();
+            fastn_core::commands::report::output_error(command, &e, format);
+            std::process::exit(match format {
+                OutputFormat::Text => 1,
+                OutputFormat::Json => ExitStatus::Error.code(),
+            });
+        }
     }
 }
 
@@ -24,13 +36,66 @@ pub enum Error {
     FastnCloudError(#[from] commands::cloud::Error),
 }
 
-async fn async_main() -> Result<(), Error> {
-    let matches = app(version()).get_matches();
-    if cloud_commands(&matches).await? {
-        return Ok(());
+async fn async_main(
+    matches: &clap::ArgMatches,
+    format: fastn_core::commands::report::OutputFormat,
+) -> Result<fastn_core::commands::report::ExitStatus, Error> {
+    if let Some(status) = report_commands(matches, format).await? {
+        return Ok(status);
+    }
+    if format.eq(&fastn_core::commands::report::OutputFormat::Json) {
+        return Err(fastn_core::Error::UsageError {
+            message: format!(
+                "`--format json` is not supported by `fastn {}`",
+                matches.subcommand_name().unwrap_or_default()
+            ),
+        }
+        .into());
+    }
+    if !cloud_commands(matches).await? {
+        fastn_core_commands(matches).await?;
     }
-    fastn_core_commands(&matches).await?;
-    Ok(())
+    Ok(fastn_core::commands::report::ExitStatus::Clean)
+}
+
+/// `report_commands()` runs the commands that can print what they found as json, see
+/// `fastn_core::commands::report`.
+async fn report_commands(
+    matches: &clap::ArgMatches,
+    format: fastn_core::commands::report::OutputFormat,
+) -> fastn_core::Result<Option<fastn_core::commands::report::ExitStatus>> {
+    use fastn_core::commands::report::output;
+    use fastn_core::utils::ValueOf;
+
+    let config = || fastn_core::Config::read(None, true, None);
+    let status = match matches.subcommand() {
+        Some((fastn_core::commands::sync_status::COMMAND, matches)) => {
+            fastn_core::commands::sync_status::handle_command(matches).await?
+        }
+        Some(("status", status)) => {
+            // TODO: handle multiple files
+            let report = fastn_core::status(&config().await?, status.value_of_("file")).await?;
+            output("status", &report, format)?
+        }
+        Some(("diff", diff)) => {
+            let all = diff.get_flag("all");
+            let files = diff
+                .get_many::<String>("file")
+                .map(|source| source.map(|v| v.to_string()).collect());
+            let report = fastn_core::diff(&config().await?, files, all).await?;
+            output("diff", &report, format)?
+        }
+        Some(("translation-status", _)) => {
+            let report = fastn_core::translation_status(&config().await?).await?;
+            output("translation-status", &report, format)?
+        }
+        Some(("check", _)) => {
+            let report = fastn_core::check(&config().await?).await?;
+            output("check", &report, format)?
+        }
+        _ => return Ok(None),
+    };
+    Ok(Some(status))
 }
 
 async fn cloud_commands(matches: &clap::ArgMatches) -> Result<bool, commands::cloud::Error> {
@@ -48,8 +113,11 @@ async fn fastn_core_commands(matches: &clap::ArgMatches) -> fastn_core::Result<(
         Some((fastn_core::commands::stop_tracking::COMMAND, matches)) => {
             return fastn_core::commands::stop_tracking::handle_command(matches).await;
         }
-        Some((fastn_core::commands::sync_status::COMMAND, matches)) => {
-            return fastn_core::commands::sync_status::handle_command(matches).await;
+        Some((fastn_core::commands::vendor::COMMAND, matches)) => {
+            return fastn_core::commands::vendor::handle_command(matches).await;
+        }
+        Some((fastn_core::commands::fmt::COMMAND, matches)) => {
+            return fastn_core::commands::fmt::handle_command(matches).await;
         }
         _ => {}
     }
@@ -67,6 +135,15 @@ async fn fastn_core_commands(matches: &clap::ArgMatches) -> fastn_core::Result<(
         return fastn_core::clone(clone.value_of_("source").unwrap()).await;
     }
 
+    // dependencies are resolved by `Config::read()`, they have to be vendored before
+    let offline = match matches.subcommand() {
+        Some(("serve", matches)) | Some(("build", matches)) => matches.get_flag("offline"),
+        _ => false,
+    };
+    if offline {
+        fastn_core::commands::vendor::use_vendored().await?;
+    }
+
     let mut config = fastn_core::Config::read(None, true, None).await?;
     let package_name = config.package.name.clone();
 
@@ -158,6 +235,16 @@ async fn fastn_core_commands(matches: &clap::ArgMatches) -> fastn_core::Result<(
             build.get_flag("ignore-failed"),
             build.get_flag("test"),
             build.get_flag("check-build"),
+            build.value_of_("jobs").map(|j| match j.parse::<usize>() {
+                Ok(v) => v,
+                Err(_) => {
+                    eprintln!(
+                        "Provided jobs {} is not a valid number.",
+                        j.to_string().red()
+                    );
+                    std::process::exit(1);
+                }
+            }),
         )
         .await;
     }
@@ -198,21 +285,6 @@ async fn fastn_core_commands(matches: &clap::ArgMatches) -> fastn_core::Result<(
     if let Some(close_cr) = matches.subcommand_matches("close-cr") {
         return fastn_core::close_cr(&config, close_cr.value_of_("cr").unwrap()).await;
     }
-    if let Some(status) = matches.subcommand_matches("status") {
-        // TODO: handle multiple files
-        return fastn_core::status(&config, status.value_of_("file")).await;
-    }
-    if matches.subcommand_matches("translation-status").is_some() {
-        return fastn_core::translation_status(&config).await;
-    }
-    if let Some(diff) = matches.subcommand_matches("diff") {
-        let all = diff.get_flag("all");
-        return if let Some(source) = diff.get_many::<String>("file") {
-            fastn_core::diff(&config, Some(source.map(|v| v.to_string()).collect()), all).await
-        } else {
-            fastn_core::diff(&config, None, all).await
-        };
-    }
 
     if let Some(resolve_conflict) = matches.subcommand_matches("resolve-conflict") {
         let use_ours = resolve_conflict.get_flag("use-ours");
@@ -237,10 +309,6 @@ async fn fastn_core_commands(matches: &clap::ArgMatches) -> fastn_core::Result<(
         return fastn_core::mark_upto_date(&config, source, target).await;
     }
 
-    if matches.subcommand_matches("check").is_some() {
-        return fastn_core::post_build_check(&config).await;
-    }
-
     unreachable!("No subcommand matched");
 }
 
@@ -251,6 +319,7 @@ fn app(version: &'static str) -> clap::Command {
         .arg(clap::arg!(verbose: -v "Sets the level of verbosity"))
         .arg(clap::arg!(--test "Runs the command in test mode").hide(true))
         .arg(clap::arg!(--trace "Activate tracing").hide(true))
+        .arg(fastn_core::commands::report::arg())
         .subcommand(
             // Initial subcommand format
             // fastn create-package <project-name> [project-path]
@@ -270,6 +339,8 @@ fn app(version: &'static str) -> clap::Command {
                 .arg(clap::arg!(--"ignore-failed" "Ignore failed files."))
                 .arg(clap::arg!(--"check-build" "Checks .build for index files validation."))
                 .arg(clap::arg!(--"test" "Use for test"))
+                .arg(clap::arg!(--offline "Only use dependencies from `fastn vendor`, never download them"))
+                .arg(clap::arg!(-j --jobs <JOBS> "Number of documents built in parallel [default: number of CPUs]"))
                 .arg(clap::arg!(--"external-js" <URL> "Script added in ftd files")
                     .action(clap::ArgAction::Append))
                 .arg(clap::arg!(--"js" <URL> "Script text added in ftd files")
@@ -413,6 +484,8 @@ fn app(version: &'static str) -> clap::Command {
                 .hide(true) // hidden since the feature is not being released yet.
         )
         .subcommand(fastn_core::commands::stop_tracking::command())
+        .subcommand(fastn_core::commands::vendor::command())
+        .subcommand(fastn_core::commands::fmt::command())
         .subcommand(sub_command::serve())
         .subcommand(sub_command::publish_static())
 }
@@ -427,6 +500,7 @@ mod sub_command {
             .arg(clap::arg!(--port <PORT> "The port to listen on [default: first available port starting 8000]"))
             .arg(clap::arg!(--bind <ADDRESS> "The address to bind to").default_value("127.0.0.1"))
             .arg(clap::arg!(--edition <EDITION> "The FTD edition"))
+            .arg(clap::arg!(--offline "Only use dependencies from `fastn vendor`, never download them"))
             .arg(clap::arg!(--"external-js" <URL> "Script added in ftd files")
                 .action(clap::ArgAction::Append))
             .arg(clap::arg!(--"js" <URL> "Script text added in ftd files")
@@ -3,6 +3,8 @@ use crate::ast::kind::{HeaderValue, HeaderValues};
 #[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
 pub struct ComponentDefinition {
     pub name: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub type_parameters: Vec<String>,
     pub arguments: Vec<Argument>,
     pub definition: Component,
     pub css: Option<String>,
@@ -14,6 +16,7 @@ pub const COMPONENT: &str = "component";
 impl ComponentDefinition {
     fn new(
         name: &str,
+        type_parameters: Vec<String>,
         arguments: Vec<Argument>,
         definition: Component,
         css: Option<String>,
@@ -21,6 +24,7 @@ impl ComponentDefinition {
     ) -> ComponentDefinition {
         ComponentDefinition {
             name: name.to_string(),
+            type_parameters,
             arguments,
             definition,
             css,
@@ -62,9 +66,15 @@ impl ComponentDefinition {
             ftd::ast::utils::get_css_and_fields_from_headers(&section.headers, doc_id)?;
 
         let definition = Component::from_p1(section.sub_sections.first().unwrap(), doc_id)?;
+        let (name, type_parameters) = ftd::ast::utils::get_name_and_type_parameters(
+            section.name.as_str(),
+            doc_id,
+            section.line_number,
+        )?;
 
         Ok(ComponentDefinition::new(
-            section.name.as_str(),
+            name.as_str(),
+            type_parameters,
             arguments,
             definition,
             css,
@@ -156,6 +166,7 @@ impl Component {
             if let Some(ftd::p1::Body {
                 ref value,
                 line_number,
+                ..
             }) = section.body
             {
                 properties.push(Property::from_value(
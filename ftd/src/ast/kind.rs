@@ -8,10 +8,12 @@ pub struct VariableKind {
 pub enum VariableModifier {
     List,
     Optional,
+    Map,
 }
 
 pub const OPTIONAL: &str = "optional";
 pub const LIST: &str = "list";
+pub const MAP: &str = "map";
 
 impl VariableModifier {
     pub(crate) fn is_optional_from_expr(expr: &str) -> bool {
@@ -22,10 +24,18 @@ impl VariableModifier {
         expr.eq(LIST)
     }
 
+    pub(crate) fn is_map_from_expr(expr: &str) -> bool {
+        expr.eq(MAP)
+    }
+
     fn is_list(&self) -> bool {
         matches!(self, VariableModifier::List)
     }
 
+    fn is_map(&self) -> bool {
+        matches!(self, VariableModifier::Map)
+    }
+
     fn is_optional(&self) -> bool {
         matches!(self, VariableModifier::Optional)
     }
@@ -37,6 +47,8 @@ impl VariableModifier {
                 return Some(VariableModifier::Optional);
             } else if VariableModifier::is_list_from_expr(expr.last().unwrap()) {
                 return Some(VariableModifier::List);
+            } else if VariableModifier::is_map_from_expr(expr.last().unwrap()) {
+                return Some(VariableModifier::Map);
             }
         }
         None
@@ -68,7 +80,9 @@ impl VariableKind {
         let modifier = VariableModifier::get_modifier(kind);
         let kind = match modifier {
             Some(VariableModifier::Optional) if expr.len() >= 2 => expr[1..].join(" "),
-            Some(VariableModifier::List) if expr.len() >= 2 => expr[..expr.len() - 1].join(" "),
+            Some(VariableModifier::List) | Some(VariableModifier::Map) if expr.len() >= 2 => {
+                expr[..expr.len() - 1].join(" ")
+            }
             None => expr.join(" "),
             _ => {
                 return ftd::ast::parse_error(
@@ -522,6 +536,36 @@ impl VariableValue {
                     )
                 }
             }
+            Some(modifier) if modifier.is_map() => {
+                if self.is_null() {
+                    Ok(VariableValue::Record {
+                        name: kind.kind.clone(),
+                        caption: Box::new(None),
+                        headers: HeaderValues::new(vec![]),
+                        body: None,
+                        values: vec![],
+                        line_number: self.line_number(),
+                    })
+                } else if self.is_record() {
+                    Ok(self)
+                } else if let VariableValue::String { ref value, .. } = self {
+                    if value.starts_with('$') {
+                        Ok(self)
+                    } else {
+                        ftd::ast::parse_error(
+                            format!("Expected Map found: `{:?}`", self),
+                            doc_id,
+                            line_number,
+                        )
+                    }
+                } else {
+                    ftd::ast::parse_error(
+                        format!("Expected Map found: `{:?}`", self),
+                        doc_id,
+                        line_number,
+                    )
+                }
+            }
             Some(modifier) if modifier.is_optional() => Ok(self.into_optional()),
             _ => Ok(self),
         }
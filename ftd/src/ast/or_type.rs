@@ -117,6 +117,14 @@ impl OrTypeVariant {
         }
     }
 
+    pub fn line_number(&self) -> usize {
+        match self {
+            OrTypeVariant::AnonymousRecord(r) => r.line_number,
+            OrTypeVariant::Regular(f) => f.line_number,
+            OrTypeVariant::Constant(f) => f.line_number,
+        }
+    }
+
     pub(crate) fn is_constant(section: &ftd::p1::Section) -> bool {
         section
             .name
@@ -1,14 +1,22 @@
 #[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
 pub struct Record {
     pub name: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub type_parameters: Vec<String>,
     pub fields: Vec<Field>,
     pub line_number: usize,
 }
 
 impl Record {
-    fn new(name: &str, fields: Vec<Field>, line_number: usize) -> Record {
+    fn new(
+        name: &str,
+        type_parameters: Vec<String>,
+        fields: Vec<Field>,
+        line_number: usize,
+    ) -> Record {
         Record {
             name: name.to_string(),
+            type_parameters,
             fields,
             line_number,
         }
@@ -30,9 +38,15 @@ impl Record {
             );
         }
 
+        let (name, type_parameters) = ftd::ast::utils::get_name_and_type_parameters(
+            section.name.as_str(),
+            doc_id,
+            section.line_number,
+        )?;
         let fields = get_fields_from_headers(&section.headers, doc_id)?;
         Ok(Record::new(
-            section.name.as_str(),
+            name.as_str(),
+            type_parameters,
             fields,
             section.line_number,
         ))
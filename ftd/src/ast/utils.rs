@@ -72,6 +72,65 @@ pub(crate) fn get_css_and_fields_from_headers(
     Ok((css, fields))
 }
 
+/// Splits a generic name like `card<person, string list>` into its name, `card`, and its type
+/// arguments, `person` and `string list`. A name without type arguments is returned as it is.
+pub fn split_type_arguments(name: &str) -> (String, Vec<String>) {
+    let (base, arguments) = match name.split_once('<') {
+        Some((base, rest)) if name.ends_with('>') => (base, &rest[..rest.len() - 1]),
+        _ => return (name.to_string(), vec![]),
+    };
+
+    let mut type_arguments = vec![];
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (index, c) in arguments.char_indices() {
+        match c {
+            '<' => depth += 1,
+            '>' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                type_arguments.push(arguments[start..index].trim().to_string());
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    type_arguments.push(arguments[start..].trim().to_string());
+
+    (base.trim().to_string(), type_arguments)
+}
+
+/// Splits the name of a `record` or `component` definition into its name and the names of its
+/// type parameters, checking that each type parameter is a plain, unique name.
+pub(crate) fn get_name_and_type_parameters(
+    name: &str,
+    doc_id: &str,
+    line_number: usize,
+) -> ftd::ast::Result<(String, Vec<String>)> {
+    let (name, type_parameters) = split_type_arguments(name);
+    for (index, type_parameter) in type_parameters.iter().enumerate() {
+        if type_parameter.is_empty()
+            || type_parameter.contains(|c: char| c.is_whitespace() || "<>,".contains(c))
+        {
+            return ftd::ast::parse_error(
+                format!("Invalid type parameter `{}` for `{}`", type_parameter, name),
+                doc_id,
+                line_number,
+            );
+        }
+        if type_parameters[..index].contains(type_parameter) {
+            return ftd::ast::parse_error(
+                format!(
+                    "Type parameter `{}` is declared more than once for `{}`",
+                    type_parameter, name
+                ),
+                doc_id,
+                line_number,
+            );
+        }
+    }
+    Ok((name, type_parameters))
+}
+
 pub const REFERENCE: &str = "$";
 pub const CLONE: &str = "*$";
 pub const LOOP: &str = "$loop$";
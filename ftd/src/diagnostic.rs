@@ -0,0 +1,515 @@
+/// `Diagnostic` is an error located in the source of a document, it is shown to the user the
+/// way rustc shows errors: the message, the location, an excerpt of the source with the span
+/// underlined, and an optional help text.
+///
+/// ```text
+/// error: header `title` not found
+///   --> foo/index:3:1
+///   |
+/// 3 | -- ftd.text: hello
+///   | ^^^^^^^^^^^^^^^^^^
+///   |
+///   = help: add the `title` header
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub doc_id: String,
+    pub message: String,
+    pub span: ftd::p1::Span,
+    pub help: Option<String>,
+}
+
+/// only this many lines of a multi line span are shown, the rest are elided
+const MAX_EXCERPT_LINES: usize = 6;
+
+impl Diagnostic {
+    /// `at_line()` creates a diagnostic for an error reported at `line_number` of `source`. The
+    /// span is the most specific section header or body at that line, if `source` can be parsed,
+    /// else the whole line.
+    pub fn at_line(
+        doc_id: &str,
+        message: String,
+        source: &str,
+        line_number: usize,
+        help: Option<String>,
+    ) -> Diagnostic {
+        let span = match ftd::p1::parse(source, doc_id) {
+            Ok(sections) => ftd::p1::span::find(&sections, source, line_number),
+            Err(_) => ftd::p1::Span::line(source, line_number),
+        };
+        Diagnostic {
+            doc_id: doc_id.to_string(),
+            message,
+            span,
+            help,
+        }
+    }
+
+    /// `located()` is `at_line()` for errors that know their span already, an empty `span` is
+    /// looked up from `line_number`.
+    pub fn located(
+        doc_id: &str,
+        message: String,
+        source: &str,
+        line_number: usize,
+        span: ftd::p1::Span,
+        help: Option<String>,
+    ) -> Diagnostic {
+        if span.is_empty() {
+            return Diagnostic::at_line(doc_id, message, source, line_number, help);
+        }
+        Diagnostic {
+            doc_id: doc_id.to_string(),
+            message,
+            span,
+            help,
+        }
+    }
+
+    pub fn render(&self, source: &str) -> String {
+        self.render_as("error", source)
+    }
+
+    /// `render_warning()` renders the diagnostic as a warning, for problems that do not stop the
+    /// document from working, like an unused import.
+    pub fn render_warning(&self, source: &str) -> String {
+        self.render_as("warning", source)
+    }
+
+    fn render_as(&self, label: &str, source: &str) -> String {
+        let lines = source.split('\n').collect::<Vec<_>>();
+        let start = self.span.start;
+        let end = self.span.end;
+
+        let mut shown = (start.line..=end.line).collect::<Vec<_>>();
+        if shown.len() > MAX_EXCERPT_LINES {
+            let tail = shown.split_off(shown.len() - 2);
+            shown.truncate(MAX_EXCERPT_LINES - 2);
+            shown.push(0); // elided lines
+            shown.extend(tail);
+        }
+        let width = end.line.to_string().len();
+        let gutter = " ".repeat(width);
+
+        let mut out = format!(
+            "{}: {}\n{} --> {}:{}:{}\n{} |\n",
+            label, self.message, gutter, self.doc_id, start.line, start.column, gutter
+        );
+        for line_number in shown {
+            if line_number == 0 {
+                out.push_str(format!("{}...\n", gutter).as_str());
+                continue;
+            }
+            let line = match line_number.checked_sub(1).and_then(|i| lines.get(i)) {
+                Some(line) => line.trim_end(),
+                None => continue,
+            };
+            let leading = line.chars().take_while(|c| c.is_whitespace()).count() + 1;
+            let from = if line_number == start.line {
+                start.column
+            } else {
+                leading
+            };
+            let to = if line_number == end.line {
+                end.column
+            } else {
+                line.chars().count() + 1
+            };
+            out.push_str(
+                format!(
+                    "{:>width$} | {}\n{} | {}{}\n",
+                    line_number,
+                    line,
+                    gutter,
+                    " ".repeat(from.saturating_sub(1)),
+                    "^".repeat(to.saturating_sub(from).max(1)),
+                    width = width
+                )
+                .as_str(),
+            );
+        }
+        if let Some(ref help) = self.help {
+            out.push_str(format!("{} |\n{} = help: {}\n", gutter, gutter, help).as_str());
+        }
+        out
+    }
+}
+
+impl ftd::p1::Error {
+    /// the id of the document this error is reported for
+    pub fn doc_id(&self) -> Option<&str> {
+        match self {
+            ftd::p1::Error::SectionNotFound { doc_id, .. }
+            | ftd::p1::Error::MoreThanOneCaption { doc_id, .. }
+            | ftd::p1::Error::ParseError { doc_id, .. }
+            | ftd::p1::Error::MoreThanOneHeader { doc_id, .. }
+            | ftd::p1::Error::HeaderNotFound { doc_id, .. }
+            | ftd::p1::Error::ForbiddenUsage { doc_id, .. }
+            | ftd::p1::Error::NotFound { doc_id, .. }
+            | ftd::p1::Error::MoreThanOneSubSections { doc_id, .. } => Some(doc_id),
+            ftd::p1::Error::Serde { .. } | ftd::p1::Error::Syntect { .. } => None,
+        }
+    }
+
+    /// `location_mut()` is the line this error is reported at, and its span, which is empty till
+    /// the error is located with `locate()`
+    fn location_mut(&mut self) -> Option<(&str, usize, &mut ftd::p1::Span)> {
+        match self {
+            ftd::p1::Error::SectionNotFound {
+                doc_id,
+                line_number,
+                span,
+            }
+            | ftd::p1::Error::MoreThanOneCaption {
+                doc_id,
+                line_number,
+                span,
+            }
+            | ftd::p1::Error::ParseError {
+                doc_id,
+                line_number,
+                span,
+                ..
+            }
+            | ftd::p1::Error::MoreThanOneHeader {
+                doc_id,
+                line_number,
+                span,
+                ..
+            }
+            | ftd::p1::Error::HeaderNotFound {
+                doc_id,
+                line_number,
+                span,
+                ..
+            }
+            | ftd::p1::Error::ForbiddenUsage {
+                doc_id,
+                line_number,
+                span,
+                ..
+            }
+            | ftd::p1::Error::NotFound {
+                doc_id,
+                line_number,
+                span,
+                ..
+            }
+            | ftd::p1::Error::MoreThanOneSubSections {
+                doc_id,
+                line_number,
+                span,
+                ..
+            } => Some((doc_id.as_str(), *line_number, span)),
+            ftd::p1::Error::Serde { .. } | ftd::p1::Error::Syntect { .. } => None,
+        }
+    }
+
+    /// the part of the document this error is reported for, `None` till it is located
+    pub fn span(&self) -> Option<ftd::p1::Span> {
+        match self {
+            ftd::p1::Error::SectionNotFound { span, .. }
+            | ftd::p1::Error::MoreThanOneCaption { span, .. }
+            | ftd::p1::Error::ParseError { span, .. }
+            | ftd::p1::Error::MoreThanOneHeader { span, .. }
+            | ftd::p1::Error::HeaderNotFound { span, .. }
+            | ftd::p1::Error::ForbiddenUsage { span, .. }
+            | ftd::p1::Error::NotFound { span, .. }
+            | ftd::p1::Error::MoreThanOneSubSections { span, .. } => {
+                Some(*span).filter(|v| !v.is_empty())
+            }
+            ftd::p1::Error::Serde { .. } | ftd::p1::Error::Syntect { .. } => None,
+        }
+    }
+
+    /// `locate()` sets the span of this error, if it is not set yet, to the span `spans` has for
+    /// the line it is reported at. `spans` are the spans of the document `doc_id`, errors of other
+    /// documents are left as they are.
+    pub fn locate(mut self, doc_id: &str, spans: &ftd::p1::Spans) -> Self {
+        if let Some((id, line_number, span)) = self.location_mut() {
+            if id.eq(doc_id) && span.is_empty() {
+                *span = spans.get(line_number);
+            }
+        }
+        self
+    }
+
+    /// `diagnostic()` locates this error in `source`, the source of the document the error is
+    /// reported for. Errors without a location return `None`.
+    pub fn diagnostic(&self, source: &str) -> Option<Diagnostic> {
+        let (doc_id, line_number, span, message, help) = match self {
+            ftd::p1::Error::SectionNotFound {
+                doc_id,
+                line_number,
+                span,
+            } => (
+                doc_id,
+                line_number,
+                span,
+                "section not found".to_string(),
+                Some("a section starts with `-- <section-name>:` on a new line".to_string()),
+            ),
+            ftd::p1::Error::MoreThanOneCaption {
+                doc_id,
+                line_number,
+                span,
+            } => (
+                doc_id,
+                line_number,
+                span,
+                "more than one caption".to_string(),
+                Some("pass the caption either after `:` or as a `caption` header".to_string()),
+            ),
+            ftd::p1::Error::ParseError {
+                message,
+                doc_id,
+                line_number,
+                span,
+            }
+            | ftd::p1::Error::ForbiddenUsage {
+                message,
+                doc_id,
+                line_number,
+                span,
+            } => (doc_id, line_number, span, message.to_string(), None),
+            ftd::p1::Error::MoreThanOneHeader {
+                key,
+                doc_id,
+                line_number,
+                span,
+            } => (
+                doc_id,
+                line_number,
+                span,
+                format!("more than one `{}` header", key),
+                Some(format!("`{}` can only be passed once", key)),
+            ),
+            ftd::p1::Error::HeaderNotFound {
+                key,
+                doc_id,
+                line_number,
+                span,
+            } => (
+                doc_id,
+                line_number,
+                span,
+                format!("header `{}` not found", key),
+                Some(format!("add the `{}` header", key)),
+            ),
+            ftd::p1::Error::NotFound {
+                doc_id,
+                line_number,
+                key,
+                span,
+            } => (
+                doc_id,
+                line_number,
+                span,
+                format!("`{}` not found", key),
+                None,
+            ),
+            ftd::p1::Error::MoreThanOneSubSections {
+                key,
+                doc_id,
+                line_number,
+                span,
+            } => (
+                doc_id,
+                line_number,
+                span,
+                format!("more than one `{}` sub-section", key),
+                None,
+            ),
+            ftd::p1::Error::Serde { .. } | ftd::p1::Error::Syntect { .. } => return None,
+        };
+        Some(Diagnostic::located(
+            doc_id,
+            message,
+            source,
+            *line_number,
+            *span,
+            help,
+        ))
+    }
+}
+
+impl ftd::ast::Error {
+    pub fn doc_id(&self) -> Option<&str> {
+        match self {
+            ftd::ast::Error::P1(e) => e.doc_id(),
+            ftd::ast::Error::Parse { doc_id, .. } => Some(doc_id),
+            ftd::ast::Error::ParseBool(_) => None,
+        }
+    }
+
+    pub fn diagnostic(&self, source: &str) -> Option<Diagnostic> {
+        match self {
+            ftd::ast::Error::P1(e) => e.diagnostic(source),
+            ftd::ast::Error::Parse {
+                message,
+                doc_id,
+                line_number,
+            } => Some(Diagnostic::at_line(
+                doc_id,
+                message.to_string(),
+                source,
+                *line_number,
+                None,
+            )),
+            ftd::ast::Error::ParseBool(_) => None,
+        }
+    }
+}
+
+impl ftd::interpreter::Error {
+    pub fn doc_id(&self) -> Option<&str> {
+        match self {
+            ftd::interpreter::Error::P1Error(e) => e.doc_id(),
+            ftd::interpreter::Error::ASTError(e) => e.doc_id(),
+            ftd::interpreter::Error::InvalidKind { doc_id, .. }
+            | ftd::interpreter::Error::ValueNotFound { doc_id, .. }
+            | ftd::interpreter::Error::ParseError { doc_id, .. } => Some(doc_id),
+            _ => None,
+        }
+    }
+
+    /// the part of the document this error is reported for, `None` till it is located
+    pub fn span(&self) -> Option<ftd::p1::Span> {
+        match self {
+            ftd::interpreter::Error::P1Error(e) => e.span(),
+            ftd::interpreter::Error::ASTError(ftd::ast::Error::P1(e)) => e.span(),
+            ftd::interpreter::Error::InvalidKind { span, .. }
+            | ftd::interpreter::Error::ValueNotFound { span, .. }
+            | ftd::interpreter::Error::ParseError { span, .. } => {
+                Some(*span).filter(|v| !v.is_empty())
+            }
+            _ => None,
+        }
+    }
+
+    /// `locate()` sets the span of this error if it is reported for `doc_id`, see
+    /// `ftd::p1::Error::locate()`.
+    pub fn locate(self, doc_id: &str, spans: &ftd::p1::Spans) -> Self {
+        match self {
+            ftd::interpreter::Error::P1Error(e) => {
+                ftd::interpreter::Error::P1Error(e.locate(doc_id, spans))
+            }
+            ftd::interpreter::Error::ASTError(ftd::ast::Error::P1(e)) => {
+                ftd::interpreter::Error::ASTError(ftd::ast::Error::P1(e.locate(doc_id, spans)))
+            }
+            ftd::interpreter::Error::InvalidKind {
+                doc_id: id,
+                line_number,
+                message,
+                span,
+            } if id.eq(doc_id) && span.is_empty() => ftd::interpreter::Error::InvalidKind {
+                span: spans.get(line_number),
+                doc_id: id,
+                line_number,
+                message,
+            },
+            ftd::interpreter::Error::ValueNotFound {
+                doc_id: id,
+                line_number,
+                message,
+                span,
+            } if id.eq(doc_id) && span.is_empty() => ftd::interpreter::Error::ValueNotFound {
+                span: spans.get(line_number),
+                doc_id: id,
+                line_number,
+                message,
+            },
+            ftd::interpreter::Error::ParseError {
+                message,
+                doc_id: id,
+                line_number,
+                span,
+            } if id.eq(doc_id) && span.is_empty() => ftd::interpreter::Error::ParseError {
+                span: spans.get(line_number),
+                message,
+                doc_id: id,
+                line_number,
+            },
+            e => e,
+        }
+    }
+
+    pub fn diagnostic(&self, source: &str) -> Option<Diagnostic> {
+        match self {
+            ftd::interpreter::Error::P1Error(e) => e.diagnostic(source),
+            ftd::interpreter::Error::ASTError(e) => e.diagnostic(source),
+            ftd::interpreter::Error::InvalidKind {
+                doc_id,
+                line_number,
+                message,
+                span,
+            } => Some(Diagnostic::located(
+                doc_id,
+                format!("invalid kind: {}", message),
+                source,
+                *line_number,
+                *span,
+                None,
+            )),
+            ftd::interpreter::Error::ValueNotFound {
+                doc_id,
+                line_number,
+                message,
+                span,
+            } => Some(Diagnostic::located(
+                doc_id,
+                format!("value not found: {}", message),
+                source,
+                *line_number,
+                *span,
+                Some(
+                    "check the spelling, and that the module defining it is imported with \
+                    `-- import:`"
+                        .to_string(),
+                ),
+            )),
+            ftd::interpreter::Error::ParseError {
+                message,
+                doc_id,
+                line_number,
+                span,
+            } => Some(Diagnostic::located(
+                doc_id,
+                message.to_string(),
+                source,
+                *line_number,
+                *span,
+                None,
+            )),
+            _ => None,
+        }
+    }
+}
+
+impl ftd::executor::Error {
+    pub fn doc_id(&self) -> Option<&str> {
+        match self {
+            ftd::executor::Error::InterpreterError(e) => e.doc_id(),
+            ftd::executor::Error::ParseError { doc_id, .. } => Some(doc_id),
+            ftd::executor::Error::Syntect { .. } => None,
+        }
+    }
+
+    pub fn diagnostic(&self, source: &str) -> Option<Diagnostic> {
+        match self {
+            ftd::executor::Error::InterpreterError(e) => e.diagnostic(source),
+            ftd::executor::Error::ParseError {
+                message,
+                doc_id,
+                line_number,
+            } => Some(Diagnostic::at_line(
+                doc_id,
+                message.to_string(),
+                source,
+                *line_number,
+                None,
+            )),
+            ftd::executor::Error::Syntect { .. } => None,
+        }
+    }
+}
@@ -15,6 +15,9 @@ pub enum Element {
     RawElement(RawElement),
     IterativeElement(IterativeElement),
     CheckBox(CheckBox),
+    Form(Form),
+    Select(Select),
+    Radio(Radio),
     WebComponent(WebComponent),
     Rive(Rive),
     Null { line_number: usize },
@@ -35,6 +38,9 @@ impl Element {
             Element::Iframe(i) => Some(&i.common),
             Element::TextInput(i) => Some(&i.common),
             Element::CheckBox(c) => Some(&c.common),
+            Element::Form(f) => Some(&f.common),
+            Element::Select(s) => Some(&s.common),
+            Element::Radio(r) => Some(&r.common),
             Element::Document(_) => None,
             Element::Null { .. } => None,
             Element::RawElement(_) => None,
@@ -48,6 +54,7 @@ impl Element {
         match self {
             Element::Row(r) => Some(&mut r.container.children),
             Element::Column(c) => Some(&mut c.container.children),
+            Element::Form(f) => Some(&mut f.container.children),
             Element::Document(d) => Some(&mut d.children),
             Element::RawElement(r) => Some(&mut r.children),
             _ => None,
@@ -75,6 +82,9 @@ impl Element {
             Element::RawElement(r) => r.line_number,
             Element::IterativeElement(i) => i.iteration.line_number,
             Element::CheckBox(c) => c.common.line_number,
+            Element::Form(f) => f.common.line_number,
+            Element::Select(s) => s.common.line_number,
+            Element::Radio(r) => r.common.line_number,
             Element::WebComponent(w) => w.line_number,
             Element::Rive(r) => r.common.line_number,
             Element::Null { line_number } => *line_number,
@@ -2445,3 +2455,262 @@ pub fn checkbox_from_properties(
         common,
     })
 }
+
+#[derive(serde::Deserialize, Debug, Default, PartialEq, Clone, serde::Serialize)]
+pub struct Form {
+    pub action: ftd::executor::Value<Option<String>>,
+    pub method: ftd::executor::Value<Option<String>>,
+    pub container: Container,
+    pub common: Common,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn form_from_properties(
+    properties: &[ftd::interpreter::Property],
+    events: &[ftd::interpreter::Event],
+    arguments: &[ftd::interpreter::Argument],
+    condition: &Option<ftd::interpreter::Expression>,
+    doc: &mut ftd::executor::TDoc,
+    local_container: &[usize],
+    line_number: usize,
+    children: Vec<Element>,
+    inherited_variables: &ftd::VecMap<(String, Vec<usize>)>,
+    device: Option<ftd::executor::Device>,
+) -> ftd::executor::Result<Form> {
+    let action = ftd::executor::value::optional_string(
+        "action",
+        "ftd#form",
+        properties,
+        arguments,
+        doc,
+        line_number,
+    )?;
+
+    let method = ftd::executor::value::optional_string(
+        "method",
+        "ftd#form",
+        properties,
+        arguments,
+        doc,
+        line_number,
+    )?;
+
+    let common = common_from_properties(
+        properties,
+        events,
+        arguments,
+        condition,
+        doc,
+        local_container,
+        line_number,
+        inherited_variables,
+        "ftd#form",
+        device.clone(),
+    )?;
+    let container = container_from_properties(
+        properties,
+        arguments,
+        doc,
+        line_number,
+        children,
+        inherited_variables,
+        "ftd#form",
+        device,
+    )?;
+    Ok(Form {
+        action,
+        method,
+        container,
+        common,
+    })
+}
+
+/// `FormField` is what `ftd.select` and `ftd.radio` share with the other fields of a form.
+#[derive(serde::Deserialize, Debug, Default, PartialEq, Clone, serde::Serialize)]
+pub struct FormField {
+    pub name: ftd::executor::Value<Option<String>>,
+    pub required: ftd::executor::Value<Option<bool>>,
+    pub enabled: ftd::executor::Value<Option<bool>>,
+}
+
+fn form_field_from_properties(
+    properties: &[ftd::interpreter::Property],
+    arguments: &[ftd::interpreter::Argument],
+    doc: &ftd::executor::TDoc,
+    line_number: usize,
+    inherited_variables: &ftd::VecMap<(String, Vec<usize>)>,
+    component_name: &str,
+) -> ftd::executor::Result<FormField> {
+    let name = ftd::executor::value::optional_string(
+        "name",
+        component_name,
+        properties,
+        arguments,
+        doc,
+        line_number,
+    )?;
+
+    let required = ftd::executor::value::optional_bool(
+        "required",
+        component_name,
+        properties,
+        arguments,
+        doc,
+        line_number,
+        inherited_variables,
+    )?;
+
+    let enabled = ftd::executor::value::optional_bool(
+        "enabled",
+        component_name,
+        properties,
+        arguments,
+        doc,
+        line_number,
+        inherited_variables,
+    )?;
+
+    Ok(FormField {
+        name,
+        required,
+        enabled,
+    })
+}
+
+#[derive(serde::Deserialize, Debug, Default, PartialEq, Clone, serde::Serialize)]
+pub struct Select {
+    pub options: ftd::executor::Value<Vec<String>>,
+    pub value: ftd::executor::Value<Option<String>>,
+    pub field: FormField,
+    pub common: Common,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn select_from_properties(
+    properties: &[ftd::interpreter::Property],
+    events: &[ftd::interpreter::Event],
+    arguments: &[ftd::interpreter::Argument],
+    condition: &Option<ftd::interpreter::Expression>,
+    doc: &mut ftd::executor::TDoc,
+    local_container: &[usize],
+    line_number: usize,
+    inherited_variables: &ftd::VecMap<(String, Vec<usize>)>,
+    device: Option<ftd::executor::Device>,
+) -> ftd::executor::Result<Select> {
+    let options = ftd::executor::value::string_list(
+        "options",
+        "ftd#select",
+        properties,
+        arguments,
+        doc,
+        line_number,
+        inherited_variables,
+    )?;
+
+    let value = ftd::executor::value::optional_string(
+        "value",
+        "ftd#select",
+        properties,
+        arguments,
+        doc,
+        line_number,
+    )?;
+
+    let field = form_field_from_properties(
+        properties,
+        arguments,
+        doc,
+        line_number,
+        inherited_variables,
+        "ftd#select",
+    )?;
+
+    let common = common_from_properties(
+        properties,
+        events,
+        arguments,
+        condition,
+        doc,
+        local_container,
+        line_number,
+        inherited_variables,
+        "ftd#select",
+        device,
+    )?;
+
+    Ok(Select {
+        options,
+        value,
+        field,
+        common,
+    })
+}
+
+#[derive(serde::Deserialize, Debug, Default, PartialEq, Clone, serde::Serialize)]
+pub struct Radio {
+    pub value: ftd::executor::Value<Option<String>>,
+    pub checked: ftd::executor::Value<Option<bool>>,
+    pub field: FormField,
+    pub common: Common,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn radio_from_properties(
+    properties: &[ftd::interpreter::Property],
+    events: &[ftd::interpreter::Event],
+    arguments: &[ftd::interpreter::Argument],
+    condition: &Option<ftd::interpreter::Expression>,
+    doc: &mut ftd::executor::TDoc,
+    local_container: &[usize],
+    line_number: usize,
+    inherited_variables: &ftd::VecMap<(String, Vec<usize>)>,
+    device: Option<ftd::executor::Device>,
+) -> ftd::executor::Result<Radio> {
+    let value = ftd::executor::value::optional_string(
+        "value",
+        "ftd#radio",
+        properties,
+        arguments,
+        doc,
+        line_number,
+    )?;
+
+    let checked = ftd::executor::value::optional_bool(
+        "checked",
+        "ftd#radio",
+        properties,
+        arguments,
+        doc,
+        line_number,
+        inherited_variables,
+    )?;
+
+    let field = form_field_from_properties(
+        properties,
+        arguments,
+        doc,
+        line_number,
+        inherited_variables,
+        "ftd#radio",
+    )?;
+
+    let common = common_from_properties(
+        properties,
+        events,
+        arguments,
+        condition,
+        doc,
+        local_container,
+        line_number,
+        inherited_variables,
+        "ftd#radio",
+        device,
+    )?;
+
+    Ok(Radio {
+        value,
+        checked,
+        field,
+        common,
+    })
+}
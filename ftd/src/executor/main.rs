@@ -373,7 +373,7 @@ impl<'a> ExecuteDoc<'a> {
         )
         .0;
 
-        let children_length = iteration.children(&doc.itdoc())?.0.len();
+        let items = iteration.items(&doc.itdoc())?;
         let reference_name =
             iteration
                 .on
@@ -387,7 +387,7 @@ impl<'a> ExecuteDoc<'a> {
                     line_number: iteration.line_number,
                 })?;
         let mut elements = vec![];
-        for index in 0..children_length {
+        for (index, (item, counter)) in items.into_iter().enumerate() {
             let local_container = {
                 let mut local_container = parent_container.to_vec();
                 local_container.push(index + start_index);
@@ -396,7 +396,8 @@ impl<'a> ExecuteDoc<'a> {
             let new_instruction = ftd::executor::utils::update_instruction_for_loop_element(
                 instruction,
                 doc,
-                index,
+                item.as_str(),
+                counter,
                 iteration.alias.as_str(),
                 reference_name,
                 inherited_variables,
@@ -607,6 +608,7 @@ impl<'a> ExecuteDoc<'a> {
                 current = match &mut current[*i] {
                     ftd::executor::Element::Row(r) => &mut r.container.children,
                     ftd::executor::Element::Column(r) => &mut r.container.children,
+                    ftd::executor::Element::Form(f) => &mut f.container.children,
                     ftd::executor::Element::Container(e) => &mut e.children,
                     ftd::executor::Element::Document(r) => &mut r.children,
                     t => unreachable!("{:?}", t),
@@ -772,7 +774,7 @@ impl<'a> ExecuteDoc<'a> {
 
         match component_definition.name.as_str() {
             "ftd#row" | "ftd#column" | "ftd#container" | "ftd#document" | "ftd#desktop"
-            | "ftd#mobile" => {
+            | "ftd#mobile" | "ftd#form" => {
                 doc.insert_local_variables(
                     component_definition.name.as_str(),
                     instruction.properties.as_slice(),
@@ -1019,6 +1021,46 @@ impl<'a> ExecuteDoc<'a> {
                     device,
                 )?)
             }
+            "ftd#form" => {
+                ftd::executor::Element::Form(ftd::executor::element::form_from_properties(
+                    instruction.properties.as_slice(),
+                    instruction.events.as_slice(),
+                    component_definition.arguments.as_slice(),
+                    instruction.condition.as_ref(),
+                    doc,
+                    local_container,
+                    instruction.line_number,
+                    vec![],
+                    inherited_variables,
+                    device,
+                )?)
+            }
+            "ftd#select" => {
+                ftd::executor::Element::Select(ftd::executor::element::select_from_properties(
+                    instruction.properties.as_slice(),
+                    instruction.events.as_slice(),
+                    component_definition.arguments.as_slice(),
+                    instruction.condition.as_ref(),
+                    doc,
+                    local_container,
+                    instruction.line_number,
+                    inherited_variables,
+                    device,
+                )?)
+            }
+            "ftd#radio" => {
+                ftd::executor::Element::Radio(ftd::executor::element::radio_from_properties(
+                    instruction.properties.as_slice(),
+                    instruction.events.as_slice(),
+                    component_definition.arguments.as_slice(),
+                    instruction.condition.as_ref(),
+                    doc,
+                    local_container,
+                    instruction.line_number,
+                    inherited_variables,
+                    device,
+                )?)
+            }
             _ => unimplemented!(),
         })
     }
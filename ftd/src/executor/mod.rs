@@ -18,9 +18,9 @@ pub type FieldWithValue = (ftd::interpreter::Field, Option<ftd::ast::VariableVal
 
 pub use dummy::{DummyElement, ElementConstructor};
 pub use element::{
-    CheckBox, Code, Column, Common, Container, ContainerElement, Document, Element, Event,
-    HTMLData, Iframe, Image, ImageSrc, IterativeElement, RawElement, RawImage, Rive, Row, Text,
-    TextInput, WebComponent,
+    CheckBox, Code, Column, Common, Container, ContainerElement, Document, Element, Event, Form,
+    FormField, HTMLData, Iframe, Image, ImageSrc, IterativeElement, Radio, RawElement, RawImage,
+    Rive, Row, Select, Text, TextInput, WebComponent,
 };
 pub use main::{Device, ExecuteDoc, RT};
 pub use rive::RiveData;
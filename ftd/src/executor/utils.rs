@@ -45,7 +45,8 @@ pub(crate) fn create_dummy_instruction_for_loop_element(
 pub(crate) fn update_instruction_for_loop_element(
     instruction: &ftd::interpreter::Component,
     doc: &mut ftd::executor::TDoc,
-    index_in_loop: usize,
+    item: &str,
+    counter: ftd::interpreter::Value,
     alias: &str,
     reference_name: &str,
     inherited_variables: &mut ftd::VecMap<(String, Vec<usize>)>,
@@ -55,16 +56,13 @@ pub(crate) fn update_instruction_for_loop_element(
     let mut instruction = instruction.clone();
     let reference_replace_pattern = ftd::interpreter::PropertyValueSource::Loop(alias.to_string())
         .get_reference_name(alias, &doc.itdoc());
-    let replace_with = format!("{}.{}", reference_name, index_in_loop);
+    let replace_with = format!("{}.{}", reference_name, item);
     let map =
         std::iter::IntoIterator::into_iter([(reference_replace_pattern, replace_with)]).collect();
     let replace_property_value = std::iter::IntoIterator::into_iter([(
         doc.itdoc()
             .resolve_name(format!("{}#{}", doc_name, ftd::interpreter::FTD_LOOP_COUNTER).as_str()),
-        ftd::interpreter::Value::Integer {
-            value: index_in_loop as i64,
-        }
-        .into_property_value(false, instruction.line_number),
+        counter.into_property_value(false, instruction.line_number),
     )])
     .collect();
 
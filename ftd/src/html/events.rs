@@ -142,6 +142,7 @@ fn to_event_name(event_name: &ftd::interpreter::EventName) -> String {
         ftd::interpreter::EventName::Change => "onchange".to_string(),
         ftd::interpreter::EventName::Blur => "onblur".to_string(),
         ftd::interpreter::EventName::Focus => "onfocus".to_string(),
+        ftd::interpreter::EventName::Submit => "onsubmit".to_string(),
         ftd::interpreter::EventName::RivePlay(timeline) => format!("onriveplay[{}]", timeline),
         ftd::interpreter::EventName::RiveStateChange(state_change) => {
             format!("onrivestatechange[{}]", state_change)
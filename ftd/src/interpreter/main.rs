@@ -112,6 +112,7 @@ impl InterpreterState {
                     message: format!("Cannot find this document: `{}`", doc_name),
                     doc_id: doc_name.to_string(),
                     line_number,
+                    span: Default::default(),
                 })?;
         Ok(ftd::interpreter::TDoc::new(
             &parsed_document.name,
@@ -135,7 +136,31 @@ impl InterpreterState {
     }
 
     #[tracing::instrument(name = "continue_processing", skip_all)]
-    pub fn continue_processing(mut self) -> ftd::interpreter::Result<Interpreter> {
+    pub fn continue_processing(self) -> ftd::interpreter::Result<Interpreter> {
+        self.locating(InterpreterState::continue_processing_)
+    }
+
+    /// `locating()` runs `f` on the state, and sets the span of the error it returns using the
+    /// spans of the document the error is reported for, see `ftd::interpreter::Error::locate()`.
+    fn locating<T>(
+        self,
+        f: impl FnOnce(InterpreterState) -> ftd::interpreter::Result<T>,
+    ) -> ftd::interpreter::Result<T> {
+        let spans: ftd::Map<ftd::p1::Spans> = self
+            .parsed_libs
+            .iter()
+            .map(|(id, document)| (id.to_string(), document.spans.clone()))
+            .collect();
+        f(self).map_err(|e| {
+            let doc_id = e.doc_id().map(|v| v.to_string());
+            match doc_id.and_then(|id| spans.get(&id).map(|spans| (id, spans))) {
+                Some((id, spans)) => e.locate(id.as_str(), spans),
+                None => e,
+            }
+        })
+    }
+
+    fn continue_processing_(mut self) -> ftd::interpreter::Result<Interpreter> {
         while let Some((doc_name, number_of_scan, ast, exports)) = self.get_next_ast() {
             if let Some(interpreter) = self.resolve_pending_imports::<ftd::interpreter::Thing>()? {
                 match interpreter {
@@ -750,6 +775,14 @@ impl InterpreterState {
 
     #[tracing::instrument(skip_all)]
     pub fn continue_after_processor(
+        self,
+        value: ftd::interpreter::Value,
+        ast: ftd::ast::AST,
+    ) -> ftd::interpreter::Result<Interpreter> {
+        self.locating(|s| s.continue_after_processor_(value, ast))
+    }
+
+    fn continue_after_processor_(
         mut self,
         value: ftd::interpreter::Value,
         ast: ftd::ast::AST,
@@ -796,6 +829,15 @@ impl InterpreterState {
 
     #[tracing::instrument(skip_all)]
     pub fn continue_after_variable(
+        self,
+        module: &str,
+        variable: &str,
+        value: ftd::interpreter::Value,
+    ) -> ftd::interpreter::Result<Interpreter> {
+        self.locating(|s| s.continue_after_variable_(module, variable, value))
+    }
+
+    fn continue_after_variable_(
         mut self,
         module: &str,
         variable: &str,
@@ -866,6 +908,22 @@ pub fn interpret_with_line_number(
     s.continue_processing()
 }
 
+/// `interpret_with_recovery()` interprets the sections of `source` that parse, and returns the
+/// errors of the ones that do not along with the result of the interpretation. `line_number` is
+/// the number of lines prepended to the document, see `interpret_with_line_number()`.
+pub fn interpret_with_recovery(
+    id: &str,
+    source: &str,
+    line_number: usize,
+) -> (
+    Vec<ftd::interpreter::Error>,
+    ftd::interpreter::Result<Interpreter>,
+) {
+    let (doc, errors) =
+        ParsedDocument::parse_with_line_number_and_recovery(id, source, line_number);
+    (errors, interpret_with_line_number(id, doc, line_number))
+}
+
 #[derive(Debug, Clone, Default, PartialEq, serde::Deserialize, serde::Serialize)]
 pub struct ParsedDocument {
     pub name: String,
@@ -876,6 +934,9 @@ pub struct ParsedDocument {
     pub exposings: ftd::Map<String>,
     pub foreign_variable: Vec<String>,
     pub foreign_function: Vec<String>,
+    /// the spans of the lines of the document, used to locate the errors reported for it
+    #[serde(default)]
+    pub spans: ftd::p1::Spans,
 }
 
 #[derive(Debug, Clone, Default, PartialEq, serde::Deserialize, serde::Serialize)]
@@ -895,10 +956,57 @@ impl ParsedDocument {
         source: &str,
         line_number: usize,
     ) -> ftd::interpreter::Result<ParsedDocument> {
-        let ast = ftd::ast::AST::from_sections(
-            ftd::p1::parse_with_line_number(source, id, line_number)?.as_slice(),
-            id,
-        )?;
+        let sections = ftd::p1::parse_with_line_number(source, id, line_number)?;
+        let spans = ftd::p1::Spans::new(&sections, source);
+        let ast = ftd::ast::AST::from_sections(sections.as_slice(), id)
+            .map_err(|e| ftd::interpreter::Error::from(e).locate(id, &spans))?;
+        Ok(ParsedDocument {
+            spans,
+            ..ParsedDocument::from_ast(id, ast)
+        })
+    }
+
+    /// `parse_with_recovery()` parses `source` like `parse()`, but does not stop at the first
+    /// error. Sections that fail to parse, or to convert to ast, are left out of the document and
+    /// their errors are returned instead.
+    pub fn parse_with_recovery(
+        id: &str,
+        source: &str,
+    ) -> (ParsedDocument, Vec<ftd::interpreter::Error>) {
+        ParsedDocument::parse_with_line_number_and_recovery(id, source, 0)
+    }
+
+    /// `parse_with_line_number_and_recovery()` is `parse_with_recovery()` for `source` with
+    /// `line_number` lines prepended to it.
+    pub fn parse_with_line_number_and_recovery(
+        id: &str,
+        source: &str,
+        line_number: usize,
+    ) -> (ParsedDocument, Vec<ftd::interpreter::Error>) {
+        let (sections, errors) =
+            ftd::p1::parse_with_line_number_and_recovery(source, id, line_number);
+        let mut errors = errors
+            .into_iter()
+            .map(ftd::interpreter::Error::from)
+            .collect::<Vec<_>>();
+        let spans = ftd::p1::Spans::new(&sections, source);
+        let mut ast = vec![];
+        for section in sections.iter() {
+            match ftd::ast::AST::from_sections(std::slice::from_ref(section), id) {
+                Ok(a) => ast.extend(a),
+                Err(e) => errors.push(ftd::interpreter::Error::from(e).locate(id, &spans)),
+            }
+        }
+        (
+            ParsedDocument {
+                spans,
+                ..ParsedDocument::from_ast(id, ast)
+            },
+            errors,
+        )
+    }
+
+    fn from_ast(id: &str, ast: Vec<ftd::ast::AST>) -> ParsedDocument {
         let (doc_aliases, re_exports, exposings) = {
             let mut doc_aliases = ftd::interpreter::default::default_aliases();
             let mut re_exports = ReExport {
@@ -939,7 +1047,7 @@ impl ParsedDocument {
             (doc_aliases, re_exports, exposings)
         };
 
-        Ok(ParsedDocument {
+        ParsedDocument {
             name: id.to_string(),
             ast,
             processing_imports: true,
@@ -948,7 +1056,8 @@ impl ParsedDocument {
             exposings,
             foreign_variable: vec![],
             foreign_function: vec![],
-        })
+            spans: Default::default(),
+        }
     }
 
     pub fn get_doc_aliases(&self) -> ftd::Map<String> {
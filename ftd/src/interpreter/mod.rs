@@ -61,6 +61,7 @@ pub enum Error {
         doc_id: String,
         line_number: usize,
         message: String,
+        span: ftd::p1::Span,
     },
 
     #[error("ValueNotFound: {doc_id}:{line_number} -> {message}")]
@@ -68,6 +69,7 @@ pub enum Error {
         doc_id: String,
         line_number: usize,
         message: String,
+        span: ftd::p1::Span,
     },
 
     #[error("ParseIntError: {}", _0)]
@@ -84,6 +86,7 @@ pub enum Error {
         message: String,
         doc_id: String,
         line_number: usize,
+        span: ftd::p1::Span,
     },
 
     #[error("EvalexprError: {}", _0)]
@@ -1,8 +1,8 @@
 pub use ftd::interpreter::constants::*;
 pub use ftd::interpreter::main::{
-    interpret, interpret_with_line_number, Document, Interpreter, InterpreterState,
-    InterpreterWithoutState, ParsedDocument, PendingImportItem, StateWithThing, ToProcess,
-    ToProcessItem,
+    interpret, interpret_with_line_number, interpret_with_recovery, Document, Interpreter,
+    InterpreterState, InterpreterWithoutState, ParsedDocument, PendingImportItem, StateWithThing,
+    ToProcess, ToProcessItem,
 };
 
 pub use ftd::interpreter::things::{
@@ -58,6 +58,25 @@ impl<'a> TDoc<'a> {
         }
     }
 
+    /// Adds `thing` to the bag, unless there already is a thing with this name. New things can
+    /// only be added while the document is being interpreted.
+    pub(crate) fn insert_thing(
+        &mut self,
+        name: &str,
+        thing: ftd::interpreter::Thing,
+        line_number: usize,
+    ) -> ftd::interpreter::Result<()> {
+        match &mut self.bag {
+            BagOrState::State(s) => {
+                s.bag.entry(name.to_string()).or_insert(thing);
+                return Ok(());
+            }
+            BagOrState::Bag(b) if b.contains_key(name) => return Ok(()),
+            BagOrState::Bag(_) => {}
+        }
+        self.err("can't add", name, "insert_thing", line_number)
+    }
+
     pub fn get_record(
         &'a self,
         name: &'a str,
@@ -249,6 +268,7 @@ impl<'a> TDoc<'a> {
                             message: format!("Can't find field `{}` in record `{}`", p1, rec_name),
                             doc_id: doc.name.to_string(),
                             line_number,
+                            span: Default::default(),
                         })?
                         .clone()
                         .resolve_with_inherited(doc, line_number, inherited_variables)?;
@@ -274,6 +294,29 @@ impl<'a> TDoc<'a> {
                             ),
                             doc_id: doc.name.to_string(),
                             line_number,
+                            span: Default::default(),
+                        })?
+                        .clone()
+                        .resolve_with_inherited(doc, line_number, inherited_variables)?;
+                    if let Some(p2) = p2 {
+                        return resolve_(
+                            p2.as_str(),
+                            &value,
+                            line_number,
+                            doc,
+                            inherited_variables,
+                        );
+                    }
+                    Ok(value)
+                }
+                ftd::interpreter::Value::Map { data, kind } => {
+                    let value = data
+                        .get(p1.as_str())
+                        .ok_or(ftd::interpreter::Error::ParseError {
+                            message: format!("Can't find key `{}` in map of kind `{:?}`", p1, kind),
+                            doc_id: doc.name.to_string(),
+                            line_number,
+                            span: Default::default(),
                         })?
                         .clone()
                         .resolve_with_inherited(doc, line_number, inherited_variables)?;
@@ -366,6 +409,7 @@ impl<'a> TDoc<'a> {
                         ),
                         doc_id: doc.name.to_string(),
                         line_number,
+                        span: Default::default(),
                     },
                 )?;
 
@@ -383,6 +427,7 @@ impl<'a> TDoc<'a> {
                                 ),
                                 doc_id: doc.name.to_string(),
                                 line_number,
+                                span: Default::default(),
                             })?
                             .to_owned();
                         if let Some(variable) =
@@ -391,6 +436,15 @@ impl<'a> TDoc<'a> {
                             return Ok(Some(variable));
                         }
                     }
+                    ftd::interpreter::Value::Map { data, .. } => {
+                        if let Some(item) = data.get(p1.as_str()) {
+                            if let Some(variable) =
+                                find_variable_reference(item, p2, doc, line_number)?
+                            {
+                                return Ok(Some(variable));
+                            }
+                        }
+                    }
                     t => {
                         return ftd::interpreter::utils::e2(
                             format!(
@@ -439,10 +493,26 @@ impl<'a> TDoc<'a> {
                                     ),
                                     doc_id: doc.name.to_string(),
                                     line_number,
+                                    span: Default::default(),
                                 },
                             )?;
                             change_value(field, set, p2, doc, line_number)?;
                         }
+                        ftd::interpreter::Value::Map { data, kind } => match data
+                            .get_mut(p1.as_str())
+                        {
+                            Some(item) => change_value(item, set, p2, doc, line_number)?,
+                            None if p2.is_none() && kind.kind.is_same_as(&set.kind()) => {
+                                data.insert(p1, set);
+                            }
+                            None => {
+                                return ftd::interpreter::utils::e2(
+                                    format!("Can't find key `{}` in map of kind `{:?}`", p1, kind),
+                                    doc.name,
+                                    line_number,
+                                )
+                            }
+                        },
                         t => {
                             return ftd::interpreter::utils::e2(
                                 format!("Expected record, found `{:?}`", t).as_str(),
@@ -490,6 +560,7 @@ impl<'a> TDoc<'a> {
                                 ),
                                 doc_id: doc.name.to_string(),
                                 line_number,
+                                span: Default::default(),
                             })?;
                         *value = ftd::interpreter::PropertyValue::Value {
                             value: resolved_value,
@@ -653,6 +724,15 @@ impl<'a> TDoc<'a> {
                         ))
                     }
                 }
+                ftd::interpreter::Kind::Map { kind } => {
+                    if let Some(remaining) = remaining {
+                        get_kind_(*kind, &remaining, doc, line_number)
+                    } else {
+                        Ok(ftd::interpreter::StateWithThing::new_thing(
+                            ftd::interpreter::KindData::new(*kind),
+                        ))
+                    }
+                }
                 ftd::interpreter::Kind::Optional { kind } => {
                     let state_with_thing = get_kind_(*kind, name, doc, line_number)?;
                     if let ftd::interpreter::StateWithThing::Thing(ref t) = state_with_thing {
@@ -862,6 +942,7 @@ impl<'a> TDoc<'a> {
                     let fields = match value.resolve(doc, line_number)?.inner() {
                         Some(ftd::interpreter::Value::Record { fields, .. }) => fields,
                         Some(ftd::interpreter::Value::Object { values }) => values,
+                        Some(ftd::interpreter::Value::Map { data, .. }) => data,
                         Some(ftd::interpreter::Value::List { data, .. }) => data
                             .into_iter()
                             .enumerate()
@@ -1283,6 +1364,7 @@ impl<'a> TDoc<'a> {
                     let fields = match value.resolve(doc, line_number)?.inner() {
                         Some(ftd::interpreter::Value::Record { fields, .. }) => fields,
                         Some(ftd::interpreter::Value::Object { values }) => values,
+                        Some(ftd::interpreter::Value::Map { data, .. }) => data,
                         Some(ftd::interpreter::Value::List { data, .. }) => data
                             .into_iter()
                             .enumerate()
@@ -1855,6 +1937,7 @@ impl<'a> TDoc<'a> {
             message: format!("Can't serialize to json: {e:?}, key={name}, found: {json:?}"),
             doc_id: self.name.to_string(),
             line_number: value.line_number(),
+            span: Default::default(),
         })?;
 
         self.as_json_(
@@ -1921,6 +2004,7 @@ impl<'a> TDoc<'a> {
                                 message: format!("Can't parse to integer, found: {json}"),
                                 doc_id: self.name.to_string(),
                                 line_number,
+                                span: Default::default(),
                             })?
                     }
                     serde_json::Value::String(s) => {
@@ -1929,6 +2013,7 @@ impl<'a> TDoc<'a> {
                                 message: format!("Can't parse to integer, found: {json}"),
                                 doc_id: self.name.to_string(),
                                 line_number,
+                                span: Default::default(),
                             })?
                     }
                     serde_json::Value::Object(o) => {
@@ -1951,6 +2036,7 @@ impl<'a> TDoc<'a> {
                                 message: format!("Can't parse to decimal, found: {json}"),
                                 doc_id: self.name.to_string(),
                                 line_number,
+                                span: Default::default(),
                             })?
                     }
                     serde_json::Value::String(s) => {
@@ -1959,6 +2045,7 @@ impl<'a> TDoc<'a> {
                                 message: format!("Can't parse to decimal, found: {json}"),
                                 doc_id: self.name.to_string(),
                                 line_number,
+                                span: Default::default(),
                             })?
                     }
                     serde_json::Value::Object(o) => {
@@ -1982,6 +2069,7 @@ impl<'a> TDoc<'a> {
                                 message: format!("Can't parse to boolean, found: {}", json),
                                 doc_id: self.name.to_string(),
                                 line_number,
+                                span: Default::default(),
                             })?
                     }
                     serde_json::Value::Object(o) => {
@@ -2005,6 +2093,9 @@ impl<'a> TDoc<'a> {
                             Some(v) => v.to_owned(),
                             None if field.kind.is_optional() => serde_json::Value::Null,
                             None if field.kind.is_list() => serde_json::Value::Array(vec![]),
+                            None if field.kind.is_map() => {
+                                serde_json::Value::Object(Default::default())
+                            }
                             None => {
                                 return ftd::interpreter::utils::e2(
                                     format!("key not found: {}", field.name.as_str()),
@@ -2087,6 +2178,31 @@ impl<'a> TDoc<'a> {
                     kind: kind.to_owned().into_kind_data(),
                 }
             }
+            ftd::interpreter::Kind::Map { kind, .. } => {
+                let mut data: ftd::Map<ftd::interpreter::PropertyValue> = Default::default();
+                if let serde_json::Value::Object(o) = json {
+                    for (key, item) in o {
+                        data.insert(
+                            key.to_string(),
+                            ftd::interpreter::PropertyValue::Value {
+                                value: self.as_json_(kind, item, None, None, line_number)?,
+                                is_mutable: false,
+                                line_number,
+                            },
+                        );
+                    }
+                } else {
+                    return ftd::interpreter::utils::e2(
+                        format!("expected object of map type, found: {}", json),
+                        self.name,
+                        line_number,
+                    );
+                }
+                ftd::interpreter::Value::Map {
+                    data,
+                    kind: kind.to_owned().into_kind_data(),
+                }
+            }
             ftd::interpreter::Kind::Optional { kind, .. } => {
                 let kind = kind.as_ref();
                 match json {
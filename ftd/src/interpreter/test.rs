@@ -258,3 +258,45 @@ fn evalexpr_test() {
         }
             */
 }
+
+#[test]
+fn error_span() {
+    let source = "-- integer x: 1\n\n-- ftd.text: $y\n";
+    let document = ftd::interpreter::ParsedDocument::parse("foo", source).unwrap();
+    let error = |doc_id: &str| ftd::interpreter::Error::ParseError {
+        message: "`y` not found".to_string(),
+        doc_id: doc_id.to_string(),
+        line_number: 3,
+        span: Default::default(),
+    };
+
+    assert_eq!(error("foo").span(), None);
+    assert_eq!(
+        error("foo").locate("foo", &document.spans).span(),
+        Some(ftd::p1::Span::new(
+            ftd::p1::Position::new(3, 1),
+            ftd::p1::Position::new(3, 16)
+        ))
+    );
+    // errors of other documents are not located with the spans of this one
+    assert_eq!(error("bar").locate("foo", &document.spans).span(), None);
+}
+
+#[test]
+fn interpret_with_recovery() {
+    // the first two lines stand for the lines prepended to the document, like auto imports
+    let source = "-- integer x: 1\n\n-- ftd.row\n\n-- integer y: 2\n";
+    let (errors, interpreter) = ftd::interpreter::interpret_with_recovery("foo", source, 2);
+    assert_eq!(errors.len(), 1);
+    assert!(matches!(
+        errors[0],
+        ftd::interpreter::Error::P1Error(ftd::p1::Error::SectionNotFound { .. })
+    ));
+
+    let document = match interpreter.unwrap() {
+        ftd::interpreter::Interpreter::Done { document } => document,
+        interpreter => panic!("expected the document, found {:?}", interpreter),
+    };
+    assert!(document.data.contains_key("foo#x"));
+    assert!(document.data.contains_key("foo#y"));
+}
@@ -46,7 +46,11 @@ impl ComponentDefinition {
             doc,
         )?;
 
-        Argument::scan_ast_fields(component_definition.arguments, doc, &Default::default())?;
+        Argument::scan_ast_fields(
+            component_definition.arguments,
+            doc,
+            &Argument::type_parameter_kinds(component_definition.type_parameters.as_slice()),
+        )?;
 
         Ok(())
     }
@@ -79,8 +83,15 @@ impl ComponentDefinition {
             component_definition.name.as_str(),
             component_definition.arguments,
             doc,
-            &Default::default(),
+            &Argument::type_parameter_kinds(component_definition.type_parameters.as_slice()),
         )?);
+        Argument::check_type_parameters(
+            name.as_str(),
+            component_definition.type_parameters.as_slice(),
+            arguments.as_slice(),
+            doc.name,
+            component_definition.line_number,
+        )?;
 
         let definition_name_with_arguments =
             (component_definition.name.as_str(), arguments.as_mut_slice());
@@ -95,6 +106,7 @@ impl ComponentDefinition {
                     .to_string(),
                 doc_id: doc.name.to_string(),
                 line_number: iteration.line_number,
+                span: Default::default(),
             });
         }
         Ok(ftd::interpreter::StateWithThing::new_thing(
@@ -227,14 +239,19 @@ impl Component {
         doc: &mut ftd::interpreter::TDoc,
     ) -> ftd::interpreter::Result<()> {
         Property::scan_ast_children(ast_component.children, definition_name_with_arguments, doc)?;
+        // generic components are erased, `card-list<person>` invokes `card-list`
+        let (name, type_arguments) = ftd::ast::utils::split_type_arguments(&ast_component.name);
         match definition_name_with_arguments {
             Some((definition, _))
-                if ast_component.name.eq(definition)
-                    || ast_component
-                        .name
-                        .starts_with(format!("{definition}.").as_str()) => {}
-            _ => doc.scan_thing(ast_component.name.as_str(), ast_component.line_number)?,
+                if name.eq(definition) || name.starts_with(format!("{definition}.").as_str()) => {}
+            _ => doc.scan_thing(name.as_str(), ast_component.line_number)?,
         }
+        ftd::interpreter::KindData::scan_type_arguments(
+            type_arguments,
+            &Default::default(),
+            doc,
+            ast_component.line_number,
+        )?;
 
         let mut loop_object_name_and_kind = None;
         if let Some(v) = ast_component.iteration {
@@ -281,7 +298,9 @@ impl Component {
         definition_name_with_arguments: &mut Option<(&str, &mut [Argument])>,
         doc: &mut ftd::interpreter::TDoc,
     ) -> ftd::interpreter::Result<ftd::interpreter::StateWithThing<Component>> {
-        let name = doc.resolve_name(ast_component.name.as_str());
+        // generic components are erased, `card-list<person>` invokes `card-list`
+        let (base_name, _) = ftd::ast::utils::split_type_arguments(&ast_component.name);
+        let name = doc.resolve_name(base_name.as_str());
 
         // If the component is from `module` type argument
         ftd::interpreter::utils::insert_module_thing(
@@ -329,7 +348,7 @@ impl Component {
         )?);
 
         if let Some(component) = try_ok_state!(Component::variable_component_from_ast(
-            ast_component.name.as_str(),
+            base_name.as_str(),
             definition_name_with_arguments,
             doc,
             &iteration,
@@ -698,6 +717,7 @@ impl Property {
                 message: "SubSection is unexpected".to_string(),
                 doc_id: doc.name.to_string(),
                 line_number,
+                span: Default::default(),
             },
         )?;
 
@@ -922,6 +942,7 @@ impl Property {
                         ),
                         doc_id: doc.name.to_string(),
                         line_number: ast_property.line_number,
+                        span: Default::default(),
                     })
                     .map(ToOwned::to_owned)?,
             )),
@@ -936,6 +957,7 @@ impl Property {
                         ),
                         doc_id: doc.name.to_string(),
                         line_number: ast_property.line_number,
+                        span: Default::default(),
                     })
                     .map(ToOwned::to_owned)?,
             )),
@@ -951,6 +973,7 @@ impl Property {
                         ),
                         doc_id: doc.name.to_string(),
                         line_number: ast_property.line_number,
+                        span: Default::default(),
                     })?
                     .to_owned();
                 if !argument.mutable.eq(mutable) {
@@ -1186,9 +1209,11 @@ impl Loop {
     ) -> ftd::interpreter::Result<ftd::interpreter::Kind> {
         let kind = self.on.kind();
         match kind {
-            ftd::interpreter::Kind::List { kind } => Ok(kind.as_ref().to_owned()),
+            ftd::interpreter::Kind::List { kind } | ftd::interpreter::Kind::Map { kind } => {
+                Ok(kind.as_ref().to_owned())
+            }
             t => ftd::interpreter::utils::e2(
-                format!("Expected list kind, found: {:?}", t),
+                format!("Expected list or map kind, found: {:?}", t),
                 doc_id,
                 self.line_number,
             ),
@@ -1259,14 +1284,44 @@ impl Loop {
         ftd::interpreter::KindData,
     )> {
         let value = self.on.clone().resolve(doc, self.line_number)?;
-        if let ftd::interpreter::Value::List { data, kind } = value {
-            Ok((data, kind))
-        } else {
-            ftd::interpreter::utils::e2(
-                format!("Expected list type data, found: {:?}", self.on),
+        match value {
+            ftd::interpreter::Value::List { data, kind } => Ok((data, kind)),
+            ftd::interpreter::Value::Map { data, kind } => Ok((data.into_values().collect(), kind)),
+            _ => ftd::interpreter::utils::e2(
+                format!("Expected list or map type data, found: {:?}", self.on),
                 doc.name,
                 self.line_number,
-            )
+            ),
+        }
+    }
+
+    /// `items()` returns, for every item of the loop, the name the item has in the loop object
+    /// (its index for a list, its key for a map) along with the value of the loop counter.
+    pub fn items(
+        &self,
+        doc: &ftd::interpreter::TDoc,
+    ) -> ftd::interpreter::Result<Vec<(String, ftd::interpreter::Value)>> {
+        let value = self.on.clone().resolve(doc, self.line_number)?;
+        match value {
+            ftd::interpreter::Value::List { data, .. } => Ok((0..data.len())
+                .map(|index| {
+                    (
+                        index.to_string(),
+                        ftd::interpreter::Value::Integer {
+                            value: index as i64,
+                        },
+                    )
+                })
+                .collect()),
+            ftd::interpreter::Value::Map { data, .. } => Ok(data
+                .into_keys()
+                .map(|key| (key.clone(), ftd::interpreter::Value::String { text: key }))
+                .collect()),
+            _ => ftd::interpreter::utils::e2(
+                format!("Expected list or map type data, found: {:?}", self.on),
+                doc.name,
+                self.line_number,
+            ),
         }
     }
 }
@@ -1389,6 +1444,7 @@ pub enum EventName {
     Change,
     Blur,
     Focus,
+    Submit,
     RivePlay(String),
     RiveStateChange(String),
     RivePause(String),
@@ -1411,6 +1467,7 @@ impl EventName {
             "change" => Ok(EventName::Change),
             "blur" => Ok(EventName::Blur),
             "focus" => Ok(EventName::Focus),
+            "submit" => Ok(EventName::Submit),
             t if t.starts_with("global-key[") && t.ends_with(']') => {
                 let keys = t
                     .trim_start_matches("global-key[")
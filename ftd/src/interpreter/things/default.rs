@@ -209,6 +209,18 @@ pub fn default_bag() -> indexmap::IndexMap<String, ftd::interpreter::Thing> {
             "ftd#checkbox".to_string(),
             ftd::interpreter::Thing::Component(checkbox_function()),
         ),
+        (
+            "ftd#form".to_string(),
+            ftd::interpreter::Thing::Component(form_function()),
+        ),
+        (
+            "ftd#select".to_string(),
+            ftd::interpreter::Thing::Component(select_function()),
+        ),
+        (
+            "ftd#radio".to_string(),
+            ftd::interpreter::Thing::Component(radio_function()),
+        ),
         (
             "ftd#image".to_string(),
             ftd::interpreter::Thing::Component(image_function()),
@@ -759,6 +771,40 @@ pub fn default_bag() -> indexmap::IndexMap<String, ftd::interpreter::Thing> {
                 external_implementation: true
             })
         ),
+        (
+            "ftd#submit-form".to_string(),
+            ftd::interpreter::Thing::Function(ftd::interpreter::Function {
+                name: "ftd#submit-form".to_string(),
+                return_kind: ftd::interpreter::KindData {
+                    kind: ftd::interpreter::Kind::void(),
+                    caption: false,
+                    body: false,
+                },
+                arguments: vec![
+                    ftd::interpreter::Argument {
+                        name: "id".to_string(),
+                        kind: ftd::interpreter::KindData {
+                            kind: ftd::interpreter::Kind::string(),
+                            caption: false,
+                            body: false,
+                        },
+                        mutable: false,
+                        value: None,
+                        access_modifier: Default::default(),
+                        line_number: 0,
+                    },
+                ],
+                expression: vec![
+                    ftd::interpreter::things::function::Expression {
+                        expression: "ftd.submit_form(id)".to_string(),
+                        line_number: 0,
+                    }
+                ],
+                js: None,
+                line_number: 0,
+                external_implementation: true
+            })
+        ),
         (
             "ftd#set-bool".to_string(),
             ftd::interpreter::Thing::Function(ftd::interpreter::Function {
@@ -9688,6 +9734,7 @@ pub fn checkbox_function() -> ftd::interpreter::ComponentDefinition {
         name: "ftd#checkbox".to_string(),
         arguments: [
             common_arguments(),
+            form_field_arguments(),
             vec![
                 ftd::interpreter::Argument::default(
                     "checked",
@@ -9718,7 +9765,20 @@ pub fn text_input_function() -> ftd::interpreter::ComponentDefinition {
         arguments: [
             text_arguments(),
             common_arguments(),
+            form_field_arguments(),
             vec![
+                ftd::interpreter::Argument::default(
+                    "pattern",
+                    ftd::interpreter::Kind::string()
+                        .into_optional()
+                        .into_kind_data(),
+                ),
+                ftd::interpreter::Argument::default(
+                    "min-length",
+                    ftd::interpreter::Kind::integer()
+                        .into_optional()
+                        .into_kind_data(),
+                ),
                 ftd::interpreter::Argument::default(
                     "placeholder",
                     ftd::interpreter::Kind::string()
@@ -9772,6 +9832,107 @@ pub fn text_input_function() -> ftd::interpreter::ComponentDefinition {
     }
 }
 
+pub fn form_function() -> ftd::interpreter::ComponentDefinition {
+    ftd::interpreter::ComponentDefinition {
+        name: "ftd#form".to_string(),
+        arguments: [
+            container_root_arguments(),
+            container_arguments(),
+            common_arguments(),
+            vec![
+                ftd::interpreter::Argument::default(
+                    "action",
+                    ftd::interpreter::Kind::string()
+                        .into_optional()
+                        .into_kind_data(),
+                ),
+                ftd::interpreter::Argument::default(
+                    "method",
+                    ftd::interpreter::Kind::string()
+                        .into_optional()
+                        .into_kind_data(),
+                ),
+            ],
+        ]
+        .concat()
+        .into_iter()
+        .collect(),
+        definition: ftd::interpreter::Component::from_name("ftd.kernel"),
+        css: None,
+        line_number: 0,
+    }
+}
+
+pub fn select_function() -> ftd::interpreter::ComponentDefinition {
+    ftd::interpreter::ComponentDefinition {
+        name: "ftd#select".to_string(),
+        arguments: [
+            common_arguments(),
+            form_field_arguments(),
+            vec![
+                ftd::interpreter::Argument::default(
+                    "options",
+                    ftd::interpreter::Kind::string()
+                        .into_list()
+                        .into_kind_data(),
+                ),
+                ftd::interpreter::Argument::default(
+                    "value",
+                    ftd::interpreter::Kind::string()
+                        .into_optional()
+                        .into_kind_data(),
+                ),
+                ftd::interpreter::Argument::default(
+                    "enabled",
+                    ftd::interpreter::Kind::boolean()
+                        .into_optional()
+                        .into_kind_data(),
+                ),
+            ],
+        ]
+        .concat()
+        .into_iter()
+        .collect(),
+        definition: ftd::interpreter::Component::from_name("ftd.kernel"),
+        css: None,
+        line_number: 0,
+    }
+}
+
+pub fn radio_function() -> ftd::interpreter::ComponentDefinition {
+    ftd::interpreter::ComponentDefinition {
+        name: "ftd#radio".to_string(),
+        arguments: [
+            common_arguments(),
+            form_field_arguments(),
+            vec![
+                ftd::interpreter::Argument::default(
+                    "value",
+                    ftd::interpreter::Kind::string().into_kind_data().caption(),
+                ),
+                ftd::interpreter::Argument::default(
+                    "checked",
+                    ftd::interpreter::Kind::boolean()
+                        .into_optional()
+                        .into_kind_data(),
+                ),
+                ftd::interpreter::Argument::default(
+                    "enabled",
+                    ftd::interpreter::Kind::boolean()
+                        .into_optional()
+                        .into_kind_data(),
+                ),
+            ],
+        ]
+        .concat()
+        .into_iter()
+        .collect(),
+        definition: ftd::interpreter::Component::from_name("ftd.kernel"),
+        css: None,
+        line_number: 0,
+    }
+}
+
 pub fn integer_function() -> ftd::interpreter::ComponentDefinition {
     ftd::interpreter::ComponentDefinition {
         name: "ftd#integer".to_string(),
@@ -10303,6 +10464,25 @@ fn container_arguments() -> Vec<ftd::interpreter::Argument> {
     ]
 }
 
+/// The arguments of the elements whose values `ftd.form` submits: the name the value is
+/// submitted with, and whether the form can be submitted without it.
+fn form_field_arguments() -> Vec<ftd::interpreter::Argument> {
+    vec![
+        ftd::interpreter::Argument::default(
+            "name",
+            ftd::interpreter::Kind::string()
+                .into_optional()
+                .into_kind_data(),
+        ),
+        ftd::interpreter::Argument::default(
+            "required",
+            ftd::interpreter::Kind::boolean()
+                .into_optional()
+                .into_kind_data(),
+        ),
+    ]
+}
+
 fn common_arguments() -> Vec<ftd::interpreter::Argument> {
     vec![
         ftd::interpreter::Argument::default(
@@ -132,6 +132,7 @@ impl Function {
                         ),
                         doc_id: doc.name.to_string(),
                         line_number,
+                        span: Default::default(),
                     })?;
             if !argument.mutable.eq(&function_value.is_mutable()) {
                 return ftd::interpreter::utils::e2(
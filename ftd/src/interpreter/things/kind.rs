@@ -16,6 +16,9 @@ pub enum Kind {
     List {
         kind: Box<Kind>,
     },
+    Map {
+        kind: Box<Kind>,
+    }, // the keys of a map are always strings, `kind` is the kind of its values
     Optional {
         kind: Box<Kind>,
     },
@@ -27,6 +30,10 @@ pub enum Kind {
     Constant {
         kind: Box<Kind>,
     },
+    TypeParameter {
+        name: String,
+        index: usize,
+    }, // a type parameter of a generic record or component, `index` is its position
     Void,
     Module,
 }
@@ -40,6 +47,7 @@ impl Kind {
             Kind::Decimal { .. } => "decimal".to_string(),
             Kind::Constant { .. } => "constant".to_string(),
             Kind::List { .. } => "list".to_string(),
+            Kind::Map { .. } => "map".to_string(),
             Kind::Object { .. } => "object".to_string(),
             Kind::OrType { name, .. } => name.clone(),
             Kind::Optional { .. } => "optional".to_string(),
@@ -47,6 +55,7 @@ impl Kind {
             Kind::Module => "module".to_string(),
             Kind::UI { name, .. } => name.clone().unwrap_or("record".to_string()),
             Kind::Record { name } => name.clone(),
+            Kind::TypeParameter { name, .. } => name.clone(),
         }
     }
 
@@ -61,6 +70,7 @@ impl Kind {
             (Self::Optional { kind, .. }, _) => kind.is_same_as(other),
             (_, Self::Optional { kind: other, .. }) => self.is_same_as(other),
             (Self::List { kind: k1 }, Self::List { kind: k2 }) => k1.is_same_as(k2),
+            (Self::Map { kind: k1 }, Self::Map { kind: k2 }) => k1.is_same_as(k2),
             _ => self.eq(other),
         }
     }
@@ -153,6 +163,12 @@ impl Kind {
         }
     }
 
+    pub fn into_map(self) -> Kind {
+        Kind::Map {
+            kind: Box::new(self),
+        }
+    }
+
     pub fn into_optional(self) -> Kind {
         Kind::Optional {
             kind: Box::new(self),
@@ -194,10 +210,28 @@ impl Kind {
         }
     }
 
+    pub fn inner_map(self) -> Kind {
+        match self {
+            Kind::Map { kind } => kind.as_ref().to_owned(),
+            t => t,
+        }
+    }
+
+    pub fn ref_inner_map(&self) -> &Kind {
+        match self {
+            Kind::Map { kind } => kind,
+            t => t,
+        }
+    }
+
     pub fn is_list(&self) -> bool {
         matches!(self, Kind::List { .. })
     }
 
+    pub fn is_map(&self) -> bool {
+        matches!(self, Kind::Map { .. })
+    }
+
     pub fn is_subsection_ui(&self) -> bool {
         matches!(
             self,
@@ -325,6 +359,119 @@ impl Kind {
             _ => None,
         }
     }
+
+    pub fn type_parameter(&self) -> Option<(&str, usize)> {
+        match self {
+            Kind::TypeParameter { name, index } => Some((name, *index)),
+            Kind::List { kind }
+            | Kind::Map { kind }
+            | Kind::Optional { kind }
+            | Kind::Constant { kind } => kind.type_parameter(),
+            _ => None,
+        }
+    }
+
+    /// Replaces the type parameters in this kind by the `type_arguments` of an instantiation.
+    pub(crate) fn substitute_type_parameters(&self, type_arguments: &[Kind]) -> Kind {
+        match self {
+            Kind::TypeParameter { index, .. } => type_arguments
+                .get(*index)
+                .cloned()
+                .unwrap_or_else(|| self.clone()),
+            Kind::List { kind } => Kind::List {
+                kind: Box::new(kind.substitute_type_parameters(type_arguments)),
+            },
+            Kind::Map { kind } => Kind::Map {
+                kind: Box::new(kind.substitute_type_parameters(type_arguments)),
+            },
+            Kind::Optional { kind } => Kind::Optional {
+                kind: Box::new(kind.substitute_type_parameters(type_arguments)),
+            },
+            Kind::Constant { kind } => Kind::Constant {
+                kind: Box::new(kind.substitute_type_parameters(type_arguments)),
+            },
+            t => t.clone(),
+        }
+    }
+
+    /// How this kind is spelled as a type argument in the name of an instantiated generic record,
+    /// like `foo#person list` in `foo#card<foo#person list>`.
+    fn type_argument_name(&self) -> String {
+        match self {
+            Kind::List { kind } => format!("{} list", kind.type_argument_name()),
+            Kind::Map { kind } => format!("{} map", kind.type_argument_name()),
+            Kind::Optional { kind } => format!("optional {}", kind.type_argument_name()),
+            Kind::Constant { kind } => kind.type_argument_name(),
+            Kind::UI { .. } => "ftd.ui".to_string(),
+            t => t.get_name(),
+        }
+    }
+
+    /// Resolves an instantiation of a generic record, like `card<person>`, to the record with its
+    /// type parameters replaced by the type arguments. The instantiated record is added to the
+    /// bag the first time it is used, so that it can be looked up like any other record.
+    fn from_generic_record(
+        kind: &str,
+        known_kinds: &ftd::Map<ftd::interpreter::Kind>,
+        doc: &mut ftd::interpreter::TDoc,
+        line_number: usize,
+    ) -> ftd::interpreter::Result<ftd::interpreter::StateWithThing<Kind>> {
+        use itertools::Itertools;
+
+        let (name, type_arguments) = ftd::ast::utils::split_type_arguments(kind);
+        let type_arguments = try_ok_state!(KindData::from_type_arguments(
+            type_arguments.as_slice(),
+            known_kinds,
+            doc,
+            line_number
+        )?);
+        if let Some((type_parameter, _)) = type_arguments.iter().find_map(|v| v.type_parameter()) {
+            return ftd::interpreter::utils::e2(
+                format!(
+                    "Type parameter `{}` can't be used as type argument of record `{}`",
+                    type_parameter, name
+                ),
+                doc.name,
+                line_number,
+            );
+        }
+
+        let record = try_ok_state!(doc.search_record(name.as_str(), line_number)?);
+        ftd::interpreter::Field::check_type_arguments(
+            record.name.as_str(),
+            record.fields.as_slice(),
+            type_arguments.as_slice(),
+            doc.name,
+            line_number,
+        )?;
+
+        let name = format!(
+            "{}<{}>",
+            record.name,
+            type_arguments
+                .iter()
+                .map(|v| v.type_argument_name())
+                .join(",")
+        );
+        let fields = record
+            .fields
+            .iter()
+            .map(|v| v.substitute_type_parameters(type_arguments.as_slice()))
+            .collect_vec();
+        doc.insert_thing(
+            name.as_str(),
+            ftd::interpreter::Thing::Record(ftd::interpreter::Record {
+                name: name.to_string(),
+                fields,
+                line_number: record.line_number,
+            }),
+            line_number,
+        )?;
+
+        Ok(ftd::interpreter::StateWithThing::new_thing(Kind::record(
+            name.as_str(),
+        )))
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
@@ -366,6 +513,7 @@ impl KindData {
         match modifier {
             ftd::ast::VariableModifier::Optional => self.optional(),
             ftd::ast::VariableModifier::List => self.list(),
+            ftd::ast::VariableModifier::Map => self.map(),
         }
     }
 
@@ -380,10 +528,59 @@ impl KindData {
             "string" | "object" | "integer" | "decimal" | "boolean" | "void" | "ftd.ui"
             | "children" => Ok(()),
             k if known_kinds.contains_key(k) => Ok(()),
+            k if k.ends_with('>') => {
+                let (name, type_arguments) = ftd::ast::utils::split_type_arguments(k);
+                doc.scan_thing(name.as_str(), line_number)?;
+                KindData::scan_type_arguments(type_arguments, known_kinds, doc, line_number)
+            }
             k => doc.scan_thing(k, line_number),
         }
     }
 
+    pub(crate) fn scan_type_arguments(
+        type_arguments: Vec<String>,
+        known_kinds: &ftd::Map<ftd::interpreter::Kind>,
+        doc: &mut ftd::interpreter::TDoc,
+        line_number: usize,
+    ) -> ftd::interpreter::Result<()> {
+        for type_argument in type_arguments {
+            let kind =
+                ftd::ast::VariableKind::get_kind(type_argument.as_str(), doc.name, line_number)?;
+            KindData::scan_ast_kind(kind, known_kinds, doc, line_number)?;
+        }
+        Ok(())
+    }
+
+    /// Resolves the type arguments of an instantiation, like `person` and `string list` in
+    /// `card<person, string list>`.
+    pub(crate) fn from_type_arguments(
+        type_arguments: &[String],
+        known_kinds: &ftd::Map<ftd::interpreter::Kind>,
+        doc: &mut ftd::interpreter::TDoc,
+        line_number: usize,
+    ) -> ftd::interpreter::Result<ftd::interpreter::StateWithThing<Vec<Kind>>> {
+        let mut kinds = vec![];
+        for type_argument in type_arguments {
+            let kind =
+                ftd::ast::VariableKind::get_kind(type_argument.as_str(), doc.name, line_number)?;
+            let kind_data = try_ok_state!(KindData::from_ast_kind(
+                kind,
+                known_kinds,
+                doc,
+                line_number
+            )?);
+            if kind_data.caption || kind_data.body {
+                return ftd::interpreter::utils::e2(
+                    format!("Type argument `{}` can't be caption or body", type_argument),
+                    doc.name,
+                    line_number,
+                );
+            }
+            kinds.push(kind_data.kind);
+        }
+        Ok(ftd::interpreter::StateWithThing::new_thing(kinds))
+    }
+
     pub fn from_ast_kind(
         var_kind: ftd::ast::VariableKind,
         known_kinds: &ftd::Map<ftd::interpreter::Kind>,
@@ -437,8 +634,20 @@ impl KindData {
                 }
             }
             k if known_kinds.contains_key(k) => known_kinds.get(k).unwrap().to_owned(),
+            k if k.ends_with('>') => {
+                try_ok_state!(Kind::from_generic_record(k, known_kinds, doc, line_number)?)
+            }
             k => match try_ok_state!(doc.search_thing(k, line_number)?) {
-                ftd::interpreter::Thing::Record(r) => Kind::record(r.name.as_str()),
+                ftd::interpreter::Thing::Record(r) => {
+                    ftd::interpreter::Field::check_type_arguments(
+                        r.name.as_str(),
+                        r.fields.as_slice(),
+                        &[],
+                        doc.name,
+                        line_number,
+                    )?;
+                    Kind::record(r.name.as_str())
+                }
                 ftd::interpreter::Thing::Component(_) => Kind::ui(),
                 ftd::interpreter::Thing::OrType(o) => Kind::or_type(o.name.as_str()),
                 ftd::interpreter::Thing::OrTypeWithVariant { or_type, variant } => {
@@ -492,10 +701,32 @@ impl KindData {
         }
     }
 
+    fn map(self) -> KindData {
+        KindData {
+            kind: Kind::Map {
+                kind: Box::new(self.kind),
+            },
+            caption: self.caption,
+            body: self.body,
+        }
+    }
+
+    pub(crate) fn substitute_type_parameters(&self, type_arguments: &[Kind]) -> KindData {
+        KindData {
+            kind: self.kind.substitute_type_parameters(type_arguments),
+            caption: self.caption,
+            body: self.body,
+        }
+    }
+
     pub fn is_list(&self) -> bool {
         self.kind.is_list()
     }
 
+    pub fn is_map(&self) -> bool {
+        self.kind.is_map()
+    }
+
     pub fn is_or_type(&self) -> bool {
         self.kind.is_or_type()
     }
@@ -560,6 +791,14 @@ impl KindData {
         }
     }
 
+    pub fn inner_map(self) -> KindData {
+        KindData {
+            kind: self.kind.inner_map(),
+            caption: self.caption,
+            body: self.body,
+        }
+    }
+
     pub fn inner(self) -> KindData {
         let kind = match self.kind {
             Kind::Optional { kind } => kind.as_ref().to_owned(),
@@ -27,11 +27,14 @@ impl Record {
         doc: &mut ftd::interpreter::TDoc,
     ) -> ftd::interpreter::Result<()> {
         let name = doc.resolve_name(record.name.as_str());
-        let known_kinds = std::iter::IntoIterator::into_iter([(
+        let mut known_kinds = std::iter::IntoIterator::into_iter([(
             record.name.to_string(),
             ftd::interpreter::Kind::record(name.as_str()),
         )])
         .collect::<ftd::Map<ftd::interpreter::Kind>>();
+        known_kinds.extend(Field::type_parameter_kinds(
+            record.type_parameters.as_slice(),
+        ));
         Field::scan_ast_fields(record.fields, doc, &known_kinds)
     }
 
@@ -48,13 +51,16 @@ impl Record {
         doc: &mut ftd::interpreter::TDoc,
     ) -> ftd::interpreter::Result<ftd::interpreter::StateWithThing<ftd::interpreter::Record>> {
         let name = doc.resolve_name(record.name.as_str());
-        let known_kinds = std::iter::IntoIterator::into_iter([(
+        let mut known_kinds = std::iter::IntoIterator::into_iter([(
             record.name.to_string(),
             ftd::interpreter::Kind::Record {
                 name: name.to_string(),
             },
         )])
         .collect::<ftd::Map<ftd::interpreter::Kind>>();
+        known_kinds.extend(Field::type_parameter_kinds(
+            record.type_parameters.as_slice(),
+        ));
         let fields = try_ok_state!(Field::from_ast_fields(
             record.name.as_str(),
             record.fields,
@@ -62,6 +68,13 @@ impl Record {
             &known_kinds
         )?);
         validate_record_fields(name.as_str(), &fields, doc.name)?;
+        Field::check_type_parameters(
+            name.as_str(),
+            record.type_parameters.as_slice(),
+            fields.as_slice(),
+            doc.name,
+            record.line_number,
+        )?;
         Ok(ftd::interpreter::StateWithThing::new_thing(Record::new(
             name.as_str(),
             fields,
@@ -415,11 +428,171 @@ impl Field {
         Ok(ftd::interpreter::StateWithThing::new_thing(
             match definition_name_with_arguments {
                 Some((name, arg)) if name.eq(&component_name) => arg.to_vec(),
+                _ if component_name.ends_with('>') => try_ok_state!(Field::for_generic_component(
+                    component_name,
+                    definition_name_with_arguments,
+                    doc,
+                    line_number
+                )?),
                 _ => try_ok_state!(doc.search_component(component_name, line_number)?).arguments,
             },
         ))
     }
 
+    /// The arguments of a generic component invoked with type arguments, like
+    /// `card-list<person>`, with its type parameters replaced by the type arguments. The type
+    /// arguments can refer to the type parameters of the component being defined.
+    fn for_generic_component(
+        component_name: &str,
+        definition_name_with_arguments: &Option<(&str, &mut [Field])>,
+        doc: &mut ftd::interpreter::TDoc,
+        line_number: usize,
+    ) -> ftd::interpreter::Result<ftd::interpreter::StateWithThing<Vec<Field>>> {
+        use itertools::Itertools;
+
+        let (name, type_arguments) = ftd::ast::utils::split_type_arguments(component_name);
+        let known_kinds = definition_name_with_arguments
+            .as_ref()
+            .map(|(_, arguments)| {
+                arguments
+                    .iter()
+                    .filter_map(|v| v.kind.kind.type_parameter())
+                    .map(|(name, index)| {
+                        (
+                            name.to_string(),
+                            ftd::interpreter::Kind::TypeParameter {
+                                name: name.to_string(),
+                                index,
+                            },
+                        )
+                    })
+                    .collect::<ftd::Map<ftd::interpreter::Kind>>()
+            })
+            .unwrap_or_default();
+        let type_arguments = try_ok_state!(ftd::interpreter::KindData::from_type_arguments(
+            type_arguments.as_slice(),
+            &known_kinds,
+            doc,
+            line_number
+        )?);
+
+        let arguments = match definition_name_with_arguments {
+            Some((definition, arguments)) if definition.eq(&name) => arguments.to_vec(),
+            _ => try_ok_state!(doc.search_component(name.as_str(), line_number)?).arguments,
+        };
+        Field::check_type_arguments(
+            name.as_str(),
+            arguments.as_slice(),
+            type_arguments.as_slice(),
+            doc.name,
+            line_number,
+        )?;
+
+        Ok(ftd::interpreter::StateWithThing::new_thing(
+            arguments
+                .iter()
+                .map(|v| v.substitute_type_parameters(type_arguments.as_slice()))
+                .collect_vec(),
+        ))
+    }
+
+    /// The kinds of the `type_parameters` of a generic record or component, used as known kinds
+    /// while resolving its fields.
+    pub(crate) fn type_parameter_kinds(
+        type_parameters: &[String],
+    ) -> ftd::Map<ftd::interpreter::Kind> {
+        type_parameters
+            .iter()
+            .enumerate()
+            .map(|(index, name)| {
+                (
+                    name.to_string(),
+                    ftd::interpreter::Kind::TypeParameter {
+                        name: name.to_string(),
+                        index,
+                    },
+                )
+            })
+            .collect()
+    }
+
+    /// Every type parameter has to be used by some field, as the type parameters of a generic
+    /// record or component are known from the kinds of its fields.
+    pub(crate) fn check_type_parameters(
+        name: &str,
+        type_parameters: &[String],
+        fields: &[Field],
+        doc_id: &str,
+        line_number: usize,
+    ) -> ftd::interpreter::Result<()> {
+        for (index, type_parameter) in type_parameters.iter().enumerate() {
+            if !fields
+                .iter()
+                .any(|v| matches!(v.kind.kind.type_parameter(), Some((_, i)) if i == index))
+            {
+                return ftd::interpreter::utils::e2(
+                    format!(
+                        "Type parameter `{}` of `{}` is not used by any of its fields",
+                        type_parameter, name
+                    ),
+                    doc_id,
+                    line_number,
+                );
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn type_parameter_count(fields: &[Field]) -> usize {
+        fields
+            .iter()
+            .filter_map(|v| v.kind.kind.type_parameter())
+            .map(|(_, index)| index + 1)
+            .max()
+            .unwrap_or_default()
+    }
+
+    /// Checks that the record or component `name`, with `fields`, is instantiated with as many
+    /// type arguments as it has type parameters.
+    pub(crate) fn check_type_arguments(
+        name: &str,
+        fields: &[Field],
+        type_arguments: &[ftd::interpreter::Kind],
+        doc_id: &str,
+        line_number: usize,
+    ) -> ftd::interpreter::Result<()> {
+        let count = Field::type_parameter_count(fields);
+        if count == type_arguments.len() {
+            return Ok(());
+        }
+
+        let message = if count == 0 {
+            format!(
+                "`{}` is not generic, found {} type argument(s)",
+                name,
+                type_arguments.len()
+            )
+        } else {
+            format!(
+                "`{}` expects {} type argument(s), found {}",
+                name,
+                count,
+                type_arguments.len()
+            )
+        };
+        ftd::interpreter::utils::e2(message, doc_id, line_number)
+    }
+
+    pub(crate) fn substitute_type_parameters(
+        &self,
+        type_arguments: &[ftd::interpreter::Kind],
+    ) -> Field {
+        Field {
+            kind: self.kind.substitute_type_parameters(type_arguments),
+            ..self.clone()
+        }
+    }
+
     pub(crate) fn for_web_component(
         component_name: &str,
         definition_name_with_arguments: &Option<(&str, &mut [Field])>,
@@ -466,6 +639,7 @@ impl Field {
                         ),
                         doc_id: doc.name.to_string(),
                         line_number,
+                        span: Default::default(),
                     })?;
 
                 check_variant_if_constant(or_variant, remaining, doc)?;
@@ -116,6 +116,7 @@ impl PropertyValue {
                         ),
                         doc_id: doc.name.to_string(),
                         line_number,
+                        span: Default::default(),
                     },
                 )
             }
@@ -548,6 +549,7 @@ impl PropertyValue {
                                     ),
                                     doc_id: doc.name.to_string(),
                                     line_number,
+                                    span: Default::default(),
                                 })?
                                 .clone();
                             result_field.insert(field.name.to_string(), property_value);
@@ -712,6 +714,7 @@ impl PropertyValue {
             message: "Need expected kind".to_string(),
             doc_id: doc.name.to_string(),
             line_number: value.line_number(),
+            span: Default::default(),
         })?;
         return get_property_value(
             value,
@@ -856,6 +859,42 @@ impl PropertyValue {
                         line_number,
                     })
                 }
+                ftd::interpreter::Kind::Map { kind } => {
+                    let line_number = value.line_number();
+                    let (_, _, headers, _, _, _) = value.get_record(doc.name)?;
+                    let kind = ftd::interpreter::KindData {
+                        kind: kind.as_ref().clone(),
+                        caption: expected_kind.caption,
+                        body: expected_kind.body,
+                    };
+                    let mut data: ftd::Map<PropertyValue> = Default::default();
+                    for header in headers.0.iter() {
+                        if data.contains_key(header.key.as_str()) {
+                            return ftd::interpreter::utils::e2(
+                                format!("Duplicate key `{}` in map", header.key),
+                                doc.name,
+                                header.line_number,
+                            );
+                        }
+                        let value = try_ok_state!(PropertyValue::from_ast_value_with_argument(
+                            header.value.clone(),
+                            doc,
+                            is_mutable,
+                            Some(&kind),
+                            definition_name_with_arguments,
+                            loop_object_name_and_kind
+                        )?);
+                        data.insert(header.key.to_string(), value);
+                    }
+                    ftd::interpreter::StateWithThing::new_thing(PropertyValue::Value {
+                        value: ftd::interpreter::Value::Map {
+                            data,
+                            kind: expected_kind.clone().inner_map(),
+                        },
+                        is_mutable,
+                        line_number,
+                    })
+                }
                 ftd::interpreter::Kind::Record { name }
                     if value.is_record() || value.is_string() =>
                 {
@@ -888,6 +927,7 @@ impl PropertyValue {
                                 ),
                                 doc_id: doc.name.to_string(),
                                 line_number: value.line_number(),
+                                span: Default::default(),
                             })?;
                         let value = match &variant {
                         ftd::interpreter::OrTypeVariant::Constant(c) => return ftd::interpreter::utils::e2(format!("Cannot pass constant variant as property, variant: `{}`. Help: Pass variant as value instead", c.name), doc.name, c.line_number),
@@ -973,6 +1013,7 @@ impl PropertyValue {
                                     ),
                                     doc_id: doc.name.to_string(),
                                     line_number: constant.line_number,
+                                    span: Default::default(),
                                 })?;
 
                         ftd::interpreter::StateWithThing::new_thing(
@@ -1462,6 +1503,10 @@ pub enum Value {
         data: Vec<PropertyValue>,
         kind: ftd::interpreter::KindData,
     },
+    Map {
+        data: ftd::Map<PropertyValue>,
+        kind: ftd::interpreter::KindData,
+    },
     Optional {
         data: Box<Option<Value>>,
         kind: ftd::interpreter::KindData,
@@ -1496,12 +1541,11 @@ impl Value {
     }
 
     pub fn is_empty(&self) -> bool {
-        if let Self::List { data, .. } = self {
-            if data.is_empty() {
-                return true;
-            }
+        match self {
+            Self::List { data, .. } => data.is_empty(),
+            Self::Map { data, .. } => data.is_empty(),
+            _ => false,
         }
-        false
     }
 
     pub fn is_record(&self, rec_name: &str) -> bool {
@@ -1613,7 +1657,8 @@ impl Value {
                 is_static
             }
             ftd::interpreter::Value::Record { fields, .. }
-            | ftd::interpreter::Value::Object { values: fields, .. } => {
+            | ftd::interpreter::Value::Object { values: fields, .. }
+            | ftd::interpreter::Value::Map { data: fields, .. } => {
                 let mut is_static = true;
                 for d in fields.values() {
                     if !d.is_static(doc) {
@@ -1636,6 +1681,7 @@ impl Value {
             Value::Object { .. } => ftd::interpreter::Kind::object(),
             Value::Record { name, .. } => ftd::interpreter::Kind::record(name),
             Value::List { kind, .. } => kind.kind.clone().into_list(),
+            Value::Map { kind, .. } => kind.kind.clone().into_map(),
             Value::Optional { kind, .. } => ftd::interpreter::Kind::Optional {
                 kind: Box::new(kind.kind.clone()),
             },
@@ -1974,7 +2020,7 @@ impl Value {
                 }
                 serde_json::to_value(&new_values).ok()
             }
-            Value::Record { fields, .. } => {
+            Value::Record { fields, .. } | Value::Map { data: fields, .. } => {
                 let mut new_values: ftd::Map<serde_json::Value> = Default::default();
                 for (k, v) in fields {
                     if let ftd::interpreter::PropertyValue::Value { value, .. } = v {
@@ -2013,9 +2059,10 @@ impl Value {
                     Some("".to_string())
                 }
             }
-            Value::Object { .. } | Value::Record { .. } | Value::List { .. } => {
-                serde_json::to_string(&self.to_serde_value()).ok()
-            }
+            Value::Object { .. }
+            | Value::Record { .. }
+            | Value::List { .. }
+            | Value::Map { .. } => serde_json::to_string(&self.to_serde_value()).ok(),
             _ => None,
         }
     }
@@ -57,6 +57,7 @@ where
         message: m.into(),
         doc_id: doc_id.to_string(),
         line_number,
+        span: Default::default(),
     })
 }
 
@@ -72,6 +73,7 @@ where
         message: message.into(),
         doc_id: doc_id.to_string(),
         line_number,
+        span: Default::default(),
     }
 }
 
@@ -301,6 +303,17 @@ pub fn get_argument_for_reference_and_remaining(
     }
     if let Some((loop_name, loop_argument, loop_counter_alias)) = loop_object_name_and_kind {
         let p2 = ftd::interpreter::utils::split_at(name, ".").1;
+        // the loop counter of a map is the key of the item
+        let counter_kind = if loop_argument
+            .value
+            .as_ref()
+            .map(|v| v.kind().is_map())
+            .unwrap_or_default()
+        {
+            ftd::interpreter::Kind::string()
+        } else {
+            ftd::interpreter::Kind::integer()
+        };
         let name = doc.resolve_name(name);
         if name.starts_with(format!("{}.", loop_name).as_str())
             || name.starts_with(format!("{}#{}.", doc.name, loop_name).as_str())
@@ -318,9 +331,7 @@ pub fn get_argument_for_reference_and_remaining(
             return Ok(Some((
                 ftd::interpreter::Field::default(
                     ftd::interpreter::FTD_LOOP_COUNTER,
-                    ftd::interpreter::Kind::integer()
-                        .into_optional()
-                        .into_kind_data(),
+                    counter_kind.clone().into_optional().into_kind_data(),
                 ),
                 None,
                 ftd::interpreter::PropertyValueSource::Loop(loop_name.to_string()),
@@ -332,9 +343,7 @@ pub fn get_argument_for_reference_and_remaining(
                 return Ok(Some((
                     ftd::interpreter::Field::default(
                         loop_counter_alias,
-                        ftd::interpreter::Kind::integer()
-                            .into_optional()
-                            .into_kind_data(),
+                        counter_kind.into_optional().into_kind_data(),
                     ),
                     None,
                     ftd::interpreter::PropertyValueSource::Loop(loop_name.to_string()),
@@ -647,6 +656,7 @@ pub(crate) fn insert_module_thing(
         doc_id: doc.name.to_string(),
         line_number,
         message: format!("{} not found in component arguments.", reference,),
+        span: Default::default(),
     })?;
     if let ftd::interpreter::Value::Module {
         things,
@@ -658,6 +668,7 @@ pub(crate) fn insert_module_thing(
             doc_id: doc.name.to_string(),
             line_number,
             message: format!("{} not found in component arguments.", reference),
+            span: Default::default(),
         })?
         .value_mut(doc.name, line_number)?
     {
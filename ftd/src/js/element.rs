@@ -16,6 +16,9 @@ pub enum Element {
     Device(Device),
     CheckBox(CheckBox),
     TextInput(TextInput),
+    Form(Form),
+    Select(Select),
+    Radio(Radio),
     Iframe(Iframe),
     Code(Code),
     Rive(Rive),
@@ -39,6 +42,9 @@ impl Element {
             "ftd#video" => Element::Video(Video::from(component)),
             "ftd#checkbox" => Element::CheckBox(CheckBox::from(component)),
             "ftd#text-input" => Element::TextInput(TextInput::from(component)),
+            "ftd#form" => Element::Form(Form::from(component)),
+            "ftd#select" => Element::Select(Select::from(component)),
+            "ftd#radio" => Element::Radio(Radio::from(component)),
             "ftd#iframe" => Element::Iframe(Iframe::from(component)),
             "ftd#code" => Element::Code(Code::from(component, doc)),
             "ftd#desktop" | "ftd#mobile" => {
@@ -125,6 +131,20 @@ impl Element {
             Element::TextInput(t) => {
                 t.to_component_statements(parent, index, doc, &mut rdata, should_return)
             }
+            Element::Form(f) => f.to_component_statements(
+                parent,
+                index,
+                doc,
+                &mut rdata,
+                should_return,
+                has_rive_components,
+            ),
+            Element::Select(s) => {
+                s.to_component_statements(parent, index, doc, &mut rdata, should_return)
+            }
+            Element::Radio(r) => {
+                r.to_component_statements(parent, index, doc, &mut rdata, should_return)
+            }
             Element::Iframe(i) => {
                 i.to_component_statements(parent, index, doc, &mut rdata, should_return)
             }
@@ -142,6 +162,7 @@ impl Element {
 pub struct CheckBox {
     pub enabled: Option<ftd::js::Value>,
     pub checked: Option<ftd::js::Value>,
+    pub form_field: FormField,
     pub common: Common,
 }
 
@@ -165,6 +186,10 @@ impl CheckBox {
                 component.properties.as_slice(),
                 component_definition.arguments.as_slice(),
             ),
+            form_field: FormField::from(
+                component.properties.as_slice(),
+                component_definition.arguments.as_slice(),
+            ),
             common: Common::from(
                 component.properties.as_slice(),
                 component_definition.arguments.as_slice(),
@@ -189,6 +214,11 @@ impl CheckBox {
             doc,
             rdata,
         ));
+        component_statements.extend(self.form_field.to_set_properties(
+            kernel.name.as_str(),
+            doc,
+            rdata,
+        ));
 
         if let Some(ref checked) = self.checked {
             component_statements.push(fastn_js::ComponentStatement::SetProperty(
@@ -225,9 +255,12 @@ pub struct TextInput {
     pub placeholder: Option<ftd::js::Value>,
     pub multiline: Option<ftd::js::Value>,
     pub max_length: Option<ftd::js::Value>,
+    pub min_length: Option<ftd::js::Value>,
+    pub pattern: Option<ftd::js::Value>,
     pub _type: Option<ftd::js::Value>,
     pub default_value: Option<ftd::js::Value>,
     pub enabled: Option<ftd::js::Value>,
+    pub form_field: FormField,
     pub common: Common,
 }
 
@@ -271,6 +304,20 @@ impl TextInput {
                 component.properties.as_slice(),
                 component_definition.arguments.as_slice(),
             ),
+            min_length: ftd::js::value::get_optional_js_value(
+                "min-length",
+                component.properties.as_slice(),
+                component_definition.arguments.as_slice(),
+            ),
+            pattern: ftd::js::value::get_optional_js_value(
+                "pattern",
+                component.properties.as_slice(),
+                component_definition.arguments.as_slice(),
+            ),
+            form_field: FormField::from(
+                component.properties.as_slice(),
+                component_definition.arguments.as_slice(),
+            ),
             common: Common::from(
                 component.properties.as_slice(),
                 component_definition.arguments.as_slice(),
@@ -295,6 +342,11 @@ impl TextInput {
             doc,
             rdata,
         ));
+        component_statements.extend(self.form_field.to_set_properties(
+            kernel.name.as_str(),
+            doc,
+            rdata,
+        ));
 
         if let Some(ref placeholder) = self.placeholder {
             component_statements.push(fastn_js::ComponentStatement::SetProperty(
@@ -356,6 +408,393 @@ impl TextInput {
                 ),
             ));
         }
+        if let Some(ref min_length) = self.min_length {
+            component_statements.push(fastn_js::ComponentStatement::SetProperty(
+                min_length.to_set_property(
+                    fastn_js::PropertyKind::InputMinLength,
+                    doc,
+                    kernel.name.as_str(),
+                    rdata,
+                ),
+            ));
+        }
+        if let Some(ref pattern) = self.pattern {
+            component_statements.push(fastn_js::ComponentStatement::SetProperty(
+                pattern.to_set_property(
+                    fastn_js::PropertyKind::InputPattern,
+                    doc,
+                    kernel.name.as_str(),
+                    rdata,
+                ),
+            ));
+        }
+
+        if should_return {
+            component_statements.push(fastn_js::ComponentStatement::Return {
+                component_name: kernel.name,
+            });
+        }
+        component_statements
+    }
+}
+
+/// The properties of the elements whose values `ftd.form` submits.
+#[derive(Debug)]
+pub struct FormField {
+    pub name: Option<ftd::js::Value>,
+    pub required: Option<ftd::js::Value>,
+}
+
+impl FormField {
+    pub fn from(
+        properties: &[ftd::interpreter::Property],
+        arguments: &[ftd::interpreter::Argument],
+    ) -> FormField {
+        FormField {
+            name: ftd::js::value::get_optional_js_value("name", properties, arguments),
+            required: ftd::js::value::get_optional_js_value("required", properties, arguments),
+        }
+    }
+
+    pub fn to_set_properties(
+        &self,
+        element_name: &str,
+        doc: &ftd::interpreter::TDoc,
+        rdata: &ftd::js::ResolverData,
+    ) -> Vec<fastn_js::ComponentStatement> {
+        let mut component_statements = vec![];
+        if let Some(ref name) = self.name {
+            component_statements.push(fastn_js::ComponentStatement::SetProperty(
+                name.to_set_property(fastn_js::PropertyKind::InputName, doc, element_name, rdata),
+            ));
+        }
+        if let Some(ref required) = self.required {
+            component_statements.push(fastn_js::ComponentStatement::SetProperty(
+                required.to_set_property(
+                    fastn_js::PropertyKind::Required,
+                    doc,
+                    element_name,
+                    rdata,
+                ),
+            ));
+        }
+        component_statements
+    }
+}
+
+#[derive(Debug)]
+pub struct Form {
+    pub action: Option<ftd::js::Value>,
+    pub method: Option<ftd::js::Value>,
+    pub container: Container,
+    pub container_properties: ContainerProperties,
+    pub common: Common,
+}
+
+impl Form {
+    pub fn from(component: &ftd::interpreter::Component) -> Form {
+        let component_definition = ftd::interpreter::default::default_bag()
+            .get("ftd#form")
+            .unwrap()
+            .clone()
+            .component()
+            .unwrap();
+
+        Form {
+            action: ftd::js::value::get_optional_js_value(
+                "action",
+                component.properties.as_slice(),
+                component_definition.arguments.as_slice(),
+            ),
+            method: ftd::js::value::get_optional_js_value(
+                "method",
+                component.properties.as_slice(),
+                component_definition.arguments.as_slice(),
+            ),
+            container: Container::from(
+                component.properties.as_slice(),
+                component_definition.arguments.as_slice(),
+            ),
+            container_properties: ContainerProperties::from(
+                component.properties.as_slice(),
+                component_definition.arguments.as_slice(),
+            ),
+            common: Common::from(
+                component.properties.as_slice(),
+                component_definition.arguments.as_slice(),
+                component.events.as_slice(),
+            ),
+        }
+    }
+
+    pub fn to_component_statements(
+        &self,
+        parent: &str,
+        index: usize,
+        doc: &ftd::interpreter::TDoc,
+        rdata: &mut ftd::js::ResolverData,
+        should_return: bool,
+        has_rive_components: &mut bool,
+    ) -> Vec<fastn_js::ComponentStatement> {
+        let mut component_statements = vec![];
+        let kernel = create_element(fastn_js::ElementKind::Form, parent, index, rdata);
+        component_statements.push(fastn_js::ComponentStatement::CreateKernel(kernel.clone()));
+        component_statements.extend(self.common.to_set_properties(
+            kernel.name.as_str(),
+            doc,
+            rdata,
+        ));
+
+        if let Some(ref action) = self.action {
+            component_statements.push(fastn_js::ComponentStatement::SetProperty(
+                action.to_set_property(
+                    fastn_js::PropertyKind::FormAction,
+                    doc,
+                    kernel.name.as_str(),
+                    rdata,
+                ),
+            ));
+        }
+        if let Some(ref method) = self.method {
+            component_statements.push(fastn_js::ComponentStatement::SetProperty(
+                method.to_set_property(
+                    fastn_js::PropertyKind::FormMethod,
+                    doc,
+                    kernel.name.as_str(),
+                    rdata,
+                ),
+            ));
+        }
+
+        component_statements.extend(self.container_properties.to_set_properties(
+            kernel.name.as_str(),
+            doc,
+            rdata,
+        ));
+
+        component_statements.extend(self.container.to_component_statements(
+            doc,
+            rdata,
+            has_rive_components,
+            false,
+        ));
+
+        if should_return {
+            component_statements.push(fastn_js::ComponentStatement::Return {
+                component_name: kernel.name,
+            });
+        }
+        component_statements
+    }
+}
+
+#[derive(Debug)]
+pub struct Select {
+    pub options: Option<ftd::js::Value>,
+    pub value: Option<ftd::js::Value>,
+    pub enabled: Option<ftd::js::Value>,
+    pub form_field: FormField,
+    pub common: Common,
+}
+
+impl Select {
+    pub fn from(component: &ftd::interpreter::Component) -> Select {
+        let component_definition = ftd::interpreter::default::default_bag()
+            .get("ftd#select")
+            .unwrap()
+            .clone()
+            .component()
+            .unwrap();
+
+        Select {
+            options: ftd::js::value::get_optional_js_value(
+                "options",
+                component.properties.as_slice(),
+                component_definition.arguments.as_slice(),
+            ),
+            value: ftd::js::value::get_optional_js_value(
+                "value",
+                component.properties.as_slice(),
+                component_definition.arguments.as_slice(),
+            ),
+            enabled: ftd::js::value::get_optional_js_value(
+                "enabled",
+                component.properties.as_slice(),
+                component_definition.arguments.as_slice(),
+            ),
+            form_field: FormField::from(
+                component.properties.as_slice(),
+                component_definition.arguments.as_slice(),
+            ),
+            common: Common::from(
+                component.properties.as_slice(),
+                component_definition.arguments.as_slice(),
+                component.events.as_slice(),
+            ),
+        }
+    }
+
+    pub fn to_component_statements(
+        &self,
+        parent: &str,
+        index: usize,
+        doc: &ftd::interpreter::TDoc,
+        rdata: &mut ftd::js::ResolverData,
+        should_return: bool,
+    ) -> Vec<fastn_js::ComponentStatement> {
+        let mut component_statements = vec![];
+        let kernel = create_element(fastn_js::ElementKind::Select, parent, index, rdata);
+        component_statements.push(fastn_js::ComponentStatement::CreateKernel(kernel.clone()));
+        component_statements.extend(self.common.to_set_properties(
+            kernel.name.as_str(),
+            doc,
+            rdata,
+        ));
+        component_statements.extend(self.form_field.to_set_properties(
+            kernel.name.as_str(),
+            doc,
+            rdata,
+        ));
+
+        // the options are created before the value, which selects one of them
+        if let Some(ref options) = self.options {
+            component_statements.push(fastn_js::ComponentStatement::SetProperty(
+                options.to_set_property(
+                    fastn_js::PropertyKind::SelectOptions,
+                    doc,
+                    kernel.name.as_str(),
+                    rdata,
+                ),
+            ));
+        }
+        if let Some(ref value) = self.value {
+            component_statements.push(fastn_js::ComponentStatement::SetProperty(
+                value.to_set_property(
+                    fastn_js::PropertyKind::SelectValue,
+                    doc,
+                    kernel.name.as_str(),
+                    rdata,
+                ),
+            ));
+        }
+        if let Some(ref enabled) = self.enabled {
+            component_statements.push(fastn_js::ComponentStatement::SetProperty(
+                enabled.to_set_property(
+                    fastn_js::PropertyKind::Enabled,
+                    doc,
+                    kernel.name.as_str(),
+                    rdata,
+                ),
+            ));
+        }
+
+        if should_return {
+            component_statements.push(fastn_js::ComponentStatement::Return {
+                component_name: kernel.name,
+            });
+        }
+        component_statements
+    }
+}
+
+#[derive(Debug)]
+pub struct Radio {
+    pub value: Option<ftd::js::Value>,
+    pub checked: Option<ftd::js::Value>,
+    pub enabled: Option<ftd::js::Value>,
+    pub form_field: FormField,
+    pub common: Common,
+}
+
+impl Radio {
+    pub fn from(component: &ftd::interpreter::Component) -> Radio {
+        let component_definition = ftd::interpreter::default::default_bag()
+            .get("ftd#radio")
+            .unwrap()
+            .clone()
+            .component()
+            .unwrap();
+
+        Radio {
+            value: ftd::js::value::get_optional_js_value(
+                "value",
+                component.properties.as_slice(),
+                component_definition.arguments.as_slice(),
+            ),
+            checked: ftd::js::value::get_optional_js_value(
+                "checked",
+                component.properties.as_slice(),
+                component_definition.arguments.as_slice(),
+            ),
+            enabled: ftd::js::value::get_optional_js_value(
+                "enabled",
+                component.properties.as_slice(),
+                component_definition.arguments.as_slice(),
+            ),
+            form_field: FormField::from(
+                component.properties.as_slice(),
+                component_definition.arguments.as_slice(),
+            ),
+            common: Common::from(
+                component.properties.as_slice(),
+                component_definition.arguments.as_slice(),
+                component.events.as_slice(),
+            ),
+        }
+    }
+
+    pub fn to_component_statements(
+        &self,
+        parent: &str,
+        index: usize,
+        doc: &ftd::interpreter::TDoc,
+        rdata: &mut ftd::js::ResolverData,
+        should_return: bool,
+    ) -> Vec<fastn_js::ComponentStatement> {
+        let mut component_statements = vec![];
+        let kernel = create_element(fastn_js::ElementKind::Radio, parent, index, rdata);
+        component_statements.push(fastn_js::ComponentStatement::CreateKernel(kernel.clone()));
+        component_statements.extend(self.common.to_set_properties(
+            kernel.name.as_str(),
+            doc,
+            rdata,
+        ));
+        component_statements.extend(self.form_field.to_set_properties(
+            kernel.name.as_str(),
+            doc,
+            rdata,
+        ));
+
+        if let Some(ref value) = self.value {
+            component_statements.push(fastn_js::ComponentStatement::SetProperty(
+                value.to_set_property(
+                    fastn_js::PropertyKind::InputValue,
+                    doc,
+                    kernel.name.as_str(),
+                    rdata,
+                ),
+            ));
+        }
+        if let Some(ref checked) = self.checked {
+            component_statements.push(fastn_js::ComponentStatement::SetProperty(
+                checked.to_set_property(
+                    fastn_js::PropertyKind::Checked,
+                    doc,
+                    kernel.name.as_str(),
+                    rdata,
+                ),
+            ));
+        }
+        if let Some(ref enabled) = self.enabled {
+            component_statements.push(fastn_js::ComponentStatement::SetProperty(
+                enabled.to_set_property(
+                    fastn_js::PropertyKind::Enabled,
+                    doc,
+                    kernel.name.as_str(),
+                    rdata,
+                ),
+            ));
+        }
 
         if should_return {
             component_statements.push(fastn_js::ComponentStatement::Return {
@@ -3070,6 +3509,7 @@ impl ftd::interpreter::EventName {
             ftd::interpreter::EventName::Change => Some(fastn_js::Event::Change),
             ftd::interpreter::EventName::Blur => Some(fastn_js::Event::Blur),
             ftd::interpreter::EventName::Focus => Some(fastn_js::Event::Focus),
+            ftd::interpreter::EventName::Submit => Some(fastn_js::Event::Submit),
             ftd::interpreter::EventName::RivePlay(_)
             | ftd::interpreter::EventName::RivePause(_)
             | ftd::interpreter::EventName::RiveStateChange(_) => None,
@@ -3090,6 +3530,9 @@ pub fn is_kernel(s: &str) -> bool {
         "ftd#mobile",
         "ftd#checkbox",
         "ftd#text-input",
+        "ftd#form",
+        "ftd#select",
+        "ftd#radio",
         "ftd#iframe",
         "ftd#code",
         "ftd#image",
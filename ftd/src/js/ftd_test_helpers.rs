@@ -127,7 +127,8 @@ fn p(s: &str, t: &str, fix: bool, manual: bool, script: bool, file_location: &st
             let ssr_body = fastn_js::ssr_with_js_string(
                 "foo",
                 format!("{js_ftd_script}\n{js_document_script}").as_str(),
-            );
+            )
+            .unwrap_or_else(|e| panic!("{}", e));
 
             ftd::ftd_js_html()
                 .replace("__fastn_package__", dummy_package_data.as_str())
@@ -159,9 +160,11 @@ fn p(s: &str, t: &str, fix: bool, manual: bool, script: bool, file_location: &st
                             <script src="../../prism/prism-javascript.js"></script>
                             <link rel="stylesheet" href="../../prism/prism-line-highlight.css">
                             <link rel="stylesheet" href="../../prism/prism-line-numbers.css">
-                            <script>{}</script>
+                            <script>{}{}{}</script>
                         "#,
-                                ftd::js::all_js_without_test("foo")
+                                fastn_js::all_js_without_test(),
+                                fastn_js::web_component_js(),
+                                js_ftd_script
                             )
                         } else {
                             "<script src=\"fastn-js.js\"></script>".to_string()
@@ -14,12 +14,63 @@ pub use value::Value;
 
 pub const CODE_DEFAULT_THEME: &str = "fastn-theme.dark";
 
+/// `all_js_without_test()` is the runtime and the default bag, shared by every page of the
+/// package. The functions of the `ftd` module are left out, see `default_functions_into_js_ast()`.
 pub fn all_js_without_test(package_name: &str) -> String {
     let all_js = fastn_js::all_js_without_test();
-    let default_bag_js = fastn_js::to_js(default_bag_into_js_ast().as_slice(), false, package_name);
+    let default_bag_js = fastn_js::to_js(
+        default_bag_into_js_ast()
+            .into_iter()
+            .filter(|ast| !is_default_function(ast))
+            .collect::<Vec<_>>()
+            .as_slice(),
+        false,
+        package_name,
+    );
     format!("{all_js}\n{default_bag_js}")
 }
 
+/// `default_functions_into_js_ast()` is the asts of the functions of the `ftd` module in
+/// `functions`, the ones a page calls, see `fastn_js::Features`. A page carries these along with
+/// its own js, so pages only get the functions they use.
+pub fn default_functions_into_js_ast(
+    functions: &std::collections::BTreeSet<String>,
+) -> Vec<fastn_js::Ast> {
+    let asts = default_bag_into_js_ast();
+    let mut functions = functions.clone();
+    for ast in asts.iter() {
+        if let fastn_js::Ast::Export { from, to } = ast {
+            if functions.contains(to) {
+                functions.insert(from.to_string());
+            }
+        }
+    }
+    let (exports, mut udfs): (Vec<_>, Vec<_>) = asts
+        .into_iter()
+        .filter(|ast| match ast {
+            fastn_js::Ast::UDF(udf) => functions.contains(&udf.name),
+            fastn_js::Ast::Export { to, .. } => is_default_function(ast) && functions.contains(to),
+            _ => false,
+        })
+        .partition(|ast| matches!(ast, fastn_js::Ast::Export { .. }));
+    // an export can only alias a function once the function is defined
+    udfs.extend(exports);
+    udfs
+}
+
+/// `is_default_function()` checks if `ast`, from `default_bag_into_js_ast()`, is a function of
+/// the `ftd` module or an export of one.
+fn is_default_function(ast: &fastn_js::Ast) -> bool {
+    match ast {
+        fastn_js::Ast::UDF(_) => true,
+        fastn_js::Ast::Export { from, .. } => matches!(
+            ftd::interpreter::default::default_bag().get(from),
+            Some(ftd::interpreter::Thing::Function(f)) if !f.external_implementation
+        ),
+        _ => false,
+    }
+}
+
 /// This returns asts of things present in `ftd` module or `default_bag`
 pub fn default_bag_into_js_ast() -> Vec<fastn_js::Ast> {
     let mut ftd_asts = vec![];
@@ -156,7 +207,11 @@ impl ftd::interpreter::Function {
             self.expression
                 .iter()
                 .map(|e| {
-                    fastn_grammar::evalexpr::build_operator_tree(e.expression.as_str()).unwrap()
+                    let mut node =
+                        fastn_grammar::evalexpr::build_operator_tree(e.expression.as_str())
+                            .unwrap();
+                    ftd::js::utils::add_http_response_kind(&mut node, &self.arguments, doc);
+                    node
                 })
                 .collect_vec(),
             self.arguments
@@ -221,6 +276,14 @@ impl ftd::interpreter::Variable {
                     }),
                     prefix,
                 });
+            } else if self.kind.is_map() {
+                return fastn_js::Ast::RecordInstance(fastn_js::RecordInstance {
+                    name: self.name.to_string(),
+                    fields: self
+                        .value
+                        .to_fastn_js_value_with_none(doc, has_rive_components),
+                    prefix,
+                });
             } else if self.kind.is_list() {
                 // Todo: It should be only for Mutable not Static
                 return fastn_js::Ast::MutableList(fastn_js::MutableList {
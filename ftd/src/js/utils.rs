@@ -287,3 +287,125 @@ pub(crate) fn get_set_property_values_for_provided_component_properties(
         })
         .ok()
 }
+
+/// `ftd.http(url, method, body, headers, response, loading, error)` sets the JSON it gets back
+/// to the `response` variable. The JSON is converted in the browser the way `TDoc::from_json()`
+/// converts it on the server, for which `ftd.http` needs the kind of `response`. It is passed to
+/// every `ftd.http` call, whose `response` is an argument of the function or a variable, as an
+/// extra, last argument: a string constant with the kind, see `kind_to_json()`.
+pub(crate) fn add_http_response_kind(
+    node: &mut fastn_grammar::evalexpr::ExprNode,
+    arguments: &[ftd::interpreter::Argument],
+    doc: &ftd::interpreter::TDoc,
+) {
+    const RESPONSE: usize = 4;
+    const KIND: usize = 7;
+
+    if matches!(
+        node.operator(),
+        fastn_grammar::evalexpr::Operator::FunctionIdentifier { identifier }
+            if identifier.eq("ftd.http")
+    ) {
+        if let Some(http_arguments) = function_call_arguments(node) {
+            let kind = http_arguments
+                .get(RESPONSE)
+                .and_then(|v| v.operator().get_variable_identifier_read())
+                .and_then(|name| match arguments.iter().find(|v| v.name.eq(&name)) {
+                    Some(argument) => Some(argument.kind.kind.clone()),
+                    None => doc.get_variable(name.as_str(), 0).ok().map(|v| v.kind.kind),
+                })
+                .map(|kind| kind_to_json(&kind, doc, &mut vec![]));
+            if let Some(kind) = kind {
+                while http_arguments.len() < KIND {
+                    http_arguments.push(fastn_grammar::evalexpr::ExprNode::new(
+                        fastn_grammar::evalexpr::Operator::Const {
+                            value: fastn_grammar::evalexpr::Value::Empty,
+                        },
+                    ));
+                }
+                http_arguments.truncate(KIND);
+                http_arguments.push(fastn_grammar::evalexpr::ExprNode::new(
+                    fastn_grammar::evalexpr::Operator::Const {
+                        value: fastn_grammar::evalexpr::Value::String(js_string_content(
+                            kind.to_string().as_str(),
+                        )),
+                    },
+                ));
+            }
+        }
+    }
+
+    for child in node.children_mut() {
+        add_http_response_kind(child, arguments, doc);
+    }
+}
+
+/// `js_string_content()` escapes `s` to go between the quotes of a js string, the way a string
+/// constant of an expression is written out.
+fn js_string_content(s: &str) -> String {
+    let quoted = serde_json::Value::String(s.to_string()).to_string();
+    quoted[1..quoted.len() - 1].to_string()
+}
+
+/// The arguments of a function call node: `f(a, b)` is the function identifier with a root node
+/// holding the tuple of its arguments.
+fn function_call_arguments(
+    node: &mut fastn_grammar::evalexpr::ExprNode,
+) -> Option<&mut Vec<fastn_grammar::evalexpr::ExprNode>> {
+    let root = node.children_mut().first_mut()?;
+    if !matches!(root.operator(), fastn_grammar::evalexpr::Operator::Tuple) {
+        let tuple = root.children_mut().first_mut()?;
+        if !matches!(tuple.operator(), fastn_grammar::evalexpr::Operator::Tuple) {
+            return None;
+        }
+        return Some(tuple.children_mut());
+    }
+    Some(root.children_mut())
+}
+
+/// The description of `kind` that `fastn_utils.fromJson()` converts JSON with. Records refer to
+/// their fields by both their ftd name, the key in the JSON, and their JS name.
+fn kind_to_json(
+    kind: &ftd::interpreter::Kind,
+    doc: &ftd::interpreter::TDoc,
+    records: &mut Vec<String>,
+) -> serde_json::Value {
+    match kind {
+        ftd::interpreter::Kind::String => serde_json::json!("string"),
+        ftd::interpreter::Kind::Integer => serde_json::json!("integer"),
+        ftd::interpreter::Kind::Decimal => serde_json::json!("decimal"),
+        ftd::interpreter::Kind::Boolean => serde_json::json!("boolean"),
+        ftd::interpreter::Kind::Optional { kind } => {
+            serde_json::json!({ "optional": kind_to_json(kind, doc, records) })
+        }
+        ftd::interpreter::Kind::List { kind } => {
+            serde_json::json!({ "list": kind_to_json(kind, doc, records) })
+        }
+        ftd::interpreter::Kind::Map { kind } => {
+            serde_json::json!({ "map": kind_to_json(kind, doc, records) })
+        }
+        // a recursive record is converted as it is below its first level
+        ftd::interpreter::Kind::Record { name } if !records.contains(name) => {
+            let record = match doc.get_record(name, 0) {
+                Ok(record) => record,
+                Err(_) => return serde_json::json!("any"),
+            };
+            records.push(name.to_string());
+            let fields = record
+                .fields
+                .iter()
+                .map(|field| {
+                    serde_json::json!({
+                        "name": field.name,
+                        "key": fastn_js::utils::name_to_js_(field.name.as_str()),
+                        "kind": kind_to_json(&field.kind.kind, doc, records),
+                        "caption": field.kind.caption,
+                    })
+                })
+                .collect::<Vec<_>>();
+            records.pop();
+            serde_json::json!({ "record": fields })
+        }
+        _ => serde_json::json!("any"),
+    }
+}
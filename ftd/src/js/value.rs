@@ -189,6 +189,11 @@ impl ftd::interpreter::Argument {
                 data: vec![],
                 kind: self.kind.clone(),
             }))
+        } else if self.kind.is_map() {
+            Some(ftd::js::Value::Data(ftd::interpreter::Value::Map {
+                data: Default::default(),
+                kind: self.kind.clone().inner_map(),
+            }))
         } else if self.kind.is_optional() {
             Some(ftd::js::Value::Data(ftd::interpreter::Value::Optional {
                 data: Box::new(None),
@@ -376,7 +381,8 @@ impl ftd::interpreter::Value {
                         .collect_vec(),
                 })
             }
-            ftd::interpreter::Value::Record { fields, .. } => {
+            ftd::interpreter::Value::Record { fields, .. }
+            | ftd::interpreter::Value::Map { data: fields, .. } => {
                 fastn_js::SetPropertyValue::Value(fastn_js::Value::Record {
                     fields: fields
                         .iter()
@@ -2,6 +2,7 @@
 
 extern crate self as ftd;
 
+pub use diagnostic::Diagnostic;
 pub use ftd2021::component::{ChildComponent, Component, Instruction};
 pub use ftd2021::condition::Condition;
 pub use ftd2021::constants::{identifier, regex};
@@ -17,6 +18,7 @@ pub use ftd2021::value_with_default::ValueWithDefault;
 pub use ftd2021::variable::{PropertyValue, TextSource, Value, Variable, VariableFlags};
 
 pub mod ast;
+mod diagnostic;
 pub mod executor;
 pub mod ftd2021;
 pub mod html;
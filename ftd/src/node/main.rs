@@ -242,6 +242,9 @@ impl ftd::executor::Element {
             ftd::executor::Element::Iframe(i) => i.to_node(doc_id, anchor_ids),
             ftd::executor::Element::TextInput(i) => i.to_node(doc_id, anchor_ids),
             ftd::executor::Element::CheckBox(c) => c.to_node(doc_id, anchor_ids),
+            ftd::executor::Element::Form(f) => f.to_node(doc_id, anchor_ids),
+            ftd::executor::Element::Select(s) => s.to_node(doc_id, anchor_ids),
+            ftd::executor::Element::Radio(r) => r.to_node(doc_id, anchor_ids),
             ftd::executor::Element::Rive(r) => r.to_node(doc_id, anchor_ids),
             ftd::executor::Element::Null { line_number } => Node {
                 classes: vec![],
@@ -999,6 +1002,179 @@ impl ftd::executor::CheckBox {
     }
 }
 
+impl ftd::executor::Form {
+    pub fn to_node(&self, doc_id: &str, anchor_ids: &mut Vec<String>) -> Node {
+        use ftd::node::utils::CheckMap;
+
+        // a form is laid out as a column
+        let mut n = ftd::executor::Column {
+            container: self.container.to_owned(),
+            common: self.common.to_owned(),
+        }
+        .to_node(doc_id, anchor_ids);
+        n.node = s("form");
+
+        n.attrs.check_and_insert(
+            "action",
+            ftd::node::Value::from_executor_value(
+                self.action.to_owned().value,
+                self.action.to_owned(),
+                None,
+                doc_id,
+            ),
+        );
+
+        n.attrs.check_and_insert(
+            "method",
+            ftd::node::Value::from_executor_value(
+                self.method.to_owned().value,
+                self.method.to_owned(),
+                None,
+                doc_id,
+            ),
+        );
+        n
+    }
+}
+
+impl ftd::executor::FormField {
+    fn insert_attrs(&self, n: &mut Node, doc_id: &str) {
+        use ftd::node::utils::CheckMap;
+
+        n.attrs.check_and_insert(
+            "name",
+            ftd::node::Value::from_executor_value(
+                self.name.to_owned().value,
+                self.name.to_owned(),
+                None,
+                doc_id,
+            ),
+        );
+
+        n.attrs.check_and_insert(
+            "required",
+            ftd::node::Value::from_executor_value(
+                self.required
+                    .to_owned()
+                    .map(|v| {
+                        v.map(|b| {
+                            if b {
+                                s(ftd::interpreter::FTD_NO_VALUE)
+                            } else {
+                                s(ftd::interpreter::FTD_IGNORE_KEY)
+                            }
+                        })
+                    })
+                    .value,
+                self.required.to_owned(),
+                Some(ftd::executor::CheckBox::checked_pattern()),
+                doc_id,
+            ),
+        );
+
+        n.attrs.check_and_insert(
+            "disabled",
+            ftd::node::Value::from_executor_value(
+                self.enabled
+                    .to_owned()
+                    .map(|v| {
+                        v.map(|b| {
+                            if b {
+                                s(ftd::interpreter::FTD_IGNORE_KEY)
+                            } else {
+                                s(ftd::interpreter::FTD_NO_VALUE)
+                            }
+                        })
+                    })
+                    .value,
+                self.enabled.to_owned(),
+                Some(ftd::executor::CheckBox::enabled_pattern()),
+                doc_id,
+            ),
+        );
+    }
+}
+
+impl ftd::executor::Select {
+    pub fn to_node(&self, doc_id: &str, anchor_ids: &mut Vec<String>) -> Node {
+        let mut n = Node::from_common("select", "block", &self.common, doc_id, anchor_ids);
+        self.field.insert_attrs(&mut n, doc_id);
+
+        n.children = self
+            .options
+            .value
+            .iter()
+            .map(|option| {
+                let mut attrs: ftd::Map<ftd::node::Value> = Default::default();
+                attrs.insert(s("value"), ftd::node::Value::from_string(option));
+                if self.value.value.as_ref().map_or(false, |v| v.eq(option)) {
+                    attrs.insert(
+                        s("selected"),
+                        ftd::node::Value::from_string(ftd::interpreter::FTD_NO_VALUE),
+                    );
+                }
+                Node {
+                    node: s("option"),
+                    display: s("block"),
+                    attrs,
+                    text: ftd::node::Value::from_string(option),
+                    line_number: self.common.line_number,
+                    ..Default::default()
+                }
+            })
+            .collect();
+
+        n.classes.extend(self.common.add_class());
+        n
+    }
+}
+
+impl ftd::executor::Radio {
+    pub fn to_node(&self, doc_id: &str, anchor_ids: &mut Vec<String>) -> Node {
+        use ftd::node::utils::CheckMap;
+
+        let mut n = Node::from_common("input", "block", &self.common, doc_id, anchor_ids);
+
+        n.attrs
+            .check_and_insert("type", ftd::node::Value::from_string(s("radio")));
+        self.field.insert_attrs(&mut n, doc_id);
+
+        n.attrs.check_and_insert(
+            "value",
+            ftd::node::Value::from_executor_value(
+                self.value.to_owned().value,
+                self.value.to_owned(),
+                None,
+                doc_id,
+            ),
+        );
+
+        n.attrs.check_and_insert(
+            "checked",
+            ftd::node::Value::from_executor_value(
+                self.checked
+                    .to_owned()
+                    .map(|v| {
+                        v.map(|b| {
+                            if b {
+                                s(ftd::interpreter::FTD_NO_VALUE)
+                            } else {
+                                s(ftd::interpreter::FTD_IGNORE_KEY)
+                            }
+                        })
+                    })
+                    .value,
+                self.checked.to_owned(),
+                Some(ftd::executor::CheckBox::checked_pattern()),
+                doc_id,
+            ),
+        );
+
+        n.classes.extend(self.common.add_class());
+        n
+    }
+}
+
 impl ftd::executor::Image {
     pub fn to_node(&self, doc_id: &str, anchor_ids: &mut Vec<String>) -> Node {
         return if self.common.link.value.is_some() {
@@ -0,0 +1,558 @@
+use ftd::p1::parser::{clean_line, valid_line};
+
+#[derive(Debug, Default, Clone)]
+pub struct FormatOptions {
+    /// sort the inline headers of component invocations by key. Headers of definitions
+    /// (`-- record`, `-- component` etc.) are never sorted, as their order is significant.
+    pub sort_headers: bool,
+}
+
+/// `format()` pretty-prints an ftd document: sections start on unindented lines, headers and
+/// values get a single space after `:`, top level sections are separated by two empty lines and
+/// nested sections by one, and every section with children is closed by its own `-- end:`.
+///
+/// Comments, `/`-commented sections and headers, and the text of bodies are kept as they are.
+/// The formatted document is parsed again and compared with the original, if they differ (or a
+/// comment could not be kept) an error is returned, so formatting never changes what a document
+/// means.
+pub fn format(source: &str, doc_id: &str) -> ftd::p1::Result<String> {
+    format_with_options(source, doc_id, &Default::default())
+}
+
+pub fn format_with_options(
+    source: &str,
+    doc_id: &str,
+    options: &FormatOptions,
+) -> ftd::p1::Result<String> {
+    let sections = ftd::p1::parse(source, doc_id)?;
+
+    let mut formatter = Formatter::new(source, options);
+    let mut previous: Option<&ftd::p1::Section> = None;
+    for section in sections.iter() {
+        match previous {
+            Some(previous) if is_bare_import(previous) && is_bare_import(section) => {}
+            Some(_) => formatter.empty_lines(2),
+            None => {}
+        }
+        formatter.section(section);
+        previous = Some(section);
+    }
+    if !formatter.comments.is_empty() && !formatter.output.is_empty() {
+        formatter.empty_lines(1);
+    }
+    formatter.comments(usize::MAX);
+    if formatter.output.is_empty() {
+        return Ok(String::new());
+    }
+
+    let mut formatted = formatter.output.join("\n");
+    formatted.push('\n');
+    check_round_trip(
+        source,
+        formatted.as_str(),
+        sections.as_slice(),
+        doc_id,
+        options,
+    )?;
+    Ok(formatted)
+}
+
+struct Formatter<'a> {
+    lines: Vec<&'a str>,
+    /// full line `;;` comments not yet printed, with their line number
+    comments: std::collections::VecDeque<(usize, &'a str)>,
+    options: &'a FormatOptions,
+    output: Vec<String>,
+}
+
+impl<'a> Formatter<'a> {
+    fn new(source: &'a str, options: &'a FormatOptions) -> Formatter<'a> {
+        let lines: Vec<&str> = source.split('\n').collect();
+        let comments = lines
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| !valid_line(line))
+            .map(|(index, line)| (index + 1, line.trim_end()))
+            .collect();
+        Formatter {
+            lines,
+            comments,
+            options,
+            output: vec![],
+        }
+    }
+
+    fn line(&self, line_number: usize) -> &'a str {
+        line_number
+            .checked_sub(1)
+            .and_then(|index| self.lines.get(index))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    fn push(&mut self, line: String) {
+        self.output.push(line);
+    }
+
+    fn empty_lines(&mut self, count: usize) {
+        for _ in 0..count {
+            self.output.push(String::new());
+        }
+    }
+
+    /// prints the comments written before `line_number`
+    fn comments(&mut self, line_number: usize) {
+        while let Some(&(l, comment)) = self.comments.front() {
+            if l >= line_number {
+                break;
+            }
+            self.output.push(comment.to_string());
+            self.comments.pop_front();
+        }
+    }
+
+    fn section(&mut self, section: &ftd::p1::Section) {
+        self.comments(section.line_number);
+
+        let inline_caption = match section.caption {
+            Some(ftd::p1::Header::KV(ref kv)) => kv.line_number == section.line_number,
+            _ => true,
+        };
+        let caption = if inline_caption {
+            section.caption.as_ref().and_then(header_value)
+        } else {
+            None
+        };
+        let mut line = format!(
+            "{}-- {}{}:",
+            if section.is_commented { "/" } else { "" },
+            kind_prefix(section.kind.as_deref()),
+            section.name
+        );
+        push_value(
+            &mut line,
+            self.tail(section.line_number, caption).as_deref(),
+        );
+        self.push(line);
+
+        let mut inline_headers = vec![];
+        let mut block_headers = vec![];
+        for header in section.headers.0.iter() {
+            match header {
+                ftd::p1::Header::KV(kv) if kv.source == ftd::p1::header::KVSource::Header => {
+                    inline_headers.push(kv)
+                }
+                _ => block_headers.push(header),
+            }
+        }
+        if sort_headers(section, self.options) {
+            inline_headers.sort_by(|a, b| a.key.cmp(&b.key));
+        }
+        for kv in inline_headers {
+            self.comments(kv.line_number);
+            let mut line = format!("{}:", kv_name(kv));
+            push_value(
+                &mut line,
+                self.tail(kv.line_number, kv.value.as_deref()).as_deref(),
+            );
+            self.push(line);
+        }
+
+        if !inline_caption {
+            if let Some(ftd::p1::Header::KV(ref kv)) = section.caption {
+                let value = kv.value.clone().unwrap_or_default();
+                let lines = self.value_until(kv.line_number, value.as_str());
+                self.empty_lines(1);
+                self.push(format!("-- {}.caption:", section.name));
+                self.empty_lines(1);
+                self.output.extend(lines);
+            }
+        }
+        for header in block_headers.iter() {
+            self.empty_lines(1);
+            self.block_header(section.name.as_str(), header);
+        }
+        if let Some(ref body) = section.body {
+            let lines = self.value_until(body.line_number, body.value.as_str());
+            if !inline_caption || !block_headers.is_empty() {
+                self.empty_lines(1);
+                self.push(format!("-- {}.body:", section.name));
+            }
+            self.empty_lines(1);
+            self.output.extend(lines);
+        }
+
+        if !section.sub_sections.is_empty() {
+            for sub_section in section.sub_sections.iter() {
+                self.empty_lines(1);
+                self.section(sub_section);
+            }
+            self.empty_lines(1);
+            self.push(format!("-- end: {}", section.name));
+        }
+    }
+
+    fn block_header(&mut self, section_name: &str, header: &ftd::p1::Header) {
+        match header {
+            ftd::p1::Header::KV(kv) => self.block_kv(section_name, kv),
+            ftd::p1::Header::Section(s) => {
+                let name = format!("{}.{}", section_name, s.key);
+                self.push(format!("-- {}{}:", kind_prefix(s.kind.as_deref()), name));
+                self.condition(s.condition.as_deref());
+                for section in s.section.iter() {
+                    self.empty_lines(1);
+                    self.section(section);
+                }
+                self.empty_lines(1);
+                self.push(format!("-- end: {}", name));
+            }
+            ftd::p1::Header::BlockRecordHeader(b) => {
+                let (inline_fields, block_fields): (Vec<_>, Vec<_>) =
+                    b.fields.iter().partition(|field| {
+                        matches!(
+                            field,
+                            ftd::p1::Header::KV(kv)
+                                if kv.source == ftd::p1::header::KVSource::Header
+                        )
+                    });
+                let name = format!("{}.{}", section_name, b.key);
+
+                let has_record_header =
+                    b.caption.is_some() || b.body.0.is_some() || !inline_fields.is_empty();
+                if has_record_header {
+                    self.comments(b.line_number);
+                    let mut line = format!("-- {}{}:", kind_prefix(b.kind.as_deref()), name);
+                    push_value(&mut line, b.caption.as_deref());
+                    self.push(line);
+                    self.condition(b.condition.as_deref());
+                    for field in inline_fields {
+                        if let ftd::p1::Header::KV(kv) = field {
+                            let mut line = format!("{}:", kv_name(kv));
+                            push_value(&mut line, kv.value.as_deref());
+                            self.push(line);
+                        }
+                    }
+                    if let (Some(value), Some(line_number)) = &b.body {
+                        let lines = self.value_from(*line_number, value.as_str());
+                        self.empty_lines(1);
+                        self.output.extend(lines);
+                    }
+                }
+                for (index, field) in block_fields.into_iter().enumerate() {
+                    if has_record_header || index > 0 {
+                        self.empty_lines(1);
+                    }
+                    self.block_header(name.as_str(), field);
+                }
+            }
+        }
+    }
+
+    fn block_kv(&mut self, section_name: &str, kv: &ftd::p1::header::KV) {
+        let name = format!(
+            "-- {}{}{}.{}:",
+            modifier_prefix(&kv.access_modifier),
+            kind_prefix(kv.kind.as_deref()),
+            section_name,
+            kv.key
+        );
+        match (&kv.source, &kv.value) {
+            (ftd::p1::header::KVSource::Body, Some(value)) => {
+                let lines = self.value_from(kv.line_number, value.as_str());
+                self.push(name);
+                self.condition(kv.condition.as_deref());
+                self.empty_lines(1);
+                self.output.extend(lines);
+            }
+            (_, value) => {
+                self.comments(kv.line_number);
+                let mut line = name;
+                push_value(&mut line, value.as_deref());
+                self.push(line);
+                self.condition(kv.condition.as_deref());
+            }
+        }
+    }
+
+    fn condition(&mut self, condition: Option<&str>) {
+        if let Some(condition) = condition {
+            self.push(format!("{}: {}", ftd::p1::utils::IF, condition));
+        }
+    }
+
+    /// `tail()` returns what follows `:` on the line `line_number`, as written in the source, so
+    /// that inline comments and escapes are kept. The parsed `value` is returned instead if the
+    /// line does not have it.
+    fn tail(&self, line_number: usize, value: Option<&str>) -> Option<String> {
+        let line = self.line(line_number).trim();
+        let written = line
+            .split_once(':')
+            .map(|(_, tail)| tail.trim())
+            .unwrap_or_default();
+        let cleaned = clean_line(line);
+        let parsed = cleaned
+            .split_once(':')
+            .map(|(_, tail)| tail.trim())
+            .filter(|tail| !tail.is_empty());
+        if parsed == value && !written.is_empty() {
+            Some(match (value, comment(written)) {
+                (Some(value), Some(comment)) if !written.contains('\\') => {
+                    format!("{} {}", value, comment)
+                }
+                _ => written.to_string(),
+            })
+        } else {
+            value.map(ToString::to_string)
+        }
+    }
+
+    /// `value_from()` returns the lines of a multi-line `value` that starts at `line_number`, as
+    /// written in the source.
+    fn value_from(&mut self, line_number: usize, value: &str) -> Vec<String> {
+        let count = value.split('\n').count();
+        let mut end = line_number;
+        let mut seen = 0;
+        while end <= self.lines.len() {
+            if valid_line(self.line(end)) {
+                seen += 1;
+            }
+            if seen == count {
+                return self.written_lines(line_number, end, value);
+            }
+            end += 1;
+        }
+        escaped_lines(value)
+    }
+
+    /// `value_until()` returns the lines of a multi-line `value` that ends at, or before,
+    /// `line_number`, as written in the source.
+    fn value_until(&mut self, line_number: usize, value: &str) -> Vec<String> {
+        let mut end = line_number;
+        while end > 0 && !is_value_line(self.line(end)) {
+            end -= 1;
+        }
+        let count = value.split('\n').count();
+        let mut start = end;
+        let mut seen = 0;
+        while start > 0 {
+            if valid_line(self.line(start)) {
+                seen += 1;
+            }
+            if seen == count {
+                return self.written_lines(start, end, value);
+            }
+            start -= 1;
+        }
+        escaped_lines(value)
+    }
+
+    fn written_lines(&mut self, start: usize, end: usize, value: &str) -> Vec<String> {
+        let lines = (start..=end).map(|l| self.line(l)).collect::<Vec<_>>();
+        let parsed = lines
+            .iter()
+            .filter(|line| valid_line(line))
+            .map(|line| clean_line(line))
+            .collect::<Vec<_>>()
+            .join("\n");
+        if parsed.trim() != value {
+            return escaped_lines(value);
+        }
+
+        // comments before the value are printed before it, comments inside it are kept in place
+        self.comments(start);
+        self.comments.retain(|(l, _)| *l > end);
+        lines.iter().map(|line| line.to_string()).collect()
+    }
+}
+
+fn escaped_lines(value: &str) -> Vec<String> {
+    value
+        .split('\n')
+        .map(|line| {
+            if line.starts_with(";;") || line.starts_with("-- ") {
+                format!("\\{}", line)
+            } else {
+                line.to_string()
+            }
+        })
+        .collect()
+}
+
+fn is_value_line(line: &str) -> bool {
+    valid_line(line) && !clean_line(line).trim().is_empty()
+}
+
+fn is_bare_import(section: &ftd::p1::Section) -> bool {
+    section.name.eq("import")
+        && !section.is_commented
+        && section.headers.0.is_empty()
+        && section.body.is_none()
+        && section.sub_sections.is_empty()
+}
+
+fn sort_headers(section: &ftd::p1::Section, options: &FormatOptions) -> bool {
+    options.sort_headers && section.kind.is_none()
+}
+
+fn header_value(header: &ftd::p1::Header) -> Option<&str> {
+    match header {
+        ftd::p1::Header::KV(kv) => kv.value.as_deref(),
+        _ => None,
+    }
+}
+
+fn push_value(line: &mut String, value: Option<&str>) {
+    if let Some(value) = value {
+        line.push(' ');
+        line.push_str(value);
+    }
+}
+
+fn kind_prefix(kind: Option<&str>) -> String {
+    kind.map(|kind| format!("{} ", kind)).unwrap_or_default()
+}
+
+fn modifier_prefix(modifier: &ftd::p1::header::AccessModifier) -> &'static str {
+    match modifier {
+        ftd::p1::header::AccessModifier::Private => "private ",
+        ftd::p1::header::AccessModifier::Public => "",
+    }
+}
+
+/// `kv_name()` is the part of a header line before `:`, like `private string name if { c }`.
+fn kv_name(kv: &ftd::p1::header::KV) -> String {
+    let mut name = format!(
+        "{}{}{}",
+        modifier_prefix(&kv.access_modifier),
+        kind_prefix(kv.kind.as_deref()),
+        kv.key
+    );
+    if let Some(ref condition) = kv.condition {
+        name.push_str(ftd::p1::utils::INLINE_IF);
+        name.push_str(condition);
+    }
+    name
+}
+
+fn check_round_trip(
+    source: &str,
+    formatted: &str,
+    sections: &[ftd::p1::Section],
+    doc_id: &str,
+    options: &FormatOptions,
+) -> ftd::p1::Result<()> {
+    let error = |line_number: usize, message: &str| ftd::p1::Error::ParseError {
+        message: message.to_string(),
+        doc_id: doc_id.to_string(),
+        line_number,
+        span: Default::default(),
+    };
+
+    let formatted_sections = ftd::p1::parse(formatted, doc_id).map_err(|_| {
+        error(
+            sections.first().map(|s| s.line_number).unwrap_or(1),
+            "the document can not be formatted without changing what it means",
+        )
+    })?;
+    for (index, section) in sections.iter().enumerate() {
+        if formatted_sections
+            .get(index)
+            .map(|s| normalize(s, options))
+            .ne(&Some(normalize(section, options)))
+        {
+            return Err(error(
+                section.line_number,
+                "this section can not be formatted without changing what it means",
+            ));
+        }
+    }
+    if formatted_sections.len() != sections.len() {
+        return Err(error(
+            sections.last().map(|s| s.line_number).unwrap_or(1),
+            "the document can not be formatted without changing what it means",
+        ));
+    }
+
+    let mut kept = comments(formatted);
+    for (index, line) in source.split('\n').enumerate() {
+        let comment = match comment(line) {
+            Some(comment) => comment,
+            None => continue,
+        };
+        match kept.iter().position(|c| *c == comment) {
+            Some(position) => {
+                kept.remove(position);
+            }
+            None => {
+                return Err(error(
+                    index + 1,
+                    "this comment can not be kept by the formatter",
+                ))
+            }
+        }
+    }
+    Ok(())
+}
+
+fn comments(source: &str) -> Vec<&str> {
+    source.split('\n').filter_map(comment).collect()
+}
+
+fn comment(line: &str) -> Option<&str> {
+    line.find(";;").map(|index| line[index..].trim_end())
+}
+
+/// `normalize()` drops what formatting is allowed to change: line numbers, spans, and the order
+/// of the headers that `FormatOptions::sort_headers` sorts.
+fn normalize(section: &ftd::p1::Section, options: &FormatOptions) -> ftd::p1::Section {
+    let (mut headers, block_headers): (Vec<_>, Vec<_>) = section
+        .headers
+        .0
+        .iter()
+        .map(|header| normalize_header(header, options))
+        .partition(|header| {
+            matches!(
+                header,
+                ftd::p1::Header::KV(kv) if kv.source == ftd::p1::header::KVSource::Header
+            )
+        });
+    if sort_headers(section, options) {
+        headers.sort_by_key(|header| header.get_key());
+    }
+    headers.extend(block_headers);
+
+    ftd::p1::Section {
+        headers: ftd::p1::Headers(headers),
+        sub_sections: section
+            .sub_sections
+            .iter()
+            .map(|s| normalize(s, options))
+            .collect(),
+        ..section.without_line_number()
+    }
+}
+
+fn normalize_header(header: &ftd::p1::Header, options: &FormatOptions) -> ftd::p1::Header {
+    match header {
+        ftd::p1::Header::Section(s) => ftd::p1::Header::Section(ftd::p1::HSection {
+            line_number: 0,
+            section: s.section.iter().map(|s| normalize(s, options)).collect(),
+            span: Default::default(),
+            ..s.clone()
+        }),
+        ftd::p1::Header::BlockRecordHeader(b) => {
+            ftd::p1::Header::BlockRecordHeader(ftd::p1::header::BlockRecordHeader {
+                body: (b.body.0.clone(), None),
+                fields: b
+                    .fields
+                    .iter()
+                    .map(|f| normalize_header(f, options))
+                    .collect(),
+                line_number: 0,
+                span: Default::default(),
+                ..b.clone()
+            })
+        }
+        ftd::p1::Header::KV(_) => header.without_line_number(),
+    }
+}
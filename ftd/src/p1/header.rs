@@ -16,6 +16,8 @@ pub struct BlockRecordHeader {
     pub fields: Vec<Header>,
     pub condition: Option<String>,
     pub line_number: usize,
+    #[serde(default, skip_serializing_if = "ftd::p1::Span::is_empty")]
+    pub span: ftd::p1::Span,
 }
 
 impl BlockRecordHeader {
@@ -36,6 +38,7 @@ impl BlockRecordHeader {
             fields,
             condition,
             line_number,
+            span: Default::default(),
         }
     }
 }
@@ -58,6 +61,8 @@ pub struct KV {
     pub condition: Option<String>,
     pub access_modifier: AccessModifier,
     pub source: KVSource,
+    #[serde(default, skip_serializing_if = "ftd::p1::Span::is_empty")]
+    pub span: ftd::p1::Span,
 }
 
 impl KV {
@@ -84,6 +89,7 @@ impl KV {
             condition,
             access_modifier,
             source: source.unwrap_or_default(),
+            span: Default::default(),
         }
     }
 }
@@ -145,6 +151,8 @@ pub struct Section {
     pub kind: Option<String>,
     pub section: Vec<ftd::p1::Section>,
     pub condition: Option<String>,
+    #[serde(default, skip_serializing_if = "ftd::p1::Span::is_empty")]
+    pub span: ftd::p1::Span,
 }
 
 impl Header {
@@ -183,6 +191,7 @@ impl Header {
             kind,
             section,
             condition,
+            span: Default::default(),
         })
     }
 
@@ -213,11 +222,13 @@ impl Header {
             Header::KV(kv) => {
                 let mut kv = (*kv).clone();
                 kv.line_number = 0;
+                kv.span = Default::default();
                 Header::KV(kv)
             }
             Header::Section(s) => {
                 let mut s = (*s).clone();
                 s.line_number = 0;
+                s.span = Default::default();
                 s.section = s
                     .section
                     .iter()
@@ -228,12 +239,13 @@ impl Header {
             Header::BlockRecordHeader(b) => {
                 let mut blockrecord = (*b).clone();
                 blockrecord.line_number = 0;
+                blockrecord.span = Default::default();
                 Header::BlockRecordHeader(blockrecord)
             }
         }
     }
 
-    pub(crate) fn get_key(&self) -> String {
+    pub fn get_key(&self) -> String {
         match self {
             Header::KV(ftd::p1::header::KV { key, .. })
             | Header::Section(ftd::p1::header::Section { key, .. })
@@ -293,6 +305,7 @@ impl Header {
                 ),
                 doc_id: doc_id.to_string(),
                 line_number: self.get_line_number(),
+                span: self.get_span(),
             }),
             Header::BlockRecordHeader(_) => Err(ftd::p1::Error::ParseError {
                 message: format!(
@@ -301,6 +314,7 @@ impl Header {
                 ),
                 doc_id: doc_id.to_string(),
                 line_number: self.get_line_number(),
+                span: self.get_span(),
             }),
         }
     }
@@ -314,6 +328,7 @@ impl Header {
                 ),
                 doc_id: doc_id.to_string(),
                 line_number: self.get_line_number(),
+                span: self.get_span(),
             }),
             Header::Section(ftd::p1::header::Section { section, .. }) => Ok(section),
         }
@@ -329,7 +344,15 @@ impl Header {
         }
     }
 
-    pub(crate) fn get_kind(&self) -> Option<String> {
+    pub fn get_span(&self) -> ftd::p1::Span {
+        match self {
+            Header::KV(ftd::p1::header::KV { span, .. })
+            | Header::Section(ftd::p1::header::Section { span, .. })
+            | Header::BlockRecordHeader(ftd::p1::header::BlockRecordHeader { span, .. }) => *span,
+        }
+    }
+
+    pub fn get_kind(&self) -> Option<String> {
         match self {
             Header::KV(ftd::p1::header::KV { kind, .. })
             | Header::Section(ftd::p1::header::Section { kind, .. })
@@ -427,12 +450,14 @@ impl Headers {
             key: key.to_string(),
             doc_id: doc_id.to_string(),
             line_number,
+            span: Default::default(),
         })?;
         if headers.len() > 1 {
             return Err(ftd::p1::Error::MoreThanOneHeader {
                 key: key.to_string(),
                 doc_id: doc_id.to_string(),
                 line_number: header.get_line_number(),
+                span: header.get_span(),
             });
         }
         Ok(header)
@@ -451,6 +476,7 @@ impl Headers {
                 key: key.to_string(),
                 doc_id: doc_id.to_string(),
                 line_number,
+                span: Default::default(),
             })
     }
 
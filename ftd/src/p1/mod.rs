@@ -2,29 +2,46 @@
 #[macro_use]
 mod test;
 
+pub mod fmt;
 pub(crate) mod header;
 mod parser;
 mod section;
+pub mod span;
 pub mod utils;
 
 pub use header::{Header, Headers, Section as HSection, KV};
-pub use parser::{parse, parse_with_line_number};
+pub use parser::{
+    parse, parse_with_line_number, parse_with_line_number_and_recovery, parse_with_recovery,
+};
 pub use section::Body;
 pub use section::Section;
+pub use span::{Position, Span, Spans};
 
+/// Errors reported at a line of a document also have the `span` of the part of the document they
+/// are about. It is empty where the error is created, and set by `Error::locate()` once the spans
+/// of the document are known.
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
     #[error("{doc_id}:{line_number} -> SectionNotFound")]
-    SectionNotFound { doc_id: String, line_number: usize },
+    SectionNotFound {
+        doc_id: String,
+        line_number: usize,
+        span: ftd::p1::Span,
+    },
 
     #[error("{doc_id}:{line_number} -> MoreThanOneCaption")]
-    MoreThanOneCaption { doc_id: String, line_number: usize },
+    MoreThanOneCaption {
+        doc_id: String,
+        line_number: usize,
+        span: ftd::p1::Span,
+    },
 
     #[error("{doc_id}:{line_number} -> {message}")]
     ParseError {
         message: String,
         doc_id: String,
         line_number: usize,
+        span: ftd::p1::Span,
     },
 
     #[error("{doc_id}:{line_number} -> MoreThanOneHeader for key {key}")]
@@ -32,6 +49,7 @@ pub enum Error {
         key: String,
         doc_id: String,
         line_number: usize,
+        span: ftd::p1::Span,
     },
 
     #[error("{doc_id}:{line_number} -> HeaderNotFound for key {key}")]
@@ -39,6 +57,7 @@ pub enum Error {
         key: String,
         doc_id: String,
         line_number: usize,
+        span: ftd::p1::Span,
     },
 
     #[error("forbidden usage: {message}, line_number: {line_number}, doc: {doc_id}")]
@@ -46,6 +65,7 @@ pub enum Error {
         message: String,
         doc_id: String,
         line_number: usize,
+        span: ftd::p1::Span,
     },
 
     #[error("key not found: {key}, line number: {line_number}, doc: {doc_id}")]
@@ -53,6 +73,7 @@ pub enum Error {
         doc_id: String,
         line_number: usize,
         key: String,
+        span: ftd::p1::Span,
     },
 
     #[error("got more than one sub-sections: {key}, line number: {line_number}, doc: {doc_id}")]
@@ -60,6 +81,7 @@ pub enum Error {
         key: String,
         doc_id: String,
         line_number: usize,
+        span: ftd::p1::Span,
     },
 
     #[error("serde error: {source}")]
@@ -89,6 +89,7 @@ impl State {
                 message: "section name not provided for `end`".to_string(),
                 doc_id: self.doc_id.to_string(),
                 line_number: ftd::p1::utils::i32_to_usize(self.line_number),
+                span: Default::default(),
             })?;
             let mut sections = vec![];
             loop {
@@ -101,6 +102,7 @@ impl State {
                             message: format!("No section found to end: {}", caption),
                             doc_id: self.doc_id.to_string(),
                             line_number: ftd::p1::utils::i32_to_usize(self.line_number),
+                            span: Default::default(),
                         }
                     })?;
                     sections.push(section);
@@ -175,6 +177,7 @@ impl State {
                     line_number: ftd::p1::utils::i32_to_usize(
                         self.line_number + (scan_line_number as i32) + 1,
                     ),
+                    span: Default::default(),
                 })
             };
         }
@@ -216,6 +219,7 @@ impl State {
                         line_number: ftd::p1::utils::i32_to_usize(
                             self.line_number + (scan_line_number as i32) + 1,
                         ),
+                        span: Default::default(),
                     });
                 }
             }
@@ -224,6 +228,7 @@ impl State {
 
         self.line_number += (scan_line_number as i32) + 1;
         let section = ftd::p1::Section {
+            span: Default::default(),
             name: section_name,
             kind,
             caption: caption.map(|v| {
@@ -354,6 +359,7 @@ impl State {
                 .ok_or_else(|| ftd::p1::Error::SectionNotFound {
                     doc_id: self.doc_id.to_string(),
                     line_number: ftd::p1::utils::i32_to_usize(self.line_number),
+                    span: Default::default(),
                 })?;
 
         let header_not_found_next_state = if !section.block_body {
@@ -423,6 +429,7 @@ impl State {
             return Err(ftd::p1::Error::MoreThanOneCaption {
                 doc_id: self.doc_id.to_string(),
                 line_number: section.line_number,
+                span: Default::default(),
             });
         }
 
@@ -489,6 +496,7 @@ impl State {
                             message: format!("start section body '{}' after a newline!!", line),
                             doc_id: self.doc_id.to_string(),
                             line_number: ftd::p1::utils::i32_to_usize(self.line_number),
+                            span: Default::default(),
                         });
                     }
                     first_line = false;
@@ -531,6 +539,7 @@ impl State {
                 .ok_or(ftd::p1::Error::SectionNotFound {
                     doc_id: doc_id.clone(),
                     line_number: header_line_number,
+                    span: Default::default(),
                 })?
                 .0;
             let value = (value.0.join("\n").trim().to_string(), value.1);
@@ -601,6 +610,7 @@ impl State {
                         message: format!("start section caption '{}' after a newline!!", line),
                         doc_id: self.doc_id.to_string(),
                         line_number: ftd::p1::utils::i32_to_usize(self.line_number),
+                        span: Default::default(),
                     });
                 }
                 first_line = false;
@@ -615,6 +625,7 @@ impl State {
             .ok_or(ftd::p1::Error::SectionNotFound {
                 doc_id,
                 line_number: ftd::p1::utils::i32_to_usize(line_number),
+                span: Default::default(),
             })?
             .0;
 
@@ -646,6 +657,7 @@ impl State {
                         message: format!("start section body '{}' after a newline!!", line),
                         doc_id: self.doc_id.to_string(),
                         line_number: ftd::p1::utils::i32_to_usize(self.line_number),
+                        span: Default::default(),
                     });
                 }
                 first_line = false;
@@ -661,6 +673,7 @@ impl State {
             .ok_or(ftd::p1::Error::SectionNotFound {
                 doc_id,
                 line_number: ftd::p1::utils::i32_to_usize(line_number),
+                span: Default::default(),
             })?
             .0;
         let value = value.join("\n").trim().to_string();
@@ -721,6 +734,7 @@ impl State {
             .ok_or(ftd::p1::Error::SectionNotFound {
                 doc_id,
                 line_number: ftd::p1::utils::i32_to_usize(line_number),
+                span: Default::default(),
             })?
             .0;
         section.headers.0.extend(headers);
@@ -752,6 +766,7 @@ impl State {
                     message: format!("`{}` section state is not yet empty", section.name),
                     doc_id: self.doc_id.to_string(),
                     line_number: ftd::p1::utils::i32_to_usize(self.line_number),
+                    span: Default::default(),
                 });
             }
         }
@@ -815,8 +830,111 @@ pub fn parse_with_line_number(
         sections: Default::default(),
         state: Default::default(),
     };
-    state.next()?;
-    Ok(state.sections)
+    let result = state.next();
+    ftd::p1::span::add_spans(&mut state.sections, content, line_number);
+    match result {
+        Ok(()) => Ok(state.sections),
+        Err(e) => Err(e.locate(doc_id, &ftd::p1::Spans::new(&state.sections, content))),
+    }
+}
+
+/// `parse_with_recovery()` parses `content` like `parse()`, but does not stop at the first
+/// error. The document is split in top level sections (a section with all its sub-sections up to
+/// its `-- end:`), a top level section that fails to parse is skipped, and parsing continues from
+/// the next one.
+///
+/// Returns the sections that parsed, and the errors of the ones that did not.
+pub fn parse_with_recovery(
+    content: &str,
+    doc_id: &str,
+) -> (Vec<ftd::p1::Section>, Vec<ftd::p1::Error>) {
+    parse_with_line_number_and_recovery(content, doc_id, 0)
+}
+
+/// `parse_with_line_number_and_recovery()` is `parse_with_recovery()` for `content` with
+/// `line_number` lines prepended to it, see `parse_with_line_number()`.
+pub fn parse_with_line_number_and_recovery(
+    content: &str,
+    doc_id: &str,
+    line_number: usize,
+) -> (Vec<ftd::p1::Section>, Vec<ftd::p1::Error>) {
+    if let Ok(sections) = parse_with_line_number(content, doc_id, line_number) {
+        return (sections, vec![]);
+    }
+
+    let lines = content.split('\n').collect::<Vec<_>>();
+    let mut sections = vec![];
+    let mut errors = vec![];
+    for (start, end) in top_level_sections(&lines) {
+        // empty lines before the section keep the line numbers same as in `content`
+        let section_content = format!("{}{}", "\n".repeat(start), lines[start..end].join("\n"));
+        match parse_with_line_number(section_content.as_str(), doc_id, line_number) {
+            Ok(s) => sections.extend(s),
+            Err(e) => errors.push(e),
+        }
+    }
+    (sections, errors)
+}
+
+/// `top_level_sections()` returns the `[start, end)` line ranges of the top level sections of a
+/// document. Anything before the first section is a range of its own, so it gets reported.
+fn top_level_sections(lines: &[&str]) -> Vec<(usize, usize)> {
+    // (line, section name) of every line starting a section, header section or `end`
+    let starts = lines
+        .iter()
+        .enumerate()
+        .filter_map(|(i, line)| {
+            let line = line
+                .strip_prefix("-- ")
+                .or_else(|| line.strip_prefix("/-- "))?;
+            let name_with_kind = line.split_once(':').map(|(v, _)| v).unwrap_or(line);
+            let (name, _) = get_name_and_kind(name_with_kind.trim());
+            Some((i, name, line))
+        })
+        .collect::<Vec<_>>();
+
+    // sections closed by an `-- end:` contain everything up to it
+    let mut containers: Vec<(usize, usize)> = vec![];
+    let mut open: Vec<(usize, &str)> = vec![];
+    for (i, name, line) in starts.iter() {
+        if !is_end(name) {
+            open.push((*i, name.as_str()));
+            continue;
+        }
+        let ended = line
+            .split_once(':')
+            .map(|(_, v)| v.trim())
+            .unwrap_or_default();
+        while let Some((start, name)) = open.pop() {
+            if name.eq(ended) {
+                containers.push((start, *i));
+                break;
+            }
+        }
+    }
+
+    let mut boundaries = vec![0];
+    let mut current: Option<&str> = None;
+    for (i, name, _) in starts.iter() {
+        let nested = containers.iter().any(|(s, e)| s < i && i <= e);
+        let is_block_header = current
+            .map(|c| name.starts_with(format!("{}.", c).as_str()))
+            .unwrap_or(false);
+        if nested || is_block_header || is_end(name) {
+            continue;
+        }
+        current = Some(name.as_str());
+        if *i > 0 {
+            boundaries.push(*i);
+        }
+    }
+    boundaries.push(lines.len());
+
+    boundaries
+        .windows(2)
+        .map(|w| (w[0], w[1]))
+        .filter(|(start, end)| start < end)
+        .collect()
 }
 
 fn colon_separated_values(
@@ -830,6 +948,7 @@ fn colon_separated_values(
             // TODO: context should be a few lines before and after the input
             doc_id: doc_id.to_string(),
             line_number,
+            span: Default::default(),
         });
     }
 
@@ -861,7 +980,7 @@ fn get_name_and_kind(name_with_kind: &str) -> (String, Option<String>) {
         }
     }
 
-    if let Some((kind, name)) = name_with_kind.rsplit_once(' ') {
+    if let Some((kind, name)) = rsplit_kind_and_name(name_with_kind.as_str()) {
         return (name.to_string(), Some(kind.to_string()));
     }
 
@@ -876,14 +995,35 @@ fn get_name_kind_and_condition(name_with_kind: &str) -> (String, Option<String>,
     } else {
         (name_with_kind.to_string(), None)
     };
-    if let Some((kind, name)) = name_with_kind.rsplit_once(' ') {
+    if let Some((kind, name)) = rsplit_kind_and_name(name_with_kind.as_str()) {
         return (name.to_string(), Some(kind.to_string()), condition);
     }
 
     (name_with_kind, None, condition)
 }
 
-fn clean_line(line: &str) -> String {
+/// Splits `kind name` at the last space, ignoring the spaces inside the type arguments of a
+/// generic kind, so that `card<person list> c` gives `card<person list>` and `c`.
+fn rsplit_kind_and_name(name_with_kind: &str) -> Option<(&str, &str)> {
+    let mut depth = 0usize;
+    let mut split_at = None;
+    for (index, c) in name_with_kind.char_indices() {
+        match c {
+            '<' => depth += 1,
+            '>' => depth = depth.saturating_sub(1),
+            ' ' if depth == 0 => split_at = Some(index),
+            _ => {}
+        }
+    }
+
+    if depth != 0 {
+        return name_with_kind.rsplit_once(' ');
+    }
+
+    split_at.map(|index| (&name_with_kind[..index], &name_with_kind[index + 1..]))
+}
+
+pub(crate) fn clean_line(line: &str) -> String {
     if line.starts_with("\\;;") || line.starts_with("\\-- ") {
         return line[1..].to_string();
     }
@@ -948,7 +1088,7 @@ fn remove_inline_comments(line: &str) -> String {
     output.to_string()
 }
 
-fn valid_line(line: &str) -> bool {
+pub(crate) fn valid_line(line: &str) -> bool {
     !line.starts_with(";;")
 }
 
@@ -0,0 +1,270 @@
+/// `Position` is a location in a document, both `line` and `column` start from 1. `column` counts
+/// characters, not bytes.
+#[derive(
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Clone,
+    Copy,
+    Default,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Position {
+    pub fn new(line: usize, column: usize) -> Position {
+        Position { line, column }
+    }
+}
+
+/// `Span` is the part of the document a section, header or body was parsed from. `end` is
+/// exclusive, so a span covering `abc` on line 2 is `2:1` to `2:4`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default, serde::Serialize, serde::Deserialize)]
+pub struct Span {
+    pub start: Position,
+    pub end: Position,
+}
+
+impl Span {
+    pub fn new(start: Position, end: Position) -> Span {
+        Span { start, end }
+    }
+
+    /// the span of the trimmed content of `line_number`
+    pub fn line(source: &str, line_number: usize) -> Span {
+        line_span(&source.split('\n').collect::<Vec<_>>(), line_number, 0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.eq(&Span::default())
+    }
+
+    pub fn contains_line(&self, line_number: usize) -> bool {
+        self.start.line <= line_number && line_number <= self.end.line
+    }
+
+    fn merge(self, other: Span) -> Span {
+        if other.is_empty() {
+            return self;
+        }
+        if self.is_empty() {
+            return other;
+        }
+        Span {
+            start: self.start.min(other.start),
+            end: self.end.max(other.end),
+        }
+    }
+}
+
+/// `Spans` is the span `find()` returns for every line of a document. Parsed documents keep it so
+/// the errors reported for them, which only know their line, can be given a span later on.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Spans(std::sync::Arc<Vec<Span>>);
+
+impl Spans {
+    pub fn new(sections: &[ftd::p1::Section], source: &str) -> Spans {
+        let lines = source.split('\n').collect::<Vec<_>>();
+        Spans(std::sync::Arc::new(
+            (1..=lines.len())
+                .map(|line_number| {
+                    find_in_sections(sections, line_number)
+                        .unwrap_or_else(|| line_span(&lines, line_number, 0))
+                })
+                .collect(),
+        ))
+    }
+
+    /// the span for `line_number`, empty if the document does not have that line
+    pub fn get(&self, line_number: usize) -> Span {
+        line_number
+            .checked_sub(1)
+            .and_then(|index| self.0.get(index))
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+// `Spans` is serialized as the list of spans, parsed documents are cached with their spans
+impl serde::Serialize for Spans {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serde::Serialize::serialize(self.0.as_slice(), serializer)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Spans {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let spans: Vec<Span> = serde::Deserialize::deserialize(deserializer)?;
+        Ok(Spans(std::sync::Arc::new(spans)))
+    }
+}
+
+/// `add_spans()` fills the span of every section, header and body of `sections`, parsed from
+/// `source` with `parse_with_line_number(source, _, line_offset)`.
+///
+/// The parser works on trimmed lines and only keeps line numbers, so the spans are computed
+/// afterwards from the line numbers it recorded.
+pub(crate) fn add_spans(sections: &mut [ftd::p1::Section], source: &str, line_offset: usize) {
+    let lines = source.split('\n').collect::<Vec<_>>();
+    for section in sections.iter_mut() {
+        section_span(section, &lines, line_offset);
+    }
+}
+
+/// `find()` returns the span of the most specific header or body of `sections` at
+/// `line_number`, or the span of the line itself if no header or body is there, like for the
+/// first line of a section.
+pub fn find(sections: &[ftd::p1::Section], source: &str, line_number: usize) -> Span {
+    find_in_sections(sections, line_number).unwrap_or_else(|| Span::line(source, line_number))
+}
+
+fn find_in_sections(sections: &[ftd::p1::Section], line_number: usize) -> Option<Span> {
+    let section = sections
+        .iter()
+        .find(|s| s.span.contains_line(line_number))?;
+
+    if let Some(span) = find_in_sections(&section.sub_sections, line_number) {
+        return Some(span);
+    }
+    for header in section.caption.iter().chain(section.headers.0.iter()) {
+        let span = header.get_span();
+        if !span.contains_line(line_number) || span.start.line == section.line_number {
+            continue;
+        }
+        if let ftd::p1::Header::Section(s) = header {
+            if let Some(span) = find_in_sections(&s.section, line_number) {
+                return Some(span);
+            }
+        }
+        return Some(span);
+    }
+    match section.body {
+        Some(ref body) if body.span.contains_line(line_number) => Some(body.span),
+        _ => None,
+    }
+}
+
+fn section_span(section: &mut ftd::p1::Section, lines: &[&str], line_offset: usize) -> Span {
+    let mut span = line_span(lines, section.line_number, line_offset);
+
+    if let Some(ref mut caption) = section.caption {
+        span = span.merge(header_span(caption, lines, line_offset));
+    }
+    for header in section.headers.0.iter_mut() {
+        span = span.merge(header_span(header, lines, line_offset));
+    }
+    if let Some(ref mut body) = section.body {
+        body.span = body_span(body, lines, line_offset, section.line_number);
+        span = span.merge(body.span);
+    }
+    for sub_section in section.sub_sections.iter_mut() {
+        span = span.merge(section_span(sub_section, lines, line_offset));
+    }
+
+    section.span = span;
+    span
+}
+
+fn header_span(header: &mut ftd::p1::Header, lines: &[&str], line_offset: usize) -> Span {
+    match header {
+        ftd::p1::Header::KV(kv) => {
+            kv.span = kv_span(kv, lines, line_offset);
+            kv.span
+        }
+        ftd::p1::Header::Section(s) => {
+            let mut span = line_span(lines, s.line_number, line_offset);
+            for section in s.section.iter_mut() {
+                span = span.merge(section_span(section, lines, line_offset));
+            }
+            s.span = span;
+            span
+        }
+        ftd::p1::Header::BlockRecordHeader(b) => {
+            let mut span = line_span(lines, b.line_number, line_offset);
+            for field in b.fields.iter_mut() {
+                span = span.merge(header_span(field, lines, line_offset));
+            }
+            if let (Some(value), Some(line_number)) = b.body.clone() {
+                span = span.merge(lines_span(lines, line_number, &value, line_offset));
+            }
+            b.span = span;
+            span
+        }
+    }
+}
+
+fn kv_span(kv: &ftd::p1::header::KV, lines: &[&str], line_offset: usize) -> Span {
+    match (&kv.source, &kv.value) {
+        (ftd::p1::header::KVSource::Body, Some(value)) => {
+            lines_span(lines, kv.line_number, value, line_offset)
+        }
+        (ftd::p1::header::KVSource::Caption, Some(value)) => {
+            // underline only the caption, not the section name before it
+            let line = get_line(lines, kv.line_number, line_offset);
+            match line.rfind(value.as_str()) {
+                Some(index) => {
+                    let start = line[..index].chars().count() + 1;
+                    Span::new(
+                        Position::new(kv.line_number, start),
+                        Position::new(kv.line_number, start + value.chars().count()),
+                    )
+                }
+                None => line_span(lines, kv.line_number, line_offset),
+            }
+        }
+        _ => line_span(lines, kv.line_number, line_offset),
+    }
+}
+
+fn body_span(
+    body: &ftd::p1::Body,
+    lines: &[&str],
+    line_offset: usize,
+    section_line_number: usize,
+) -> Span {
+    // the parser records the last line it read for the body, which can be followed by empty
+    // lines before the next section
+    let mut end = body.line_number;
+    while end > section_line_number && get_line(lines, end, line_offset).trim().is_empty() {
+        end -= 1;
+    }
+    let start = end
+        .saturating_sub(body.value.split('\n').count() - 1)
+        .max(section_line_number + 1);
+    Span::new(
+        line_span(lines, start, line_offset).start,
+        line_span(lines, end, line_offset).end,
+    )
+}
+
+fn lines_span(lines: &[&str], line_number: usize, value: &str, line_offset: usize) -> Span {
+    let end = line_number + value.split('\n').count() - 1;
+    Span::new(
+        line_span(lines, line_number, line_offset).start,
+        line_span(lines, end, line_offset).end,
+    )
+}
+
+fn line_span(lines: &[&str], line_number: usize, line_offset: usize) -> Span {
+    let line = get_line(lines, line_number, line_offset);
+    let leading = line.chars().take_while(|c| c.is_whitespace()).count();
+    let length = line.trim_end().chars().count();
+    Span::new(
+        Position::new(line_number, leading + 1),
+        Position::new(line_number, length.max(leading) + 1),
+    )
+}
+
+fn get_line<'a>(lines: &[&'a str], line_number: usize, line_offset: usize) -> &'a str {
+    (line_number + line_offset)
+        .checked_sub(1)
+        .and_then(|index| lines.get(index))
+        .copied()
+        .unwrap_or_default()
+}
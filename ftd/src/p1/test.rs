@@ -922,3 +922,219 @@ fn kind() {
             .list(),
     );
 }
+
+fn span(start: (usize, usize), end: (usize, usize)) -> ftd::p1::Span {
+    ftd::p1::Span::new(
+        ftd::p1::Position::new(start.0, start.1),
+        ftd::p1::Position::new(end.0, end.1),
+    )
+}
+
+const SPAN_SOURCE: &str = indoc!(
+    "
+    -- ftd.text: hello
+    color: red
+
+    -- ftd.column:
+    padding.px: 10
+
+    This is the body
+    of the column
+
+    -- end: ftd.column
+    "
+);
+
+#[test]
+fn spans() {
+    let sections = super::parse(SPAN_SOURCE, "foo").unwrap_or_else(|e| panic!("{:?}", e));
+
+    assert_eq!(sections[0].span, span((1, 1), (2, 11)));
+    assert_eq!(
+        sections[0].caption.as_ref().unwrap().get_span(),
+        span((1, 14), (1, 19))
+    );
+    assert_eq!(sections[0].headers.0[0].get_span(), span((2, 1), (2, 11)));
+
+    assert_eq!(sections[1].span, span((4, 1), (8, 14)));
+    assert_eq!(sections[1].headers.0[0].get_span(), span((5, 1), (5, 15)));
+    assert_eq!(
+        sections[1].body.as_ref().unwrap().span,
+        span((7, 1), (8, 14))
+    );
+
+    assert_eq!(
+        ftd::p1::span::find(&sections, SPAN_SOURCE, 1),
+        span((1, 1), (1, 19))
+    );
+    assert_eq!(
+        ftd::p1::span::find(&sections, SPAN_SOURCE, 8),
+        span((7, 1), (8, 14))
+    );
+}
+
+#[test]
+fn error_spans() {
+    // errors of the parser have the span of the line they are reported at
+    let source = "-- ftd.text: hello\n\n-- end: ftd.column\n";
+    let e = super::parse(source, "foo").unwrap_err();
+    let line_number = match e {
+        ftd::p1::Error::ParseError { line_number, .. } => line_number,
+        ref e => panic!("{:?}", e),
+    };
+    assert_eq!(e.span(), Some(ftd::p1::Span::line(source, line_number)));
+
+    // errors of headers have the span of the header
+    let sections = super::parse(SPAN_SOURCE, "foo").unwrap_or_else(|e| panic!("{:?}", e));
+    let e = sections[0]
+        .headers
+        .find_once("align", "foo", sections[0].line_number)
+        .unwrap_err();
+    assert_eq!(e.span(), None);
+    let e = e.locate("foo", &ftd::p1::Spans::new(&sections, SPAN_SOURCE));
+    assert_eq!(e.span(), Some(span((1, 1), (1, 19))));
+    let e = sections[0].headers.0[0].get_sections("foo").unwrap_err();
+    assert_eq!(e.span(), Some(span((2, 1), (2, 11))));
+}
+
+#[test]
+fn render_diagnostic() {
+    let diagnostic = ftd::Diagnostic::at_line(
+        "foo",
+        "body is not allowed".to_string(),
+        SPAN_SOURCE,
+        7,
+        Some("remove the body".to_string()),
+    );
+
+    assert_eq!(
+        diagnostic.render(SPAN_SOURCE),
+        indoc!(
+            "
+            error: body is not allowed
+              --> foo:7:1
+              |
+            7 | This is the body
+              | ^^^^^^^^^^^^^^^^
+            8 | of the column
+              | ^^^^^^^^^^^^^
+              |
+              = help: remove the body
+            "
+        )
+    );
+}
+
+#[test]
+fn recovery() {
+    let source = indoc!(
+        "
+        hello world
+
+        -- ftd.column:
+
+        -- ftd.text: inside
+
+        -- end: ftd.column
+
+        -- ftd.text: world
+        align: center
+
+        -- ftd.row
+        "
+    );
+
+    let (sections, errors) = super::parse_with_recovery(source, "foo");
+    assert_eq!(
+        sections.iter().map(|v| v.name.as_str()).collect::<Vec<_>>(),
+        vec!["ftd.column", "ftd.text"]
+    );
+    assert_eq!(sections[0].sub_sections.len(), 1);
+    assert_eq!(sections[1].line_number, 9);
+    assert_eq!(errors.len(), 2);
+    assert!(matches!(errors[0], ftd::p1::Error::SectionNotFound { .. }));
+    assert!(matches!(errors[1], ftd::p1::Error::ParseError { .. }));
+}
+
+#[test]
+fn format() {
+    let source = indoc!(
+        "
+        ;; the page
+        -- ftd.column:
+        padding.px:10
+        spacing.fixed.px :  5   ;; gap
+
+        /-- ftd.text: hidden
+
+        -- ftd.text:   hello
+        color: red
+
+        -- ftd.text:
+
+        body *text*
+        ;; inside
+
+        more
+        -- end: ftd.column
+        -- import: a
+        -- import: b
+        "
+    );
+    let expected = indoc!(
+        "
+        ;; the page
+        -- ftd.column:
+        padding.px: 10
+        spacing.fixed.px: 5 ;; gap
+
+        /-- ftd.text: hidden
+
+        -- ftd.text: hello
+        color: red
+
+        -- ftd.text:
+
+        body *text*
+        ;; inside
+
+        more
+
+        -- end: ftd.column
+
+
+        -- import: a
+        -- import: b
+        "
+    );
+
+    let formatted = super::fmt::format(source, "foo").unwrap_or_else(|e| panic!("{:?}", e));
+    assert_eq!(expected, formatted);
+    assert_eq!(
+        expected,
+        super::fmt::format(formatted.as_str(), "foo").unwrap_or_else(|e| panic!("{:?}", e))
+    );
+}
+
+#[test]
+fn format_sort_headers() {
+    let options = super::fmt::FormatOptions { sort_headers: true };
+    assert_eq!(
+        "-- ftd.text: hello\nalign: center\ncolor: red\n",
+        super::fmt::format_with_options(
+            "-- ftd.text: hello\ncolor: red\nalign: center",
+            "foo",
+            &options
+        )
+        .unwrap_or_else(|e| panic!("{:?}", e))
+    );
+    assert_eq!(
+        "-- record person:\nstring name:\ninteger age:\n",
+        super::fmt::format_with_options(
+            "-- record person:\nstring name:\ninteger age:",
+            "foo",
+            &options
+        )
+        .unwrap_or_else(|e| panic!("{:?}", e))
+    );
+}